@@ -0,0 +1,26 @@
+-- Migration: Zero-based budgeting with per-month income allocation
+-- Opt-in per user; budgets remain independent caps unless the mode is enabled.
+
+ALTER TABLE users ADD COLUMN IF NOT EXISTS zero_based_budgeting BOOLEAN NOT NULL DEFAULT false;
+
+-- Amount of income assigned to a category for a given month
+CREATE TABLE IF NOT EXISTS budget_allocations (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    month VARCHAR NOT NULL,  -- format: YYYY-MM
+    category VARCHAR NOT NULL,
+    assigned DECIMAL NOT NULL DEFAULT 0 CHECK (assigned >= 0),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    UNIQUE(user_id, month, category)
+);
+
+CREATE INDEX IF NOT EXISTS idx_budget_allocations_user_month ON budget_allocations(user_id, month);
+
+ALTER TABLE budget_allocations ENABLE ROW LEVEL SECURITY;
+
+CREATE POLICY budget_allocations_user_isolation ON budget_allocations
+    FOR ALL
+    TO PUBLIC
+    USING (user_id = current_setting('app.current_user_id', true)::uuid);
@@ -0,0 +1,10 @@
+-- Migration: Secret tokens for subscribing to the upcoming bills calendar feed
+-- Calendar clients cannot send bearer tokens, so the feed URL embeds a per-user secret.
+-- Only a SHA-256 hash of the token is stored.
+-- No RLS: like users, rows are looked up before the user context is known.
+
+CREATE TABLE IF NOT EXISTS calendar_feed_tokens (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    token_hash TEXT NOT NULL UNIQUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,39 @@
+-- Migration: Per-user notification feed and delivery preferences
+
+CREATE TABLE IF NOT EXISTS notifications (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    event_type VARCHAR NOT NULL,
+    title TEXT NOT NULL,
+    body TEXT NOT NULL,
+    read_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_notifications_user_created ON notifications(user_id, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_notifications_user_unread ON notifications(user_id) WHERE read_at IS NULL;
+
+-- One row per (event type, channel) the user has explicitly configured;
+-- missing rows fall back to application defaults.
+CREATE TABLE IF NOT EXISTS notification_preferences (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    event_type VARCHAR NOT NULL,
+    channel VARCHAR NOT NULL,
+    enabled BOOLEAN NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (user_id, event_type, channel)
+);
+
+ALTER TABLE notifications ENABLE ROW LEVEL SECURITY;
+ALTER TABLE notification_preferences ENABLE ROW LEVEL SECURITY;
+
+CREATE POLICY notifications_user_isolation ON notifications
+    FOR ALL
+    TO PUBLIC
+    USING (user_id = current_setting('app.current_user_id', true)::uuid);
+
+CREATE POLICY notification_preferences_user_isolation ON notification_preferences
+    FOR ALL
+    TO PUBLIC
+    USING (user_id = current_setting('app.current_user_id', true)::uuid);
@@ -0,0 +1,47 @@
+-- Migration: User-configurable alert rules evaluated after each sync
+
+CREATE TABLE IF NOT EXISTS alert_rules (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    rule_type VARCHAR NOT NULL,  -- budget_threshold | large_transaction | low_balance | new_merchant
+    threshold DECIMAL,
+    percentages INTEGER[] NOT NULL DEFAULT '{}',
+    account_id UUID REFERENCES accounts(id) ON DELETE CASCADE,
+    category VARCHAR,
+    cooldown_hours INTEGER NOT NULL DEFAULT 24 CHECK (cooldown_hours >= 0),
+    enabled BOOLEAN NOT NULL DEFAULT true,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_alert_rules_user ON alert_rules(user_id);
+
+-- Every time a rule fired. dedup_key identifies the condition (a transaction,
+-- a budget month and percentage, an account) so it is not reported again
+-- until the rule's cooldown has passed.
+CREATE TABLE IF NOT EXISTS fired_alerts (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    rule_id UUID NOT NULL REFERENCES alert_rules(id) ON DELETE CASCADE,
+    rule_type VARCHAR NOT NULL,
+    dedup_key VARCHAR NOT NULL,
+    title TEXT NOT NULL,
+    body TEXT NOT NULL,
+    fired_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_fired_alerts_user_fired ON fired_alerts(user_id, fired_at DESC);
+CREATE INDEX IF NOT EXISTS idx_fired_alerts_rule_key ON fired_alerts(rule_id, dedup_key, fired_at DESC);
+
+ALTER TABLE alert_rules ENABLE ROW LEVEL SECURITY;
+ALTER TABLE fired_alerts ENABLE ROW LEVEL SECURITY;
+
+CREATE POLICY alert_rules_user_isolation ON alert_rules
+    FOR ALL
+    TO PUBLIC
+    USING (user_id = current_setting('app.current_user_id', true)::uuid);
+
+CREATE POLICY fired_alerts_user_isolation ON fired_alerts
+    FOR ALL
+    TO PUBLIC
+    USING (user_id = current_setting('app.current_user_id', true)::uuid);
@@ -0,0 +1,34 @@
+-- Migration: Multi-currency accounts and transactions with FX conversion
+-- Existing rows predate currency tracking and were all synced in USD.
+
+ALTER TABLE accounts ADD COLUMN IF NOT EXISTS iso_currency_code VARCHAR(3) NOT NULL DEFAULT 'USD';
+ALTER TABLE transactions ADD COLUMN IF NOT EXISTS iso_currency_code VARCHAR(3) NOT NULL DEFAULT 'USD';
+
+-- Dated exchange rates: 1 unit of base_currency = rate units of quote_currency.
+-- Shared reference data imported from a rates file, so no RLS.
+CREATE TABLE IF NOT EXISTS fx_rates (
+    base_currency VARCHAR(3) NOT NULL,
+    quote_currency VARCHAR(3) NOT NULL,
+    rate_date DATE NOT NULL,
+    rate NUMERIC(24, 12) NOT NULL CHECK (rate > 0),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (base_currency, quote_currency, rate_date)
+);
+
+CREATE INDEX IF NOT EXISTS idx_fx_rates_date ON fx_rates(rate_date);
+
+-- Per-user preferences; missing rows fall back to application defaults.
+CREATE TABLE IF NOT EXISTS user_settings (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    reporting_currency VARCHAR(3) NOT NULL DEFAULT 'USD',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+ALTER TABLE user_settings ENABLE ROW LEVEL SECURITY;
+
+CREATE POLICY user_settings_user_isolation ON user_settings
+    FOR ALL
+    TO PUBLIC
+    USING (user_id = current_setting('app.current_user_id', true)::uuid);
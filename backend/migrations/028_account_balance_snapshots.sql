@@ -0,0 +1,24 @@
+-- Migration: Daily account balance snapshots for net worth history
+-- One row per account per day; later syncs on the same day overwrite it.
+
+CREATE TABLE IF NOT EXISTS account_balance_snapshots (
+    account_id UUID NOT NULL REFERENCES accounts(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    snapshot_date DATE NOT NULL,
+    balance NUMERIC(14, 2) NOT NULL,
+    iso_currency_code VARCHAR(3) NOT NULL DEFAULT 'USD',
+    account_type VARCHAR(50) NOT NULL,
+    recorded_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (account_id, snapshot_date)
+);
+
+CREATE INDEX IF NOT EXISTS idx_account_balance_snapshots_user_date
+    ON account_balance_snapshots(user_id, snapshot_date);
+
+ALTER TABLE account_balance_snapshots ENABLE ROW LEVEL SECURITY;
+
+CREATE POLICY account_balance_snapshots_user_isolation ON account_balance_snapshots
+    FOR ALL
+    TO PUBLIC
+    USING (user_id = current_setting('app.current_user_id', true)::uuid);
@@ -0,0 +1,20 @@
+-- Migration: Free-form transaction tags
+-- A transaction can carry any number of tags; analytics can group and filter by them.
+
+CREATE TABLE IF NOT EXISTS transaction_tags (
+    transaction_id UUID NOT NULL REFERENCES transactions(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    tag VARCHAR(64) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (transaction_id, tag)
+);
+
+CREATE INDEX IF NOT EXISTS idx_transaction_tags_user_tag ON transaction_tags(user_id, tag);
+
+ALTER TABLE transaction_tags ENABLE ROW LEVEL SECURITY;
+
+CREATE POLICY transaction_tags_user_isolation ON transaction_tags
+    FOR ALL
+    TO PUBLIC
+    USING (user_id = current_setting('app.current_user_id', true)::uuid);
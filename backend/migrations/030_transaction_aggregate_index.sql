@@ -0,0 +1,7 @@
+-- Migration: Covering index for analytics aggregates
+-- Category, merchant and monthly totals are summed in SQL; carrying the grouped
+-- columns in the index lets those scans skip the heap.
+
+CREATE INDEX IF NOT EXISTS idx_transactions_user_date_aggregates
+    ON transactions(user_id, date)
+    INCLUDE (account_id, amount, iso_currency_code, category_primary, merchant_name);
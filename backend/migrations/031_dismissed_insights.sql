@@ -0,0 +1,18 @@
+-- Migration: Dismissed spending insights
+-- Insights are computed on request; only the ones a user dismissed are stored,
+-- keyed by the insight's stable id so they stay hidden on later requests.
+
+CREATE TABLE IF NOT EXISTS dismissed_insights (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    insight_id VARCHAR(255) NOT NULL,
+    dismissed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (user_id, insight_id)
+);
+
+ALTER TABLE dismissed_insights ENABLE ROW LEVEL SECURITY;
+
+CREATE POLICY dismissed_insights_user_isolation ON dismissed_insights
+    FOR ALL
+    TO PUBLIC
+    USING (user_id = current_setting('app.current_user_id', true)::uuid);
@@ -0,0 +1,75 @@
+-- Migration: Investment holdings, securities and investment transactions
+-- Investment accounts previously carried a single balance. Holdings are replaced
+-- on every sync; investment transactions are upserted by their provider id.
+
+CREATE TABLE IF NOT EXISTS securities (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    provider_security_id VARCHAR(255) NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    ticker_symbol VARCHAR(32),
+    security_type VARCHAR(64) NOT NULL,
+    iso_currency_code VARCHAR(3) NOT NULL DEFAULT 'USD',
+    close_price NUMERIC(20, 8),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    UNIQUE (user_id, provider_security_id)
+);
+
+CREATE TABLE IF NOT EXISTS investment_holdings (
+    account_id UUID NOT NULL REFERENCES accounts(id) ON DELETE CASCADE,
+    security_id UUID NOT NULL REFERENCES securities(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    quantity NUMERIC(24, 8) NOT NULL,
+    institution_price NUMERIC(20, 8) NOT NULL,
+    institution_value NUMERIC(20, 2) NOT NULL,
+    cost_basis NUMERIC(20, 2),
+    iso_currency_code VARCHAR(3) NOT NULL DEFAULT 'USD',
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (account_id, security_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_investment_holdings_user ON investment_holdings(user_id);
+
+CREATE TABLE IF NOT EXISTS investment_transactions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    account_id UUID NOT NULL REFERENCES accounts(id) ON DELETE CASCADE,
+    security_id UUID REFERENCES securities(id) ON DELETE SET NULL,
+    provider_investment_transaction_id VARCHAR(255) NOT NULL,
+    date DATE NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    transaction_type VARCHAR(32) NOT NULL,
+    subtype VARCHAR(64),
+    quantity NUMERIC(24, 8) NOT NULL DEFAULT 0,
+    price NUMERIC(20, 8) NOT NULL DEFAULT 0,
+    amount NUMERIC(20, 2) NOT NULL,
+    fees NUMERIC(20, 2),
+    iso_currency_code VARCHAR(3) NOT NULL DEFAULT 'USD',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    UNIQUE (user_id, provider_investment_transaction_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_investment_transactions_user_date
+    ON investment_transactions(user_id, date DESC);
+
+ALTER TABLE securities ENABLE ROW LEVEL SECURITY;
+ALTER TABLE investment_holdings ENABLE ROW LEVEL SECURITY;
+ALTER TABLE investment_transactions ENABLE ROW LEVEL SECURITY;
+
+CREATE POLICY securities_user_isolation ON securities
+    FOR ALL
+    TO PUBLIC
+    USING (user_id = current_setting('app.current_user_id', true)::uuid);
+
+CREATE POLICY investment_holdings_user_isolation ON investment_holdings
+    FOR ALL
+    TO PUBLIC
+    USING (user_id = current_setting('app.current_user_id', true)::uuid);
+
+CREATE POLICY investment_transactions_user_isolation ON investment_transactions
+    FOR ALL
+    TO PUBLIC
+    USING (user_id = current_setting('app.current_user_id', true)::uuid);
@@ -0,0 +1,28 @@
+-- Migration: Liability details for credit and loan accounts
+-- One row per account. Rows synced from a provider are refreshed on every sync;
+-- rows a user entered by hand are never overwritten by a sync.
+
+CREATE TABLE IF NOT EXISTS liabilities (
+    account_id UUID PRIMARY KEY REFERENCES accounts(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    liability_type VARCHAR(32) NOT NULL,
+    apr NUMERIC(7, 4),
+    minimum_payment NUMERIC(14, 2),
+    statement_balance NUMERIC(14, 2),
+    next_payment_due_date DATE,
+    loan_term_months INTEGER,
+    maturity_date DATE,
+    source VARCHAR(16) NOT NULL DEFAULT 'provider',
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT liabilities_source_check CHECK (source IN ('provider', 'manual'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_liabilities_user ON liabilities(user_id);
+
+ALTER TABLE liabilities ENABLE ROW LEVEL SECURITY;
+
+CREATE POLICY liabilities_user_isolation ON liabilities
+    FOR ALL
+    TO PUBLIC
+    USING (user_id = current_setting('app.current_user_id', true)::uuid);
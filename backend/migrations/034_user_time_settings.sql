@@ -0,0 +1,6 @@
+-- Migration: Per-user time zone and month start day
+-- Both default to the behaviour before this migration: UTC calendar months.
+
+ALTER TABLE user_settings ADD COLUMN IF NOT EXISTS timezone VARCHAR(64) NOT NULL DEFAULT 'UTC';
+ALTER TABLE user_settings ADD COLUMN IF NOT EXISTS month_start_day SMALLINT NOT NULL DEFAULT 1
+    CHECK (month_start_day BETWEEN 1 AND 28);
@@ -0,0 +1,14 @@
+-- Migration: Per-transaction review and analytics exclusion flags
+-- Set through bulk edits; excluded transactions are left out of
+-- spending aggregates, so the covering aggregate index carries the flag too.
+
+ALTER TABLE transactions
+    ADD COLUMN IF NOT EXISTS reviewed BOOLEAN NOT NULL DEFAULT FALSE,
+    ADD COLUMN IF NOT EXISTS excluded_from_analytics BOOLEAN NOT NULL DEFAULT FALSE;
+
+DROP INDEX IF EXISTS idx_transactions_user_date_aggregates;
+
+CREATE INDEX IF NOT EXISTS idx_transactions_user_date_aggregates
+    ON transactions(user_id, date)
+    INCLUDE (account_id, amount, iso_currency_code, category_primary, merchant_name,
+             excluded_from_analytics);
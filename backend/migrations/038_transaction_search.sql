@@ -0,0 +1,30 @@
+-- Migration: Indexed transaction search
+-- A generated tsvector over merchant and category backs word and prefix matches;
+-- trigram indexes back typo-tolerant merchant matches and substring filters.
+
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+ALTER TABLE transactions
+    ADD COLUMN IF NOT EXISTS search_vector tsvector
+    GENERATED ALWAYS AS (
+        to_tsvector(
+            'simple',
+            COALESCE(merchant_name, '') || ' ' ||
+            REPLACE(category_primary, '_', ' ') || ' ' ||
+            category_detailed
+        )
+    ) STORED;
+
+CREATE INDEX IF NOT EXISTS idx_transactions_search_vector
+    ON transactions USING GIN (search_vector);
+
+CREATE INDEX IF NOT EXISTS idx_transactions_merchant_trgm
+    ON transactions USING GIN (merchant_name gin_trgm_ops);
+
+-- The category filter matches either of these two expressions, so both need an
+-- index for Postgres to combine them.
+CREATE INDEX IF NOT EXISTS idx_transactions_category_primary_trgm
+    ON transactions USING GIN ((REPLACE(category_primary, '_', ' ')) gin_trgm_ops);
+
+CREATE INDEX IF NOT EXISTS idx_transactions_category_detailed_trgm
+    ON transactions USING GIN (category_detailed gin_trgm_ops);
@@ -0,0 +1,31 @@
+-- Migration: Saved views
+-- A named transaction search, account filter and fixed or relative date range.
+-- Budgets and alert rules may point at a view to measure only what it matches.
+
+CREATE TABLE IF NOT EXISTS saved_views (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL,
+    search TEXT,
+    account_ids UUID[] NOT NULL DEFAULT '{}',
+    date_preset VARCHAR(20),  -- last_7_days | ... | last_year; NULL uses start_date/end_date
+    start_date DATE,
+    end_date DATE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    UNIQUE (user_id, name)
+);
+
+ALTER TABLE saved_views ENABLE ROW LEVEL SECURITY;
+
+CREATE POLICY saved_views_user_isolation ON saved_views
+    FOR ALL
+    TO PUBLIC
+    USING (user_id = current_setting('app.current_user_id', true)::uuid);
+
+ALTER TABLE budgets
+    ADD COLUMN IF NOT EXISTS saved_view_id UUID REFERENCES saved_views(id) ON DELETE SET NULL;
+
+ALTER TABLE alert_rules
+    ADD COLUMN IF NOT EXISTS saved_view_id UUID REFERENCES saved_views(id) ON DELETE SET NULL;
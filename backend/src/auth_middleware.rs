@@ -1,11 +1,13 @@
 use crate::middleware::telemetry_middleware::{attach_encrypted_token_to_current_span, hash_token};
+use crate::models::access_token::TokenScope;
 use crate::models::api_error::ApiErrorResponse;
 use crate::models::auth::AuthError;
 pub use crate::models::auth::{AuthContext, AuthMiddlewareState};
 use crate::services::auth_service::AuthService;
+use crate::services::AccessTokenService;
 use axum::{
     extract::{Request, State},
-    http::{HeaderMap, StatusCode},
+    http::{HeaderMap, Method, StatusCode},
     middleware::Next,
     response::{IntoResponse, Json, Response},
 };
@@ -41,6 +43,15 @@ pub async fn auth_middleware(
     let encrypted_token = hash_token(token);
     attach_encrypted_token_to_current_span(&encrypted_token);
 
+    if AccessTokenService::is_access_token(token) {
+        let method = request.method().clone();
+        let path = request.uri().path().to_string();
+        let auth_context =
+            authenticate_access_token(&middleware_state, token, &method, &path).await?;
+        request.extensions_mut().insert(auth_context);
+        return Ok(next.run(request).await);
+    }
+
     let auth_context = match extract_user_context(&middleware_state.auth_service, token) {
         Ok(context) => context,
         Err(auth_error) => {
@@ -135,6 +146,72 @@ pub async fn auth_middleware(
     Ok(next.run(request).await)
 }
 
+/// Personal access tokens skip the session check: they are long-lived and
+/// revoked in the database instead, and may only call endpoints their scopes
+/// allow.
+async fn authenticate_access_token(
+    middleware_state: &AuthMiddlewareState,
+    token: &str,
+    method: &Method,
+    path: &str,
+) -> Result<AuthContext, Response> {
+    let access_token = match middleware_state
+        .access_token_service
+        .authenticate(&*middleware_state.db_repository, token)
+        .await
+    {
+        Ok(Some(access_token)) => access_token,
+        Ok(None) => {
+            tracing::warn!(
+                auth_error_type = "invalid_access_token",
+                path = %path,
+                method = %method,
+                "Authentication failure: Unknown, revoked or expired access token"
+            );
+            let error_response = ApiErrorResponse::with_code(
+                "UNAUTHORIZED",
+                "Invalid, revoked or expired access token",
+                "INVALID_TOKEN",
+            );
+            return Err((StatusCode::UNAUTHORIZED, Json(error_response)).into_response());
+        }
+        Err(e) => {
+            tracing::error!(
+                auth_error_type = "access_token_error",
+                path = %path,
+                method = %method,
+                error = %e,
+                "Authentication failure: Access token lookup failed"
+            );
+            let error_response =
+                ApiErrorResponse::with_code("UNAUTHORIZED", "Authentication failed", "AUTH_ERROR");
+            return Err((StatusCode::UNAUTHORIZED, Json(error_response)).into_response());
+        }
+    };
+
+    let allowed = TokenScope::required_for(method, path)
+        .is_some_and(|scope| access_token.scopes.contains(&scope));
+    if !allowed {
+        tracing::warn!(
+            auth_error_type = "insufficient_scope",
+            path = %path,
+            method = %method,
+            "Authorization failure: Access token scopes do not cover this endpoint"
+        );
+        let error_response = ApiErrorResponse::with_code(
+            "FORBIDDEN",
+            "Access token is not allowed to call this endpoint",
+            "INSUFFICIENT_SCOPE",
+        );
+        return Err((StatusCode::FORBIDDEN, Json(error_response)).into_response());
+    }
+
+    Ok(AuthContext {
+        user_id: access_token.user_id,
+        jwt_id: format!("pat_{}", access_token.id),
+    })
+}
+
 pub fn extract_bearer_token(headers: &HeaderMap) -> Option<&str> {
     headers
         .get("authorization")
@@ -13,11 +13,32 @@ impl EnvironmentProvider for SystemEnvironment {
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SmtpSecurity {
+    /// Plain connection; only suitable for local relays and test sinks.
+    None,
+    StartTls,
+    Tls,
+}
+
+#[derive(Clone)]
+pub struct SmtpSettings {
+    pub host: String,
+    pub port: u16,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub from: String,
+    pub security: SmtpSecurity,
+}
+
 #[derive(Clone)]
 pub struct Config {
     default_provider: String,
     teller_application_id: Option<String>,
     teller_environment: String,
+    smtp: Option<SmtpSettings>,
+    fx_rates_file: Option<String>,
+    webhook_allowed_hosts: Vec<String>,
 }
 
 impl Config {
@@ -36,13 +57,68 @@ impl Config {
             .or_else(|| env.get_var("TELLER_ENVIRONMENT"))
             .ok_or_else(|| anyhow!("TELLER_ENV (or TELLER_ENVIRONMENT) must be set"))?;
 
+        let smtp = Self::smtp_from_env(env)?;
+        let fx_rates_file = env.get_var("FX_RATES_FILE").filter(|path| !path.is_empty());
+        let webhook_allowed_hosts = env
+            .get_var("WEBHOOK_ALLOWED_HOSTS")
+            .map(|hosts| {
+                hosts
+                    .split(',')
+                    .map(|host| host.trim().to_lowercase())
+                    .filter(|host| !host.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default();
+
         Ok(Self {
             default_provider,
             teller_application_id,
             teller_environment,
+            smtp,
+            fx_rates_file,
+            webhook_allowed_hosts,
         })
     }
 
+    fn smtp_from_env(env: &dyn EnvironmentProvider) -> Result<Option<SmtpSettings>> {
+        // docker-compose passes unset variables through as empty strings.
+        let var = |key: &str| env.get_var(key).filter(|value| !value.is_empty());
+        let Some(host) = var("SMTP_HOST") else {
+            return Ok(None);
+        };
+        let security = match var("SMTP_SECURITY")
+            .unwrap_or_else(|| "starttls".to_string())
+            .to_lowercase()
+            .as_str()
+        {
+            "none" => SmtpSecurity::None,
+            "starttls" => SmtpSecurity::StartTls,
+            "tls" => SmtpSecurity::Tls,
+            other => return Err(anyhow!("Unsupported SMTP_SECURITY value: {}", other)),
+        };
+        let port = match var("SMTP_PORT") {
+            Some(port) => port
+                .parse()
+                .map_err(|_| anyhow!("SMTP_PORT must be a valid port number"))?,
+            None => match security {
+                SmtpSecurity::None => 25,
+                SmtpSecurity::StartTls => 587,
+                SmtpSecurity::Tls => 465,
+            },
+        };
+        let from = var("SMTP_FROM")
+            .ok_or_else(|| anyhow!("SMTP_FROM must be set when SMTP_HOST is configured"))?;
+
+        Ok(Some(SmtpSettings {
+            host,
+            port,
+            username: var("SMTP_USERNAME"),
+            password: var("SMTP_PASSWORD"),
+            from,
+            security,
+        }))
+    }
+
     pub fn get_default_provider(&self) -> &str {
         &self.default_provider
     }
@@ -54,6 +130,19 @@ impl Config {
     pub fn get_teller_environment(&self) -> &str {
         &self.teller_environment
     }
+
+    pub fn get_smtp_settings(&self) -> Option<&SmtpSettings> {
+        self.smtp.as_ref()
+    }
+
+    pub fn get_fx_rates_file(&self) -> Option<&str> {
+        self.fx_rates_file.as_deref()
+    }
+
+    /// Webhook hosts allowed to resolve to loopback or private addresses.
+    pub fn get_webhook_allowed_hosts(&self) -> &[String] {
+        &self.webhook_allowed_hosts
+    }
 }
 
 #[cfg(test)]
@@ -1,6 +1,10 @@
 use anyhow::Result;
 use axum::{
-    body::Body, extract::Request, http::header::AUTHORIZATION, middleware::Next, response::Response,
+    body::Body,
+    extract::{MatchedPath, Request},
+    http::header::AUTHORIZATION,
+    middleware::Next,
+    response::Response,
 };
 use axum_tracing_opentelemetry::tracing_opentelemetry_instrumentation_sdk as otel_sdk;
 use chrono::Utc;
@@ -29,6 +33,9 @@ use tracing_subscriber::{
 
 const SENSITIVE_REQUEST_PATHS: &[&str] = &["/api/plaid/exchange-token", "/api/providers/connect"];
 
+/// Paths that carry a secret in the URL itself, such as the calendar feed token.
+const SECRET_PATH_PREFIXES: &[&str] = &["/api/bills/calendar/"];
+
 pub struct TelemetryConfig {
     pub env_filter: Option<String>,
     pub otlp_endpoint: String,
@@ -154,9 +161,28 @@ pub fn attach_encrypted_token_to_current_span(encrypted_token: &str) {
     attach_encrypted_token_to_span(&span, encrypted_token);
 }
 
+/// Whether the OpenTelemetry layer may trace a path. It records the raw path, so
+/// paths with secrets in them are left to `request_tracing_middleware`.
+pub fn is_traceable_path(path: &str) -> bool {
+    !SECRET_PATH_PREFIXES
+        .iter()
+        .any(|prefix| path.starts_with(prefix))
+}
+
+/// The route to record for a request: its route template, e.g.
+/// `/api/bills/calendar/{token}/feed.ics`, so path parameters never reach traces.
+/// Unmatched paths are recorded as-is unless they could carry a secret.
+pub fn traced_route(request: &Request<Body>) -> String {
+    match request.extensions().get::<MatchedPath>() {
+        Some(matched) => matched.as_str().to_string(),
+        None if is_traceable_path(request.uri().path()) => request.uri().path().to_string(),
+        None => "[redacted]".to_string(),
+    }
+}
+
 pub async fn request_tracing_middleware(request: Request<Body>, next: Next) -> Response {
     let method = request.method().clone();
-    let path = request.uri().path().to_string();
+    let path = traced_route(&request);
     if SENSITIVE_REQUEST_PATHS
         .iter()
         .any(|&sensitive| sensitive == path)
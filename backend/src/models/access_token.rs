@@ -0,0 +1,136 @@
+use axum::http::Method;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[allow(unused_imports)]
+use serde_json::json;
+
+/// What a personal access token may do. Session tokens are not scoped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+pub enum TokenScope {
+    #[serde(rename = "read:transactions")]
+    ReadTransactions,
+    #[serde(rename = "read:analytics")]
+    ReadAnalytics,
+    #[serde(rename = "write:budgets")]
+    WriteBudgets,
+    #[serde(rename = "sync")]
+    Sync,
+}
+
+impl TokenScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TokenScope::ReadTransactions => "read:transactions",
+            TokenScope::ReadAnalytics => "read:analytics",
+            TokenScope::WriteBudgets => "write:budgets",
+            TokenScope::Sync => "sync",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "read:transactions" => Some(TokenScope::ReadTransactions),
+            "read:analytics" => Some(TokenScope::ReadAnalytics),
+            "write:budgets" => Some(TokenScope::WriteBudgets),
+            "sync" => Some(TokenScope::Sync),
+            _ => None,
+        }
+    }
+
+    /// The scope a token needs to call an endpoint, or `None` when tokens may
+    /// not call it at all. Anything not listed here stays session-only, which
+    /// covers account, credential and token management.
+    pub fn required_for(method: &Method, path: &str) -> Option<TokenScope> {
+        let under = |prefix: &str| {
+            path == prefix
+                || path
+                    .strip_prefix(prefix)
+                    .is_some_and(|rest| rest.starts_with('/'))
+        };
+        match *method {
+            Method::GET if under("/api/transactions") => Some(TokenScope::ReadTransactions),
+            Method::GET
+                if path == "/api/export/journal"
+                    || path == "/api/providers/accounts"
+                    || path == "/api/plaid/accounts"
+                    || under("/api/investments")
+                    || path == "/api/liabilities" =>
+            {
+                Some(TokenScope::ReadTransactions)
+            }
+            Method::GET
+                if under("/api/analytics")
+                    || under("/api/budgets")
+                    || path == "/api/bills/upcoming" =>
+            {
+                Some(TokenScope::ReadAnalytics)
+            }
+            Method::POST
+                if path == "/api/analytics/query" || path == "/api/liabilities/payoff-plan" =>
+            {
+                Some(TokenScope::ReadAnalytics)
+            }
+            Method::POST | Method::PUT | Method::DELETE if under("/api/budgets") => {
+                Some(TokenScope::WriteBudgets)
+            }
+            Method::POST if path == "/api/providers/sync-transactions" => Some(TokenScope::Sync),
+            Method::GET if path == "/api/providers/sync/events" => Some(TokenScope::Sync),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, ToSchema)]
+#[schema(example = json!({
+    "id": "0f8fad5b-d9cb-469f-a165-70867728950e",
+    "name": "Monthly report script",
+    "token_prefix": "smr_pat_3f9a",
+    "scopes": ["read:transactions", "read:analytics"],
+    "expires_at": null,
+    "last_used_at": "2024-03-01T06:00:00Z",
+    "revoked_at": null,
+    "created_at": "2024-02-01T12:00:00Z"
+}))]
+pub struct PersonalAccessToken {
+    pub id: Uuid,
+    #[serde(skip)]
+    pub user_id: Uuid,
+    pub name: String,
+    /// Leading characters of the token, for telling tokens apart.
+    pub token_prefix: String,
+    pub scopes: Vec<TokenScope>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl PersonalAccessToken {
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.revoked_at.is_none() && self.expires_at.is_none_or(|expires_at| expires_at > now)
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[schema(example = json!({
+    "name": "Monthly report script",
+    "scopes": ["read:transactions", "read:analytics"],
+    "expires_in_days": 90
+}))]
+pub struct CreateAccessTokenRequest {
+    pub name: String,
+    pub scopes: Vec<TokenScope>,
+    /// Days until the token stops working, at most 3650; omit for a token that
+    /// lasts until revoked.
+    pub expires_in_days: Option<u32>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CreateAccessTokenResponse {
+    /// The token itself. It is not stored and cannot be shown again.
+    pub token: String,
+    pub access_token: PersonalAccessToken,
+}
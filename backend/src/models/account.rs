@@ -3,6 +3,8 @@ use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 use uuid::Uuid;
 
+use crate::models::currency::{default_currency, normalize_currency_code};
+
 #[allow(unused_imports)]
 use serde_json::json;
 
@@ -15,6 +17,7 @@ use serde_json::json;
     "name": "Demo Checking",
     "account_type": "depository",
     "balance_current": "1234.56",
+    "iso_currency_code": "USD",
     "mask": "1234",
     "institution_name": "Demo Bank"
 }))]
@@ -27,6 +30,8 @@ pub struct Account {
     pub account_type: String,
     #[schema(value_type = Option<String>)]
     pub balance_current: Option<Decimal>,
+    #[serde(default = "default_currency")]
+    pub iso_currency_code: String,
     pub mask: Option<String>,
     pub institution_name: Option<String>,
 }
@@ -40,6 +45,7 @@ pub struct Account {
     "name": "Demo Checking",
     "account_type": "depository",
     "balance_current": "1234.56",
+    "iso_currency_code": "USD",
     "mask": "1234",
     "transaction_count": 42,
     "institution_name": "Demo Bank"
@@ -53,6 +59,7 @@ pub struct AccountResponse {
     pub account_type: String,
     #[schema(value_type = Option<String>)]
     pub balance_current: Option<rust_decimal::Decimal>,
+    pub iso_currency_code: String,
     pub mask: Option<String>,
     pub transaction_count: i64,
     pub institution_name: Option<String>,
@@ -68,6 +75,10 @@ impl Account {
             name: teller_acc["name"].as_str().unwrap_or("Unknown").to_string(),
             account_type: teller_acc["type"].as_str().unwrap_or("other").to_string(),
             balance_current: None,
+            iso_currency_code: teller_acc["currency"]
+                .as_str()
+                .and_then(normalize_currency_code)
+                .unwrap_or_else(default_currency),
             mask: teller_acc["last_four"].as_str().map(String::from),
             institution_name: teller_acc["institution"]["name"].as_str().map(String::from),
         }
@@ -84,8 +95,19 @@ impl Account {
             balance_current: plaid_acc["balances"]["current"]
                 .as_f64()
                 .and_then(Decimal::from_f64_retain),
+            iso_currency_code: Self::plaid_currency(&plaid_acc["balances"]),
             mask: plaid_acc["mask"].as_str().map(String::from),
             institution_name: None,
         }
     }
+
+    /// Plaid reports `iso_currency_code`, or `unofficial_currency_code` for
+    /// currencies outside ISO 4217; the two are mutually exclusive.
+    pub fn plaid_currency(balances: &serde_json::Value) -> String {
+        balances["iso_currency_code"]
+            .as_str()
+            .or_else(|| balances["unofficial_currency_code"].as_str())
+            .and_then(normalize_currency_code)
+            .unwrap_or_else(default_currency)
+    }
 }
@@ -0,0 +1,151 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::models::notification::NotificationEventType;
+
+#[allow(unused_imports)]
+use serde_json::json;
+
+pub const DEFAULT_ALERT_COOLDOWN_HOURS: i32 = 24;
+pub const DEFAULT_BUDGET_ALERT_PERCENTAGES: [i32; 2] = [80, 100];
+pub const DEFAULT_FIRED_ALERTS_LIMIT: i64 = 50;
+pub const MAX_FIRED_ALERTS_LIMIT: i64 = 200;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertRuleType {
+    /// Spending in a budget category reaches one of the configured percentages.
+    BudgetThreshold,
+    /// A single spending transaction exceeds the threshold.
+    LargeTransaction,
+    /// A cash account balance drops below the threshold.
+    LowBalance,
+    /// First charge from a merchant not seen before exceeds the threshold.
+    NewMerchant,
+}
+
+impl AlertRuleType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::BudgetThreshold => "budget_threshold",
+            Self::LargeTransaction => "large_transaction",
+            Self::LowBalance => "low_balance",
+            Self::NewMerchant => "new_merchant",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        [
+            Self::BudgetThreshold,
+            Self::LargeTransaction,
+            Self::LowBalance,
+            Self::NewMerchant,
+        ]
+        .into_iter()
+        .find(|t| t.as_str() == value)
+    }
+
+    pub fn notification_event_type(&self) -> NotificationEventType {
+        match self {
+            Self::BudgetThreshold => NotificationEventType::BudgetThreshold,
+            Self::LargeTransaction => NotificationEventType::LargeTransaction,
+            Self::LowBalance => NotificationEventType::LowBalance,
+            Self::NewMerchant => NotificationEventType::NewMerchant,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, PartialEq, ToSchema)]
+#[schema(example = json!({
+    "id": "550e8400-e29b-41d4-a716-446655440000",
+    "rule_type": "low_balance",
+    "threshold": "1000.00",
+    "percentages": [],
+    "account_id": "aaaaaaaa-bbbb-cccc-dddd-eeeeeeeeeeee",
+    "category": null,
+    "saved_view_id": null,
+    "cooldown_hours": 24,
+    "enabled": true,
+    "created_at": "2024-03-01T12:00:00Z",
+    "updated_at": "2024-03-01T12:00:00Z"
+}))]
+pub struct AlertRule {
+    pub id: Uuid,
+    #[serde(skip)]
+    pub user_id: Uuid,
+    pub rule_type: String,
+    #[schema(value_type = Option<String>)]
+    pub threshold: Option<Decimal>,
+    pub percentages: Vec<i32>,
+    pub account_id: Option<Uuid>,
+    pub category: Option<String>,
+    /// Limits the rule to transactions the saved view matches.
+    #[serde(default)]
+    pub saved_view_id: Option<Uuid>,
+    pub cooldown_hours: i32,
+    pub enabled: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl AlertRule {
+    pub fn kind(&self) -> Option<AlertRuleType> {
+        AlertRuleType::parse(&self.rule_type)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+#[schema(example = json!({
+    "rule_type": "budget_threshold",
+    "percentages": [80, 100],
+    "cooldown_hours": 24
+}))]
+pub struct AlertRuleRequest {
+    pub rule_type: AlertRuleType,
+    #[schema(value_type = Option<String>)]
+    pub threshold: Option<Decimal>,
+    pub percentages: Option<Vec<i32>>,
+    pub account_id: Option<Uuid>,
+    pub category: Option<String>,
+    /// Saved view the rule's transactions (or a budget rule's spending) must match.
+    pub saved_view_id: Option<Uuid>,
+    pub cooldown_hours: Option<i32>,
+    pub enabled: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, PartialEq, ToSchema)]
+#[schema(example = json!({
+    "id": "11111111-2222-3333-4444-555555555555",
+    "rule_id": "550e8400-e29b-41d4-a716-446655440000",
+    "rule_type": "large_transaction",
+    "dedup_key": "transaction:44444444-5555-6666-7777-888888888888",
+    "title": "Large transaction at Apple Store",
+    "body": "A 1299.00 charge at Apple Store on 2024-03-01 is above your 500.00 alert.",
+    "fired_at": "2024-03-01T12:05:00Z"
+}))]
+pub struct FiredAlert {
+    pub id: Uuid,
+    pub rule_id: Uuid,
+    pub rule_type: String,
+    pub dedup_key: String,
+    pub title: String,
+    pub body: String,
+    pub fired_at: DateTime<Utc>,
+}
+
+/// Last time a rule fired for a given condition.
+#[derive(Debug, Clone, FromRow, PartialEq)]
+pub struct AlertHistoryEntry {
+    pub rule_id: Uuid,
+    pub dedup_key: String,
+    pub last_fired_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FiredAlertsQuery {
+    pub limit: Option<i64>,
+}
@@ -1,8 +1,10 @@
+use chrono::NaiveDate;
 use rust_decimal::Decimal;
 use serde::de::{self, Deserializer, IgnoredAny, MapAccess, Visitor};
 use serde::{Deserialize, Serialize};
 use std::fmt;
 use utoipa::ToSchema;
+use uuid::Uuid;
 
 #[allow(unused_imports)]
 use serde_json::json;
@@ -24,9 +26,11 @@ pub struct CategorySpending {
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
-#[schema(example = json!({"day": 15, "spend": "75.60", "cumulative": "890.25"}))]
+#[schema(example = json!({"day": 15, "date": "2024-03-15", "spend": "75.60", "cumulative": "890.25"}))]
 pub struct DailySpending {
+    /// 1 for the first day of the month.
     pub day: u32,
+    pub date: NaiveDate,
     #[schema(value_type = String)]
     pub spend: Decimal,
     #[schema(value_type = String)]
@@ -44,19 +48,31 @@ pub struct TopMerchant {
     pub percentage: Decimal,
 }
 
-#[derive(Debug, Serialize, Deserialize, ToSchema)]
-#[schema(example = json!({"date": "2024-01-31", "value": "12500.90"}))]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
+#[schema(example = json!({
+    "date": "2024-01-31",
+    "value": "12500.90",
+    "assets": "18700.90",
+    "liabilities": "6200.00"
+}))]
 pub struct NetWorthSeriesPoint {
     pub date: String,
+    /// Net worth: `assets - liabilities`.
     #[schema(value_type = String)]
     pub value: Decimal,
+    /// Cash and investment balances.
+    #[schema(value_type = String)]
+    pub assets: Decimal,
+    /// Amounts owed on credit and loan accounts, as a positive number.
+    #[schema(value_type = String)]
+    pub liabilities: Decimal,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 #[schema(example = json!({
     "series": [
-        {"date": "2024-01-31", "value": "12500.90"},
-        {"date": "2024-02-29", "value": "13150.25"}
+        {"date": "2024-01-31", "value": "12500.90", "assets": "18700.90", "liabilities": "6200.00"},
+        {"date": "2024-02-29", "value": "13150.25", "assets": "19050.25", "liabilities": "5900.00"}
     ],
     "currency": "USD"
 }))]
@@ -84,6 +100,59 @@ pub struct MonthlyTotalsQuery {
     pub account_ids: Vec<String>,
 }
 
+/// Narrows the SQL aggregate queries. The date range applies only when both
+/// ends are given, matching `AnalyticsService::filter_by_date_range`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AggregateFilter {
+    pub date_range: Option<(NaiveDate, NaiveDate)>,
+    pub account_ids: Vec<Uuid>,
+    /// Amounts already in this currency are summed across dates; the rest stay
+    /// split by day so each can be converted at that day's rate.
+    pub reporting_currency: String,
+    /// Day of the month on which monthly buckets start.
+    pub month_start_day: u32,
+}
+
+impl AggregateFilter {
+    pub fn new(
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+        account_ids: Vec<Uuid>,
+        reporting_currency: String,
+    ) -> Self {
+        Self {
+            date_range: start_date.zip(end_date),
+            account_ids,
+            reporting_currency,
+            month_start_day: 1,
+        }
+    }
+
+    pub fn with_month_start_day(mut self, month_start_day: u32) -> Self {
+        self.month_start_day = month_start_day;
+        self
+    }
+}
+
+/// One group of an SQL aggregate in its original currency. `rate_date` is
+/// `None` when the amounts are already in the reporting currency.
+#[derive(Debug, Clone, PartialEq, sqlx::FromRow)]
+pub struct AggregateBucket {
+    pub key: String,
+    pub iso_currency_code: String,
+    pub rate_date: Option<NaiveDate>,
+    pub total: Decimal,
+    pub count: i64,
+}
+
+/// An aggregate group restated in the reporting currency.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AggregateTotal {
+    pub key: String,
+    pub total: Decimal,
+    pub count: i64,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
 #[serde(rename_all = "camelCase")]
 #[schema(example = json!({
@@ -162,13 +231,125 @@ pub struct BankTotals {
         "net": "6300.00",
         "ratio": "1.40"
     }],
-    "mixed_currency": false
+    "mixed_currency": false,
+    "currency": "USD"
 }))]
 pub struct BalancesOverviewResponse {
     pub as_of: String,
     pub overall: Totals,
     pub banks: Vec<BankTotals>,
+    /// Some balances were left out because no FX rate into `currency` was known.
     pub mixed_currency: bool,
+    /// Reporting currency every total is expressed in.
+    pub currency: String,
+    /// Present when a `compare_to` date was requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comparison: Option<BalancesComparison>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(example = json!({
+    "cash": "450.00",
+    "credit": "-120.00",
+    "loan": "300.00",
+    "investments": "800.00",
+    "positives_total": "1250.00",
+    "negatives_total": "180.00",
+    "net": "1430.00"
+}))]
+pub struct TotalsChange {
+    #[schema(value_type = String)]
+    pub cash: Decimal,
+    #[schema(value_type = String)]
+    pub credit: Decimal,
+    #[schema(value_type = String)]
+    pub loan: Decimal,
+    #[schema(value_type = String)]
+    pub investments: Decimal,
+    #[schema(value_type = String)]
+    pub positives_total: Decimal,
+    #[schema(value_type = String)]
+    pub negatives_total: Decimal,
+    #[schema(value_type = String)]
+    pub net: Decimal,
+}
+
+impl TotalsChange {
+    pub fn between(from: &Totals, to: &Totals) -> Self {
+        Self {
+            cash: to.cash - from.cash,
+            credit: to.credit - from.credit,
+            loan: to.loan - from.loan,
+            investments: to.investments - from.investments,
+            positives_total: to.positives_total - from.positives_total,
+            negatives_total: to.negatives_total - from.negatives_total,
+            net: to.net - from.net,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BankTotalsChange {
+    pub bank_id: String,
+    pub bank_name: String,
+    #[serde(flatten)]
+    pub change: TotalsChange,
+}
+
+/// Balances on `compare_to`, and how each total moved from then to `as_of`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BalancesComparison {
+    pub compare_to: String,
+    pub overall: Totals,
+    pub banks: Vec<BankTotals>,
+    pub overall_change: TotalsChange,
+    pub bank_changes: Vec<BankTotalsChange>,
+}
+
+impl BalancesComparison {
+    /// Banks present on only one of the two dates are compared against zero.
+    pub fn between(
+        previous: &BalancesOverviewResponse,
+        current: &BalancesOverviewResponse,
+    ) -> Self {
+        let empty = finalize_totals(Decimal::ZERO, Decimal::ZERO, Decimal::ZERO, Decimal::ZERO);
+        let find = |banks: &[BankTotals], bank_id: &str| {
+            banks
+                .iter()
+                .find(|b| b.bank_id == bank_id)
+                .map(|b| b.totals.clone())
+                .unwrap_or_else(|| empty.clone())
+        };
+
+        let mut bank_ids: Vec<(&str, &str)> = Vec::new();
+        for bank in current.banks.iter().chain(previous.banks.iter()) {
+            if !bank_ids.iter().any(|(id, _)| *id == bank.bank_id) {
+                bank_ids.push((&bank.bank_id, &bank.bank_name));
+            }
+        }
+        let bank_changes = bank_ids
+            .into_iter()
+            .map(|(bank_id, bank_name)| BankTotalsChange {
+                bank_id: bank_id.to_string(),
+                bank_name: bank_name.to_string(),
+                change: TotalsChange::between(
+                    &find(&previous.banks, bank_id),
+                    &find(&current.banks, bank_id),
+                ),
+            })
+            .collect();
+
+        Self {
+            compare_to: previous.as_of.clone(),
+            overall: previous.overall.clone(),
+            banks: previous.banks.clone(),
+            overall_change: TotalsChange::between(&previous.overall, &current.overall),
+            bank_changes,
+        }
+    }
 }
 
 impl<'de> Deserialize<'de> for DateRangeQuery {
@@ -281,13 +462,13 @@ impl<'de> Deserialize<'de> for MonthlyTotalsQuery {
 
 #[derive(Deserialize)]
 #[serde(untagged)]
-enum VecOrOne<T> {
+pub(crate) enum VecOrOne<T> {
     Vec(Vec<T>),
     One(T),
 }
 
 impl<T> VecOrOne<T> {
-    fn into_vec(self) -> Vec<T> {
+    pub(crate) fn into_vec(self) -> Vec<T> {
         match self {
             VecOrOne::Vec(vec) => vec,
             VecOrOne::One(item) => vec![item],
@@ -0,0 +1,235 @@
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use utoipa::ToSchema;
+
+#[allow(unused_imports)]
+use serde_json::json;
+
+pub const MAX_QUERY_DIMENSIONS: usize = 3;
+pub const DEFAULT_QUERY_LIMIT: u32 = 500;
+pub const MAX_QUERY_LIMIT: u32 = 5000;
+
+/// What rows of an analytics query are grouped by.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryDimension {
+    Category,
+    Merchant,
+    /// The account id.
+    Account,
+    Institution,
+    /// `YYYY-MM-DD`.
+    Day,
+    /// The Monday starting the ISO week, as `YYYY-MM-DD`.
+    Week,
+    /// `YYYY-MM`.
+    Month,
+    /// `Monday` through `Sunday`.
+    Weekday,
+    /// Transactions with several tags count once per tag; untagged ones fall under `Untagged`.
+    Tag,
+}
+
+impl QueryDimension {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Category => "category",
+            Self::Merchant => "merchant",
+            Self::Account => "account",
+            Self::Institution => "institution",
+            Self::Day => "day",
+            Self::Week => "week",
+            Self::Month => "month",
+            Self::Weekday => "weekday",
+            Self::Tag => "tag",
+        }
+    }
+
+    /// Time dimensions order rows chronologically rather than by measure.
+    pub fn is_time(&self) -> bool {
+        matches!(self, Self::Day | Self::Week | Self::Month | Self::Weekday)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMeasure {
+    Sum,
+    Count,
+    Avg,
+    Median,
+}
+
+impl QueryMeasure {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Sum => "sum",
+            Self::Count => "count",
+            Self::Avg => "avg",
+            Self::Median => "median",
+        }
+    }
+}
+
+/// Restricts a query to money out or money in, using the same rule as the
+/// other analytics endpoints.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryFlow {
+    Spending,
+    Income,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, ToSchema)]
+#[serde(default)]
+pub struct AnalyticsQueryFilters {
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+    pub account_ids: Vec<String>,
+    pub categories: Vec<String>,
+    pub merchants: Vec<String>,
+    /// Keeps transactions carrying any of these tags.
+    pub tags: Vec<String>,
+    #[schema(value_type = Option<String>)]
+    pub min_amount: Option<Decimal>,
+    #[schema(value_type = Option<String>)]
+    pub max_amount: Option<Decimal>,
+    pub flow: Option<QueryFlow>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
+#[schema(example = json!({
+    "group_by": ["month", "category"],
+    "measures": ["sum", "count"],
+    "filters": {
+        "start_date": "2024-01-01",
+        "end_date": "2024-06-30",
+        "flow": "spending"
+    },
+    "limit": 100
+}))]
+pub struct AnalyticsQueryRequest {
+    #[serde(default)]
+    pub group_by: Vec<QueryDimension>,
+    /// Defaults to `sum`.
+    #[serde(default)]
+    pub measures: Vec<QueryMeasure>,
+    #[serde(default)]
+    pub filters: AnalyticsQueryFilters,
+    pub limit: Option<u32>,
+}
+
+impl AnalyticsQueryRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.group_by.len() > MAX_QUERY_DIMENSIONS {
+            return Err(format!(
+                "At most {} group_by dimensions are allowed",
+                MAX_QUERY_DIMENSIONS
+            ));
+        }
+        if let Some(dimension) = first_duplicate(&self.group_by) {
+            return Err(format!("Duplicate dimension: {}", dimension.as_str()));
+        }
+        if let Some(measure) = first_duplicate(&self.measures) {
+            return Err(format!("Duplicate measure: {}", measure.as_str()));
+        }
+        if let (Some(start), Some(end)) = (self.filters.start_date, self.filters.end_date) {
+            if start > end {
+                return Err("start_date must not be after end_date".to_string());
+            }
+        }
+        if let (Some(min), Some(max)) = (self.filters.min_amount, self.filters.max_amount) {
+            if min > max {
+                return Err("min_amount must not be greater than max_amount".to_string());
+            }
+        }
+        if let Some(limit) = self.limit {
+            if limit == 0 || limit > MAX_QUERY_LIMIT {
+                return Err(format!("limit must be between 1 and {}", MAX_QUERY_LIMIT));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn effective_measures(&self) -> Vec<QueryMeasure> {
+        if self.measures.is_empty() {
+            vec![QueryMeasure::Sum]
+        } else {
+            self.measures.clone()
+        }
+    }
+
+    pub fn effective_limit(&self) -> u32 {
+        self.limit.unwrap_or(DEFAULT_QUERY_LIMIT)
+    }
+}
+
+fn first_duplicate<T: PartialEq + Copy>(items: &[T]) -> Option<T> {
+    items
+        .iter()
+        .enumerate()
+        .find(|(i, item)| items[..*i].contains(item))
+        .map(|(_, item)| *item)
+}
+
+/// One group of an analytics query. Amounts are in the user's reporting
+/// currency, converted at the rate on each transaction's date.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
+#[schema(example = json!({
+    "group": {"month": "2024-03", "category": "FOOD_AND_DRINK"},
+    "currency": "USD",
+    "sum": "412.35",
+    "count": 18
+}))]
+pub struct AnalyticsQueryRow {
+    /// Keyed by dimension name.
+    pub group: BTreeMap<String, String>,
+    /// The reporting currency.
+    pub currency: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>)]
+    pub sum: Option<Decimal>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>)]
+    pub avg: Option<Decimal>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>)]
+    pub median: Option<Decimal>,
+}
+
+/// Rates that restate a query's amounts in the reporting currency, one per
+/// currency and day among the matching transactions in other currencies.
+/// Transactions without a rate here are left out of the query.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct QueryRates {
+    pub reporting_currency: String,
+    pub currencies: Vec<String>,
+    pub dates: Vec<NaiveDate>,
+    pub rates: Vec<Decimal>,
+}
+
+impl QueryRates {
+    pub fn new(reporting_currency: &str) -> Self {
+        Self {
+            reporting_currency: reporting_currency.to_string(),
+            ..Self::default()
+        }
+    }
+
+    pub fn push(&mut self, currency: &str, date: NaiveDate, rate: Decimal) {
+        self.currencies.push(currency.to_string());
+        self.dates.push(date);
+        self.rates.push(rate);
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
+pub struct AnalyticsQueryResponse {
+    pub rows: Vec<AnalyticsQueryRow>,
+    /// True when more groups matched than `limit`.
+    pub truncated: bool,
+}
@@ -20,6 +20,24 @@ pub struct AppState {
     pub(crate) connection_service: Arc<ConnectionService>,
     pub(crate) auth_service: Arc<AuthService>,
     pub(crate) provider_registry: Arc<ProviderRegistry>,
+    pub(crate) saved_view_service: Arc<crate::services::SavedViewService>,
+    pub(crate) bulk_edit_service: Arc<crate::services::BulkEditService>,
+    pub(crate) sync_progress_service: Arc<crate::services::SyncProgressService>,
+    pub(crate) webhook_service: Arc<crate::services::WebhookService>,
+    pub(crate) access_token_service: Arc<crate::services::AccessTokenService>,
+    pub(crate) import_service: Arc<crate::services::ImportService>,
+    pub(crate) archive_service: Arc<crate::services::ArchiveService>,
+    pub(crate) export_service: Arc<crate::services::ExportService>,
+    pub(crate) user_settings_service: Arc<crate::services::UserSettingsService>,
+    pub(crate) liability_service: Arc<crate::services::LiabilityService>,
+    pub(crate) investment_service: Arc<crate::services::InvestmentService>,
+    pub(crate) insights_service: Arc<crate::services::InsightsService>,
+    pub(crate) net_worth_service: Arc<crate::services::NetWorthService>,
+    pub(crate) currency_service: Arc<crate::services::CurrencyService>,
+    pub(crate) alert_service: Arc<crate::services::AlertService>,
+    pub(crate) notification_service: Arc<crate::services::NotificationService>,
+    pub(crate) bills_service: Arc<crate::services::BillsService>,
+    pub(crate) forecast_service: Arc<crate::services::ForecastService>,
 }
 
 impl Clone for AppState {
@@ -36,6 +54,24 @@ impl Clone for AppState {
             connection_service: self.connection_service.clone(),
             auth_service: self.auth_service.clone(),
             provider_registry: self.provider_registry.clone(),
+            saved_view_service: self.saved_view_service.clone(),
+            bulk_edit_service: self.bulk_edit_service.clone(),
+            sync_progress_service: self.sync_progress_service.clone(),
+            webhook_service: self.webhook_service.clone(),
+            access_token_service: self.access_token_service.clone(),
+            import_service: self.import_service.clone(),
+            archive_service: self.archive_service.clone(),
+            export_service: self.export_service.clone(),
+            user_settings_service: self.user_settings_service.clone(),
+            liability_service: self.liability_service.clone(),
+            investment_service: self.investment_service.clone(),
+            insights_service: self.insights_service.clone(),
+            net_worth_service: self.net_worth_service.clone(),
+            currency_service: self.currency_service.clone(),
+            alert_service: self.alert_service.clone(),
+            notification_service: self.notification_service.clone(),
+            bills_service: self.bills_service.clone(),
+            forecast_service: self.forecast_service.clone(),
         }
     }
 }
@@ -0,0 +1,107 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::models::{
+    account::Account,
+    alert::AlertRule,
+    budget::{Budget, BudgetAllocation},
+    liability::Liability,
+    notification::NotificationPreference,
+    plaid::ProviderConnection,
+    transaction::Transaction,
+    user_settings::UserSettings,
+};
+
+#[allow(unused_imports)]
+use serde_json::json;
+
+pub const ARCHIVE_FORMAT: &str = "sumurai-archive";
+
+/// Bumped whenever a file is removed or changes meaning; new optional files
+/// keep the version.
+pub const ARCHIVE_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ArchiveManifest {
+    pub format: String,
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    /// Number of records per archive file, keyed by file name.
+    pub counts: BTreeMap<String, usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ArchiveProfile {
+    pub email: String,
+    pub provider: String,
+    pub created_at: DateTime<Utc>,
+    pub onboarding_completed: bool,
+    pub zero_based_budgeting: bool,
+    pub settings: UserSettings,
+}
+
+/// A provider connection without its access token. Restored connections are
+/// disconnected until the user links the institution again.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ArchivedConnection {
+    pub id: Uuid,
+    pub item_id: String,
+    pub institution_id: Option<String>,
+    pub institution_name: Option<String>,
+    pub connected_at: Option<DateTime<Utc>>,
+    pub last_sync_at: Option<DateTime<Utc>>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+impl From<&ProviderConnection> for ArchivedConnection {
+    fn from(connection: &ProviderConnection) -> Self {
+        Self {
+            id: connection.id,
+            item_id: connection.item_id.clone(),
+            institution_id: connection.institution_id.clone(),
+            institution_name: connection.institution_name.clone(),
+            connected_at: connection.connected_at,
+            last_sync_at: connection.last_sync_at,
+            created_at: connection.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TransactionTag {
+    pub transaction_id: Uuid,
+    pub tag: String,
+}
+
+/// Everything a user owns, as stored in an archive.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UserArchive {
+    pub profile: ArchiveProfile,
+    pub connections: Vec<ArchivedConnection>,
+    pub accounts: Vec<Account>,
+    pub transactions: Vec<Transaction>,
+    pub transaction_tags: Vec<TransactionTag>,
+    pub budgets: Vec<Budget>,
+    pub budget_allocations: Vec<BudgetAllocation>,
+    pub liabilities: Vec<Liability>,
+    pub alert_rules: Vec<AlertRule>,
+    pub notification_preferences: Vec<NotificationPreference>,
+    pub dismissed_insights: Vec<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[schema(example = json!({
+    "connections": 1,
+    "accounts": 3,
+    "transactions": 1284,
+    "budgets": 6
+}))]
+pub struct ArchiveRestoreResponse {
+    pub connections: usize,
+    pub accounts: usize,
+    pub transactions: usize,
+    pub budgets: usize,
+}
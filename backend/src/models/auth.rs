@@ -42,6 +42,8 @@ pub struct AuthContext {
 pub struct AuthMiddlewareState {
     pub auth_service: std::sync::Arc<crate::services::AuthService>,
     pub cache_service: std::sync::Arc<dyn crate::services::CacheService>,
+    pub access_token_service: std::sync::Arc<crate::services::AccessTokenService>,
+    pub db_repository: std::sync::Arc<dyn crate::services::repository_service::DatabaseRepository>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
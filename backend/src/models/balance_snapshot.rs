@@ -0,0 +1,15 @@
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// An account's balance as recorded at the end of a sync or by the daily job.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, PartialEq)]
+pub struct BalanceSnapshot {
+    pub account_id: Uuid,
+    pub snapshot_date: NaiveDate,
+    pub balance: Decimal,
+    pub iso_currency_code: String,
+    pub account_type: String,
+}
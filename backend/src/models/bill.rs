@@ -0,0 +1,75 @@
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::models::recurring::RecurrenceCadence;
+
+#[allow(unused_imports)]
+use serde_json::json;
+
+pub const DEFAULT_BILLS_WINDOW_DAYS: u32 = 30;
+pub const MAX_BILLS_WINDOW_DAYS: u32 = 365;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BillSource {
+    Recurring,
+    CreditCardStatement,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
+#[schema(example = json!({
+    "name": "Netflix",
+    "category": "ENTERTAINMENT",
+    "due_date": "2024-02-12",
+    "amount": "15.49",
+    "minimum_payment": null,
+    "source": "recurring",
+    "account_id": "550e8400-e29b-41d4-a716-446655440000",
+    "cadence": "monthly"
+}))]
+pub struct UpcomingBill {
+    pub name: String,
+    pub category: Option<String>,
+    pub due_date: NaiveDate,
+    #[schema(value_type = String)]
+    pub amount: Decimal,
+    #[schema(value_type = Option<String>)]
+    pub minimum_payment: Option<Decimal>,
+    pub source: BillSource,
+    pub account_id: Option<Uuid>,
+    pub cadence: Option<RecurrenceCadence>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
+#[schema(example = json!({
+    "start_date": "2024-02-01",
+    "end_date": "2024-03-02",
+    "bills": [],
+    "total": "0"
+}))]
+pub struct UpcomingBillsResponse {
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub bills: Vec<UpcomingBill>,
+    #[schema(value_type = String)]
+    pub total: Decimal,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpcomingBillsQuery {
+    pub days: Option<u32>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[schema(example = json!({
+    "token": "3f9c0a1e...",
+    "path": "/api/bills/calendar/3f9c0a1e.../feed.ics"
+}))]
+pub struct CalendarFeedResponse {
+    /// Secret token embedded in the feed URL. Only returned when the feed is created or rotated.
+    pub token: String,
+    pub path: String,
+}
@@ -4,6 +4,8 @@ use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 use uuid::Uuid;
 
+use crate::models::saved_view::deserialize_present;
+
 #[allow(unused_imports)]
 use serde_json::json;
 
@@ -13,6 +15,7 @@ use serde_json::json;
     "user_id": "99999999-8888-7777-6666-555555555555",
     "category": "groceries",
     "amount": "500.00",
+    "saved_view_id": null,
     "created_at": "2024-01-01T12:00:00Z",
     "updated_at": "2024-01-15T12:00:00Z"
 }))]
@@ -22,6 +25,10 @@ pub struct Budget {
     pub category: String,
     #[schema(value_type = String)]
     pub amount: Decimal,
+    /// When set, spending counts the transactions this saved view matches
+    /// instead of the budget's category.
+    #[serde(default)]
+    pub saved_view_id: Option<Uuid>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -34,10 +41,16 @@ impl Budget {
             user_id,
             category,
             amount,
+            saved_view_id: None,
             created_at: now,
             updated_at: now,
         }
     }
+
+    pub fn with_saved_view(mut self, saved_view_id: Option<Uuid>) -> Self {
+        self.saved_view_id = saved_view_id;
+        self
+    }
 }
 
 #[derive(Deserialize, ToSchema)]
@@ -46,6 +59,8 @@ pub struct CreateBudgetRequest {
     pub category: String,
     #[schema(value_type = String)]
     pub amount: rust_decimal::Decimal,
+    /// Saved view whose matches the budget tracks instead of its category.
+    pub saved_view_id: Option<Uuid>,
 }
 
 #[derive(Deserialize, ToSchema)]
@@ -53,6 +68,10 @@ pub struct CreateBudgetRequest {
 pub struct UpdateBudgetRequest {
     #[schema(value_type = String)]
     pub amount: rust_decimal::Decimal,
+    /// Omit to keep the linked saved view, `null` to unlink it.
+    #[serde(default, deserialize_with = "deserialize_present")]
+    #[schema(value_type = Option<String>)]
+    pub saved_view_id: Option<Option<Uuid>>,
 }
 
 #[derive(Serialize, ToSchema)]
@@ -61,3 +80,108 @@ pub struct DeleteBudgetResponse {
     pub deleted: bool,
     pub budget_id: String,
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, sqlx::FromRow, ToSchema)]
+#[schema(example = json!({
+    "id": "22222222-3333-4444-5555-666666666666",
+    "user_id": "99999999-8888-7777-6666-555555555555",
+    "month": "2024-01",
+    "category": "FOOD_AND_DRINK",
+    "assigned": "400.00",
+    "created_at": "2024-01-01T12:00:00Z",
+    "updated_at": "2024-01-15T12:00:00Z"
+}))]
+pub struct BudgetAllocation {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub month: String,
+    pub category: String,
+    #[schema(value_type = String)]
+    pub assigned: Decimal,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
+#[schema(example = json!({
+    "category": "FOOD_AND_DRINK",
+    "assigned": "400.00",
+    "activity": "312.45",
+    "available": "87.55"
+}))]
+pub struct ZeroBasedCategory {
+    pub category: String,
+    #[schema(value_type = String)]
+    pub assigned: Decimal,
+    #[schema(value_type = String)]
+    pub activity: Decimal,
+    #[schema(value_type = String)]
+    pub available: Decimal,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
+#[schema(example = json!({
+    "month": "2024-01",
+    "income": "5000.00",
+    "assigned": "4600.00",
+    "activity": "3120.10",
+    "left_to_assign": "400.00",
+    "over_assigned": false,
+    "categories": [
+        {"category": "FOOD_AND_DRINK", "assigned": "400.00", "activity": "312.45", "available": "87.55"}
+    ]
+}))]
+pub struct ZeroBasedBudgetSummary {
+    pub month: String,
+    #[schema(value_type = String)]
+    pub income: Decimal,
+    #[schema(value_type = String)]
+    pub assigned: Decimal,
+    #[schema(value_type = String)]
+    pub activity: Decimal,
+    #[schema(value_type = String)]
+    pub left_to_assign: Decimal,
+    pub over_assigned: bool,
+    pub categories: Vec<ZeroBasedCategory>,
+}
+
+#[derive(Deserialize, ToSchema)]
+#[schema(example = json!({"enabled": true}))]
+pub struct ZeroBasedModeRequest {
+    pub enabled: bool,
+}
+
+#[derive(Serialize, ToSchema)]
+#[schema(example = json!({"enabled": true}))]
+pub struct ZeroBasedModeResponse {
+    pub enabled: bool,
+}
+
+#[derive(Deserialize, ToSchema)]
+#[schema(example = json!({"month": "2024-01", "category": "FOOD_AND_DRINK", "amount": "400.00"}))]
+pub struct AssignBudgetFundsRequest {
+    pub month: String,
+    pub category: String,
+    #[schema(value_type = String)]
+    pub amount: Decimal,
+}
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+#[schema(example = json!({
+    "month": "2024-01",
+    "from_category": "ENTERTAINMENT",
+    "to_category": "FOOD_AND_DRINK",
+    "amount": "50.00"
+}))]
+pub struct MoveBudgetFundsRequest {
+    pub month: String,
+    pub from_category: String,
+    pub to_category: String,
+    #[schema(value_type = String)]
+    pub amount: Decimal,
+}
+
+#[derive(Deserialize)]
+pub struct ZeroBasedBudgetQuery {
+    pub month: Option<String>, // Format: YYYY-MM
+}
@@ -0,0 +1,88 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::models::transaction::{TransactionWithAccount, TransactionsQuery};
+
+#[allow(unused_imports)]
+use serde_json::json;
+
+/// Most transactions one bulk edit may select.
+pub const MAX_BULK_EDIT_TRANSACTIONS: usize = 1000;
+/// Matched transactions returned with a dry run.
+pub const BULK_EDIT_PREVIEW_LIMIT: usize = 50;
+/// Longest tag the `transaction_tags` table stores.
+pub const MAX_TAG_LENGTH: usize = 64;
+
+/// One change applied to every selected transaction, in request order.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum BulkTransactionOperation {
+    /// Replaces the category; the detailed category defaults to the new category.
+    SetCategory {
+        category: String,
+        category_detailed: Option<String>,
+    },
+    AddTag {
+        tag: String,
+    },
+    RemoveTag {
+        tag: String,
+    },
+    SetReviewed {
+        reviewed: bool,
+    },
+    SetExcludedFromAnalytics {
+        excluded: bool,
+    },
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[schema(example = json!({
+    "filter": {"search": "amazon", "account_ids": ["aaaaaaaa-bbbb-cccc-dddd-eeeeeeeeeeee"]},
+    "operations": [
+        {"op": "set_category", "category": "GENERAL_MERCHANDISE", "category_detailed": "Online marketplaces"},
+        {"op": "add_tag", "tag": "household"},
+        {"op": "set_reviewed", "reviewed": true}
+    ],
+    "dry_run": true
+}))]
+pub struct BulkTransactionEditRequest {
+    /// Transactions to edit by id. Give either this or `filter`.
+    pub transaction_ids: Option<Vec<Uuid>>,
+    /// Same `search` and `account_ids` filter as `GET /api/transactions`.
+    #[schema(value_type = Option<Object>)]
+    pub filter: Option<TransactionsQuery>,
+    pub operations: Vec<BulkTransactionOperation>,
+    /// Report what would change without saving anything.
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct BulkOperationResult {
+    pub operation: BulkTransactionOperation,
+    /// Selected transactions this operation changes; ones already in the requested
+    /// state are not counted.
+    pub affected: u64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[schema(example = json!({
+    "dry_run": true,
+    "matched": 182,
+    "results": [
+        {"operation": {"op": "set_category", "category": "GENERAL_MERCHANDISE", "category_detailed": "Online marketplaces"}, "affected": 176},
+        {"operation": {"op": "add_tag", "tag": "household"}, "affected": 182},
+        {"operation": {"op": "set_reviewed", "reviewed": true}, "affected": 182}
+    ],
+    "preview": []
+}))]
+pub struct BulkTransactionEditResponse {
+    pub dry_run: bool,
+    /// Transactions the selection matched.
+    pub matched: usize,
+    pub results: Vec<BulkOperationResult>,
+    /// The first matched transactions as they are now; only filled for dry runs.
+    pub preview: Vec<TransactionWithAccount>,
+}
@@ -0,0 +1,167 @@
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::de::{self, Deserializer, IgnoredAny, MapAccess, Visitor};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use utoipa::ToSchema;
+
+use crate::models::analytics::VecOrOne;
+
+#[allow(unused_imports)]
+use serde_json::json;
+
+/// Which earlier period a preset is compared against.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ComparisonBaseline {
+    /// The period of equal length immediately before.
+    #[default]
+    PreviousPeriod,
+    /// The same dates one year earlier.
+    YearAgo,
+}
+
+impl ComparisonBaseline {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "previous_period" => Some(Self::PreviousPeriod),
+            "year_ago" => Some(Self::YearAgo),
+            _ => None,
+        }
+    }
+}
+
+pub struct PeriodComparisonQuery {
+    pub period: Option<String>,
+    pub baseline: Option<String>,
+    pub current_start: Option<String>,
+    pub current_end: Option<String>,
+    pub previous_start: Option<String>,
+    pub previous_end: Option<String>,
+    pub account_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[schema(example = json!({"start_date": "2024-03-01", "end_date": "2024-03-31"}))]
+pub struct PeriodRange {
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+}
+
+/// Spending for one category or merchant in both periods.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
+#[schema(example = json!({
+    "name": "Groceries",
+    "current": "640.00",
+    "previous": "512.00",
+    "change": "128.00",
+    "change_percent": "25.0"
+}))]
+pub struct ComparisonLine {
+    pub name: String,
+    #[schema(value_type = String)]
+    pub current: Decimal,
+    #[schema(value_type = String)]
+    pub previous: Decimal,
+    #[schema(value_type = String)]
+    pub change: Decimal,
+    /// `None` when there was no spending in the previous period.
+    #[schema(value_type = Option<String>)]
+    pub change_percent: Option<Decimal>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
+#[schema(example = json!({
+    "current_period": {"start_date": "2024-03-01", "end_date": "2024-03-31"},
+    "previous_period": {"start_date": "2024-02-01", "end_date": "2024-02-29"},
+    "total": {
+        "name": "Total",
+        "current": "2140.00",
+        "previous": "1980.00",
+        "change": "160.00",
+        "change_percent": "8.1"
+    },
+    "categories": [{
+        "name": "Groceries",
+        "current": "640.00",
+        "previous": "512.00",
+        "change": "128.00",
+        "change_percent": "25.0"
+    }],
+    "merchants": [{
+        "name": "Whole Foods",
+        "current": "410.00",
+        "previous": "300.00",
+        "change": "110.00",
+        "change_percent": "36.7"
+    }]
+}))]
+pub struct PeriodComparisonResponse {
+    pub current_period: PeriodRange,
+    pub previous_period: PeriodRange,
+    pub total: ComparisonLine,
+    /// Sorted by the size of the change, largest movers first.
+    pub categories: Vec<ComparisonLine>,
+    /// Sorted by the size of the change, largest movers first.
+    pub merchants: Vec<ComparisonLine>,
+}
+
+impl<'de> Deserialize<'de> for PeriodComparisonQuery {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct PeriodComparisonVisitor;
+
+        impl<'de> Visitor<'de> for PeriodComparisonVisitor {
+            type Value = PeriodComparisonQuery;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("period comparison query parameters")
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                let mut query = PeriodComparisonQuery {
+                    period: None,
+                    baseline: None,
+                    current_start: None,
+                    current_end: None,
+                    previous_start: None,
+                    previous_end: None,
+                    account_ids: Vec::new(),
+                };
+
+                while let Some(key) = map.next_key::<String>()? {
+                    let field = match key.as_str() {
+                        "period" => &mut query.period,
+                        "baseline" => &mut query.baseline,
+                        "current_start" => &mut query.current_start,
+                        "current_end" => &mut query.current_end,
+                        "previous_start" => &mut query.previous_start,
+                        "previous_end" => &mut query.previous_end,
+                        "account_ids" | "account_ids[]" | "account_ids%5B%5D" => {
+                            let values: VecOrOne<String> = map.next_value()?;
+                            query.account_ids.extend(values.into_vec());
+                            continue;
+                        }
+                        _ => {
+                            map.next_value::<IgnoredAny>()?;
+                            continue;
+                        }
+                    };
+                    if field.is_some() {
+                        return Err(de::Error::custom(format!("duplicate field `{}`", key)));
+                    }
+                    *field = map.next_value()?;
+                }
+
+                Ok(query)
+            }
+        }
+
+        deserializer.deserialize_map(PeriodComparisonVisitor)
+    }
+}
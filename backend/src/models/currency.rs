@@ -0,0 +1,39 @@
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+
+#[allow(unused_imports)]
+use serde_json::json;
+
+/// Currency assumed for rows synced before providers reported one, and the
+/// reporting currency for users who have not picked their own.
+pub const DEFAULT_CURRENCY: &str = "USD";
+
+pub fn default_currency() -> String {
+    DEFAULT_CURRENCY.to_string()
+}
+
+/// Upper-cases a three-letter ISO 4217 code, rejecting anything else.
+pub fn normalize_currency_code(code: &str) -> Option<String> {
+    let code = code.trim();
+    (code.len() == 3 && code.chars().all(|c| c.is_ascii_alphabetic()))
+        .then(|| code.to_ascii_uppercase())
+}
+
+/// One unit of `base_currency` buys `rate` units of `quote_currency` on `rate_date`.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, PartialEq, ToSchema)]
+#[schema(example = json!({
+    "base_currency": "EUR",
+    "quote_currency": "USD",
+    "rate_date": "2024-03-01",
+    "rate": "1.0842"
+}))]
+pub struct FxRate {
+    pub base_currency: String,
+    pub quote_currency: String,
+    pub rate_date: NaiveDate,
+    #[schema(value_type = String)]
+    pub rate: Decimal,
+}
@@ -0,0 +1,190 @@
+use chrono::NaiveDate;
+use serde::Deserialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::models::transaction::TransactionWithAccount;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Csv,
+    Ofx,
+    Qif,
+    Json,
+}
+
+impl ExportFormat {
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "text/csv; charset=utf-8",
+            ExportFormat::Ofx => "application/x-ofx",
+            ExportFormat::Qif => "application/qif",
+            ExportFormat::Json => "application/json",
+        }
+    }
+
+    pub fn file_extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Ofx => "ofx",
+            ExportFormat::Qif => "qif",
+            ExportFormat::Json => "json",
+        }
+    }
+}
+
+/// Export options; the transaction filters are read from the same query string
+/// through `TransactionsQuery`.
+#[derive(Debug, Deserialize)]
+pub struct TransactionExportQuery {
+    pub format: ExportFormat,
+    /// Comma-separated CSV columns, in output order.
+    pub columns: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportColumn {
+    Id,
+    Date,
+    Amount,
+    Currency,
+    Merchant,
+    Category,
+    CategoryDetailed,
+    AccountId,
+    Account,
+    AccountType,
+    AccountMask,
+    PaymentChannel,
+    Pending,
+}
+
+impl ExportColumn {
+    pub const ALL: [ExportColumn; 13] = [
+        ExportColumn::Id,
+        ExportColumn::Date,
+        ExportColumn::Amount,
+        ExportColumn::Currency,
+        ExportColumn::Merchant,
+        ExportColumn::Category,
+        ExportColumn::CategoryDetailed,
+        ExportColumn::AccountId,
+        ExportColumn::Account,
+        ExportColumn::AccountType,
+        ExportColumn::AccountMask,
+        ExportColumn::PaymentChannel,
+        ExportColumn::Pending,
+    ];
+
+    pub const DEFAULT: [ExportColumn; 7] = [
+        ExportColumn::Date,
+        ExportColumn::Amount,
+        ExportColumn::Currency,
+        ExportColumn::Merchant,
+        ExportColumn::Category,
+        ExportColumn::Account,
+        ExportColumn::Pending,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ExportColumn::Id => "id",
+            ExportColumn::Date => "date",
+            ExportColumn::Amount => "amount",
+            ExportColumn::Currency => "currency",
+            ExportColumn::Merchant => "merchant",
+            ExportColumn::Category => "category",
+            ExportColumn::CategoryDetailed => "category_detailed",
+            ExportColumn::AccountId => "account_id",
+            ExportColumn::Account => "account",
+            ExportColumn::AccountType => "account_type",
+            ExportColumn::AccountMask => "account_mask",
+            ExportColumn::PaymentChannel => "payment_channel",
+            ExportColumn::Pending => "pending",
+        }
+    }
+
+    /// Parses a comma-separated column list, falling back to the default
+    /// columns when none are given.
+    pub fn parse_list(columns: Option<&str>) -> Result<Vec<ExportColumn>, String> {
+        let Some(columns) = columns.map(str::trim).filter(|c| !c.is_empty()) else {
+            return Ok(Self::DEFAULT.to_vec());
+        };
+
+        columns
+            .split(',')
+            .map(str::trim)
+            .map(|name| {
+                Self::ALL
+                    .into_iter()
+                    .find(|column| column.name().eq_ignore_ascii_case(name))
+                    .ok_or_else(|| format!("Invalid export column: {}", name))
+            })
+            .collect()
+    }
+
+    pub fn value(&self, transaction: &TransactionWithAccount) -> String {
+        match self {
+            ExportColumn::Id => transaction.id.to_string(),
+            ExportColumn::Date => transaction.date.to_string(),
+            ExportColumn::Amount => transaction.amount.to_string(),
+            ExportColumn::Currency => transaction.iso_currency_code.clone(),
+            ExportColumn::Merchant => transaction.merchant_name.clone().unwrap_or_default(),
+            ExportColumn::Category => transaction.category_primary.clone(),
+            ExportColumn::CategoryDetailed => transaction.category_detailed.clone(),
+            ExportColumn::AccountId => transaction.account_id.to_string(),
+            ExportColumn::Account => transaction.account_name.clone(),
+            ExportColumn::AccountType => transaction.account_type.clone(),
+            ExportColumn::AccountMask => transaction.account_mask.clone().unwrap_or_default(),
+            ExportColumn::PaymentChannel => transaction.payment_channel.clone().unwrap_or_default(),
+            ExportColumn::Pending => transaction.pending.to_string(),
+        }
+    }
+}
+
+/// Keyset position of the last exported transaction. Exports walk transactions
+/// grouped by account type and account, oldest first within each account.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransactionPageCursor {
+    pub account_type: String,
+    pub account_id: Uuid,
+    pub date: NaiveDate,
+    pub id: Uuid,
+}
+
+impl From<&TransactionWithAccount> for TransactionPageCursor {
+    fn from(transaction: &TransactionWithAccount) -> Self {
+        Self {
+            account_type: transaction.account_type.clone(),
+            account_id: transaction.account_id,
+            date: transaction.date,
+            id: transaction.id,
+        }
+    }
+}
+
+/// Plain-text accounting dialects. hledger reads Ledger journals, so the two
+/// only differ in file extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum JournalFormat {
+    Beancount,
+    Hledger,
+    Ledger,
+}
+
+impl JournalFormat {
+    pub fn file_extension(&self) -> &'static str {
+        match self {
+            JournalFormat::Beancount => "beancount",
+            JournalFormat::Hledger => "journal",
+            JournalFormat::Ledger => "ledger",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct JournalExportQuery {
+    pub format: JournalFormat,
+}
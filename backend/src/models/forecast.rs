@@ -0,0 +1,148 @@
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::de::{self, Deserializer, IgnoredAny, MapAccess, Visitor};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::models::analytics::VecOrOne;
+use crate::models::recurring::RecurringSeries;
+
+#[allow(unused_imports)]
+use serde_json::json;
+
+pub const DEFAULT_FORECAST_HORIZON_DAYS: u32 = 30;
+pub const MIN_FORECAST_HORIZON_DAYS: u32 = 30;
+pub const MAX_FORECAST_HORIZON_DAYS: u32 = 90;
+
+pub struct CashFlowForecastQuery {
+    pub horizon_days: Option<u32>,
+    pub low_balance_threshold: Option<Decimal>,
+    pub account_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
+#[schema(example = json!({
+    "date": "2024-02-01",
+    "balance": "1820.55",
+    "lower": "1640.10",
+    "upper": "2001.00",
+    "inflows": "2500.00",
+    "outflows": "1435.20",
+    "low_balance": false
+}))]
+pub struct ForecastPoint {
+    pub date: NaiveDate,
+    #[schema(value_type = String)]
+    pub balance: Decimal,
+    #[schema(value_type = String)]
+    pub lower: Decimal,
+    #[schema(value_type = String)]
+    pub upper: Decimal,
+    #[schema(value_type = String)]
+    pub inflows: Decimal,
+    #[schema(value_type = String)]
+    pub outflows: Decimal,
+    pub low_balance: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
+#[schema(example = json!({"category": "FOOD_AND_DRINK", "daily_average": "18.40"}))]
+pub struct DiscretionarySpendRate {
+    pub category: String,
+    #[schema(value_type = String)]
+    pub daily_average: Decimal,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
+#[schema(example = json!({
+    "start_date": "2024-01-31",
+    "horizon_days": 30,
+    "account_ids": ["550e8400-e29b-41d4-a716-446655440000"],
+    "starting_balance": "2150.00",
+    "low_balance_threshold": "0",
+    "series": [{
+        "date": "2024-02-01",
+        "balance": "1820.55",
+        "lower": "1640.10",
+        "upper": "2001.00",
+        "inflows": "0",
+        "outflows": "329.45",
+        "low_balance": false
+    }],
+    "low_balance_dates": [],
+    "recurring": [],
+    "discretionary": [{"category": "FOOD_AND_DRINK", "daily_average": "18.40"}]
+}))]
+pub struct CashFlowForecastResponse {
+    pub start_date: NaiveDate,
+    pub horizon_days: u32,
+    pub account_ids: Vec<Uuid>,
+    #[schema(value_type = String)]
+    pub starting_balance: Decimal,
+    #[schema(value_type = String)]
+    pub low_balance_threshold: Decimal,
+    pub series: Vec<ForecastPoint>,
+    pub low_balance_dates: Vec<NaiveDate>,
+    pub recurring: Vec<RecurringSeries>,
+    pub discretionary: Vec<DiscretionarySpendRate>,
+}
+
+impl<'de> Deserialize<'de> for CashFlowForecastQuery {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct CashFlowForecastVisitor;
+
+        impl<'de> Visitor<'de> for CashFlowForecastVisitor {
+            type Value = CashFlowForecastQuery;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("cash flow forecast query parameters")
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                let mut horizon_days: Option<Option<u32>> = None;
+                let mut low_balance_threshold: Option<Option<Decimal>> = None;
+                let mut account_ids: Vec<String> = Vec::new();
+
+                while let Some(key) = map.next_key::<String>()? {
+                    match key.as_str() {
+                        "horizon_days" => {
+                            if horizon_days.is_some() {
+                                return Err(de::Error::duplicate_field("horizon_days"));
+                            }
+                            horizon_days = Some(map.next_value()?);
+                        }
+                        "low_balance_threshold" => {
+                            if low_balance_threshold.is_some() {
+                                return Err(de::Error::duplicate_field("low_balance_threshold"));
+                            }
+                            low_balance_threshold = Some(map.next_value()?);
+                        }
+                        "account_ids" | "account_ids[]" | "account_ids%5B%5D" => {
+                            let values: VecOrOne<String> = map.next_value()?;
+                            account_ids.extend(values.into_vec());
+                        }
+                        _ => {
+                            map.next_value::<IgnoredAny>()?;
+                        }
+                    }
+                }
+
+                Ok(CashFlowForecastQuery {
+                    horizon_days: horizon_days.unwrap_or(None),
+                    low_balance_threshold: low_balance_threshold.unwrap_or(None),
+                    account_ids,
+                })
+            }
+        }
+
+        deserializer.deserialize_map(CashFlowForecastVisitor)
+    }
+}
@@ -0,0 +1,128 @@
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[allow(unused_imports)]
+use serde_json::json;
+
+/// Personal-finance apps whose exports can be imported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportSource {
+    /// Mint `transactions.csv`.
+    Mint,
+    /// YNAB register CSV, optionally with the budget CSV from the same export.
+    Ynab,
+    /// Actual Budget transaction CSV export.
+    Actual,
+}
+
+impl ImportSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ImportSource::Mint => "mint",
+            ImportSource::Ynab => "ynab",
+            ImportSource::Actual => "actual",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+#[schema(example = json!({
+    "source": "ynab",
+    "transactions_csv": "\"Account\",\"Flag\",\"Date\",\"Payee\",\"Category Group/Category\",\"Category Group\",\"Category\",\"Memo\",\"Outflow\",\"Inflow\",\"Cleared\"\n\"Checking\",\"\",\"01/15/2024\",\"Cafe\",\"Food: Dining Out\",\"Food\",\"Dining Out\",\"\",\"$4.50\",\"$0.00\",\"Cleared\"",
+    "budget_csv": null,
+    "category_mappings": {"Dining Out": "FOOD_AND_DRINK"}
+}))]
+pub struct ImportRequest {
+    pub source: ImportSource,
+    /// The transactions or register file exactly as exported.
+    pub transactions_csv: String,
+    /// YNAB budget file; budgets are only imported from this file.
+    pub budget_csv: Option<String>,
+    /// Source category name to Sumurai category, taking precedence over the
+    /// built-in mapping.
+    #[serde(default)]
+    pub category_mappings: HashMap<String, String>,
+}
+
+/// One transaction read from an export, before it is assigned to an account.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedTransaction {
+    pub account_name: String,
+    pub date: NaiveDate,
+    pub payee: Option<String>,
+    /// Positive for spending, like provider transactions.
+    pub amount: Decimal,
+    pub category: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedBudget {
+    pub category: String,
+    pub amount: Decimal,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ImportAccountPreview {
+    pub name: String,
+    pub account_type: String,
+    pub transaction_count: usize,
+    /// Existing account with the same name that transactions will be added to.
+    pub existing_account_id: Option<Uuid>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ImportCategoryMapping {
+    pub source_category: String,
+    /// `None` when neither the request nor the built-in mapping covers it.
+    pub category: Option<String>,
+    pub transaction_count: usize,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ImportBudgetPreview {
+    pub category: String,
+    #[schema(value_type = String)]
+    pub amount: Decimal,
+    /// Already budgeted; the import leaves the existing budget alone.
+    pub exists: bool,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[schema(example = json!({
+    "source": "ynab",
+    "transaction_count": 1,
+    "first_date": "2024-01-15",
+    "last_date": "2024-01-15",
+    "accounts": [{"name": "Checking", "account_type": "depository", "transaction_count": 1, "existing_account_id": null}],
+    "categories": [{"source_category": "Dining Out", "category": "FOOD_AND_DRINK", "transaction_count": 1}],
+    "unmapped_categories": [],
+    "budgets": []
+}))]
+pub struct ImportPreview {
+    pub source: ImportSource,
+    pub transaction_count: usize,
+    pub first_date: Option<NaiveDate>,
+    pub last_date: Option<NaiveDate>,
+    pub accounts: Vec<ImportAccountPreview>,
+    pub categories: Vec<ImportCategoryMapping>,
+    /// Source categories that must be mapped before the import can be committed.
+    pub unmapped_categories: Vec<String>,
+    pub budgets: Vec<ImportBudgetPreview>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[schema(example = json!({
+    "accounts_created": 1,
+    "transactions_imported": 1284,
+    "budgets_created": 6
+}))]
+pub struct ImportResult {
+    pub accounts_created: usize,
+    pub transactions_imported: usize,
+    pub budgets_created: usize,
+}
@@ -0,0 +1,82 @@
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[allow(unused_imports)]
+use serde_json::json;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum InsightKind {
+    /// A category this month is well above its trailing monthly median.
+    CategorySpike,
+    /// A merchant charged far more than it usually does.
+    UnusualAmount,
+    /// The same charge posted more than once on the same day.
+    DuplicateCharge,
+    /// A large first charge from a merchant not seen before.
+    NewMerchant,
+}
+
+impl InsightKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::CategorySpike => "category_spike",
+            Self::UnusualAmount => "unusual_amount",
+            Self::DuplicateCharge => "duplicate_charge",
+            Self::NewMerchant => "new_merchant",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        [
+            Self::CategorySpike,
+            Self::UnusualAmount,
+            Self::DuplicateCharge,
+            Self::NewMerchant,
+        ]
+        .into_iter()
+        .find(|k| k.as_str() == value)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
+#[schema(example = json!({
+    "id": "duplicate_charge:3f0c9a4e-1b2d-4c5e-8f90-0a1b2c3d4e5f",
+    "kind": "duplicate_charge",
+    "title": "Possible duplicate charge at Netflix",
+    "explanation": "Netflix charged 15.49 2 times on 2024-03-04.",
+    "date": "2024-03-04",
+    "amount": "15.49",
+    "category": "ENTERTAINMENT",
+    "merchant": "Netflix",
+    "transaction_ids": [
+        "3f0c9a4e-1b2d-4c5e-8f90-0a1b2c3d4e5f",
+        "7a8b9c0d-1e2f-4a5b-8c6d-7e8f9a0b1c2d"
+    ]
+}))]
+pub struct Insight {
+    /// Stable across requests; pass it back to dismiss the insight.
+    pub id: String,
+    pub kind: InsightKind,
+    pub title: String,
+    pub explanation: String,
+    pub date: NaiveDate,
+    /// The amount that triggered the insight, in the reporting currency.
+    #[schema(value_type = String)]
+    pub amount: Decimal,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub merchant: Option<String>,
+    pub transaction_ids: Vec<Uuid>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
+pub struct InsightsResponse {
+    /// Newest first.
+    pub insights: Vec<Insight>,
+    pub currency: String,
+}
@@ -0,0 +1,183 @@
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[allow(unused_imports)]
+use serde_json::json;
+
+/// Broad grouping of securities used for allocation breakdowns.
+#[derive(
+    Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord, ToSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum AssetClass {
+    Equity,
+    Fund,
+    FixedIncome,
+    Cash,
+    Crypto,
+    Other,
+}
+
+impl AssetClass {
+    /// Maps a provider security type (Plaid's `type`) onto an asset class.
+    pub fn from_security_type(security_type: &str) -> Self {
+        match security_type.trim().to_lowercase().as_str() {
+            "equity" => Self::Equity,
+            "etf" | "mutual fund" => Self::Fund,
+            "fixed income" => Self::FixedIncome,
+            "cash" => Self::Cash,
+            "cryptocurrency" => Self::Crypto,
+            _ => Self::Other,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, PartialEq)]
+pub struct Security {
+    pub id: Uuid,
+    pub provider_security_id: String,
+    pub name: String,
+    pub ticker_symbol: Option<String>,
+    pub security_type: String,
+    pub iso_currency_code: String,
+    pub close_price: Option<Decimal>,
+}
+
+/// A position in one security within one investment account, as of the last sync.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, PartialEq)]
+pub struct Holding {
+    pub account_id: Uuid,
+    pub security_id: Uuid,
+    pub quantity: Decimal,
+    pub institution_price: Decimal,
+    pub institution_value: Decimal,
+    pub cost_basis: Option<Decimal>,
+    pub iso_currency_code: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, PartialEq, ToSchema)]
+#[schema(example = json!({
+    "id": "9b2f8c1e-4d3a-4f5b-8c6d-7e8f9a0b1c2d",
+    "account_id": "3f0c9a4e-1b2d-4c5e-8f90-0a1b2c3d4e5f",
+    "security_id": "7a8b9c0d-1e2f-4a5b-8c6d-7e8f9a0b1c2d",
+    "date": "2024-03-04",
+    "name": "BUY Vanguard Total Stock Market ETF",
+    "transaction_type": "buy",
+    "subtype": "buy",
+    "quantity": "2.5",
+    "price": "240.10",
+    "amount": "600.25",
+    "fees": "0.00",
+    "iso_currency_code": "USD"
+}))]
+pub struct InvestmentTransaction {
+    pub id: Uuid,
+    pub account_id: Uuid,
+    pub security_id: Option<Uuid>,
+    #[serde(skip)]
+    pub provider_investment_transaction_id: String,
+    pub date: NaiveDate,
+    pub name: String,
+    /// Provider transaction type such as `buy`, `sell`, `cash` or `fee`.
+    pub transaction_type: String,
+    pub subtype: Option<String>,
+    #[schema(value_type = String)]
+    pub quantity: Decimal,
+    #[schema(value_type = String)]
+    pub price: Decimal,
+    /// Positive when cash leaves the account, as reported by Plaid.
+    #[schema(value_type = String)]
+    pub amount: Decimal,
+    #[schema(value_type = Option<String>)]
+    pub fees: Option<Decimal>,
+    pub iso_currency_code: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
+#[schema(example = json!({
+    "account_id": "3f0c9a4e-1b2d-4c5e-8f90-0a1b2c3d4e5f",
+    "account_name": "Brokerage",
+    "security_id": "7a8b9c0d-1e2f-4a5b-8c6d-7e8f9a0b1c2d",
+    "name": "Vanguard Total Stock Market ETF",
+    "ticker_symbol": "VTI",
+    "asset_class": "fund",
+    "quantity": "10",
+    "price": "250.00",
+    "market_value": "2500.00",
+    "cost_basis": "2000.00",
+    "unrealized_gain": "500.00",
+    "unrealized_gain_percent": "25.00",
+    "iso_currency_code": "USD"
+}))]
+pub struct HoldingView {
+    pub account_id: Uuid,
+    pub account_name: String,
+    pub security_id: Uuid,
+    pub name: String,
+    pub ticker_symbol: Option<String>,
+    pub asset_class: AssetClass,
+    #[schema(value_type = String)]
+    pub quantity: Decimal,
+    #[schema(value_type = String)]
+    pub price: Decimal,
+    #[schema(value_type = String)]
+    pub market_value: Decimal,
+    /// Total purchase cost; absent when the institution does not report it.
+    #[schema(value_type = Option<String>)]
+    pub cost_basis: Option<Decimal>,
+    #[schema(value_type = Option<String>)]
+    pub unrealized_gain: Option<Decimal>,
+    /// Unrealized gain as a percentage of cost basis.
+    #[schema(value_type = Option<String>)]
+    pub unrealized_gain_percent: Option<Decimal>,
+    /// Currency of this holding's own figures.
+    pub iso_currency_code: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
+pub struct HoldingsResponse {
+    /// Largest positions first, each in its own currency.
+    pub holdings: Vec<HoldingView>,
+    /// Totals below are in this reporting currency.
+    pub currency: String,
+    #[schema(value_type = String)]
+    pub total_market_value: Decimal,
+    /// Sum over holdings with a known cost basis.
+    #[schema(value_type = String)]
+    pub total_cost_basis: Decimal,
+    #[schema(value_type = String)]
+    pub total_unrealized_gain: Decimal,
+    /// Some holdings were left out of the totals because no FX rate into
+    /// `currency` was known.
+    pub mixed_currency: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
+#[schema(example = json!({
+    "asset_class": "equity",
+    "market_value": "7500.00",
+    "percentage": "75.00",
+    "holding_count": 4
+}))]
+pub struct AssetAllocation {
+    pub asset_class: AssetClass,
+    #[schema(value_type = String)]
+    pub market_value: Decimal,
+    #[schema(value_type = String)]
+    pub percentage: Decimal,
+    pub holding_count: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
+pub struct AllocationResponse {
+    /// Largest asset class first.
+    pub allocations: Vec<AssetAllocation>,
+    #[schema(value_type = String)]
+    pub total_market_value: Decimal,
+    pub currency: String,
+    pub mixed_currency: bool,
+}
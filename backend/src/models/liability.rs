@@ -0,0 +1,203 @@
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[allow(unused_imports)]
+use serde_json::json;
+
+/// Upper bound on simulated months; plans that run longer are rejected.
+pub const MAX_PAYOFF_MONTHS: u32 = 600;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LiabilitySource {
+    /// Synced from the account's provider; refreshed on every sync.
+    Provider,
+    /// Entered by the user; never overwritten by a sync.
+    Manual,
+}
+
+impl LiabilitySource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Provider => "provider",
+            Self::Manual => "manual",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, PartialEq)]
+pub struct Liability {
+    pub account_id: Uuid,
+    /// `credit`, `mortgage`, `student` or any other loan type entered by hand.
+    pub liability_type: String,
+    pub apr: Option<Decimal>,
+    pub minimum_payment: Option<Decimal>,
+    pub statement_balance: Option<Decimal>,
+    pub next_payment_due_date: Option<NaiveDate>,
+    pub loan_term_months: Option<i32>,
+    pub maturity_date: Option<NaiveDate>,
+    pub source: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
+#[schema(example = json!({
+    "account_id": "3f0c9a4e-1b2d-4c5e-8f90-0a1b2c3d4e5f",
+    "account_name": "Rewards Visa",
+    "account_type": "credit",
+    "institution_name": "Demo Bank",
+    "balance": "2450.00",
+    "iso_currency_code": "USD",
+    "liability_type": "credit",
+    "apr": "22.99",
+    "minimum_payment": "75.00",
+    "statement_balance": "2300.00",
+    "next_payment_due_date": "2024-04-12",
+    "loan_term_months": null,
+    "maturity_date": null,
+    "source": "provider"
+}))]
+pub struct LiabilityView {
+    pub account_id: Uuid,
+    pub account_name: String,
+    pub account_type: String,
+    pub institution_name: Option<String>,
+    /// Amount owed, as a positive number in the account's currency.
+    #[schema(value_type = String)]
+    pub balance: Decimal,
+    pub iso_currency_code: String,
+    pub liability_type: Option<String>,
+    /// Annual percentage rate, e.g. `22.99`.
+    #[schema(value_type = Option<String>)]
+    pub apr: Option<Decimal>,
+    #[schema(value_type = Option<String>)]
+    pub minimum_payment: Option<Decimal>,
+    #[schema(value_type = Option<String>)]
+    pub statement_balance: Option<Decimal>,
+    pub next_payment_due_date: Option<NaiveDate>,
+    pub loan_term_months: Option<i32>,
+    pub maturity_date: Option<NaiveDate>,
+    /// Absent when no details are known for the account yet.
+    pub source: Option<LiabilitySource>,
+}
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+#[schema(example = json!({
+    "liability_type": "auto",
+    "apr": "6.49",
+    "minimum_payment": "410.00",
+    "next_payment_due_date": "2024-04-01",
+    "loan_term_months": 60
+}))]
+pub struct UpdateLiabilityRequest {
+    /// Defaults to `credit` for credit accounts and `loan` otherwise.
+    pub liability_type: Option<String>,
+    #[schema(value_type = Option<String>)]
+    pub apr: Option<Decimal>,
+    #[schema(value_type = Option<String>)]
+    pub minimum_payment: Option<Decimal>,
+    #[schema(value_type = Option<String>)]
+    pub statement_balance: Option<Decimal>,
+    pub next_payment_due_date: Option<NaiveDate>,
+    pub loan_term_months: Option<i32>,
+    pub maturity_date: Option<NaiveDate>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PayoffStrategy {
+    /// Extra payments go to the highest APR first.
+    Avalanche,
+    /// Extra payments go to the smallest balance first.
+    Snowball,
+}
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+#[schema(example = json!({
+    "extra_monthly_payment": "200.00",
+    "account_ids": []
+}))]
+pub struct PayoffPlanRequest {
+    /// Paid on top of every minimum payment each month.
+    #[schema(value_type = String)]
+    pub extra_monthly_payment: Decimal,
+    /// Debts to include; all credit and loan accounts when empty.
+    #[serde(default)]
+    pub account_ids: Vec<String>,
+}
+
+/// One debt as the planner sees it, in the reporting currency.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PayoffDebt {
+    pub account_id: Uuid,
+    pub name: String,
+    pub balance: Decimal,
+    pub apr: Decimal,
+    pub minimum_payment: Decimal,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
+pub struct DebtPayment {
+    pub account_id: Uuid,
+    #[schema(value_type = String)]
+    pub payment: Decimal,
+    #[schema(value_type = String)]
+    pub interest: Decimal,
+    /// Balance left after this month's payment.
+    #[schema(value_type = String)]
+    pub balance: Decimal,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
+pub struct PayoffMonth {
+    /// 1 for the first month of the plan.
+    pub month: u32,
+    pub date: NaiveDate,
+    pub payments: Vec<DebtPayment>,
+    #[schema(value_type = String)]
+    pub total_payment: Decimal,
+    #[schema(value_type = String)]
+    pub total_interest: Decimal,
+    #[schema(value_type = String)]
+    pub remaining_balance: Decimal,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
+pub struct DebtPayoffSummary {
+    pub account_id: Uuid,
+    pub name: String,
+    pub paid_off_date: NaiveDate,
+    #[schema(value_type = String)]
+    pub interest_paid: Decimal,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
+pub struct PayoffPlan {
+    pub strategy: PayoffStrategy,
+    /// Debts in the order they are paid off.
+    pub debts: Vec<DebtPayoffSummary>,
+    pub schedule: Vec<PayoffMonth>,
+    #[schema(value_type = String)]
+    pub total_interest: Decimal,
+    #[schema(value_type = String)]
+    pub total_paid: Decimal,
+    /// Absent when there is nothing to pay off.
+    pub debt_free_date: Option<NaiveDate>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
+pub struct PayoffPlanResponse {
+    /// Every amount in the plans is in this reporting currency.
+    pub currency: String,
+    /// Sum of minimum payments plus the extra payment, paid every month.
+    #[schema(value_type = String)]
+    pub monthly_payment: Decimal,
+    pub plans: Vec<PayoffPlan>,
+    /// Defaults the planner had to fill in, such as a missing APR.
+    pub assumptions: Vec<String>,
+    /// Some debts were left out because no FX rate into `currency` was known.
+    pub mixed_currency: bool,
+}
@@ -1,10 +1,32 @@
+pub mod access_token;
 pub mod account;
+pub mod alert;
 pub mod analytics;
+pub mod analytics_query;
 pub mod api_error;
 pub mod app_state;
+pub mod archive;
 pub mod auth;
+pub mod balance_snapshot;
+pub mod bill;
 pub mod budget;
+pub mod bulk_edit;
 pub mod cache;
+pub mod comparison;
+pub mod currency;
+pub mod export;
+pub mod forecast;
+pub mod import;
+pub mod insight;
+pub mod investment;
+pub mod liability;
+pub mod notification;
 pub mod plaid;
 pub mod query;
+pub mod recurring;
+pub mod saved_view;
+pub mod sync_progress;
 pub mod transaction;
+pub mod transaction_search;
+pub mod user_settings;
+pub mod webhook;
@@ -0,0 +1,113 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[allow(unused_imports)]
+use serde_json::json;
+
+/// Channel name for the persisted in-app feed. Always available; external channels
+/// are registered by name with the notification service.
+pub const IN_APP_CHANNEL: &str = "in_app";
+
+pub const DEFAULT_NOTIFICATION_LIMIT: i64 = 50;
+pub const MAX_NOTIFICATION_LIMIT: i64 = 200;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationEventType {
+    SyncFailure,
+    BudgetThreshold,
+    LargeTransaction,
+    LowBalance,
+    NewMerchant,
+}
+
+impl NotificationEventType {
+    pub const ALL: [NotificationEventType; 5] = [
+        NotificationEventType::SyncFailure,
+        NotificationEventType::BudgetThreshold,
+        NotificationEventType::LargeTransaction,
+        NotificationEventType::LowBalance,
+        NotificationEventType::NewMerchant,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::SyncFailure => "sync_failure",
+            Self::BudgetThreshold => "budget_threshold",
+            Self::LargeTransaction => "large_transaction",
+            Self::LowBalance => "low_balance",
+            Self::NewMerchant => "new_merchant",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|t| t.as_str() == value)
+    }
+
+    /// Delivery used when the user has not chosen otherwise: everything lands in the
+    /// in-app feed, and only sync failures are emailed since they need action.
+    pub fn enabled_by_default(&self, channel: &str) -> bool {
+        channel == IN_APP_CHANNEL || matches!(self, Self::SyncFailure)
+    }
+}
+
+/// Something worth telling a user about, before it is persisted or delivered.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NotificationEvent {
+    pub user_id: Uuid,
+    pub event_type: NotificationEventType,
+    pub title: String,
+    pub body: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[schema(example = json!({
+    "id": "550e8400-e29b-41d4-a716-446655440000",
+    "event_type": "sync_failure",
+    "title": "Sync failed for Demo Bank",
+    "body": "We could not refresh Demo Bank. Reconnect the institution to resume syncing.",
+    "read_at": null,
+    "created_at": "2024-03-01T12:00:00Z"
+}))]
+pub struct Notification {
+    pub id: Uuid,
+    pub event_type: String,
+    pub title: String,
+    pub body: String,
+    pub read_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct NotificationListResponse {
+    pub notifications: Vec<Notification>,
+    pub unread_count: i64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NotificationListQuery {
+    pub unread_only: Option<bool>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, FromRow, ToSchema)]
+#[schema(example = json!({"event_type": "budget_threshold", "channel": "email", "enabled": true}))]
+pub struct NotificationPreference {
+    pub event_type: String,
+    pub channel: String,
+    pub enabled: bool,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateNotificationPreferencesRequest {
+    pub preferences: Vec<NotificationPreference>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct NotificationPreferencesResponse {
+    pub channels: Vec<String>,
+    pub preferences: Vec<NotificationPreference>,
+}
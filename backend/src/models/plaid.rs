@@ -69,9 +69,12 @@ pub struct ProviderConnectRequest {
 }
 
 #[derive(Deserialize, Serialize, ToSchema)]
-#[schema(example = json!({"connection_id": "connection-uuid"}))]
+#[schema(example = json!({"connection_id": "connection-uuid", "detach": true}))]
 pub struct SyncTransactionsRequest {
     pub connection_id: Option<String>,
+    /// Run the sync in the background and return 202 straight away.
+    #[serde(default)]
+    pub detach: Option<bool>,
 }
 
 #[derive(Debug, Deserialize, ToSchema)]
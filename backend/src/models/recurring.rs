@@ -0,0 +1,98 @@
+use chrono::{Months, NaiveDate};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[allow(unused_imports)]
+use serde_json::json;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RecurrenceCadence {
+    Weekly,
+    Biweekly,
+    Monthly,
+    Quarterly,
+    Annual,
+}
+
+impl RecurrenceCadence {
+    pub fn from_interval_days(days: i64) -> Option<Self> {
+        match days {
+            6..=8 => Some(Self::Weekly),
+            13..=16 => Some(Self::Biweekly),
+            26..=35 => Some(Self::Monthly),
+            85..=97 => Some(Self::Quarterly),
+            350..=380 => Some(Self::Annual),
+            _ => None,
+        }
+    }
+
+    pub fn nominal_days(&self) -> i64 {
+        match self {
+            Self::Weekly => 7,
+            Self::Biweekly => 14,
+            Self::Monthly => 30,
+            Self::Quarterly => 91,
+            Self::Annual => 365,
+        }
+    }
+
+    pub fn advance(&self, date: NaiveDate) -> NaiveDate {
+        let advanced = match self {
+            Self::Weekly => date.checked_add_days(chrono::Days::new(7)),
+            Self::Biweekly => date.checked_add_days(chrono::Days::new(14)),
+            Self::Monthly => date.checked_add_months(Months::new(1)),
+            Self::Quarterly => date.checked_add_months(Months::new(3)),
+            Self::Annual => date.checked_add_months(Months::new(12)),
+        };
+        advanced.unwrap_or(date)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
+#[schema(example = json!({
+    "merchant": "Netflix",
+    "category": "ENTERTAINMENT",
+    "cadence": "monthly",
+    "average_amount": "15.49",
+    "is_income": false,
+    "occurrences": 6,
+    "last_date": "2024-01-12",
+    "next_expected_date": "2024-02-12",
+    "account_id": "550e8400-e29b-41d4-a716-446655440000"
+}))]
+pub struct RecurringSeries {
+    pub merchant: String,
+    pub category: String,
+    pub cadence: RecurrenceCadence,
+    #[schema(value_type = String)]
+    pub average_amount: Decimal,
+    pub is_income: bool,
+    pub occurrences: u32,
+    pub last_date: NaiveDate,
+    pub next_expected_date: NaiveDate,
+    pub account_id: Uuid,
+    #[serde(skip)]
+    pub transaction_ids: Vec<Uuid>,
+}
+
+impl RecurringSeries {
+    /// Expected occurrence dates falling within `[start, end]`.
+    pub fn occurrences_between(&self, start: NaiveDate, end: NaiveDate) -> Vec<NaiveDate> {
+        let mut dates = Vec::new();
+        let mut next = self.next_expected_date;
+        while next <= end {
+            if next >= start {
+                dates.push(next);
+            }
+            let advanced = self.cadence.advance(next);
+            if advanced <= next {
+                break;
+            }
+            next = advanced;
+        }
+        dates
+    }
+}
@@ -0,0 +1,237 @@
+use chrono::{DateTime, Days, NaiveDate, Utc};
+use serde::{Deserialize, Deserializer, Serialize};
+use sqlx::FromRow;
+use std::collections::{HashMap, HashSet};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::models::transaction::TransactionWithAccount;
+use crate::models::user_settings::UserCalendar;
+
+#[allow(unused_imports)]
+use serde_json::json;
+
+pub const MAX_SAVED_VIEW_NAME_LENGTH: usize = 100;
+
+/// A date range relative to the day a view is run. `this_*` ranges end today;
+/// months, quarters and years follow the user's month start day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DatePreset {
+    Last7Days,
+    Last30Days,
+    Last90Days,
+    ThisMonth,
+    LastMonth,
+    ThisQuarter,
+    LastQuarter,
+    ThisYear,
+    LastYear,
+}
+
+impl DatePreset {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Last7Days => "last_7_days",
+            Self::Last30Days => "last_30_days",
+            Self::Last90Days => "last_90_days",
+            Self::ThisMonth => "this_month",
+            Self::LastMonth => "last_month",
+            Self::ThisQuarter => "this_quarter",
+            Self::LastQuarter => "last_quarter",
+            Self::ThisYear => "this_year",
+            Self::LastYear => "last_year",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        [
+            Self::Last7Days,
+            Self::Last30Days,
+            Self::Last90Days,
+            Self::ThisMonth,
+            Self::LastMonth,
+            Self::ThisQuarter,
+            Self::LastQuarter,
+            Self::ThisYear,
+            Self::LastYear,
+        ]
+        .into_iter()
+        .find(|p| p.as_str() == value)
+    }
+
+    /// Inclusive `(start, end)` of the range as seen on `today`.
+    pub fn resolve(&self, calendar: &UserCalendar, today: NaiveDate) -> (NaiveDate, NaiveDate) {
+        let days_back = |days: u64| today.checked_sub_days(Days::new(days - 1)).unwrap_or(today);
+        let (year, month) = calendar.month_of(today);
+        let month_start = |year: i32, month: u32| {
+            calendar
+                .month_range(year, month)
+                .map_or(today, |(start, _)| start)
+        };
+        let day_before = |date: NaiveDate| date.pred_opt().unwrap_or(date);
+        let quarter_month = (month - 1) / 3 * 3 + 1;
+        let (last_quarter_year, last_quarter_month) = if quarter_month == 1 {
+            (year - 1, 10)
+        } else {
+            (year, quarter_month - 3)
+        };
+
+        match self {
+            Self::Last7Days => (days_back(7), today),
+            Self::Last30Days => (days_back(30), today),
+            Self::Last90Days => (days_back(90), today),
+            Self::ThisMonth => (month_start(year, month), today),
+            Self::LastMonth => {
+                let (prev_year, prev_month) = if month == 1 {
+                    (year - 1, 12)
+                } else {
+                    (year, month - 1)
+                };
+                calendar
+                    .month_range(prev_year, prev_month)
+                    .unwrap_or((today, today))
+            }
+            Self::ThisQuarter => (month_start(year, quarter_month), today),
+            Self::LastQuarter => (
+                month_start(last_quarter_year, last_quarter_month),
+                day_before(month_start(year, quarter_month)),
+            ),
+            Self::ThisYear => (month_start(year, 1), today),
+            Self::LastYear => (month_start(year - 1, 1), day_before(month_start(year, 1))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, PartialEq, ToSchema)]
+#[schema(example = json!({
+    "id": "7c9e6679-7425-40de-944b-e07fc1f90ae7",
+    "name": "Business travel this quarter",
+    "search": "category:travel",
+    "account_ids": ["aaaaaaaa-bbbb-cccc-dddd-eeeeeeeeeeee"],
+    "date_preset": "this_quarter",
+    "start_date": null,
+    "end_date": null,
+    "created_at": "2024-03-01T12:00:00Z",
+    "updated_at": "2024-03-01T12:00:00Z"
+}))]
+pub struct SavedView {
+    pub id: Uuid,
+    #[serde(skip)]
+    pub user_id: Uuid,
+    pub name: String,
+    /// Same syntax as the `search` parameter of `GET /api/transactions`.
+    pub search: Option<String>,
+    pub account_ids: Vec<Uuid>,
+    pub date_preset: Option<String>,
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl SavedView {
+    pub fn preset(&self) -> Option<DatePreset> {
+        self.date_preset.as_deref().and_then(DatePreset::parse)
+    }
+
+    /// The view's dates as of `today`: the preset when one is set, otherwise
+    /// the fixed bounds. Either end may be open.
+    pub fn date_range(
+        &self,
+        calendar: &UserCalendar,
+        today: NaiveDate,
+    ) -> (Option<NaiveDate>, Option<NaiveDate>) {
+        match self.preset() {
+            Some(preset) => {
+                let (start, end) = preset.resolve(calendar, today);
+                (Some(start), Some(end))
+            }
+            None => (self.start_date, self.end_date),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+#[schema(example = json!({
+    "name": "Business travel this quarter",
+    "search": "category:travel",
+    "account_ids": ["aaaaaaaa-bbbb-cccc-dddd-eeeeeeeeeeee"],
+    "date_preset": "this_quarter"
+}))]
+pub struct SavedViewRequest {
+    pub name: String,
+    pub search: Option<String>,
+    #[serde(default)]
+    pub account_ids: Vec<Uuid>,
+    /// A relative range; give either this or fixed dates.
+    pub date_preset: Option<DatePreset>,
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[schema(example = json!({
+    "view": {
+        "id": "7c9e6679-7425-40de-944b-e07fc1f90ae7",
+        "name": "Business travel this quarter",
+        "search": "category:travel",
+        "account_ids": ["aaaaaaaa-bbbb-cccc-dddd-eeeeeeeeeeee"],
+        "date_preset": "this_quarter",
+        "start_date": null,
+        "end_date": null,
+        "created_at": "2024-03-01T12:00:00Z",
+        "updated_at": "2024-03-01T12:00:00Z"
+    },
+    "start_date": "2024-01-01",
+    "end_date": "2024-03-14",
+    "transactions": [],
+    "truncated": false
+}))]
+pub struct SavedViewResults {
+    pub view: SavedView,
+    /// The view's date range as resolved today.
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+    pub transactions: Vec<TransactionWithAccount>,
+    /// True when the view matched more transactions than the search returns.
+    pub truncated: bool,
+}
+
+#[derive(Serialize, ToSchema)]
+#[schema(example = json!({"deleted": true, "view_id": "7c9e6679-7425-40de-944b-e07fc1f90ae7"}))]
+pub struct DeleteSavedViewResponse {
+    pub deleted: bool,
+    pub view_id: Uuid,
+}
+
+/// Ids of the transactions each saved view matched, for filtering budgets and
+/// alert rules that point at a view.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ViewMatches(HashMap<Uuid, HashSet<Uuid>>);
+
+impl ViewMatches {
+    pub fn insert(&mut self, view_id: Uuid, transaction_ids: HashSet<Uuid>) {
+        self.0.insert(view_id, transaction_ids);
+    }
+
+    /// Whether a transaction passes an optional view filter. A view that was not
+    /// loaded, e.g. because it was deleted, matches nothing.
+    pub fn allows(&self, view_id: Option<Uuid>, transaction_id: Uuid) -> bool {
+        view_id.is_none_or(|view_id| {
+            self.0
+                .get(&view_id)
+                .is_some_and(|ids| ids.contains(&transaction_id))
+        })
+    }
+}
+
+/// Reads a field that may be absent, `null` or set as `None`, `Some(None)` or
+/// `Some(Some(value))`, so an update can tell "leave as is" from "clear".
+pub fn deserialize_present<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
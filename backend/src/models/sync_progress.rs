@@ -0,0 +1,107 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[allow(unused_imports)]
+use serde_json::json;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncPhase {
+    Started,
+    FetchingAccounts,
+    FetchingTransactions,
+    Persisting,
+    Completed,
+    Failed,
+}
+
+impl SyncPhase {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Started => "started",
+            Self::FetchingAccounts => "fetching_accounts",
+            Self::FetchingTransactions => "fetching_transactions",
+            Self::Persisting => "persisting",
+            Self::Completed => "completed",
+            Self::Failed => "failed",
+        }
+    }
+
+    /// Whether the sync has finished, successfully or not.
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, Self::Completed | Self::Failed)
+    }
+}
+
+/// One step of a connection sync, streamed to the user as a Server-Sent Event named
+/// after its phase.
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+#[schema(example = json!({
+    "connection_id": "0f8fad5b-d9cb-469f-a165-70867728950e",
+    "phase": "persisting",
+    "account_count": 3,
+    "transaction_count": 42,
+    "error": null,
+    "timestamp": "2024-03-01T12:00:05Z"
+}))]
+pub struct SyncProgressEvent {
+    #[serde(skip)]
+    pub user_id: Uuid,
+    pub connection_id: Uuid,
+    pub phase: SyncPhase,
+    /// Accounts returned by the institution, once they are known.
+    pub account_count: Option<usize>,
+    /// Transactions fetched while `fetching_transactions`; new transactions being stored
+    /// or stored from `persisting` on.
+    pub transaction_count: Option<usize>,
+    /// Why the sync failed, for `failed` events.
+    pub error: Option<String>,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl SyncProgressEvent {
+    pub fn new(user_id: Uuid, connection_id: Uuid, phase: SyncPhase) -> Self {
+        Self {
+            user_id,
+            connection_id,
+            phase,
+            account_count: None,
+            transaction_count: None,
+            error: None,
+            timestamp: Utc::now(),
+        }
+    }
+
+    pub fn with_account_count(mut self, count: usize) -> Self {
+        self.account_count = Some(count);
+        self
+    }
+
+    pub fn with_transaction_count(mut self, count: usize) -> Self {
+        self.transaction_count = Some(count);
+        self
+    }
+
+    pub fn with_error(mut self, error: &str) -> Self {
+        self.error = Some(error.to_string());
+        self
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SyncEventsQuery {
+    pub connection_id: Option<Uuid>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[schema(example = json!({
+    "connection_id": "0f8fad5b-d9cb-469f-a165-70867728950e",
+    "events_url": "/api/providers/sync/events?connection_id=0f8fad5b-d9cb-469f-a165-70867728950e"
+}))]
+pub struct SyncStartedResponse {
+    pub connection_id: Uuid,
+    /// Stream to follow for progress and the final `completed` or `failed` event.
+    pub events_url: String,
+}
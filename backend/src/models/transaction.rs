@@ -5,6 +5,9 @@ use std::str::FromStr;
 use utoipa::ToSchema;
 use uuid::Uuid;
 
+use crate::models::account::Account;
+use crate::models::currency::default_currency;
+
 #[allow(unused_imports)]
 use serde_json::json;
 
@@ -16,6 +19,7 @@ use serde_json::json;
     "provider_account_id": "acct-123",
     "provider_transaction_id": "txn-890",
     "amount": "24.99",
+    "iso_currency_code": "USD",
     "date": "2024-01-20",
     "merchant_name": "Sample Store",
     "category_primary": "SHOPPING",
@@ -23,7 +27,9 @@ use serde_json::json;
     "category_confidence": "medium",
     "payment_channel": "online",
     "pending": false,
-    "created_at": "2024-01-20T14:32:00Z"
+    "created_at": "2024-01-20T14:32:00Z",
+    "reviewed": false,
+    "excluded_from_analytics": false
 }))]
 pub struct Transaction {
     pub id: Uuid,
@@ -33,6 +39,8 @@ pub struct Transaction {
     pub provider_transaction_id: Option<String>,
     #[schema(value_type = String)]
     pub amount: Decimal,
+    #[serde(default = "default_currency")]
+    pub iso_currency_code: String,
     pub date: NaiveDate,
     pub merchant_name: Option<String>,
     pub category_primary: String,
@@ -41,6 +49,13 @@ pub struct Transaction {
     pub payment_channel: Option<String>,
     pub pending: bool,
     pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Set by the user once they have checked the transaction.
+    #[serde(default)]
+    pub reviewed: bool,
+    /// Left out of spending analytics, budgets, insights and spending alerts; balances
+    /// and forecasts still count it.
+    #[serde(default)]
+    pub excluded_from_analytics: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
@@ -51,6 +66,7 @@ pub struct Transaction {
     "provider_account_id": "acct-123",
     "provider_transaction_id": "txn-456",
     "amount": "42.75",
+    "iso_currency_code": "USD",
     "date": "2024-01-15",
     "merchant_name": "Coffee Collective",
     "category_primary": "FOOD_AND_DRINK",
@@ -59,6 +75,8 @@ pub struct Transaction {
     "payment_channel": "in_store",
     "pending": false,
     "created_at": "2024-01-15T13:45:00Z",
+    "reviewed": true,
+    "excluded_from_analytics": false,
     "account_name": "Demo Checking",
     "account_type": "depository",
     "account_mask": "1234"
@@ -71,6 +89,8 @@ pub struct TransactionWithAccount {
     pub provider_transaction_id: Option<String>,
     #[schema(value_type = String)]
     pub amount: Decimal,
+    #[serde(default = "default_currency")]
+    pub iso_currency_code: String,
     pub date: NaiveDate,
     pub merchant_name: Option<String>,
     pub category_primary: String,
@@ -79,16 +99,62 @@ pub struct TransactionWithAccount {
     pub payment_channel: Option<String>,
     pub pending: bool,
     pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(default)]
+    pub reviewed: bool,
+    #[serde(default)]
+    pub excluded_from_analytics: bool,
     pub account_name: String,
     pub account_type: String,
     pub account_mask: Option<String>,
 }
 
+#[derive(Debug)]
 pub struct TransactionsQuery {
     pub search: Option<String>,
     pub account_ids: Vec<String>,
 }
 
+impl TransactionsQuery {
+    /// Whether a transaction passes the account and text-search filters. Account
+    /// ids that are not UUIDs never match.
+    pub fn matches(&self, transaction: &TransactionWithAccount) -> bool {
+        if !self.account_ids.is_empty()
+            && !self
+                .account_ids
+                .iter()
+                .filter_map(|s| Uuid::parse_str(s).ok())
+                .any(|id| id == transaction.account_id)
+        {
+            return false;
+        }
+
+        let Some(search) = self
+            .search
+            .as_ref()
+            .map(|s| s.trim())
+            .filter(|s| !s.is_empty())
+        else {
+            return true;
+        };
+        let needle = search.to_lowercase();
+        let merchant = transaction
+            .merchant_name
+            .as_deref()
+            .unwrap_or("")
+            .to_lowercase();
+        merchant.contains(&needle)
+            || transaction
+                .category_primary
+                .to_lowercase()
+                .contains(&needle)
+            || transaction
+                .category_detailed
+                .to_lowercase()
+                .contains(&needle)
+            || transaction.account_name.to_lowercase().contains(&needle)
+    }
+}
+
 impl<'de> Deserialize<'de> for TransactionsQuery {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -235,6 +301,7 @@ impl Transaction {
             provider_account_id: provider_account_id.map(String::from),
             provider_transaction_id: teller_txn["id"].as_str().map(String::from),
             amount,
+            iso_currency_code: default_currency(),
             date,
             merchant_name,
             category_primary: Self::normalize_teller_category(category),
@@ -243,6 +310,8 @@ impl Transaction {
             payment_channel: None,
             pending: teller_txn["status"].as_str() != Some("posted"),
             created_at: Some(chrono::Utc::now()),
+            reviewed: false,
+            excluded_from_analytics: false,
         }
     }
 
@@ -278,6 +347,7 @@ impl Transaction {
             provider_account_id: plaid_txn["account_id"].as_str().map(String::from),
             provider_transaction_id: plaid_txn["transaction_id"].as_str().map(String::from),
             amount,
+            iso_currency_code: Account::plaid_currency(plaid_txn),
             date,
             merchant_name: plaid_txn["merchant_name"]
                 .as_str()
@@ -292,6 +362,8 @@ impl Transaction {
             payment_channel: plaid_txn["payment_channel"].as_str().map(String::from),
             pending: plaid_txn["pending"].as_bool().unwrap_or(false),
             created_at: Some(chrono::Utc::now()),
+            reviewed: false,
+            excluded_from_analytics: false,
         }
     }
 
@@ -0,0 +1,203 @@
+use chrono::{Datelike, Days, NaiveDate};
+use rust_decimal::Decimal;
+use std::str::FromStr;
+
+/// Most rows a search returns, the same cap as the unfiltered transaction list.
+pub const SEARCH_RESULT_LIMIT: i64 = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AmountComparison {
+    Equal,
+    Greater,
+    GreaterOrEqual,
+    Less,
+    LessOrEqual,
+}
+
+impl AmountComparison {
+    pub fn sql_operator(&self) -> &'static str {
+        match self {
+            Self::Equal => "=",
+            Self::Greater => ">",
+            Self::GreaterOrEqual => ">=",
+            Self::Less => "<",
+            Self::LessOrEqual => "<=",
+        }
+    }
+}
+
+/// A parsed transaction `search` parameter.
+///
+/// Bare words match merchant, category and account name, tolerating typos. Bare
+/// numbers (`42.75`, `$42.75`) and dates (`2026-01-05`) match the amount or day.
+/// Qualifiers narrow the results further: `merchant:`, `category:`, `account:`,
+/// `tag:`, `before:` and `after:` (exclusive dates), `on:` (a day or a `YYYY-MM`
+/// month) and `amount` with `:`, `=`, `>`, `>=`, `<` or `<=`. Amounts compare
+/// without sign, so spending and refunds match alike. Values with spaces can be
+/// quoted: `merchant:"whole foods"`.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct TransactionSearch {
+    pub text: Vec<String>,
+    pub merchants: Vec<String>,
+    pub categories: Vec<String>,
+    pub accounts: Vec<String>,
+    pub tags: Vec<String>,
+    pub amounts: Vec<(AmountComparison, Decimal)>,
+    /// Inclusive.
+    pub start_date: Option<NaiveDate>,
+    /// Inclusive.
+    pub end_date: Option<NaiveDate>,
+}
+
+impl TransactionSearch {
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let mut search = Self::default();
+        for token in Self::tokenize(input) {
+            search.apply_token(&token)?;
+        }
+        Ok(search)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Splits on whitespace outside double quotes and drops the quotes.
+    fn tokenize(input: &str) -> Vec<String> {
+        let mut tokens = Vec::new();
+        let mut current = String::new();
+        let mut quoted = false;
+        for c in input.chars() {
+            match c {
+                '"' => quoted = !quoted,
+                c if c.is_whitespace() && !quoted => {
+                    if !current.is_empty() {
+                        tokens.push(std::mem::take(&mut current));
+                    }
+                }
+                c => current.push(c),
+            }
+        }
+        if !current.is_empty() {
+            tokens.push(current);
+        }
+        tokens
+    }
+
+    fn apply_token(&mut self, token: &str) -> Result<(), String> {
+        if let Some(rest) = Self::strip_prefix_ignore_case(token, "amount") {
+            if let Some((comparison, value)) = Self::split_comparison(rest) {
+                let amount = Self::parse_amount(value)
+                    .ok_or_else(|| format!("Invalid search: '{}' is not an amount", value))?;
+                self.amounts.push((comparison, amount));
+                return Ok(());
+            }
+        }
+
+        if let Some((key, value)) = token.split_once(':') {
+            let key = key.to_lowercase();
+            let known = [
+                "merchant", "category", "account", "tag", "before", "after", "on",
+            ];
+            if known.contains(&key.as_str()) {
+                let value = value.trim();
+                if value.is_empty() {
+                    return Err(format!("Invalid search: '{}:' needs a value", key));
+                }
+                return self.apply_qualifier(&key, value);
+            }
+        }
+
+        if let Some(amount) = Self::parse_amount(token) {
+            self.amounts.push((AmountComparison::Equal, amount));
+        } else if let Ok(day) = NaiveDate::parse_from_str(token, "%Y-%m-%d") {
+            self.narrow_start(day);
+            self.narrow_end(day);
+        } else {
+            self.text.push(token.to_string());
+        }
+        Ok(())
+    }
+
+    fn apply_qualifier(&mut self, key: &str, value: &str) -> Result<(), String> {
+        match key {
+            "merchant" => self.merchants.push(value.to_string()),
+            "category" => self.categories.push(value.to_string()),
+            "account" => self.accounts.push(value.to_string()),
+            "tag" => self.tags.push(value.to_string()),
+            "before" => {
+                let day = Self::parse_day(value)?;
+                self.narrow_end(day.checked_sub_days(Days::new(1)).unwrap_or(day));
+            }
+            "after" => {
+                let day = Self::parse_day(value)?;
+                self.narrow_start(day.checked_add_days(Days::new(1)).unwrap_or(day));
+            }
+            _ => {
+                let (start, end) = Self::parse_day_or_month(value)?;
+                self.narrow_start(start);
+                self.narrow_end(end);
+            }
+        }
+        Ok(())
+    }
+
+    /// Date qualifiers intersect, so `after:` and `before:` combine into a range.
+    pub fn narrow_start(&mut self, start: NaiveDate) {
+        self.start_date = Some(self.start_date.map_or(start, |s| s.max(start)));
+    }
+
+    pub fn narrow_end(&mut self, end: NaiveDate) {
+        self.end_date = Some(self.end_date.map_or(end, |e| e.min(end)));
+    }
+
+    fn strip_prefix_ignore_case<'a>(token: &'a str, prefix: &str) -> Option<&'a str> {
+        token
+            .get(..prefix.len())
+            .filter(|head| head.eq_ignore_ascii_case(prefix))
+            .map(|_| &token[prefix.len()..])
+    }
+
+    fn split_comparison(rest: &str) -> Option<(AmountComparison, &str)> {
+        [
+            (">=", AmountComparison::GreaterOrEqual),
+            ("<=", AmountComparison::LessOrEqual),
+            (">", AmountComparison::Greater),
+            ("<", AmountComparison::Less),
+            ("=", AmountComparison::Equal),
+            (":", AmountComparison::Equal),
+        ]
+        .into_iter()
+        .find_map(|(op, comparison)| rest.strip_prefix(op).map(|value| (comparison, value)))
+    }
+
+    fn parse_amount(value: &str) -> Option<Decimal> {
+        let digits = value.strip_prefix('$').unwrap_or(value);
+        if !digits.starts_with(|c: char| c.is_ascii_digit()) {
+            return None;
+        }
+        Decimal::from_str(digits).ok()
+    }
+
+    fn parse_day(value: &str) -> Result<NaiveDate, String> {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d")
+            .map_err(|_| format!("Invalid search: '{}' is not a YYYY-MM-DD date", value))
+    }
+
+    fn parse_day_or_month(value: &str) -> Result<(NaiveDate, NaiveDate), String> {
+        if let Ok(day) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+            return Ok((day, day));
+        }
+        let start = NaiveDate::parse_from_str(&format!("{}-01", value), "%Y-%m-%d")
+            .map_err(|_| format!("Invalid search: '{}' is not a date or YYYY-MM month", value))?;
+        let next_month = if start.month() == 12 {
+            NaiveDate::from_ymd_opt(start.year() + 1, 1, 1)
+        } else {
+            NaiveDate::from_ymd_opt(start.year(), start.month() + 1, 1)
+        };
+        let end = next_month
+            .and_then(|d| d.pred_opt())
+            .ok_or_else(|| format!("Invalid search: '{}' is out of range", value))?;
+        Ok((start, end))
+    }
+}
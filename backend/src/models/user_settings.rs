@@ -0,0 +1,128 @@
+use chrono::{DateTime, Datelike, Days, Months, NaiveDate, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::models::currency::default_currency;
+
+#[allow(unused_imports)]
+use serde_json::json;
+
+pub const DEFAULT_TIMEZONE: &str = "UTC";
+
+/// Highest allowed month start day, so every month contains it.
+pub const MAX_MONTH_START_DAY: u32 = 28;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
+#[schema(example = json!({
+    "reporting_currency": "EUR",
+    "timezone": "America/New_York",
+    "month_start_day": 15
+}))]
+pub struct UserSettings {
+    pub reporting_currency: String,
+    /// IANA time zone used to decide what "today" is for the user.
+    pub timezone: String,
+    /// Day of the month on which the user's budgeting month begins (1-28).
+    pub month_start_day: u32,
+}
+
+impl Default for UserSettings {
+    fn default() -> Self {
+        Self {
+            reporting_currency: default_currency(),
+            timezone: DEFAULT_TIMEZONE.to_string(),
+            month_start_day: 1,
+        }
+    }
+}
+
+impl UserSettings {
+    pub fn calendar(&self) -> UserCalendar {
+        UserCalendar::new(
+            self.timezone.parse().unwrap_or(Tz::UTC),
+            self.month_start_day,
+        )
+    }
+
+    /// Part of the cache key of responses computed under these settings, so a
+    /// settings change stops every session from reading the old ones.
+    pub fn cache_tag(&self) -> String {
+        format!(
+            "{}_{}_{}",
+            self.reporting_currency, self.timezone, self.month_start_day
+        )
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[schema(example = json!({
+    "reporting_currency": "GBP",
+    "timezone": "Europe/London",
+    "month_start_day": 1
+}))]
+pub struct UpdateUserSettingsRequest {
+    pub reporting_currency: Option<String>,
+    pub timezone: Option<String>,
+    pub month_start_day: Option<u32>,
+}
+
+/// How a user's days and months line up with the clock. Months are keyed
+/// `YYYY-MM` by the calendar month they start in, so with a start day of 15
+/// `2024-01` runs from 15 January to 14 February.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UserCalendar {
+    timezone: Tz,
+    month_start_day: u32,
+}
+
+impl Default for UserCalendar {
+    fn default() -> Self {
+        Self::new(Tz::UTC, 1)
+    }
+}
+
+impl UserCalendar {
+    pub fn new(timezone: Tz, month_start_day: u32) -> Self {
+        Self {
+            timezone,
+            month_start_day: month_start_day.clamp(1, MAX_MONTH_START_DAY),
+        }
+    }
+
+    /// The user's local date at `instant`.
+    pub fn local_date(&self, instant: DateTime<Utc>) -> NaiveDate {
+        instant.with_timezone(&self.timezone).date_naive()
+    }
+
+    pub fn today(&self) -> NaiveDate {
+        self.local_date(Utc::now())
+    }
+
+    /// The `(year, month)` of the user's month containing `date`.
+    pub fn month_of(&self, date: NaiveDate) -> (i32, u32) {
+        let shifted = date - Days::new(u64::from(self.month_start_day - 1));
+        (shifted.year(), shifted.month())
+    }
+
+    pub fn month_key(&self, date: NaiveDate) -> String {
+        let (year, month) = self.month_of(date);
+        format!("{:04}-{:02}", year, month)
+    }
+
+    /// First and last day of the user's month `(year, month)`, or `None` when
+    /// the month lies outside the dates chrono can represent.
+    pub fn month_range(&self, year: i32, month: u32) -> Option<(NaiveDate, NaiveDate)> {
+        let start = NaiveDate::from_ymd_opt(year, month, self.month_start_day)?;
+        let end = start.checked_add_months(Months::new(1))?.pred_opt()?;
+        Some((start, end))
+    }
+
+    /// Whether `start..=end` covers whole months of this calendar.
+    pub fn is_month_aligned(&self, start: NaiveDate, end: NaiveDate) -> bool {
+        start.day() == self.month_start_day
+            && end
+                .succ_opt()
+                .is_some_and(|next| next.day() == self.month_start_day)
+    }
+}
@@ -0,0 +1,217 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[allow(unused_imports)]
+use serde_json::json;
+
+pub const WEBHOOK_STATUS_PENDING: &str = "pending";
+pub const WEBHOOK_STATUS_SUCCEEDED: &str = "succeeded";
+pub const WEBHOOK_STATUS_FAILED: &str = "failed";
+
+pub const DEFAULT_WEBHOOK_DELIVERY_LIMIT: i64 = 50;
+pub const MAX_WEBHOOK_DELIVERY_LIMIT: i64 = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+pub enum WebhookEventType {
+    /// New transactions stored by a sync, sent as one batch per sync.
+    #[serde(rename = "transaction.created")]
+    TransactionCreated,
+    #[serde(rename = "sync.completed")]
+    SyncCompleted,
+    #[serde(rename = "sync.failed")]
+    SyncFailed,
+    /// Spending in a category went over its budget; sent once per budget and month.
+    #[serde(rename = "budget.exceeded")]
+    BudgetExceeded,
+    /// The institution needs to be linked again before syncing can resume.
+    #[serde(rename = "connection.reauth_required")]
+    ConnectionReauthRequired,
+    /// Sent by the test-delivery endpoint only; endpoints cannot subscribe to it.
+    #[serde(rename = "webhook.test")]
+    Test,
+}
+
+impl WebhookEventType {
+    pub const SUBSCRIBABLE: [WebhookEventType; 5] = [
+        WebhookEventType::TransactionCreated,
+        WebhookEventType::SyncCompleted,
+        WebhookEventType::SyncFailed,
+        WebhookEventType::BudgetExceeded,
+        WebhookEventType::ConnectionReauthRequired,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::TransactionCreated => "transaction.created",
+            Self::SyncCompleted => "sync.completed",
+            Self::SyncFailed => "sync.failed",
+            Self::BudgetExceeded => "budget.exceeded",
+            Self::ConnectionReauthRequired => "connection.reauth_required",
+            Self::Test => "webhook.test",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::SUBSCRIBABLE
+            .into_iter()
+            .chain(std::iter::once(Self::Test))
+            .find(|t| t.as_str() == value)
+    }
+}
+
+/// Something that happened to a user's data, before it is queued for their endpoints.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WebhookEvent {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub event_type: WebhookEventType,
+    /// Events sharing a key are delivered to an endpoint at most once.
+    pub dedup_key: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub data: serde_json::Value,
+}
+
+impl WebhookEvent {
+    pub fn new(user_id: Uuid, event_type: WebhookEventType, data: serde_json::Value) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            user_id,
+            event_type,
+            dedup_key: None,
+            created_at: Utc::now(),
+            data,
+        }
+    }
+
+    pub fn with_dedup_key(mut self, dedup_key: String) -> Self {
+        self.dedup_key = Some(dedup_key);
+        self
+    }
+
+    /// The JSON body POSTed to endpoints.
+    pub fn payload(&self) -> serde_json::Value {
+        serde_json::json!({
+            "id": self.id,
+            "type": self.event_type.as_str(),
+            "created_at": self.created_at,
+            "data": self.data,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, ToSchema)]
+#[schema(example = json!({
+    "id": "7c9e6679-7425-40de-944b-e07fc1f90ae7",
+    "url": "https://automations.example.com/hooks/sumurai",
+    "description": "Home dashboard",
+    "events": ["transaction.created", "sync.failed"],
+    "enabled": true,
+    "created_at": "2024-03-01T12:00:00Z",
+    "updated_at": "2024-03-01T12:00:00Z"
+}))]
+pub struct WebhookEndpoint {
+    pub id: Uuid,
+    #[serde(skip)]
+    pub user_id: Uuid,
+    pub url: String,
+    pub description: Option<String>,
+    pub events: Vec<WebhookEventType>,
+    /// Key used to sign deliveries; only returned when the endpoint is created.
+    #[serde(skip)]
+    pub secret: String,
+    pub enabled: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl WebhookEndpoint {
+    pub fn subscribes_to(&self, event_type: WebhookEventType) -> bool {
+        self.enabled && self.events.contains(&event_type)
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[schema(example = json!({
+    "url": "https://automations.example.com/hooks/sumurai",
+    "description": "Home dashboard",
+    "events": ["transaction.created", "sync.failed"],
+    "enabled": true
+}))]
+pub struct WebhookEndpointRequest {
+    pub url: String,
+    pub description: Option<String>,
+    pub events: Vec<WebhookEventType>,
+    pub enabled: Option<bool>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CreateWebhookEndpointResponse {
+    /// Signing secret for verifying the `Sumurai-Signature` header. It cannot be shown
+    /// again.
+    pub secret: String,
+    pub endpoint: WebhookEndpoint,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, ToSchema)]
+#[schema(example = json!({
+    "id": "16fd2706-8baf-433b-82eb-8c7fada847da",
+    "endpoint_id": "7c9e6679-7425-40de-944b-e07fc1f90ae7",
+    "event_id": "9b2c8f0e-5f3a-4c1e-9a56-3f1d7c2b8e10",
+    "event_type": "sync.failed",
+    "payload": {"id": "9b2c8f0e-5f3a-4c1e-9a56-3f1d7c2b8e10", "type": "sync.failed", "created_at": "2024-03-01T12:00:00Z", "data": {"connection_id": "0f8fad5b-d9cb-469f-a165-70867728950e", "institution_name": "Demo Bank", "reason": "the institution rejected or failed the request"}},
+    "status": "pending",
+    "attempts": 2,
+    "response_status": 503,
+    "error": "Receiver responded with 503",
+    "next_attempt_at": "2024-03-01T12:04:00Z",
+    "created_at": "2024-03-01T12:00:00Z",
+    "delivered_at": null
+}))]
+pub struct WebhookDelivery {
+    pub id: Uuid,
+    pub endpoint_id: Uuid,
+    #[serde(skip)]
+    pub user_id: Uuid,
+    pub event_id: Uuid,
+    pub event_type: String,
+    #[serde(skip)]
+    pub dedup_key: Option<String>,
+    pub payload: serde_json::Value,
+    /// `pending` while attempts remain, then `succeeded` or `failed`.
+    pub status: String,
+    pub attempts: i32,
+    pub response_status: Option<i32>,
+    pub error: Option<String>,
+    pub next_attempt_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub delivered_at: Option<DateTime<Utc>>,
+}
+
+impl WebhookDelivery {
+    /// A delivery of `event` to `endpoint` that is due immediately.
+    pub fn queued(endpoint: &WebhookEndpoint, event: &WebhookEvent) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            endpoint_id: endpoint.id,
+            user_id: event.user_id,
+            event_id: event.id,
+            event_type: event.event_type.as_str().to_string(),
+            dedup_key: event.dedup_key.clone(),
+            payload: event.payload(),
+            status: WEBHOOK_STATUS_PENDING.to_string(),
+            attempts: 0,
+            response_status: None,
+            error: None,
+            next_attempt_at: Some(event.created_at),
+            created_at: event.created_at,
+            delivered_at: None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WebhookDeliveriesQuery {
+    pub limit: Option<i64>,
+}
@@ -0,0 +1,22 @@
+use anyhow::Result;
+use async_trait::async_trait;
+
+use crate::models::notification::NotificationEvent;
+
+#[derive(Debug, Clone)]
+pub struct NotificationRecipient {
+    pub email: String,
+}
+
+/// External delivery for notifications, in addition to the in-app feed.
+#[async_trait]
+pub trait NotificationChannel: Send + Sync {
+    /// Channel name used in delivery preferences, e.g. `"email"`.
+    fn name(&self) -> &'static str;
+
+    async fn deliver(
+        &self,
+        recipient: &NotificationRecipient,
+        event: &NotificationEvent,
+    ) -> Result<()>;
+}
@@ -0,0 +1,74 @@
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use lettre::message::{header::ContentType, Mailbox};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+
+use crate::config::{SmtpSecurity, SmtpSettings};
+use crate::models::notification::NotificationEvent;
+use crate::notifications::channel::{NotificationChannel, NotificationRecipient};
+
+pub struct SmtpEmailChannel {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+}
+
+impl SmtpEmailChannel {
+    pub fn new(settings: &SmtpSettings) -> Result<Self> {
+        let builder = match settings.security {
+            SmtpSecurity::None => {
+                AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&settings.host)
+            }
+            SmtpSecurity::StartTls => {
+                AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&settings.host)?
+            }
+            SmtpSecurity::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(&settings.host)?,
+        }
+        .port(settings.port);
+
+        let builder = match (&settings.username, &settings.password) {
+            (Some(username), Some(password)) => {
+                builder.credentials(Credentials::new(username.clone(), password.clone()))
+            }
+            _ => builder,
+        };
+
+        let from = settings
+            .from
+            .parse()
+            .map_err(|e| anyhow!("Invalid SMTP_FROM address: {}", e))?;
+
+        Ok(Self {
+            transport: builder.build(),
+            from,
+        })
+    }
+}
+
+#[async_trait]
+impl NotificationChannel for SmtpEmailChannel {
+    fn name(&self) -> &'static str {
+        "email"
+    }
+
+    async fn deliver(
+        &self,
+        recipient: &NotificationRecipient,
+        event: &NotificationEvent,
+    ) -> Result<()> {
+        let to: Mailbox = recipient
+            .email
+            .parse()
+            .map_err(|e| anyhow!("Invalid recipient address: {}", e))?;
+
+        let message = Message::builder()
+            .from(self.from.clone())
+            .to(to)
+            .subject(&event.title)
+            .header(ContentType::TEXT_PLAIN)
+            .body(event.body.clone())?;
+
+        self.transport.send(message).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,5 @@
+pub mod channel;
+pub mod email;
+
+pub use channel::{NotificationChannel, NotificationRecipient};
+pub use email::SmtpEmailChannel;
@@ -42,20 +42,99 @@ use utoipa::OpenApi;
             crate::models::auth::DeleteAccountResponse,
             crate::models::auth::LogoutResponse,
             crate::models::auth::OnboardingCompleteResponse,
+            crate::models::access_token::TokenScope,
+            crate::models::access_token::PersonalAccessToken,
+            crate::models::access_token::CreateAccessTokenRequest,
+            crate::models::access_token::CreateAccessTokenResponse,
             crate::models::transaction::TransactionWithAccount,
+            crate::models::bulk_edit::BulkTransactionEditRequest,
+            crate::models::bulk_edit::BulkTransactionEditResponse,
+            crate::models::bulk_edit::BulkTransactionOperation,
+            crate::models::bulk_edit::BulkOperationResult,
+            crate::models::saved_view::SavedView,
+            crate::models::saved_view::SavedViewRequest,
+            crate::models::saved_view::SavedViewResults,
+            crate::models::saved_view::DeleteSavedViewResponse,
+            crate::models::saved_view::DatePreset,
             crate::models::analytics::MonthlySpending,
             crate::models::analytics::CategorySpending,
             crate::models::analytics::DailySpending,
             crate::models::analytics::TopMerchant,
             crate::models::analytics::BalancesOverviewResponse,
             crate::models::analytics::NetWorthOverTimeResponse,
+            crate::models::analytics_query::QueryDimension,
+            crate::models::analytics_query::QueryMeasure,
+            crate::models::analytics_query::QueryFlow,
+            crate::models::analytics_query::AnalyticsQueryFilters,
+            crate::models::analytics_query::AnalyticsQueryRequest,
+            crate::models::analytics_query::AnalyticsQueryRow,
+            crate::models::analytics_query::AnalyticsQueryResponse,
+            crate::models::comparison::ComparisonBaseline,
+            crate::models::comparison::PeriodRange,
+            crate::models::comparison::ComparisonLine,
+            crate::models::comparison::PeriodComparisonResponse,
+            crate::models::insight::InsightKind,
+            crate::models::insight::Insight,
+            crate::models::insight::InsightsResponse,
+            crate::models::investment::AssetClass,
+            crate::models::investment::InvestmentTransaction,
+            crate::models::investment::HoldingView,
+            crate::models::investment::HoldingsResponse,
+            crate::models::investment::AssetAllocation,
+            crate::models::investment::AllocationResponse,
+            crate::models::liability::LiabilitySource,
+            crate::models::liability::LiabilityView,
+            crate::models::liability::UpdateLiabilityRequest,
+            crate::models::liability::PayoffStrategy,
+            crate::models::liability::PayoffPlanRequest,
+            crate::models::liability::DebtPayment,
+            crate::models::liability::PayoffMonth,
+            crate::models::liability::DebtPayoffSummary,
+            crate::models::liability::PayoffPlan,
+            crate::models::liability::PayoffPlanResponse,
+            crate::models::forecast::ForecastPoint,
+            crate::models::forecast::DiscretionarySpendRate,
+            crate::models::forecast::CashFlowForecastResponse,
+            crate::models::bill::BillSource,
+            crate::models::bill::UpcomingBill,
+            crate::models::bill::UpcomingBillsResponse,
+            crate::models::bill::CalendarFeedResponse,
+            crate::models::notification::NotificationEventType,
+            crate::models::notification::Notification,
+            crate::models::notification::NotificationListResponse,
+            crate::models::notification::NotificationPreference,
+            crate::models::notification::UpdateNotificationPreferencesRequest,
+            crate::models::notification::NotificationPreferencesResponse,
+            crate::models::alert::AlertRuleType,
+            crate::models::alert::AlertRule,
+            crate::models::alert::AlertRuleRequest,
+            crate::models::alert::FiredAlert,
+            crate::models::webhook::WebhookEventType,
+            crate::models::webhook::WebhookEndpoint,
+            crate::models::webhook::WebhookEndpointRequest,
+            crate::models::webhook::CreateWebhookEndpointResponse,
+            crate::models::webhook::WebhookDelivery,
+            crate::models::user_settings::UserSettings,
+            crate::models::user_settings::UpdateUserSettingsRequest,
+            crate::models::recurring::RecurrenceCadence,
+            crate::models::recurring::RecurringSeries,
             crate::models::budget::Budget,
             crate::models::budget::DeleteBudgetResponse,
+            crate::models::budget::BudgetAllocation,
+            crate::models::budget::ZeroBasedCategory,
+            crate::models::budget::ZeroBasedBudgetSummary,
+            crate::models::budget::ZeroBasedModeRequest,
+            crate::models::budget::ZeroBasedModeResponse,
+            crate::models::budget::AssignBudgetFundsRequest,
+            crate::models::budget::MoveBudgetFundsRequest,
             crate::models::plaid::LinkTokenRequest,
             crate::models::plaid::LinkTokenResponse,
             crate::models::plaid::ExchangeTokenRequest,
             crate::models::plaid::ProviderConnectRequest,
             crate::models::plaid::SyncTransactionsRequest,
+            crate::models::sync_progress::SyncPhase,
+            crate::models::sync_progress::SyncProgressEvent,
+            crate::models::sync_progress::SyncStartedResponse,
             crate::models::transaction::SyncTransactionsResponse,
             crate::models::transaction::SyncMetadata,
             crate::models::plaid::DisconnectRequest,
@@ -70,6 +149,16 @@ use utoipa::OpenApi;
             crate::models::plaid::ClearSyncedDataResponse,
             crate::models::account::AccountResponse,
             crate::models::api_error::ApiErrorResponse,
+            crate::models::export::ExportFormat,
+            crate::models::export::JournalFormat,
+            crate::models::archive::ArchiveRestoreResponse,
+            crate::models::import::ImportSource,
+            crate::models::import::ImportRequest,
+            crate::models::import::ImportPreview,
+            crate::models::import::ImportAccountPreview,
+            crate::models::import::ImportCategoryMapping,
+            crate::models::import::ImportBudgetPreview,
+            crate::models::import::ImportResult,
             schemas::SuccessResponse,
             schemas::ErrorResponse,
             schemas::HealthCheckResponse,
@@ -89,13 +178,33 @@ use utoipa::OpenApi;
         crate::logout_user,
         crate::change_user_password,
         crate::delete_user_account,
+        crate::create_personal_access_token,
+        crate::list_personal_access_tokens,
+        crate::revoke_personal_access_token,
         crate::complete_user_onboarding,
         crate::health_check,
         crate::get_authenticated_transactions,
+        crate::bulk_edit_authenticated_transactions,
+        crate::export_authenticated_transactions,
+        crate::get_authenticated_saved_views,
+        crate::create_authenticated_saved_view,
+        crate::get_authenticated_saved_view,
+        crate::update_authenticated_saved_view,
+        crate::delete_authenticated_saved_view,
+        crate::run_authenticated_saved_view,
+        crate::export_authenticated_journal,
+        crate::export_authenticated_archive,
+        crate::import_authenticated_archive,
+        crate::preview_authenticated_import,
+        crate::commit_authenticated_import,
         crate::get_authenticated_budgets,
         crate::create_authenticated_budget,
         crate::update_authenticated_budget,
         crate::delete_authenticated_budget,
+        crate::get_authenticated_zero_based_budget,
+        crate::set_authenticated_zero_based_mode,
+        crate::assign_authenticated_budget_funds,
+        crate::move_authenticated_budget_funds,
         crate::get_authenticated_current_month_spending,
         crate::get_authenticated_daily_spending,
         crate::get_authenticated_spending_by_date_range,
@@ -104,11 +213,46 @@ use utoipa::OpenApi;
         crate::get_authenticated_top_merchants,
         crate::get_authenticated_balances_overview,
         crate::get_authenticated_net_worth_over_time,
+        crate::get_authenticated_period_comparison,
+        crate::run_authenticated_analytics_query,
+        crate::get_authenticated_insights,
+        crate::dismiss_authenticated_insight,
+        crate::get_authenticated_cash_flow_forecast,
+        crate::get_authenticated_investment_holdings,
+        crate::get_authenticated_investment_allocation,
+        crate::get_authenticated_investment_transactions,
+        crate::get_authenticated_liabilities,
+        crate::update_authenticated_liability,
+        crate::plan_authenticated_debt_payoff,
+        crate::get_authenticated_upcoming_bills,
+        crate::create_authenticated_calendar_feed,
+        crate::delete_authenticated_calendar_feed,
+        crate::get_calendar_feed,
+        crate::get_authenticated_notifications,
+        crate::mark_authenticated_notification_read,
+        crate::mark_authenticated_notification_unread,
+        crate::mark_all_authenticated_notifications_read,
+        crate::get_authenticated_notification_preferences,
+        crate::update_authenticated_notification_preferences,
+        crate::get_authenticated_alert_rules,
+        crate::create_authenticated_alert_rule,
+        crate::update_authenticated_alert_rule,
+        crate::delete_authenticated_alert_rule,
+        crate::get_authenticated_fired_alerts,
+        crate::get_authenticated_webhooks,
+        crate::create_authenticated_webhook,
+        crate::update_authenticated_webhook,
+        crate::delete_authenticated_webhook,
+        crate::test_authenticated_webhook,
+        crate::get_authenticated_webhook_deliveries,
+        crate::get_authenticated_settings,
+        crate::update_authenticated_settings,
         crate::get_authenticated_provider_info,
         crate::select_authenticated_provider,
         crate::connect_authenticated_provider,
         crate::get_authenticated_provider_status,
         crate::sync_authenticated_provider_transactions,
+        crate::stream_sync_events,
         crate::disconnect_authenticated_connection,
         crate::create_authenticated_link_token,
         crate::exchange_authenticated_public_token,
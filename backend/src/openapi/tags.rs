@@ -6,7 +6,16 @@ pub const PROVIDERS_TAG: &str = "Financial Providers";
 pub const PLAID_TAG: &str = "Plaid";
 pub const TELLER_TAG: &str = "Teller";
 pub const ANALYTICS_TAG: &str = "Analytics";
+pub const INVESTMENTS_TAG: &str = "Investments";
+pub const LIABILITIES_TAG: &str = "Liabilities";
+pub const EXPORT_TAG: &str = "Export";
+pub const IMPORT_TAG: &str = "Import";
 pub const BUDGETS_TAG: &str = "Budgets";
+pub const BILLS_TAG: &str = "Bills";
+pub const NOTIFICATIONS_TAG: &str = "Notifications";
+pub const ALERTS_TAG: &str = "Alerts";
+pub const WEBHOOKS_TAG: &str = "Webhooks";
+pub const SETTINGS_TAG: &str = "Settings";
 pub const HEALTH_TAG: &str = "Health";
 
 pub fn add_tags(openapi: &mut OpenApi) {
@@ -35,10 +44,46 @@ pub fn add_tags(openapi: &mut OpenApi) {
             .name(ANALYTICS_TAG)
             .description(Some("Analytics endpoints delivering spend breakdowns, trends, balances, and net worth insights."))
             .build(),
+        openapi::tag::TagBuilder::new()
+            .name(INVESTMENTS_TAG)
+            .description(Some("Investment holdings, allocation by asset class, and investment transactions synced from providers."))
+            .build(),
+        openapi::tag::TagBuilder::new()
+            .name(LIABILITIES_TAG)
+            .description(Some("Credit and loan details, synced or entered by hand, and the avalanche and snowball debt payoff planner."))
+            .build(),
+        openapi::tag::TagBuilder::new()
+            .name(EXPORT_TAG)
+            .description(Some("Getting data out for accountants and other tools, including plain-text accounting journals."))
+            .build(),
+        openapi::tag::TagBuilder::new()
+            .name(IMPORT_TAG)
+            .description(Some("Bringing history in from Mint, YNAB and Actual Budget exports, and restoring Sumurai archives."))
+            .build(),
         openapi::tag::TagBuilder::new()
             .name(BUDGETS_TAG)
             .description(Some("Budget management APIs for CRUD operations tied to user-defined spending targets."))
             .build(),
+        openapi::tag::TagBuilder::new()
+            .name(BILLS_TAG)
+            .description(Some("Upcoming bill schedule and the subscribable calendar feed built from recurring charges and card statements."))
+            .build(),
+        openapi::tag::TagBuilder::new()
+            .name(NOTIFICATIONS_TAG)
+            .description(Some("In-app notification feed, read state, and per-event delivery preferences."))
+            .build(),
+        openapi::tag::TagBuilder::new()
+            .name(ALERTS_TAG)
+            .description(Some("User-defined alert rules evaluated after each sync, and the history of alerts they fired."))
+            .build(),
+        openapi::tag::TagBuilder::new()
+            .name(WEBHOOKS_TAG)
+            .description(Some("Outbound webhook endpoints subscribed to data events, their signed deliveries, and the delivery log."))
+            .build(),
+        openapi::tag::TagBuilder::new()
+            .name(SETTINGS_TAG)
+            .description(Some("Per-user preferences such as the reporting currency used for analytics totals."))
+            .build(),
         openapi::tag::TagBuilder::new()
             .name(HEALTH_TAG)
             .description(Some("Service health diagnostics for readiness and uptime monitoring."))
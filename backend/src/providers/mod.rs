@@ -6,4 +6,8 @@ pub mod trait_definition;
 pub use plaid_provider::PlaidProvider;
 pub use registry::ProviderRegistry;
 pub use teller_provider::TellerProvider;
-pub use trait_definition::{FinancialDataProvider, InstitutionInfo, ProviderCredentials};
+pub use trait_definition::{
+    FinancialDataProvider, InstitutionInfo, InvestmentActivity, InvestmentHoldings,
+    ProviderCredentials, ProviderHolding, ProviderInvestmentTransaction, ProviderLiability,
+    ProviderSecurity,
+};
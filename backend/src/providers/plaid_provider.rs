@@ -6,7 +6,8 @@ use uuid::Uuid;
 
 use crate::models::{account::Account, transaction::Transaction};
 use crate::providers::trait_definition::{
-    FinancialDataProvider, InstitutionInfo, ProviderCredentials,
+    FinancialDataProvider, InstitutionInfo, InvestmentActivity, InvestmentHoldings,
+    ProviderCredentials, ProviderLiability,
 };
 use crate::services::plaid_service::RealPlaidClient;
 
@@ -73,4 +74,31 @@ impl FinancialDataProvider for PlaidProvider {
             color: None,
         })
     }
+
+    async fn get_liabilities(
+        &self,
+        credentials: &ProviderCredentials,
+    ) -> Result<Vec<ProviderLiability>> {
+        self.client.get_liabilities(&credentials.access_token).await
+    }
+
+    async fn get_investment_holdings(
+        &self,
+        credentials: &ProviderCredentials,
+    ) -> Result<InvestmentHoldings> {
+        self.client
+            .get_investment_holdings(&credentials.access_token)
+            .await
+    }
+
+    async fn get_investment_transactions(
+        &self,
+        credentials: &ProviderCredentials,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<InvestmentActivity> {
+        self.client
+            .get_investment_transactions(&credentials.access_token, start_date, end_date)
+            .await
+    }
 }
@@ -41,11 +41,6 @@ impl ReqwestTellerClient {
             .build()?;
         Ok(Self { client })
     }
-
-    fn new_without_mtls() -> anyhow::Result<Self> {
-        let client = Client::builder().build()?;
-        Ok(Self { client })
-    }
 }
 
 #[async_trait]
@@ -281,7 +276,13 @@ impl FinancialDataProvider for TellerProvider {
                     }
                     false
                 })
-                .map(|t| Transaction::from_teller(t, &account.id, Some(account_id)))
+                .map(|t| {
+                    // Teller transactions carry no currency; they inherit the account's.
+                    let mut transaction =
+                        Transaction::from_teller(t, &account.id, Some(account_id));
+                    transaction.iso_currency_code = account.iso_currency_code.clone();
+                    transaction
+                })
                 .collect::<Vec<_>>();
 
             all_transactions.extend(transactions);
@@ -1,6 +1,7 @@
 use anyhow::Result;
 use async_trait::async_trait;
 use chrono::NaiveDate;
+use rust_decimal::Decimal;
 use uuid::Uuid;
 
 use crate::models::{account::Account, transaction::Transaction};
@@ -26,6 +27,34 @@ pub trait FinancialDataProvider: Send + Sync {
         &self,
         credentials: &ProviderCredentials,
     ) -> Result<InstitutionInfo>;
+
+    /// APR, payment and term details for credit cards and loans. Providers
+    /// without liability data report none.
+    async fn get_liabilities(
+        &self,
+        _credentials: &ProviderCredentials,
+    ) -> Result<Vec<ProviderLiability>> {
+        Ok(Vec::new())
+    }
+
+    /// Current positions in investment accounts along with the securities they
+    /// hold. Providers without investment data report none.
+    async fn get_investment_holdings(
+        &self,
+        _credentials: &ProviderCredentials,
+    ) -> Result<InvestmentHoldings> {
+        Ok(InvestmentHoldings::default())
+    }
+
+    /// Buys, sells, dividends and fees in investment accounts over a date range.
+    async fn get_investment_transactions(
+        &self,
+        _credentials: &ProviderCredentials,
+        _start_date: NaiveDate,
+        _end_date: NaiveDate,
+    ) -> Result<InvestmentActivity> {
+        Ok(InvestmentActivity::default())
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -44,3 +73,68 @@ pub struct InstitutionInfo {
     pub logo: Option<String>,
     pub color: Option<String>,
 }
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProviderLiability {
+    pub provider_account_id: String,
+    /// `credit`, `mortgage` or `student`, following Plaid's liability groups.
+    pub liability_type: String,
+    /// Annual percentage rate, e.g. `19.99`.
+    pub apr: Option<Decimal>,
+    pub minimum_payment: Option<Decimal>,
+    pub last_statement_balance: Option<Decimal>,
+    pub next_payment_due_date: Option<NaiveDate>,
+    pub loan_term_months: Option<i32>,
+    pub maturity_date: Option<NaiveDate>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProviderSecurity {
+    pub provider_security_id: String,
+    pub name: String,
+    pub ticker_symbol: Option<String>,
+    /// The provider's security type, e.g. `equity`, `etf` or `fixed income`.
+    pub security_type: String,
+    pub iso_currency_code: String,
+    pub close_price: Option<Decimal>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProviderHolding {
+    pub provider_account_id: String,
+    pub provider_security_id: String,
+    pub quantity: Decimal,
+    pub institution_price: Decimal,
+    pub institution_value: Decimal,
+    /// Total purchase cost of the position, when the institution reports it.
+    pub cost_basis: Option<Decimal>,
+    pub iso_currency_code: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProviderInvestmentTransaction {
+    pub provider_investment_transaction_id: String,
+    pub provider_account_id: String,
+    pub provider_security_id: Option<String>,
+    pub date: NaiveDate,
+    pub name: String,
+    pub transaction_type: String,
+    pub subtype: Option<String>,
+    pub quantity: Decimal,
+    pub price: Decimal,
+    pub amount: Decimal,
+    pub fees: Option<Decimal>,
+    pub iso_currency_code: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InvestmentHoldings {
+    pub securities: Vec<ProviderSecurity>,
+    pub holdings: Vec<ProviderHolding>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InvestmentActivity {
+    pub securities: Vec<ProviderSecurity>,
+    pub transactions: Vec<ProviderInvestmentTransaction>,
+}
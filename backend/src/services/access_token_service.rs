@@ -0,0 +1,147 @@
+use crate::models::access_token::{
+    CreateAccessTokenRequest, CreateAccessTokenResponse, PersonalAccessToken,
+};
+use crate::services::repository_service::DatabaseRepository;
+use chrono::{Duration, Utc};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+/// Marks a bearer token as a personal access token rather than a session JWT.
+pub const ACCESS_TOKEN_PREFIX: &str = "smr_pat_";
+
+/// Characters of the token kept in clear for display.
+const DISPLAY_PREFIX_LEN: usize = ACCESS_TOKEN_PREFIX.len() + 4;
+
+const MAX_NAME_LEN: usize = 100;
+
+/// Longest lifetime a token can be given, ten years.
+const MAX_EXPIRES_IN_DAYS: u32 = 3650;
+
+pub struct AccessTokenService;
+
+impl AccessTokenService {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Issues a token. Only its hash is stored, so the plain token is returned
+    /// exactly once.
+    pub async fn create<R: DatabaseRepository + ?Sized>(
+        &self,
+        repository: &R,
+        user_id: Uuid,
+        request: &CreateAccessTokenRequest,
+    ) -> Result<CreateAccessTokenResponse, String> {
+        let name = request.name.trim();
+        if name.is_empty() || name.chars().count() > MAX_NAME_LEN {
+            return Err(format!(
+                "Invalid token name: must be 1 to {} characters",
+                MAX_NAME_LEN
+            ));
+        }
+        if request.scopes.is_empty() {
+            return Err("Invalid scopes: at least one scope is required".to_string());
+        }
+        if request
+            .expires_in_days
+            .is_some_and(|days| days == 0 || days > MAX_EXPIRES_IN_DAYS)
+        {
+            return Err(format!(
+                "Invalid expires_in_days: must be 1 to {}",
+                MAX_EXPIRES_IN_DAYS
+            ));
+        }
+
+        let mut scopes = request.scopes.clone();
+        scopes.sort_by_key(|scope| scope.as_str());
+        scopes.dedup();
+
+        let bytes: [u8; 32] = rand::random();
+        let token = format!("{}{}", ACCESS_TOKEN_PREFIX, hex::encode(bytes));
+        let now = Utc::now();
+        let access_token = PersonalAccessToken {
+            id: Uuid::new_v4(),
+            user_id,
+            name: name.to_string(),
+            token_prefix: token[..DISPLAY_PREFIX_LEN].to_string(),
+            scopes,
+            expires_at: request
+                .expires_in_days
+                .map(|days| now + Duration::days(i64::from(days))),
+            last_used_at: None,
+            revoked_at: None,
+            created_at: now,
+        };
+        repository
+            .create_personal_access_token(&access_token, &Self::hash_token(&token))
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(CreateAccessTokenResponse {
+            token,
+            access_token,
+        })
+    }
+
+    pub async fn list<R: DatabaseRepository + ?Sized>(
+        &self,
+        repository: &R,
+        user_id: Uuid,
+    ) -> Result<Vec<PersonalAccessToken>, String> {
+        repository
+            .get_personal_access_tokens_for_user(&user_id)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn revoke<R: DatabaseRepository + ?Sized>(
+        &self,
+        repository: &R,
+        user_id: Uuid,
+        token_id: Uuid,
+    ) -> Result<bool, String> {
+        repository
+            .revoke_personal_access_token(&user_id, &token_id)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    /// Looks up an active token. Returns `None` for unknown, revoked or expired
+    /// tokens alike.
+    pub async fn authenticate<R: DatabaseRepository + ?Sized>(
+        &self,
+        repository: &R,
+        token: &str,
+    ) -> Result<Option<PersonalAccessToken>, String> {
+        let Some(access_token) = repository
+            .get_personal_access_token_by_hash(&Self::hash_token(token))
+            .await
+            .map_err(|e| e.to_string())?
+        else {
+            return Ok(None);
+        };
+        if !access_token.is_active(Utc::now()) {
+            return Ok(None);
+        }
+
+        if let Err(e) = repository
+            .touch_personal_access_token(&access_token.id)
+            .await
+        {
+            tracing::warn!(
+                "Failed to record use of access token {}: {}",
+                access_token.id,
+                e
+            );
+        }
+        Ok(Some(access_token))
+    }
+
+    pub fn is_access_token(token: &str) -> bool {
+        token.starts_with(ACCESS_TOKEN_PREFIX)
+    }
+
+    pub fn hash_token(token: &str) -> String {
+        hex::encode(Sha256::digest(token.as_bytes()))
+    }
+}
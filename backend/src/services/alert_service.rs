@@ -0,0 +1,573 @@
+use crate::models::account::Account;
+use crate::models::alert::{
+    AlertHistoryEntry, AlertRule, AlertRuleRequest, AlertRuleType, FiredAlert,
+    DEFAULT_ALERT_COOLDOWN_HOURS, DEFAULT_BUDGET_ALERT_PERCENTAGES,
+};
+use crate::models::analytics::BalanceCategory;
+use crate::models::budget::Budget;
+use crate::models::notification::NotificationEvent;
+use crate::models::saved_view::ViewMatches;
+use crate::models::transaction::Transaction;
+use crate::models::user_settings::UserCalendar;
+use crate::services::analytics_service::AnalyticsService;
+use crate::services::notification_service::NotificationService;
+use crate::services::recurring_service::RecurringService;
+use crate::services::repository_service::DatabaseRepository;
+use crate::services::saved_view_service::SavedViewService;
+use crate::services::user_settings_service::UserSettingsService;
+use chrono::{DateTime, Days, Duration, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Transactions posted within this window are checked by transaction rules.
+const RECENT_TRANSACTION_DAYS: u64 = 7;
+/// History used to decide whether a merchant has been seen before.
+const MERCHANT_HISTORY_DAYS: u64 = 365;
+const MAX_COOLDOWN_HOURS: i32 = 24 * 365;
+
+/// Data a sync leaves behind that rules are evaluated against.
+pub struct AlertInputs<'a> {
+    pub today: NaiveDate,
+    /// Decides which month budget rules measure spending against.
+    pub calendar: UserCalendar,
+    pub transactions: &'a [Transaction],
+    pub budgets: &'a [Budget],
+    pub accounts: &'a [Account],
+    /// Matches of the saved views that rules and budgets point at.
+    pub views: &'a ViewMatches,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlertCandidate {
+    pub rule_id: Uuid,
+    pub rule_type: AlertRuleType,
+    pub dedup_key: String,
+    /// Persistent conditions (a low balance) are reported again once the cooldown has
+    /// passed; one-off events (a transaction, a budget month) are reported once.
+    pub repeatable: bool,
+    pub title: String,
+    pub body: String,
+}
+
+pub struct AlertService {
+    notification_service: Arc<NotificationService>,
+}
+
+impl AlertService {
+    pub fn new(notification_service: Arc<NotificationService>) -> Self {
+        Self {
+            notification_service,
+        }
+    }
+
+    pub async fn list_rules<R: DatabaseRepository + ?Sized>(
+        &self,
+        repository: &R,
+        user_id: Uuid,
+    ) -> Result<Vec<AlertRule>, String> {
+        repository
+            .get_alert_rules_for_user(&user_id)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn create_rule<R: DatabaseRepository + ?Sized>(
+        &self,
+        repository: &R,
+        user_id: Uuid,
+        request: &AlertRuleRequest,
+    ) -> Result<AlertRule, String> {
+        let rule = Self::build_rule(Uuid::new_v4(), user_id, request, Utc::now())?;
+        SavedViewService::ensure_owned(repository, user_id, rule.saved_view_id).await?;
+        repository
+            .create_alert_rule(&rule)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn update_rule<R: DatabaseRepository + ?Sized>(
+        &self,
+        repository: &R,
+        user_id: Uuid,
+        rule_id: Uuid,
+        request: &AlertRuleRequest,
+    ) -> Result<AlertRule, String> {
+        let rule = Self::build_rule(rule_id, user_id, request, Utc::now())?;
+        SavedViewService::ensure_owned(repository, user_id, rule.saved_view_id).await?;
+        repository
+            .update_alert_rule(&rule)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "Alert rule not found".to_string())
+    }
+
+    pub async fn delete_rule<R: DatabaseRepository + ?Sized>(
+        &self,
+        repository: &R,
+        user_id: Uuid,
+        rule_id: Uuid,
+    ) -> Result<(), String> {
+        let deleted = repository
+            .delete_alert_rule(&user_id, &rule_id)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        if deleted {
+            Ok(())
+        } else {
+            Err("Alert rule not found".to_string())
+        }
+    }
+
+    /// Validates a request and fills in per-type defaults.
+    pub fn build_rule(
+        id: Uuid,
+        user_id: Uuid,
+        request: &AlertRuleRequest,
+        now: DateTime<Utc>,
+    ) -> Result<AlertRule, String> {
+        let cooldown_hours = request
+            .cooldown_hours
+            .unwrap_or(DEFAULT_ALERT_COOLDOWN_HOURS);
+        if !(0..=MAX_COOLDOWN_HOURS).contains(&cooldown_hours) {
+            return Err(format!(
+                "cooldown_hours must be between 0 and {}",
+                MAX_COOLDOWN_HOURS
+            ));
+        }
+
+        let (threshold, percentages) = match request.rule_type {
+            AlertRuleType::BudgetThreshold => {
+                let mut percentages = request
+                    .percentages
+                    .clone()
+                    .unwrap_or_else(|| DEFAULT_BUDGET_ALERT_PERCENTAGES.to_vec());
+                if percentages.is_empty() || percentages.iter().any(|p| !(1..=1000).contains(p)) {
+                    return Err("percentages must be between 1 and 1000".to_string());
+                }
+                percentages.sort_unstable();
+                percentages.dedup();
+                (None, percentages)
+            }
+            AlertRuleType::LargeTransaction | AlertRuleType::NewMerchant => {
+                match request.threshold {
+                    Some(threshold) if threshold > Decimal::ZERO => (Some(threshold), Vec::new()),
+                    _ => return Err("threshold must be greater than zero".to_string()),
+                }
+            }
+            AlertRuleType::LowBalance => match request.threshold {
+                Some(_) if request.saved_view_id.is_some() => {
+                    return Err("saved_view_id must not be set for low balance alerts".to_string())
+                }
+                Some(threshold) => (Some(threshold), Vec::new()),
+                None => return Err("threshold is required for low balance alerts".to_string()),
+            },
+        };
+
+        Ok(AlertRule {
+            id,
+            user_id,
+            rule_type: request.rule_type.as_str().to_string(),
+            threshold,
+            percentages,
+            account_id: request.account_id,
+            category: request
+                .category
+                .as_ref()
+                .map(|c| c.trim().to_string())
+                .filter(|c| !c.is_empty()),
+            saved_view_id: request.saved_view_id,
+            cooldown_hours,
+            enabled: request.enabled.unwrap_or(true),
+            created_at: now,
+            updated_at: now,
+        })
+    }
+
+    /// Runs every enabled rule against the user's current data, records the alerts that
+    /// pass dedup and cooldown, and publishes them as notifications.
+    pub async fn evaluate_for_user<R: DatabaseRepository + ?Sized>(
+        &self,
+        repository: &R,
+        user_id: Uuid,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<FiredAlert>, String> {
+        let rules: Vec<AlertRule> = repository
+            .get_alert_rules_for_user(&user_id)
+            .await
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .filter(|r| r.enabled)
+            .collect();
+        if rules.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let calendar = UserSettingsService::calendar(repository, user_id).await?;
+        let today = calendar.local_date(now);
+        let month_start = Self::month_start(&calendar, today);
+        let has_rule = |kind: AlertRuleType| rules.iter().any(|r| r.kind() == Some(kind));
+
+        let history_days = if has_rule(AlertRuleType::NewMerchant) {
+            MERCHANT_HISTORY_DAYS
+        } else {
+            let month_days = (today - month_start).num_days().unsigned_abs();
+            RECENT_TRANSACTION_DAYS.max(month_days)
+        };
+        let history_start = today
+            .checked_sub_days(Days::new(history_days))
+            .unwrap_or(today);
+        let mut transactions = repository
+            .get_transactions_by_date_range_for_user(&user_id, history_start, today)
+            .await
+            .map_err(|e| e.to_string())?;
+        transactions.retain(|t| !t.excluded_from_analytics);
+        let budgets = if has_rule(AlertRuleType::BudgetThreshold) {
+            repository
+                .get_budgets_for_user(user_id)
+                .await
+                .map_err(|e| e.to_string())?
+        } else {
+            Vec::new()
+        };
+        let accounts = if has_rule(AlertRuleType::LowBalance) {
+            repository
+                .get_accounts_for_user(&user_id)
+                .await
+                .map_err(|e| e.to_string())?
+        } else {
+            Vec::new()
+        };
+        let view_ids: Vec<Uuid> = rules
+            .iter()
+            .filter_map(|r| r.saved_view_id)
+            .chain(budgets.iter().filter_map(|b| b.saved_view_id))
+            .collect();
+        let views = if view_ids.is_empty() {
+            ViewMatches::default()
+        } else {
+            SavedViewService::matching_transaction_ids(
+                repository,
+                user_id,
+                &view_ids,
+                &calendar,
+                (history_start, today),
+            )
+            .await?
+        };
+        let history = repository
+            .get_alert_history_for_user(&user_id)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let inputs = AlertInputs {
+            today,
+            calendar,
+            transactions: &transactions,
+            budgets: &budgets,
+            accounts: &accounts,
+            views: &views,
+        };
+        let cooldowns: HashMap<Uuid, i32> =
+            rules.iter().map(|r| (r.id, r.cooldown_hours)).collect();
+
+        let mut fired = Vec::new();
+        for candidate in Self::evaluate_rules(&rules, &inputs) {
+            let cooldown_hours = cooldowns.get(&candidate.rule_id).copied().unwrap_or(0);
+            if !Self::should_fire(&candidate, cooldown_hours, &history, now) {
+                continue;
+            }
+
+            let alert = FiredAlert {
+                id: Uuid::new_v4(),
+                rule_id: candidate.rule_id,
+                rule_type: candidate.rule_type.as_str().to_string(),
+                dedup_key: candidate.dedup_key.clone(),
+                title: candidate.title.clone(),
+                body: candidate.body.clone(),
+                fired_at: now,
+            };
+            let refire_before = candidate
+                .repeatable
+                .then(|| now - Duration::hours(i64::from(cooldown_hours)));
+            let inserted = repository
+                .insert_fired_alert(&user_id, &alert, refire_before)
+                .await
+                .map_err(|e| e.to_string())?;
+            if !inserted {
+                // Another evaluation fired it between reading history and now.
+                continue;
+            }
+
+            let event = NotificationEvent {
+                user_id,
+                event_type: candidate.rule_type.notification_event_type(),
+                title: candidate.title,
+                body: candidate.body,
+            };
+            if let Err(e) = self.notification_service.publish(repository, event).await {
+                tracing::warn!(
+                    "Failed to publish alert {} for user {}: {}",
+                    alert.id,
+                    user_id,
+                    e
+                );
+            }
+
+            fired.push(alert);
+        }
+
+        Ok(fired)
+    }
+
+    pub fn evaluate_rules(rules: &[AlertRule], inputs: &AlertInputs) -> Vec<AlertCandidate> {
+        rules
+            .iter()
+            .filter(|rule| rule.enabled)
+            .flat_map(|rule| match rule.kind() {
+                Some(AlertRuleType::BudgetThreshold) => Self::budget_candidates(rule, inputs),
+                Some(AlertRuleType::LargeTransaction) => {
+                    Self::large_transaction_candidates(rule, inputs)
+                }
+                Some(AlertRuleType::LowBalance) => Self::low_balance_candidates(rule, inputs),
+                Some(AlertRuleType::NewMerchant) => Self::new_merchant_candidates(rule, inputs),
+                None => Vec::new(),
+            })
+            .collect()
+    }
+
+    pub fn should_fire(
+        candidate: &AlertCandidate,
+        cooldown_hours: i32,
+        history: &[AlertHistoryEntry],
+        now: DateTime<Utc>,
+    ) -> bool {
+        let last_fired = history
+            .iter()
+            .find(|h| h.rule_id == candidate.rule_id && h.dedup_key == candidate.dedup_key)
+            .map(|h| h.last_fired_at);
+
+        match last_fired {
+            None => true,
+            Some(_) if !candidate.repeatable => false,
+            Some(last) => now - last >= Duration::hours(i64::from(cooldown_hours)),
+        }
+    }
+
+    fn budget_candidates(rule: &AlertRule, inputs: &AlertInputs) -> Vec<AlertCandidate> {
+        let month_start = Self::month_start(&inputs.calendar, inputs.today);
+        let month_key = inputs.calendar.month_key(inputs.today);
+
+        inputs
+            .budgets
+            .iter()
+            .filter(|budget| budget.amount > Decimal::ZERO)
+            .filter(|budget| {
+                rule.category.as_deref().is_none_or(|category| {
+                    Self::category_key(category) == Self::category_key(&budget.category)
+                })
+            })
+            .filter_map(|budget| {
+                let spent: Decimal = inputs
+                    .transactions
+                    .iter()
+                    .filter(|t| t.date >= month_start && t.date <= inputs.today)
+                    .filter(|t| Self::budget_includes(budget, t, inputs.views))
+                    .filter(|t| inputs.views.allows(rule.saved_view_id, t.id))
+                    .map(|t| t.amount)
+                    .sum();
+                let used_percent = spent * Decimal::from(100) / budget.amount;
+                let crossed = rule
+                    .percentages
+                    .iter()
+                    .copied()
+                    .filter(|p| used_percent >= Decimal::from(*p))
+                    .max()?;
+
+                Some(AlertCandidate {
+                    rule_id: rule.id,
+                    rule_type: AlertRuleType::BudgetThreshold,
+                    dedup_key: format!("budget:{}:{}:{}", budget.id, month_key, crossed),
+                    repeatable: false,
+                    title: format!("{} budget at {}%", budget.category, crossed),
+                    body: format!(
+                        "You have spent {:.2} of your {:.2} {} budget for {} ({}%).",
+                        spent,
+                        budget.amount,
+                        budget.category,
+                        month_key,
+                        used_percent.round()
+                    ),
+                })
+            })
+            .collect()
+    }
+
+    fn large_transaction_candidates(rule: &AlertRule, inputs: &AlertInputs) -> Vec<AlertCandidate> {
+        let Some(threshold) = rule.threshold else {
+            return Vec::new();
+        };
+
+        Self::recent_spending(inputs)
+            .filter(|t| t.amount > threshold)
+            .filter(|t| inputs.views.allows(rule.saved_view_id, t.id))
+            .map(|t| {
+                let merchant = Self::merchant_label(t);
+                AlertCandidate {
+                    rule_id: rule.id,
+                    rule_type: AlertRuleType::LargeTransaction,
+                    dedup_key: format!("transaction:{}", t.id),
+                    repeatable: false,
+                    title: format!("Large transaction at {}", merchant),
+                    body: format!(
+                        "A {:.2} charge at {} on {} is above your {:.2} alert.",
+                        t.amount, merchant, t.date, threshold
+                    ),
+                }
+            })
+            .collect()
+    }
+
+    fn low_balance_candidates(rule: &AlertRule, inputs: &AlertInputs) -> Vec<AlertCandidate> {
+        let Some(threshold) = rule.threshold else {
+            return Vec::new();
+        };
+
+        inputs
+            .accounts
+            .iter()
+            .filter(|account| match rule.account_id {
+                Some(account_id) => account.id == account_id,
+                None => {
+                    AnalyticsService::map_account_to_balance_category(&account.account_type, None)
+                        == BalanceCategory::Cash
+                }
+            })
+            .filter_map(|account| {
+                let balance = account.balance_current?;
+                (balance < threshold).then(|| AlertCandidate {
+                    rule_id: rule.id,
+                    rule_type: AlertRuleType::LowBalance,
+                    dedup_key: format!("balance:{}", account.id),
+                    repeatable: true,
+                    title: format!("Low balance in {}", account.name),
+                    body: format!(
+                        "{} has a balance of {:.2}, below your {:.2} alert.",
+                        account.name, balance, threshold
+                    ),
+                })
+            })
+            .collect()
+    }
+
+    fn new_merchant_candidates(rule: &AlertRule, inputs: &AlertInputs) -> Vec<AlertCandidate> {
+        let Some(threshold) = rule.threshold else {
+            return Vec::new();
+        };
+
+        let mut first_seen: HashMap<String, NaiveDate> = HashMap::new();
+        for transaction in inputs.transactions {
+            if let Some(key) = Self::merchant_key(transaction) {
+                first_seen
+                    .entry(key)
+                    .and_modify(|d| *d = (*d).min(transaction.date))
+                    .or_insert(transaction.date);
+            }
+        }
+
+        Self::recent_spending(inputs)
+            .filter(|t| t.amount > threshold)
+            .filter(|t| inputs.views.allows(rule.saved_view_id, t.id))
+            .filter(|t| {
+                Self::merchant_key(t)
+                    .and_then(|key| first_seen.get(&key))
+                    .is_some_and(|first| *first == t.date)
+            })
+            .map(|t| {
+                let merchant = Self::merchant_label(t);
+                AlertCandidate {
+                    rule_id: rule.id,
+                    rule_type: AlertRuleType::NewMerchant,
+                    dedup_key: format!("transaction:{}", t.id),
+                    repeatable: false,
+                    title: format!("New merchant: {}", merchant),
+                    body: format!(
+                        "First charge from {}: {:.2} on {}, above your {:.2} alert.",
+                        merchant, t.amount, t.date, threshold
+                    ),
+                }
+            })
+            .collect()
+    }
+
+    /// First day of the user's month containing `today`.
+    fn month_start(calendar: &UserCalendar, today: NaiveDate) -> NaiveDate {
+        let (year, month) = calendar.month_of(today);
+        calendar
+            .month_range(year, month)
+            .map_or(today, |(start, _)| start)
+    }
+
+    fn recent_spending<'a>(inputs: &'a AlertInputs) -> impl Iterator<Item = &'a Transaction> {
+        let since = inputs
+            .today
+            .checked_sub_days(Days::new(RECENT_TRANSACTION_DAYS))
+            .unwrap_or(inputs.today);
+        inputs
+            .transactions
+            .iter()
+            .filter(move |t| t.date >= since && !t.pending)
+            .filter(|t| !AnalyticsService::is_income_transaction(t))
+    }
+
+    fn merchant_key(transaction: &Transaction) -> Option<String> {
+        transaction
+            .merchant_name
+            .as_deref()
+            .map(RecurringService::normalize_merchant)
+            .filter(|k| !k.is_empty())
+    }
+
+    fn merchant_label(transaction: &Transaction) -> String {
+        transaction
+            .merchant_name
+            .clone()
+            .unwrap_or_else(|| "an unknown merchant".to_string())
+    }
+
+    /// Whether a transaction counts toward a budget: the budget's saved view when
+    /// it has one, otherwise its category.
+    pub fn budget_includes(
+        budget: &Budget,
+        transaction: &Transaction,
+        views: &ViewMatches,
+    ) -> bool {
+        match budget.saved_view_id {
+            Some(view_id) => views.allows(Some(view_id), transaction.id),
+            None => {
+                Self::category_key(&transaction.category_primary)
+                    == Self::category_key(&budget.category)
+            }
+        }
+    }
+
+    /// Budgets may be named after the raw category ("FOOD_AND_DRINK") or its display
+    /// form ("Food and drink"); both compare equal.
+    pub fn category_key(category: &str) -> String {
+        category.trim().replace('_', " ").to_lowercase()
+    }
+
+    pub async fn list_fired<R: DatabaseRepository + ?Sized>(
+        &self,
+        repository: &R,
+        user_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<FiredAlert>, String> {
+        repository
+            .get_fired_alerts_for_user(&user_id, limit)
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
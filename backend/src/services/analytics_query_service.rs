@@ -0,0 +1,316 @@
+use crate::models::analytics_query::{
+    AnalyticsQueryRequest, AnalyticsQueryResponse, AnalyticsQueryRow, QueryDimension, QueryFlow,
+    QueryMeasure, QueryRates,
+};
+use crate::services::currency_service::{CurrencyService, FxConverter};
+use crate::services::repository_service::DatabaseRepository;
+use chrono::NaiveDate;
+use sqlx::{postgres::PgRow, Postgres, QueryBuilder, Row};
+use std::collections::{BTreeMap, HashSet};
+use uuid::Uuid;
+
+/// Mirrors `AnalyticsService::is_income_transaction`.
+const INCOME_PREDICATE: &str =
+    "(t.amount < 0 OR UPPER(t.category_primary) IN ('INCOME', 'TRANSFER_IN', 'DEPOSIT'))";
+/// The amount in the reporting currency; `fx` has no row for amounts already in it.
+const AMOUNT_SQL: &str = "(t.amount * COALESCE(fx.rate, 1))";
+
+pub struct AnalyticsQueryService;
+
+impl AnalyticsQueryService {
+    pub async fn run<R: DatabaseRepository + ?Sized>(
+        repository: &R,
+        user_id: Uuid,
+        request: &AnalyticsQueryRequest,
+        account_ids: &[Uuid],
+    ) -> Result<AnalyticsQueryResponse, String> {
+        request.validate()?;
+
+        let reporting_currency = CurrencyService::reporting_currency(repository, user_id).await?;
+        let days = repository
+            .get_analytics_query_currency_days(&user_id, request, account_ids, &reporting_currency)
+            .await
+            .map_err(|e| e.to_string())?;
+        let currencies: HashSet<String> = days.iter().map(|(c, _)| c.clone()).collect();
+        let until = days
+            .iter()
+            .map(|(_, d)| *d)
+            .max()
+            .unwrap_or_else(|| chrono::Utc::now().date_naive());
+        let converter =
+            CurrencyService::load_converter(repository, &reporting_currency, &currencies, until)
+                .await?;
+        let rates = Self::query_rates(&converter, &days);
+        if rates.rates.len() < days.len() {
+            tracing::warn!(
+                "Left {} days of transactions out of an analytics query for user {} with no FX rate into {}",
+                days.len() - rates.rates.len(),
+                user_id,
+                reporting_currency
+            );
+        }
+
+        let limit = request.effective_limit() as usize;
+        let mut rows = repository
+            .run_analytics_query(&user_id, request, account_ids, &rates)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let truncated = rows.len() > limit;
+        rows.truncate(limit);
+        Ok(AnalyticsQueryResponse { rows, truncated })
+    }
+
+    /// The rate from each `(currency, day)` into the converter's target, for
+    /// the days that have one.
+    pub fn query_rates(converter: &FxConverter, days: &[(String, NaiveDate)]) -> QueryRates {
+        let mut rates = QueryRates::new(converter.target());
+        for (currency, date) in days {
+            if let Some(rate) = converter.rate(currency, converter.target(), *date) {
+                rates.push(currency, *date, rate);
+            }
+        }
+        rates
+    }
+
+    /// The distinct currencies and days of the transactions a query matches
+    /// that are not already in `reporting_currency`.
+    pub fn build_currency_days_sql(
+        user_id: Uuid,
+        request: &AnalyticsQueryRequest,
+        account_ids: &[Uuid],
+        reporting_currency: &str,
+    ) -> QueryBuilder<'static, Postgres> {
+        let mut builder =
+            QueryBuilder::new("SELECT DISTINCT t.iso_currency_code, t.date FROM transactions t");
+        Self::push_filters(&mut builder, user_id, request, account_ids);
+        builder
+            .push(" AND t.iso_currency_code <> ")
+            .push_bind(reporting_currency.to_string());
+        builder
+    }
+
+    /// Builds the aggregation over amounts converted with `rates`. Only
+    /// whitelisted expressions are spliced into the SQL; every value from the
+    /// request is bound as a parameter. One row more than the limit is fetched
+    /// so callers can tell the result was cut.
+    pub fn build_sql(
+        user_id: Uuid,
+        request: &AnalyticsQueryRequest,
+        account_ids: &[Uuid],
+        rates: &QueryRates,
+    ) -> QueryBuilder<'static, Postgres> {
+        let filters = &request.filters;
+        let measures = request.effective_measures();
+        let mut builder = QueryBuilder::new("SELECT ");
+
+        for (i, dimension) in request.group_by.iter().enumerate() {
+            builder.push(format!("{} AS g{}, ", Self::dimension_sql(*dimension), i));
+        }
+        builder
+            .push_bind(rates.reporting_currency.clone())
+            .push("::text AS currency");
+        for measure in &measures {
+            builder.push(format!(
+                ", {} AS {}",
+                Self::measure_sql(*measure),
+                measure.as_str()
+            ));
+        }
+
+        builder
+            .push(" FROM transactions t LEFT JOIN unnest(")
+            .push_bind(rates.currencies.clone())
+            .push("::text[], ")
+            .push_bind(rates.dates.clone())
+            .push("::date[], ")
+            .push_bind(rates.rates.clone())
+            .push(
+                "::numeric[]) AS fx(currency, date, rate) \
+                 ON fx.currency = t.iso_currency_code AND fx.date = t.date",
+            );
+        if request.group_by.contains(&QueryDimension::Institution) {
+            builder.push(
+                " JOIN accounts a ON a.id = t.account_id \
+                 LEFT JOIN provider_connections pc ON pc.id = a.provider_connection_id",
+            );
+        }
+        if request.group_by.contains(&QueryDimension::Tag) {
+            builder.push(" LEFT JOIN transaction_tags tt ON tt.transaction_id = t.id");
+        }
+
+        Self::push_filters(&mut builder, user_id, request, account_ids);
+        builder
+            .push(" AND (t.iso_currency_code = ")
+            .push_bind(rates.reporting_currency.clone())
+            .push(" OR fx.rate IS NOT NULL)");
+        if let Some(min_amount) = filters.min_amount {
+            builder
+                .push(format!(" AND {} >= ", AMOUNT_SQL))
+                .push_bind(min_amount);
+        }
+        if let Some(max_amount) = filters.max_amount {
+            builder
+                .push(format!(" AND {} <= ", AMOUNT_SQL))
+                .push_bind(max_amount);
+        }
+
+        if request.group_by.is_empty() {
+            // Without groups the aggregate would return a row even when nothing matched.
+            builder.push(" HAVING COUNT(*) > 0");
+        } else {
+            let positions: Vec<String> = (1..=request.group_by.len())
+                .map(|p| p.to_string())
+                .collect();
+            builder.push(format!(" GROUP BY {}", positions.join(", ")));
+        }
+        builder.push(format!(" ORDER BY {}", Self::order_sql(request, &measures)));
+        builder
+            .push(" LIMIT ")
+            .push_bind(i64::from(request.effective_limit()) + 1);
+
+        builder
+    }
+
+    /// The `WHERE` clause shared by the aggregation and the currency days it
+    /// needs rates for; amount bounds apply to converted amounts, so they are
+    /// left to the aggregation.
+    fn push_filters(
+        builder: &mut QueryBuilder<'static, Postgres>,
+        user_id: Uuid,
+        request: &AnalyticsQueryRequest,
+        account_ids: &[Uuid],
+    ) {
+        let filters = &request.filters;
+        builder.push(" WHERE NOT t.excluded_from_analytics AND t.user_id = ");
+        builder.push_bind(user_id);
+        if let Some(start_date) = filters.start_date {
+            builder.push(" AND t.date >= ").push_bind(start_date);
+        }
+        if let Some(end_date) = filters.end_date {
+            builder.push(" AND t.date <= ").push_bind(end_date);
+        }
+        if !account_ids.is_empty() {
+            builder
+                .push(" AND t.account_id = ANY(")
+                .push_bind(account_ids.to_vec())
+                .push(")");
+        }
+        if !filters.categories.is_empty() {
+            builder
+                .push(" AND t.category_primary = ANY(")
+                .push_bind(filters.categories.clone())
+                .push(")");
+        }
+        if !filters.merchants.is_empty() {
+            builder
+                .push(" AND t.merchant_name = ANY(")
+                .push_bind(filters.merchants.clone())
+                .push(")");
+        }
+        if !filters.tags.is_empty() {
+            builder
+                .push(
+                    " AND EXISTS (SELECT 1 FROM transaction_tags ft \
+                     WHERE ft.transaction_id = t.id AND ft.tag = ANY(",
+                )
+                .push_bind(filters.tags.clone())
+                .push("))");
+        }
+        match filters.flow {
+            Some(QueryFlow::Spending) => {
+                builder.push(format!(" AND NOT {}", INCOME_PREDICATE));
+            }
+            Some(QueryFlow::Income) => {
+                builder.push(format!(" AND {}", INCOME_PREDICATE));
+            }
+            None => {}
+        }
+    }
+
+    pub fn decode_row(
+        row: &PgRow,
+        request: &AnalyticsQueryRequest,
+    ) -> Result<AnalyticsQueryRow, sqlx::Error> {
+        let mut group = BTreeMap::new();
+        for (i, dimension) in request.group_by.iter().enumerate() {
+            group.insert(
+                dimension.as_str().to_string(),
+                row.try_get::<String, _>(format!("g{}", i).as_str())?,
+            );
+        }
+
+        let mut decoded = AnalyticsQueryRow {
+            group,
+            currency: row.try_get("currency")?,
+            sum: None,
+            count: None,
+            avg: None,
+            median: None,
+        };
+        for measure in request.effective_measures() {
+            match measure {
+                QueryMeasure::Sum => decoded.sum = row.try_get("sum")?,
+                QueryMeasure::Count => decoded.count = row.try_get("count")?,
+                QueryMeasure::Avg => decoded.avg = row.try_get("avg")?,
+                QueryMeasure::Median => decoded.median = row.try_get("median")?,
+            }
+        }
+        Ok(decoded)
+    }
+
+    fn dimension_sql(dimension: QueryDimension) -> &'static str {
+        match dimension {
+            QueryDimension::Category => "COALESCE(NULLIF(t.category_primary, ''), 'Uncategorized')",
+            QueryDimension::Merchant => "COALESCE(t.merchant_name, 'Unknown Merchant')",
+            QueryDimension::Account => "t.account_id::text",
+            QueryDimension::Institution => "COALESCE(pc.institution_name, 'Unknown Institution')",
+            QueryDimension::Day => "to_char(t.date, 'YYYY-MM-DD')",
+            QueryDimension::Week => "to_char(date_trunc('week', t.date), 'YYYY-MM-DD')",
+            QueryDimension::Month => "to_char(t.date, 'YYYY-MM')",
+            QueryDimension::Weekday => "to_char(t.date, 'FMDay')",
+            QueryDimension::Tag => "COALESCE(tt.tag, 'Untagged')",
+        }
+    }
+
+    fn measure_sql(measure: QueryMeasure) -> String {
+        match measure {
+            QueryMeasure::Sum => format!("ROUND(SUM({}), 2)", AMOUNT_SQL),
+            QueryMeasure::Count => "COUNT(*)".to_string(),
+            QueryMeasure::Avg => format!("ROUND(AVG({}), 2)", AMOUNT_SQL),
+            QueryMeasure::Median => format!(
+                "ROUND(percentile_cont(0.5) WITHIN GROUP (ORDER BY {})::numeric, 2)",
+                AMOUNT_SQL
+            ),
+        }
+    }
+
+    /// Rows led by a time dimension come back in calendar order; anything else
+    /// is ranked by the first measure, largest first.
+    fn order_sql(request: &AnalyticsQueryRequest, measures: &[QueryMeasure]) -> String {
+        let by_position = |dimensions: &[QueryDimension], offset: usize| -> Vec<String> {
+            dimensions
+                .iter()
+                .enumerate()
+                .map(|(i, dimension)| match dimension {
+                    QueryDimension::Weekday => "MIN(EXTRACT(ISODOW FROM t.date))".to_string(),
+                    _ => (i + offset + 1).to_string(),
+                })
+                .collect()
+        };
+
+        let mut order = Vec::new();
+        match request.group_by.first() {
+            Some(first) if first.is_time() => {
+                order.extend(by_position(&request.group_by[..1], 0));
+                order.push(format!("{} DESC NULLS LAST", measures[0].as_str()));
+                order.extend(by_position(&request.group_by[1..], 1));
+            }
+            _ => {
+                order.push(format!("{} DESC NULLS LAST", measures[0].as_str()));
+                order.extend(by_position(&request.group_by, 0));
+            }
+        }
+        order.join(", ")
+    }
+}
@@ -1,8 +1,13 @@
 use crate::models::analytics::{
-    BalanceCategory, CategorySpending, DailySpending, MonthlySpending, TopMerchant,
+    AggregateTotal, BalanceCategory, CategorySpending, DailySpending, MonthlySpending, TopMerchant,
+};
+use crate::models::comparison::{
+    ComparisonBaseline, ComparisonLine, PeriodComparisonQuery, PeriodComparisonResponse,
+    PeriodRange,
 };
 use crate::models::transaction::Transaction;
-use chrono::Datelike;
+use crate::models::user_settings::UserCalendar;
+use chrono::Months;
 use rust_decimal::Decimal;
 
 pub struct AnalyticsService;
@@ -63,6 +68,44 @@ impl AnalyticsService {
         Self
     }
 
+    /// Income is anything flowing into an account: negative amounts under the Plaid sign
+    /// convention, or inflow categories for providers that only report absolute amounts.
+    pub fn is_income_transaction(transaction: &Transaction) -> bool {
+        transaction.amount < Decimal::ZERO
+            || Self::is_income_category(&transaction.category_primary)
+    }
+
+    pub fn is_income_category(category: &str) -> bool {
+        const INCOME_CATEGORIES: [&str; 3] = ["INCOME", "TRANSFER_IN", "DEPOSIT"];
+
+        INCOME_CATEGORIES
+            .iter()
+            .any(|c| category.eq_ignore_ascii_case(c))
+    }
+
+    /// Categories that move money between the user's own accounts or pay down a debt,
+    /// so they are neither new money nor new spending.
+    pub fn is_transfer_category(category: &str) -> bool {
+        const TRANSFER_CATEGORIES: [&str; 3] = ["TRANSFER_IN", "TRANSFER_OUT", "LOAN_PAYMENTS"];
+
+        TRANSFER_CATEGORIES
+            .iter()
+            .any(|c| category.eq_ignore_ascii_case(c))
+    }
+
+    /// Parses a `YYYY-MM` month key into its year and month parts. Years are
+    /// limited to four digits.
+    pub fn parse_month_key(month: &str) -> Option<(i32, u32)> {
+        let (year, month) = month.split_once('-')?;
+        let year = year.parse::<i32>().ok()?;
+        let month = month.parse::<u32>().ok()?;
+        if (1900..=9999).contains(&year) && (1..=12).contains(&month) {
+            Some((year, month))
+        } else {
+            None
+        }
+    }
+
     fn get_previous_month_info(year: i32, month: u32) -> (i32, u32) {
         if month == 1 {
             (year - 1, 12)
@@ -78,68 +121,26 @@ impl AnalyticsService {
         (new_year, (new_month0 + 1) as u32)
     }
 
-    pub fn get_period_date_range(period: &str) -> Option<(chrono::NaiveDate, chrono::NaiveDate)> {
-        use chrono::Datelike;
-        let now = chrono::Utc::now().naive_utc().date();
-        let year = now.year();
-        let month = now.month();
-
-        match period {
-            "current-month" => Some(Self::get_month_range_static(year, month)),
-            "past-2-months" => {
-                let (sy, sm) = Self::months_back(year, month, 1);
-                Some((
-                    chrono::NaiveDate::from_ymd_opt(sy, sm, 1).unwrap(),
-                    // end of current month
-                    if month == 12 {
-                        chrono::NaiveDate::from_ymd_opt(year + 1, 1, 1)
-                            .unwrap()
-                            .pred_opt()
-                            .unwrap()
-                    } else {
-                        chrono::NaiveDate::from_ymd_opt(year, month + 1, 1)
-                            .unwrap()
-                            .pred_opt()
-                            .unwrap()
-                    },
-                ))
-            }
-            "past-6-months" => {
-                let (sy, sm) = Self::months_back(year, month, 5);
-                Some((
-                    chrono::NaiveDate::from_ymd_opt(sy, sm, 1).unwrap(),
-                    if month == 12 {
-                        chrono::NaiveDate::from_ymd_opt(year + 1, 1, 1)
-                            .unwrap()
-                            .pred_opt()
-                            .unwrap()
-                    } else {
-                        chrono::NaiveDate::from_ymd_opt(year, month + 1, 1)
-                            .unwrap()
-                            .pred_opt()
-                            .unwrap()
-                    },
-                ))
-            }
-            "past-year" => {
-                let (sy, sm) = Self::months_back(year, month, 11);
-                Some((
-                    chrono::NaiveDate::from_ymd_opt(sy, sm, 1).unwrap(),
-                    if month == 12 {
-                        chrono::NaiveDate::from_ymd_opt(year + 1, 1, 1)
-                            .unwrap()
-                            .pred_opt()
-                            .unwrap()
-                    } else {
-                        chrono::NaiveDate::from_ymd_opt(year, month + 1, 1)
-                            .unwrap()
-                            .pred_opt()
-                            .unwrap()
-                    },
-                ))
-            }
-            _ => None,
-        }
+    /// Resolves a period preset to dates in the user's calendar, ending with
+    /// the user's month containing `today`.
+    pub fn get_period_date_range(
+        period: &str,
+        calendar: &UserCalendar,
+        today: chrono::NaiveDate,
+    ) -> Option<(chrono::NaiveDate, chrono::NaiveDate)> {
+        let months = match period {
+            "current-month" => 1,
+            "past-2-months" => 2,
+            "past-6-months" => 6,
+            "past-year" => 12,
+            _ => return None,
+        };
+        let (year, month) = calendar.month_of(today);
+        let (start_year, start_month) = Self::months_back(year, month, months - 1);
+        Some((
+            calendar.month_range(start_year, start_month)?.0,
+            calendar.month_range(year, month)?.1,
+        ))
     }
 
     pub fn filter_by_date_range<'a>(
@@ -220,6 +221,22 @@ impl AnalyticsService {
             *monthly_totals.entry(month_key).or_insert(Decimal::ZERO) += transaction.amount;
         }
 
+        Self::finish_monthly_totals(monthly_totals, months)
+    }
+
+    /// Monthly totals from `sum_by_month` aggregates already in the reporting currency.
+    pub fn monthly_totals_from_aggregates(
+        &self,
+        totals: &[AggregateTotal],
+        months: u32,
+    ) -> Vec<MonthlySpending> {
+        Self::finish_monthly_totals(totals.iter().map(|t| (t.key.clone(), t.total)), months)
+    }
+
+    fn finish_monthly_totals(
+        monthly_totals: impl IntoIterator<Item = (String, Decimal)>,
+        months: u32,
+    ) -> Vec<MonthlySpending> {
         let mut result: Vec<MonthlySpending> = monthly_totals
             .into_iter()
             .map(|(month, total)| MonthlySpending { month, total })
@@ -259,13 +276,48 @@ impl AnalyticsService {
             entry.1 += 1;
         }
 
-        let total_spend: Decimal = transactions
+        Self::rank_merchants(merchant_map, limit)
+    }
+
+    /// Top merchants from `sum_spending_by_merchant` aggregates already in the
+    /// reporting currency.
+    pub fn top_merchants_from_aggregates(
+        &self,
+        totals: &[AggregateTotal],
+        limit: usize,
+    ) -> Vec<TopMerchant> {
+        Self::rank_merchants(
+            totals
+                .iter()
+                .map(|t| (t.key.clone(), (t.total, t.count as u32))),
+            limit,
+        )
+    }
+
+    /// Category spending from `sum_spending_by_category` aggregates already in
+    /// the reporting currency.
+    pub fn category_spending_from_aggregates(
+        &self,
+        totals: &[AggregateTotal],
+    ) -> Vec<CategorySpending> {
+        totals
             .iter()
-            .filter(|t| t.amount > Decimal::ZERO)
-            .map(|t| t.amount)
-            .sum();
+            .filter(|t| t.total > Decimal::ZERO)
+            .map(|t| CategorySpending {
+                name: t.key.clone(),
+                value: t.total,
+            })
+            .collect()
+    }
+
+    fn rank_merchants(
+        merchant_totals: impl IntoIterator<Item = (String, (Decimal, u32))>,
+        limit: usize,
+    ) -> Vec<TopMerchant> {
+        let merchant_totals: Vec<(String, (Decimal, u32))> = merchant_totals.into_iter().collect();
+        let total_spend: Decimal = merchant_totals.iter().map(|(_, (amount, _))| *amount).sum();
 
-        let mut merchants: Vec<TopMerchant> = merchant_map
+        let mut merchants: Vec<TopMerchant> = merchant_totals
             .into_iter()
             .map(|(name, (amount, count))| {
                 let percentage = if total_spend > Decimal::ZERO {
@@ -283,7 +335,7 @@ impl AnalyticsService {
             })
             .collect();
 
-        merchants.sort_by(|a, b| b.amount.cmp(&a.amount));
+        merchants.sort_by_key(|m| std::cmp::Reverse(m.amount));
 
         merchants.truncate(limit);
 
@@ -303,9 +355,16 @@ impl AnalyticsService {
         self.get_top_merchants(&transactions_slice, limit)
     }
 
-    pub fn calculate_current_month_spending(&self, transactions: &[Transaction]) -> Decimal {
-        let now = chrono::Utc::now().naive_utc().date();
-        let (start, end) = self.get_month_range(now.year(), now.month());
+    pub fn calculate_current_month_spending(
+        &self,
+        transactions: &[Transaction],
+        calendar: &UserCalendar,
+        today: chrono::NaiveDate,
+    ) -> Decimal {
+        let (year, month) = calendar.month_of(today);
+        let Some((start, end)) = calendar.month_range(year, month) else {
+            return Decimal::ZERO;
+        };
         transactions
             .iter()
             .filter(|t| t.date >= start && t.date <= end)
@@ -313,21 +372,18 @@ impl AnalyticsService {
             .sum()
     }
 
+    /// Spending per day of `start..=end`; `day` counts from 1 at `start`.
     pub fn calculate_daily_spending(
         &self,
         transactions: &[Transaction],
-        year: i32,
-        month: u32,
+        start: chrono::NaiveDate,
+        end: chrono::NaiveDate,
     ) -> Vec<DailySpending> {
-        let days_in_month = chrono::NaiveDate::from_ymd_opt(year, month + 1, 1)
-            .unwrap_or(chrono::NaiveDate::from_ymd_opt(year + 1, 1, 1).unwrap())
-            .pred_opt()
-            .unwrap()
-            .day();
-        let mut totals = vec![Decimal::ZERO; days_in_month as usize];
+        let days = (end - start).num_days() + 1;
+        let mut totals = vec![Decimal::ZERO; days.max(0) as usize];
         for t in transactions {
-            if t.date.year() == year && t.date.month() == month {
-                let idx = (t.date.day() - 1) as usize;
+            if t.date >= start && t.date <= end {
+                let idx = (t.date - start).num_days() as usize;
                 totals[idx] += t.amount;
             }
         }
@@ -339,6 +395,7 @@ impl AnalyticsService {
                 cumulative += spend;
                 DailySpending {
                     day: (i + 1) as u32,
+                    date: start + chrono::Days::new(i as u64),
                     spend,
                     cumulative,
                 }
@@ -346,7 +403,7 @@ impl AnalyticsService {
             .collect()
     }
 
-    fn get_month_range_static(year: i32, month: u32) -> (chrono::NaiveDate, chrono::NaiveDate) {
+    pub fn get_month_range_static(year: i32, month: u32) -> (chrono::NaiveDate, chrono::NaiveDate) {
         let start_date = chrono::NaiveDate::from_ymd_opt(year, month, 1).unwrap();
         let end_date = if month == 12 {
             chrono::NaiveDate::from_ymd_opt(year + 1, 1, 1)
@@ -362,7 +419,185 @@ impl AnalyticsService {
         (start_date, end_date)
     }
 
-    fn get_month_range(&self, year: i32, month: u32) -> (chrono::NaiveDate, chrono::NaiveDate) {
-        Self::get_month_range_static(year, month)
+    /// The range `current` is compared against. Whole months of the user's
+    /// calendar are matched with the same number of whole months, so March
+    /// compares with all of February; any other range with the same number
+    /// of days.
+    pub fn baseline_range(
+        current: PeriodRange,
+        baseline: ComparisonBaseline,
+        calendar: &UserCalendar,
+    ) -> Option<PeriodRange> {
+        let month_aligned = calendar.is_month_aligned(current.start_date, current.end_date);
+
+        match baseline {
+            ComparisonBaseline::YearAgo => Some(PeriodRange {
+                start_date: current.start_date.checked_sub_months(Months::new(12))?,
+                end_date: if month_aligned {
+                    let (year, month) = calendar.month_of(current.end_date);
+                    calendar.month_range(year - 1, month)?.1
+                } else {
+                    current.end_date.checked_sub_months(Months::new(12))?
+                },
+            }),
+            ComparisonBaseline::PreviousPeriod if month_aligned => {
+                let (start_year, start_month) = calendar.month_of(current.start_date);
+                let (end_year, end_month) = calendar.month_of(current.end_date);
+                let months =
+                    (end_year - start_year) * 12 + end_month as i32 - start_month as i32 + 1;
+                Some(PeriodRange {
+                    start_date: current
+                        .start_date
+                        .checked_sub_months(Months::new(months as u32))?,
+                    end_date: current.start_date.pred_opt()?,
+                })
+            }
+            ComparisonBaseline::PreviousPeriod => {
+                let days = (current.end_date - current.start_date).num_days();
+                let end_date = current.start_date.pred_opt()?;
+                Some(PeriodRange {
+                    start_date: end_date - chrono::Duration::days(days),
+                    end_date,
+                })
+            }
+        }
+    }
+
+    /// Resolves the two periods to compare: explicit dates win, otherwise the
+    /// current period comes from a `period` preset and the previous one from
+    /// the requested baseline.
+    pub fn resolve_comparison_ranges(
+        query: &PeriodComparisonQuery,
+        calendar: &UserCalendar,
+        today: chrono::NaiveDate,
+    ) -> Result<(PeriodRange, PeriodRange), String> {
+        let current = match (&query.current_start, &query.current_end, &query.period) {
+            (Some(start), Some(end), _) => Self::parse_period_range(start, end)?,
+            (None, None, Some(period)) => {
+                let (start_date, end_date) =
+                    Self::get_period_date_range(period, calendar, today)
+                        .ok_or_else(|| format!("Unknown period: {}", period))?;
+                PeriodRange {
+                    start_date,
+                    end_date,
+                }
+            }
+            _ => {
+                return Err(
+                    "Either period or both current_start and current_end are required".to_string(),
+                )
+            }
+        };
+
+        let previous = match (&query.previous_start, &query.previous_end) {
+            (Some(start), Some(end)) => Self::parse_period_range(start, end)?,
+            (None, None) => {
+                let baseline = match &query.baseline {
+                    Some(value) => ComparisonBaseline::parse(value)
+                        .ok_or_else(|| format!("Unknown baseline: {}", value))?,
+                    None => ComparisonBaseline::default(),
+                };
+                Self::baseline_range(current, baseline, calendar)
+                    .ok_or_else(|| "Comparison period is out of range".to_string())?
+            }
+            _ => return Err("previous_start and previous_end must be given together".to_string()),
+        };
+
+        Ok((current, previous))
+    }
+
+    fn parse_period_range(start: &str, end: &str) -> Result<PeriodRange, String> {
+        let parse = |value: &str| {
+            chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .map_err(|_| format!("Invalid date: {}", value))
+        };
+        let range = PeriodRange {
+            start_date: parse(start)?,
+            end_date: parse(end)?,
+        };
+        if range.end_date < range.start_date {
+            return Err(format!("Invalid date range: {} is before {}", end, start));
+        }
+        Ok(range)
+    }
+
+    /// Compares spending per category and per merchant across two periods.
+    pub fn compare_periods(
+        transactions: &[Transaction],
+        current: PeriodRange,
+        previous: PeriodRange,
+    ) -> PeriodComparisonResponse {
+        type Totals = std::collections::HashMap<String, (Decimal, Decimal)>;
+        let mut categories: Totals = Totals::new();
+        let mut merchants: Totals = Totals::new();
+        let mut total = (Decimal::ZERO, Decimal::ZERO);
+
+        let in_range = |date: chrono::NaiveDate, range: PeriodRange| {
+            date >= range.start_date && date <= range.end_date
+        };
+        for transaction in transactions.iter().filter(|t| t.amount > Decimal::ZERO) {
+            let is_current = if in_range(transaction.date, current) {
+                true
+            } else if in_range(transaction.date, previous) {
+                false
+            } else {
+                continue;
+            };
+            let merchant = transaction
+                .merchant_name
+                .clone()
+                .unwrap_or_else(|| "Unknown Merchant".to_string());
+
+            for amounts in [
+                &mut total,
+                categories
+                    .entry(Self::get_category_name(transaction))
+                    .or_default(),
+                merchants.entry(merchant).or_default(),
+            ] {
+                if is_current {
+                    amounts.0 += transaction.amount;
+                } else {
+                    amounts.1 += transaction.amount;
+                }
+            }
+        }
+
+        PeriodComparisonResponse {
+            current_period: current,
+            previous_period: previous,
+            total: Self::comparison_line("Total".to_string(), total),
+            categories: Self::largest_movers(categories),
+            merchants: Self::largest_movers(merchants),
+        }
+    }
+
+    fn largest_movers(
+        totals: std::collections::HashMap<String, (Decimal, Decimal)>,
+    ) -> Vec<ComparisonLine> {
+        let mut lines: Vec<ComparisonLine> = totals
+            .into_iter()
+            .map(|(name, amounts)| Self::comparison_line(name, amounts))
+            .collect();
+        lines.sort_by(|a, b| {
+            b.change
+                .abs()
+                .cmp(&a.change.abs())
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        lines
+    }
+
+    fn comparison_line(name: String, (current, previous): (Decimal, Decimal)) -> ComparisonLine {
+        let change = current - previous;
+        let change_percent = (previous > Decimal::ZERO)
+            .then(|| Self::round_percentage(change / previous * Decimal::from(100)));
+        ComparisonLine {
+            name,
+            current: Self::round_amount(current),
+            previous: Self::round_amount(previous),
+            change: Self::round_amount(change),
+            change_percent,
+        }
     }
 }
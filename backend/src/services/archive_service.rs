@@ -0,0 +1,466 @@
+use crate::models::archive::{
+    ArchiveManifest, ArchiveProfile, ArchiveRestoreResponse, ArchivedConnection, UserArchive,
+    ARCHIVE_FORMAT, ARCHIVE_VERSION,
+};
+use crate::models::user_settings::UpdateUserSettingsRequest;
+use crate::services::repository_service::DatabaseRepository;
+use crate::services::user_settings_service::UserSettingsService;
+use anyhow::Error;
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::{Cursor, Read, Write};
+use uuid::Uuid;
+use zip::result::ZipError;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+const MANIFEST_FILE: &str = "manifest.json";
+const PROFILE_FILE: &str = "profile.json";
+
+/// Largest archive upload accepted by the restore endpoint.
+pub const MAX_ARCHIVE_UPLOAD_BYTES: usize = 100 * 1024 * 1024;
+
+/// Largest decompressed size accepted for a single archive file.
+const MAX_ARCHIVE_FILE_BYTES: u64 = 2 * MAX_ARCHIVE_UPLOAD_BYTES as u64;
+
+/// Largest decompressed size accepted for all archive files together, so a small
+/// upload of highly compressed files cannot exhaust memory.
+pub const MAX_ARCHIVE_DECOMPRESSED_BYTES: u64 = 3 * MAX_ARCHIVE_UPLOAD_BYTES as u64;
+
+/// An open archive and the decompressed bytes it may still yield.
+struct ArchiveReader<'a> {
+    zip: ZipArchive<Cursor<&'a [u8]>>,
+    remaining_bytes: u64,
+}
+
+#[derive(Debug)]
+pub enum ArchiveRestoreError {
+    /// The upload is not a readable archive or fails validation.
+    Invalid(String),
+    /// The account already holds data; archives only restore into fresh accounts.
+    AccountNotEmpty,
+    /// Records the archive restores, such as its bank connections, already exist
+    /// on this server, e.g. under the account that exported it.
+    AlreadyPresent,
+    Storage(Error),
+}
+
+pub struct ArchiveService {
+    max_decompressed_bytes: u64,
+}
+
+impl ArchiveService {
+    pub fn new() -> Self {
+        Self {
+            max_decompressed_bytes: MAX_ARCHIVE_DECOMPRESSED_BYTES,
+        }
+    }
+
+    #[cfg(test)]
+    pub fn with_max_decompressed_bytes(mut self, bytes: u64) -> Self {
+        self.max_decompressed_bytes = bytes;
+        self
+    }
+
+    /// Gathers everything the user owns. Provider credentials never leave the
+    /// database.
+    pub async fn collect<R: DatabaseRepository + ?Sized>(
+        &self,
+        repository: &R,
+        user_id: Uuid,
+    ) -> Result<UserArchive, Error> {
+        let user = repository
+            .get_user_by_id(&user_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("User {} not found", user_id))?;
+        let settings = repository
+            .get_user_settings(&user_id)
+            .await?
+            .unwrap_or_default();
+        let connections = repository
+            .get_all_provider_connections_by_user(&user_id)
+            .await?;
+
+        Ok(UserArchive {
+            profile: ArchiveProfile {
+                email: user.email,
+                provider: user.provider,
+                created_at: user.created_at,
+                onboarding_completed: user.onboarding_completed,
+                zero_based_budgeting: repository.get_zero_based_budgeting_enabled(user_id).await?,
+                settings,
+            },
+            connections: connections.iter().map(ArchivedConnection::from).collect(),
+            accounts: repository.get_accounts_for_user(&user_id).await?,
+            transactions: repository.get_transactions_for_user(&user_id).await?,
+            transaction_tags: repository.get_transaction_tags_for_user(&user_id).await?,
+            budgets: repository.get_budgets_for_user(user_id).await?,
+            budget_allocations: repository
+                .get_all_budget_allocations_for_user(user_id)
+                .await?,
+            liabilities: repository.get_liabilities_for_user(&user_id).await?,
+            alert_rules: repository.get_alert_rules_for_user(&user_id).await?,
+            notification_preferences: repository.get_notification_preferences(&user_id).await?,
+            dismissed_insights: repository.get_dismissed_insight_ids(&user_id).await?,
+        })
+    }
+
+    /// Writes the archive as a zip of JSON files, with CSV copies of the
+    /// tabular data for spreadsheets. Only the JSON files are read back.
+    pub fn write_zip(
+        &self,
+        archive: &UserArchive,
+        exported_at: DateTime<Utc>,
+    ) -> Result<Vec<u8>, String> {
+        let counts = BTreeMap::from([
+            ("connections".to_string(), archive.connections.len()),
+            ("accounts".to_string(), archive.accounts.len()),
+            ("transactions".to_string(), archive.transactions.len()),
+            (
+                "transaction_tags".to_string(),
+                archive.transaction_tags.len(),
+            ),
+            ("budgets".to_string(), archive.budgets.len()),
+            (
+                "budget_allocations".to_string(),
+                archive.budget_allocations.len(),
+            ),
+            ("liabilities".to_string(), archive.liabilities.len()),
+            ("alert_rules".to_string(), archive.alert_rules.len()),
+            (
+                "notification_preferences".to_string(),
+                archive.notification_preferences.len(),
+            ),
+            (
+                "dismissed_insights".to_string(),
+                archive.dismissed_insights.len(),
+            ),
+        ]);
+        let manifest = ArchiveManifest {
+            format: ARCHIVE_FORMAT.to_string(),
+            version: ARCHIVE_VERSION,
+            exported_at,
+            counts,
+        };
+
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        Self::write_json(&mut zip, MANIFEST_FILE, &manifest)?;
+        Self::write_json(&mut zip, PROFILE_FILE, &archive.profile)?;
+        Self::write_json(&mut zip, "connections.json", &archive.connections)?;
+        Self::write_json(&mut zip, "accounts.json", &archive.accounts)?;
+        Self::write_csv(&mut zip, "accounts.csv", &archive.accounts)?;
+        Self::write_json(&mut zip, "transactions.json", &archive.transactions)?;
+        Self::write_csv(&mut zip, "transactions.csv", &archive.transactions)?;
+        Self::write_json(&mut zip, "transaction_tags.json", &archive.transaction_tags)?;
+        Self::write_json(&mut zip, "budgets.json", &archive.budgets)?;
+        Self::write_csv(&mut zip, "budgets.csv", &archive.budgets)?;
+        Self::write_json(
+            &mut zip,
+            "budget_allocations.json",
+            &archive.budget_allocations,
+        )?;
+        Self::write_json(&mut zip, "liabilities.json", &archive.liabilities)?;
+        Self::write_json(&mut zip, "alert_rules.json", &archive.alert_rules)?;
+        Self::write_json(
+            &mut zip,
+            "notification_preferences.json",
+            &archive.notification_preferences,
+        )?;
+        Self::write_json(
+            &mut zip,
+            "dismissed_insights.json",
+            &archive.dismissed_insights,
+        )?;
+
+        zip.finish()
+            .map(Cursor::into_inner)
+            .map_err(|e| format!("Failed to write archive: {}", e))
+    }
+
+    /// Reads an archive written by this or an older version. Files added in
+    /// later minor revisions may be missing and are treated as empty.
+    pub fn read_zip(&self, bytes: &[u8]) -> Result<UserArchive, String> {
+        let reader = &mut ArchiveReader {
+            zip: ZipArchive::new(Cursor::new(bytes))
+                .map_err(|e| format!("Invalid archive: not a zip file ({})", e))?,
+            remaining_bytes: self.max_decompressed_bytes,
+        };
+
+        let manifest: ArchiveManifest = Self::read_json(reader, MANIFEST_FILE)?
+            .ok_or_else(|| format!("Invalid archive: {} is missing", MANIFEST_FILE))?;
+        if manifest.format != ARCHIVE_FORMAT {
+            return Err(format!(
+                "Invalid archive: unexpected format '{}'",
+                manifest.format
+            ));
+        }
+        if manifest.version == 0 || manifest.version > ARCHIVE_VERSION {
+            return Err(format!(
+                "Invalid archive: version {} is not supported (latest is {})",
+                manifest.version, ARCHIVE_VERSION
+            ));
+        }
+
+        Ok(UserArchive {
+            profile: Self::read_json(reader, PROFILE_FILE)?
+                .ok_or_else(|| format!("Invalid archive: {} is missing", PROFILE_FILE))?,
+            connections: Self::read_list(reader, "connections.json")?,
+            accounts: Self::read_list(reader, "accounts.json")?,
+            transactions: Self::read_list(reader, "transactions.json")?,
+            transaction_tags: Self::read_list(reader, "transaction_tags.json")?,
+            budgets: Self::read_list(reader, "budgets.json")?,
+            budget_allocations: Self::read_list(reader, "budget_allocations.json")?,
+            liabilities: Self::read_list(reader, "liabilities.json")?,
+            alert_rules: Self::read_list(reader, "alert_rules.json")?,
+            notification_preferences: Self::read_list(reader, "notification_preferences.json")?,
+            dismissed_insights: Self::read_list(reader, "dismissed_insights.json")?,
+        })
+    }
+
+    /// Checks that settings are valid and every record points at something the
+    /// archive itself contains.
+    pub fn validate(archive: &UserArchive) -> Result<(), String> {
+        let mut settings = Default::default();
+        UserSettingsService::apply_update(
+            &mut settings,
+            &UpdateUserSettingsRequest {
+                reporting_currency: Some(archive.profile.settings.reporting_currency.clone()),
+                timezone: Some(archive.profile.settings.timezone.clone()),
+                month_start_day: Some(archive.profile.settings.month_start_day),
+            },
+        )
+        .map_err(|e| format!("Invalid archive: {}", e))?;
+
+        let connection_ids =
+            Self::unique_ids("connection", archive.connections.iter().map(|c| c.id))?;
+        let account_ids = Self::unique_ids("account", archive.accounts.iter().map(|a| a.id))?;
+        let transaction_ids =
+            Self::unique_ids("transaction", archive.transactions.iter().map(|t| t.id))?;
+
+        let references = archive
+            .accounts
+            .iter()
+            .filter_map(|a| a.provider_connection_id)
+            .map(|id| ("connection", id, &connection_ids))
+            .chain(
+                archive
+                    .transactions
+                    .iter()
+                    .map(|t| ("account", t.account_id, &account_ids)),
+            )
+            .chain(
+                archive
+                    .liabilities
+                    .iter()
+                    .map(|l| ("account", l.account_id, &account_ids)),
+            )
+            .chain(
+                archive
+                    .alert_rules
+                    .iter()
+                    .filter_map(|r| r.account_id)
+                    .map(|id| ("account", id, &account_ids)),
+            )
+            .chain(
+                archive
+                    .transaction_tags
+                    .iter()
+                    .map(|t| ("transaction", t.transaction_id, &transaction_ids)),
+            );
+        for (kind, id, known) in references {
+            if !known.contains(&id) {
+                return Err(format!("Invalid archive: unknown {} {}", kind, id));
+            }
+        }
+        Ok(())
+    }
+
+    /// Restores an archive into an account that has no connections, accounts
+    /// or budgets yet. Everything is written in one database transaction.
+    pub async fn restore<R: DatabaseRepository + ?Sized>(
+        &self,
+        repository: &R,
+        user_id: Uuid,
+        bytes: &[u8],
+    ) -> Result<ArchiveRestoreResponse, ArchiveRestoreError> {
+        let archive = self.read_zip(bytes).map_err(ArchiveRestoreError::Invalid)?;
+        Self::validate(&archive).map_err(ArchiveRestoreError::Invalid)?;
+
+        let has_connections = !repository
+            .get_all_provider_connections_by_user(&user_id)
+            .await
+            .map_err(ArchiveRestoreError::Storage)?
+            .is_empty();
+        let has_accounts = !repository
+            .get_accounts_for_user(&user_id)
+            .await
+            .map_err(ArchiveRestoreError::Storage)?
+            .is_empty();
+        let has_budgets = !repository
+            .get_budgets_for_user(user_id)
+            .await
+            .map_err(ArchiveRestoreError::Storage)?
+            .is_empty();
+        if has_connections || has_accounts || has_budgets {
+            return Err(ArchiveRestoreError::AccountNotEmpty);
+        }
+
+        let archive = Self::with_fresh_ids(archive);
+        repository
+            .restore_user_archive(&user_id, &archive)
+            .await
+            .map_err(|e| {
+                let duplicate = e
+                    .downcast_ref::<sqlx::Error>()
+                    .and_then(|e| e.as_database_error())
+                    .is_some_and(|e| e.is_unique_violation());
+                if duplicate {
+                    ArchiveRestoreError::AlreadyPresent
+                } else {
+                    ArchiveRestoreError::Storage(e)
+                }
+            })?;
+
+        Ok(ArchiveRestoreResponse {
+            connections: archive.connections.len(),
+            accounts: archive.accounts.len(),
+            transactions: archive.transactions.len(),
+            budgets: archive.budgets.len(),
+        })
+    }
+
+    /// Gives every record a new id and points references at the new ids, so an
+    /// archive can be restored on the server that exported it. Saved views are not
+    /// archived, so links to them are dropped.
+    pub fn with_fresh_ids(mut archive: UserArchive) -> UserArchive {
+        let connections = Self::fresh_ids(archive.connections.iter_mut().map(|c| &mut c.id));
+        let accounts = Self::fresh_ids(archive.accounts.iter_mut().map(|a| &mut a.id));
+        let transactions = Self::fresh_ids(archive.transactions.iter_mut().map(|t| &mut t.id));
+        Self::fresh_ids(archive.budgets.iter_mut().map(|b| &mut b.id));
+        Self::fresh_ids(archive.budget_allocations.iter_mut().map(|a| &mut a.id));
+        Self::fresh_ids(archive.alert_rules.iter_mut().map(|r| &mut r.id));
+        let remap = |ids: &HashMap<Uuid, Uuid>, id: Uuid| ids.get(&id).copied().unwrap_or(id);
+
+        for account in &mut archive.accounts {
+            account.provider_connection_id = account
+                .provider_connection_id
+                .map(|id| remap(&connections, id));
+        }
+        for transaction in &mut archive.transactions {
+            transaction.account_id = remap(&accounts, transaction.account_id);
+        }
+        for tag in &mut archive.transaction_tags {
+            tag.transaction_id = remap(&transactions, tag.transaction_id);
+        }
+        for liability in &mut archive.liabilities {
+            liability.account_id = remap(&accounts, liability.account_id);
+        }
+        for budget in &mut archive.budgets {
+            budget.saved_view_id = None;
+        }
+        for rule in &mut archive.alert_rules {
+            rule.account_id = rule.account_id.map(|id| remap(&accounts, id));
+            rule.saved_view_id = None;
+        }
+        archive
+    }
+
+    /// Replaces each id with a new one, returning old → new.
+    fn fresh_ids<'a>(ids: impl Iterator<Item = &'a mut Uuid>) -> HashMap<Uuid, Uuid> {
+        ids.map(|id| {
+            let old = *id;
+            *id = Uuid::new_v4();
+            (old, *id)
+        })
+        .collect()
+    }
+
+    fn unique_ids(kind: &str, ids: impl Iterator<Item = Uuid>) -> Result<HashSet<Uuid>, String> {
+        let mut seen = HashSet::new();
+        for id in ids {
+            if !seen.insert(id) {
+                return Err(format!("Invalid archive: duplicate {} {}", kind, id));
+            }
+        }
+        Ok(seen)
+    }
+
+    fn start_file(zip: &mut ZipWriter<Cursor<Vec<u8>>>, name: &str) -> Result<(), String> {
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+        zip.start_file(name, options)
+            .map_err(|e| format!("Failed to write {}: {}", name, e))
+    }
+
+    fn write_json<T: Serialize + ?Sized>(
+        zip: &mut ZipWriter<Cursor<Vec<u8>>>,
+        name: &str,
+        value: &T,
+    ) -> Result<(), String> {
+        Self::start_file(zip, name)?;
+        serde_json::to_writer_pretty(&mut *zip, value)
+            .map_err(|e| format!("Failed to write {}: {}", name, e))
+    }
+
+    fn write_csv<T: Serialize>(
+        zip: &mut ZipWriter<Cursor<Vec<u8>>>,
+        name: &str,
+        rows: &[T],
+    ) -> Result<(), String> {
+        let mut writer = csv::Writer::from_writer(Vec::new());
+        for row in rows {
+            writer
+                .serialize(row)
+                .map_err(|e| format!("Failed to write {}: {}", name, e))?;
+        }
+        let data = writer
+            .into_inner()
+            .map_err(|e| format!("Failed to write {}: {}", name, e))?;
+        Self::start_file(zip, name)?;
+        zip.write_all(&data)
+            .map_err(|e| format!("Failed to write {}: {}", name, e))
+    }
+
+    /// Reads one JSON file, charging its decompressed size to the archive's budget.
+    /// Declared sizes are checked first but not trusted; reading stops at the limit.
+    fn read_json<T: DeserializeOwned>(
+        reader: &mut ArchiveReader,
+        name: &str,
+    ) -> Result<Option<T>, String> {
+        let file = match reader.zip.by_name(name) {
+            Ok(file) => file,
+            Err(ZipError::FileNotFound) => return Ok(None),
+            Err(e) => return Err(format!("Invalid archive: cannot read {} ({})", name, e)),
+        };
+        let limit = MAX_ARCHIVE_FILE_BYTES.min(reader.remaining_bytes);
+        let too_large = || {
+            if limit < MAX_ARCHIVE_FILE_BYTES {
+                "Invalid archive: contents are too large when decompressed".to_string()
+            } else {
+                format!("Invalid archive: {} is too large", name)
+            }
+        };
+        if file.size() > limit {
+            return Err(too_large());
+        }
+        let mut data = Vec::new();
+        file.take(limit + 1)
+            .read_to_end(&mut data)
+            .map_err(|e| format!("Invalid archive: cannot read {} ({})", name, e))?;
+        if data.len() as u64 > limit {
+            return Err(too_large());
+        }
+        reader.remaining_bytes -= data.len() as u64;
+        serde_json::from_slice(&data)
+            .map(Some)
+            .map_err(|e| format!("Invalid archive: {} is malformed ({})", name, e))
+    }
+
+    fn read_list<T: DeserializeOwned>(
+        reader: &mut ArchiveReader,
+        name: &str,
+    ) -> Result<Vec<T>, String> {
+        Ok(Self::read_json(reader, name)?.unwrap_or_default())
+    }
+}
@@ -0,0 +1,274 @@
+use crate::models::account::Account;
+use crate::models::bill::{BillSource, UpcomingBill, UpcomingBillsResponse};
+use crate::models::liability::Liability;
+use crate::models::recurring::RecurringSeries;
+use crate::models::transaction::Transaction;
+use crate::services::recurring_service::RecurringService;
+use crate::services::repository_service::DatabaseRepository;
+use chrono::{DateTime, Days, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// History used to detect recurring bills; long enough to catch annual renewals.
+const HISTORY_DAYS: u64 = 400;
+/// How far ahead the calendar feed publishes bills.
+pub const CALENDAR_FEED_WINDOW_DAYS: u32 = 90;
+/// RFC 5545 limits content lines to 75 octets, excluding the line break.
+const ICAL_LINE_LIMIT: usize = 75;
+
+#[derive(Debug, Clone, Copy)]
+pub struct BillWindow {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+}
+
+impl BillWindow {
+    pub fn from_today(today: NaiveDate, days: u32) -> Self {
+        Self {
+            start: today,
+            end: today
+                .checked_add_days(Days::new(u64::from(days)))
+                .unwrap_or(today),
+        }
+    }
+}
+
+pub struct BillsService;
+
+impl BillsService {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Reads only stored data: statement due dates come from the liability details
+    /// saved at sync time, so serving bills (and the public calendar feed) never
+    /// calls a provider.
+    pub async fn upcoming_bills_for_user<R: DatabaseRepository + ?Sized>(
+        &self,
+        repository: &R,
+        user_id: Uuid,
+        window: BillWindow,
+    ) -> Result<UpcomingBillsResponse, String> {
+        let history_start = window
+            .start
+            .checked_sub_days(Days::new(HISTORY_DAYS))
+            .unwrap_or(window.start);
+        let transactions: Vec<Transaction> = repository
+            .get_transactions_by_date_range_for_user(&user_id, history_start, window.start)
+            .await
+            .map_err(|e| e.to_string())?;
+        let accounts = repository
+            .get_accounts_for_user(&user_id)
+            .await
+            .map_err(|e| e.to_string())?;
+        let liabilities = repository
+            .get_liabilities_for_user(&user_id)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let series = RecurringService::detect_recurring_series(&transactions, window.start);
+        Ok(Self::build_upcoming_bills(
+            window,
+            &series,
+            &liabilities,
+            &accounts,
+        ))
+    }
+
+    /// Expands non-income recurring series into dated bills and adds credit card
+    /// statement due dates, ordered by due date.
+    pub fn build_upcoming_bills(
+        window: BillWindow,
+        series: &[RecurringSeries],
+        liabilities: &[Liability],
+        accounts: &[Account],
+    ) -> UpcomingBillsResponse {
+        let mut bills: Vec<UpcomingBill> = series
+            .iter()
+            .filter(|s| !s.is_income)
+            .flat_map(|s| {
+                s.occurrences_between(window.start, window.end)
+                    .into_iter()
+                    .map(move |due_date| UpcomingBill {
+                        name: s.merchant.clone(),
+                        category: Some(s.category.clone()),
+                        due_date,
+                        amount: s.average_amount,
+                        minimum_payment: None,
+                        source: BillSource::Recurring,
+                        account_id: Some(s.account_id),
+                        cadence: Some(s.cadence),
+                    })
+            })
+            .collect();
+
+        let accounts_by_id: HashMap<Uuid, &Account> = accounts.iter().map(|a| (a.id, a)).collect();
+
+        for statement in liabilities.iter().filter(|l| l.liability_type == "credit") {
+            let Some(due_date) = statement.next_payment_due_date else {
+                continue;
+            };
+            if due_date < window.start || due_date > window.end {
+                continue;
+            }
+            let account = accounts_by_id.get(&statement.account_id).copied();
+            bills.push(UpcomingBill {
+                name: account
+                    .map(|a| format!("{} payment", a.name))
+                    .unwrap_or_else(|| "Credit card payment".to_string()),
+                category: None,
+                due_date,
+                amount: statement
+                    .statement_balance
+                    .or(statement.minimum_payment)
+                    .unwrap_or(Decimal::ZERO),
+                minimum_payment: statement.minimum_payment,
+                source: BillSource::CreditCardStatement,
+                account_id: Some(statement.account_id),
+                cadence: None,
+            });
+        }
+
+        bills.sort_by(|a, b| a.due_date.cmp(&b.due_date).then(a.name.cmp(&b.name)));
+        let total = bills.iter().map(|b| b.amount).sum();
+
+        UpcomingBillsResponse {
+            start_date: window.start,
+            end_date: window.end,
+            bills,
+            total,
+        }
+    }
+
+    /// Issues a new feed token for the user, replacing any previous one. Only the
+    /// hash is stored, so the plain token is returned exactly once.
+    pub async fn rotate_calendar_feed_token<R: DatabaseRepository + ?Sized>(
+        &self,
+        repository: &R,
+        user_id: Uuid,
+    ) -> Result<String, String> {
+        let bytes: [u8; 32] = rand::random();
+        let token = hex::encode(bytes);
+        repository
+            .upsert_calendar_feed_token(&user_id, &Self::hash_feed_token(&token))
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(token)
+    }
+
+    pub async fn resolve_calendar_feed_token<R: DatabaseRepository + ?Sized>(
+        &self,
+        repository: &R,
+        token: &str,
+    ) -> Result<Option<Uuid>, String> {
+        repository
+            .get_user_id_by_calendar_feed_token(&Self::hash_feed_token(token))
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    pub fn hash_feed_token(token: &str) -> String {
+        hex::encode(Sha256::digest(token.as_bytes()))
+    }
+
+    /// Renders bills as an RFC 5545 calendar of all-day events.
+    pub fn render_icalendar(bills: &[UpcomingBill], generated_at: DateTime<Utc>) -> String {
+        let dtstamp = generated_at.format("%Y%m%dT%H%M%SZ").to_string();
+        let mut lines = vec![
+            "BEGIN:VCALENDAR".to_string(),
+            "VERSION:2.0".to_string(),
+            "PRODID:-//Sumurai//Upcoming Bills//EN".to_string(),
+            "CALSCALE:GREGORIAN".to_string(),
+            "METHOD:PUBLISH".to_string(),
+            "X-WR-CALNAME:Upcoming bills".to_string(),
+        ];
+
+        for bill in bills {
+            let next_day = bill.due_date.succ_opt().unwrap_or(bill.due_date);
+            let mut description = format!("Expected amount: {:.2}", bill.amount);
+            if let Some(minimum) = bill.minimum_payment {
+                description.push_str(&format!("\nMinimum payment: {:.2}", minimum));
+            }
+            if let Some(category) = &bill.category {
+                description.push_str(&format!("\nCategory: {}", category));
+            }
+
+            lines.push("BEGIN:VEVENT".to_string());
+            lines.push(format!("UID:{}", Self::event_uid(bill)));
+            lines.push(format!("DTSTAMP:{}", dtstamp));
+            lines.push(format!(
+                "DTSTART;VALUE=DATE:{}",
+                bill.due_date.format("%Y%m%d")
+            ));
+            lines.push(format!("DTEND;VALUE=DATE:{}", next_day.format("%Y%m%d")));
+            lines.push(format!(
+                "SUMMARY:{}",
+                Self::escape_text(&format!("{} ({:.2})", bill.name, bill.amount))
+            ));
+            lines.push(format!("DESCRIPTION:{}", Self::escape_text(&description)));
+            lines.push("TRANSP:TRANSPARENT".to_string());
+            lines.push("END:VEVENT".to_string());
+        }
+
+        lines.push("END:VCALENDAR".to_string());
+
+        lines
+            .iter()
+            .map(|line| Self::fold_line(line))
+            .collect::<Vec<_>>()
+            .join("\r\n")
+            + "\r\n"
+    }
+
+    /// Stable across feed refreshes so calendar clients update events in place.
+    fn event_uid(bill: &UpcomingBill) -> String {
+        let key = format!(
+            "{:?}|{}|{}",
+            bill.source,
+            bill.name,
+            bill.account_id.map(|id| id.to_string()).unwrap_or_default()
+        );
+        let digest = hex::encode(Sha256::digest(key.as_bytes()));
+        format!(
+            "{}-{}@sumurai",
+            bill.due_date.format("%Y%m%d"),
+            &digest[..16]
+        )
+    }
+
+    pub fn escape_text(value: &str) -> String {
+        let mut escaped = String::with_capacity(value.len());
+        for c in value.chars() {
+            match c {
+                '\\' => escaped.push_str("\\\\"),
+                ';' => escaped.push_str("\\;"),
+                ',' => escaped.push_str("\\,"),
+                '\n' => escaped.push_str("\\n"),
+                '\r' => {}
+                _ => escaped.push(c),
+            }
+        }
+        escaped
+    }
+
+    fn fold_line(line: &str) -> String {
+        if line.len() <= ICAL_LINE_LIMIT {
+            return line.to_string();
+        }
+
+        let mut folded = String::with_capacity(line.len() + line.len() / ICAL_LINE_LIMIT * 3);
+        let mut current_len = 0;
+        for c in line.chars() {
+            // Continuation lines start with a space, which counts towards the limit.
+            if current_len + c.len_utf8() > ICAL_LINE_LIMIT {
+                folded.push_str("\r\n ");
+                current_len = 1;
+            }
+            folded.push(c);
+            current_len += c.len_utf8();
+        }
+        folded
+    }
+}
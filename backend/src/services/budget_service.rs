@@ -1,6 +1,14 @@
-use crate::models::budget::Budget;
+use crate::models::budget::{
+    AssignBudgetFundsRequest, Budget, BudgetAllocation, MoveBudgetFundsRequest,
+    UpdateBudgetRequest, ZeroBasedBudgetSummary, ZeroBasedCategory,
+};
+use crate::models::transaction::Transaction;
+use crate::services::analytics_service::AnalyticsService;
 use crate::services::repository_service::DatabaseRepository;
+use crate::services::saved_view_service::SavedViewService;
+use crate::services::user_settings_service::UserSettingsService;
 use rust_decimal::Decimal;
+use std::collections::{BTreeMap, HashSet};
 use uuid::Uuid;
 
 pub struct BudgetService;
@@ -10,29 +18,27 @@ impl BudgetService {
         Self
     }
 
-    pub async fn create_budget_for_user<R: DatabaseRepository + ?Sized>(
+    pub async fn create_budget<R: DatabaseRepository + ?Sized>(
         &self,
         repository: &R,
-        user_id: Uuid,
-        category: String,
-        amount: Decimal,
+        budget: Budget,
     ) -> Result<Budget, String> {
-        if amount <= Decimal::ZERO {
+        if budget.amount <= Decimal::ZERO {
             return Err("Budget amount must be greater than zero".to_string());
         }
+        SavedViewService::ensure_owned(repository, budget.user_id, budget.saved_view_id).await?;
         // Prevent duplicate categories for the same user
         let existing = repository
-            .get_budgets_for_user(user_id)
+            .get_budgets_for_user(budget.user_id)
             .await
             .map_err(|e| e.to_string())?;
         if existing
             .iter()
-            .any(|b| b.category.eq_ignore_ascii_case(&category))
+            .any(|b| b.category.eq_ignore_ascii_case(&budget.category))
         {
             return Err("Category already exists".to_string());
         }
 
-        let budget = Budget::new(user_id, category, amount);
         repository
             .create_budget_for_user(budget)
             .await
@@ -44,14 +50,17 @@ impl BudgetService {
         repository: &R,
         budget_id: Uuid,
         user_id: Uuid,
-        amount: Decimal,
+        request: &UpdateBudgetRequest,
     ) -> Result<Budget, String> {
-        if amount <= Decimal::ZERO {
+        if request.amount <= Decimal::ZERO {
             return Err("Budget amount must be greater than zero".to_string());
         }
+        if let Some(saved_view_id) = request.saved_view_id {
+            SavedViewService::ensure_owned(repository, user_id, saved_view_id).await?;
+        }
 
         repository
-            .update_budget_for_user(budget_id, user_id, amount)
+            .update_budget_for_user(budget_id, user_id, request.amount, request.saved_view_id)
             .await
             .map_err(|e| e.to_string())
     }
@@ -78,4 +87,186 @@ impl BudgetService {
             .await
             .map_err(|e| e.to_string())
     }
+
+    pub async fn set_zero_based_mode<R: DatabaseRepository + ?Sized>(
+        &self,
+        repository: &R,
+        user_id: Uuid,
+        enabled: bool,
+    ) -> Result<bool, String> {
+        repository
+            .set_zero_based_budgeting_enabled(user_id, enabled)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(enabled)
+    }
+
+    pub async fn get_zero_based_summary<R: DatabaseRepository + ?Sized>(
+        &self,
+        repository: &R,
+        user_id: Uuid,
+        month: &str,
+    ) -> Result<ZeroBasedBudgetSummary, String> {
+        let (year, month_number) = Self::parse_budget_month(month)?;
+        self.ensure_zero_based_enabled(repository, user_id).await?;
+        let (start, end) = UserSettingsService::calendar(repository, user_id)
+            .await?
+            .month_range(year, month_number)
+            .ok_or_else(|| "Invalid budget month, expected YYYY-MM".to_string())?;
+
+        let allocations = repository
+            .get_budget_allocations_for_user(user_id, month)
+            .await
+            .map_err(|e| e.to_string())?;
+        let mut transactions = repository
+            .get_transactions_by_date_range_for_user(&user_id, start, end)
+            .await
+            .map_err(|e| e.to_string())?;
+        transactions.retain(|t| !t.excluded_from_analytics);
+        let credit_account_ids: HashSet<Uuid> = repository
+            .get_accounts_for_user(&user_id)
+            .await
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .filter(|a| a.account_type.eq_ignore_ascii_case("credit"))
+            .map(|a| a.id)
+            .collect();
+
+        Ok(Self::build_zero_based_summary(
+            month,
+            &transactions,
+            &allocations,
+            &credit_account_ids,
+        ))
+    }
+
+    pub async fn assign_funds<R: DatabaseRepository + ?Sized>(
+        &self,
+        repository: &R,
+        user_id: Uuid,
+        request: &AssignBudgetFundsRequest,
+    ) -> Result<BudgetAllocation, String> {
+        Self::parse_budget_month(&request.month)?;
+        if request.amount < Decimal::ZERO {
+            return Err("Assigned amount cannot be negative".to_string());
+        }
+        if request.category.trim().is_empty() {
+            return Err("Category is required".to_string());
+        }
+        self.ensure_zero_based_enabled(repository, user_id).await?;
+
+        repository
+            .upsert_budget_allocation(user_id, request)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn move_funds<R: DatabaseRepository + ?Sized>(
+        &self,
+        repository: &R,
+        user_id: Uuid,
+        request: &MoveBudgetFundsRequest,
+    ) -> Result<Vec<BudgetAllocation>, String> {
+        Self::parse_budget_month(&request.month)?;
+        if request.amount <= Decimal::ZERO {
+            return Err("Move amount must be greater than zero".to_string());
+        }
+        if request
+            .from_category
+            .eq_ignore_ascii_case(&request.to_category)
+        {
+            return Err("Source and destination categories must differ".to_string());
+        }
+        self.ensure_zero_based_enabled(repository, user_id).await?;
+
+        repository
+            .move_budget_allocation(user_id, request)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    /// Builds the zero-based view for a month: inflows become assignable income, outflows
+    /// become per-category activity drawn against what was assigned to that category.
+    /// Transfers between the user's accounts and card payments are neither, and inflows
+    /// on credit cards are refunds that offset their category's activity.
+    pub fn build_zero_based_summary(
+        month: &str,
+        transactions: &[Transaction],
+        allocations: &[BudgetAllocation],
+        credit_account_ids: &HashSet<Uuid>,
+    ) -> ZeroBasedBudgetSummary {
+        let mut income = Decimal::ZERO;
+        let mut categories: BTreeMap<String, (Decimal, Decimal)> = BTreeMap::new();
+
+        for allocation in allocations {
+            categories
+                .entry(allocation.category.clone())
+                .or_insert((Decimal::ZERO, Decimal::ZERO))
+                .0 += allocation.assigned;
+        }
+
+        for transaction in transactions {
+            if AnalyticsService::is_transfer_category(&transaction.category_primary) {
+                continue;
+            }
+            if AnalyticsService::is_income_transaction(transaction)
+                && !credit_account_ids.contains(&transaction.account_id)
+            {
+                income += transaction.amount.abs();
+                continue;
+            }
+            let category = if transaction.category_primary.is_empty() {
+                "Uncategorized".to_string()
+            } else {
+                transaction.category_primary.clone()
+            };
+            categories
+                .entry(category)
+                .or_insert((Decimal::ZERO, Decimal::ZERO))
+                .1 += transaction.amount;
+        }
+
+        let assigned: Decimal = categories.values().map(|(a, _)| *a).sum();
+        let activity: Decimal = categories.values().map(|(_, s)| *s).sum();
+        let left_to_assign = income - assigned;
+
+        ZeroBasedBudgetSummary {
+            month: month.to_string(),
+            income: income.round_dp(2),
+            assigned: assigned.round_dp(2),
+            activity: activity.round_dp(2),
+            left_to_assign: left_to_assign.round_dp(2),
+            over_assigned: left_to_assign < Decimal::ZERO,
+            categories: categories
+                .into_iter()
+                .map(|(category, (assigned, activity))| ZeroBasedCategory {
+                    category,
+                    assigned: assigned.round_dp(2),
+                    activity: activity.round_dp(2),
+                    available: (assigned - activity).round_dp(2),
+                })
+                .collect(),
+        }
+    }
+
+    fn parse_budget_month(month: &str) -> Result<(i32, u32), String> {
+        AnalyticsService::parse_month_key(month)
+            .ok_or_else(|| "Invalid budget month, expected YYYY-MM".to_string())
+    }
+
+    async fn ensure_zero_based_enabled<R: DatabaseRepository + ?Sized>(
+        &self,
+        repository: &R,
+        user_id: Uuid,
+    ) -> Result<(), String> {
+        let enabled = repository
+            .get_zero_based_budgeting_enabled(user_id)
+            .await
+            .map_err(|e| e.to_string())?;
+        if enabled {
+            Ok(())
+        } else {
+            Err("Zero-based budgeting is not enabled".to_string())
+        }
+    }
 }
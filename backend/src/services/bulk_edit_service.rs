@@ -0,0 +1,226 @@
+use crate::models::archive::TransactionTag;
+use crate::models::bulk_edit::{
+    BulkOperationResult, BulkTransactionEditRequest, BulkTransactionEditResponse,
+    BulkTransactionOperation, BULK_EDIT_PREVIEW_LIMIT, MAX_BULK_EDIT_TRANSACTIONS, MAX_TAG_LENGTH,
+};
+use crate::models::transaction::TransactionWithAccount;
+use crate::services::repository_service::DatabaseRepository;
+use crate::services::transaction_search_service::TransactionSearchService;
+use std::collections::HashSet;
+use uuid::Uuid;
+
+/// Applies one set of edits to many transactions at once.
+pub struct BulkEditService;
+
+impl BulkEditService {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Resolves the selection and applies the operations in one database transaction,
+    /// or with `dry_run` only reports what they would change.
+    pub async fn apply<R: DatabaseRepository + ?Sized>(
+        &self,
+        repository: &R,
+        user_id: Uuid,
+        request: &BulkTransactionEditRequest,
+    ) -> Result<BulkTransactionEditResponse, String> {
+        let operations = Self::validate_operations(&request.operations)?;
+        let selected = Self::select(repository, user_id, request).await?;
+        let transaction_ids: Vec<Uuid> = selected.iter().map(|t| t.id).collect();
+
+        let affected = if transaction_ids.is_empty() {
+            vec![0; operations.len()]
+        } else if request.dry_run {
+            let tags = repository
+                .get_transaction_tags_for_user(&user_id)
+                .await
+                .map_err(|e| e.to_string())?;
+            Self::simulate(&selected, &tags, &operations)
+        } else {
+            repository
+                .apply_bulk_transaction_edit(&user_id, &transaction_ids, &operations)
+                .await
+                .map_err(|e| e.to_string())?
+        };
+
+        let preview = if request.dry_run {
+            selected
+                .iter()
+                .take(BULK_EDIT_PREVIEW_LIMIT)
+                .cloned()
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        Ok(BulkTransactionEditResponse {
+            dry_run: request.dry_run,
+            matched: selected.len(),
+            results: operations
+                .into_iter()
+                .zip(affected)
+                .map(|(operation, affected)| BulkOperationResult {
+                    operation,
+                    affected,
+                })
+                .collect(),
+            preview,
+        })
+    }
+
+    /// Trims names and rejects operations the database would refuse.
+    pub fn validate_operations(
+        operations: &[BulkTransactionOperation],
+    ) -> Result<Vec<BulkTransactionOperation>, String> {
+        if operations.is_empty() {
+            return Err("Invalid operations: at least one is required".to_string());
+        }
+
+        operations
+            .iter()
+            .map(|operation| match operation {
+                BulkTransactionOperation::SetCategory {
+                    category,
+                    category_detailed,
+                } => {
+                    let category = category.trim();
+                    if category.is_empty() {
+                        return Err("Invalid category: must not be empty".to_string());
+                    }
+                    Ok(BulkTransactionOperation::SetCategory {
+                        category: category.to_string(),
+                        category_detailed: category_detailed
+                            .as_deref()
+                            .map(str::trim)
+                            .filter(|d| !d.is_empty())
+                            .map(str::to_string),
+                    })
+                }
+                BulkTransactionOperation::AddTag { tag } => Ok(BulkTransactionOperation::AddTag {
+                    tag: Self::normalize_tag(tag)?,
+                }),
+                BulkTransactionOperation::RemoveTag { tag } => {
+                    Ok(BulkTransactionOperation::RemoveTag {
+                        tag: Self::normalize_tag(tag)?,
+                    })
+                }
+                other => Ok(other.clone()),
+            })
+            .collect()
+    }
+
+    fn normalize_tag(tag: &str) -> Result<String, String> {
+        let tag = tag.trim();
+        if tag.is_empty() || tag.chars().count() > MAX_TAG_LENGTH {
+            return Err(format!(
+                "Invalid tag: must be 1 to {} characters",
+                MAX_TAG_LENGTH
+            ));
+        }
+        Ok(tag.to_string())
+    }
+
+    async fn select<R: DatabaseRepository + ?Sized>(
+        repository: &R,
+        user_id: Uuid,
+        request: &BulkTransactionEditRequest,
+    ) -> Result<Vec<TransactionWithAccount>, String> {
+        match (&request.transaction_ids, &request.filter) {
+            (Some(ids), None) => {
+                let ids: HashSet<Uuid> = ids.iter().copied().collect();
+                if ids.is_empty() || ids.len() > MAX_BULK_EDIT_TRANSACTIONS {
+                    return Err(format!(
+                        "Invalid transaction_ids: select 1 to {} transactions",
+                        MAX_BULK_EDIT_TRANSACTIONS
+                    ));
+                }
+                let ids: Vec<Uuid> = ids.into_iter().collect();
+                let transactions = repository
+                    .get_transactions_with_account_by_ids(&user_id, &ids)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                if transactions.len() < ids.len() {
+                    return Err(format!(
+                        "Invalid transaction_ids: {} do not exist",
+                        ids.len() - transactions.len()
+                    ));
+                }
+                Ok(transactions)
+            }
+            (None, Some(filter)) => {
+                let ids = TransactionSearchService::find_ids(repository, user_id, filter).await?;
+                if ids.len() > MAX_BULK_EDIT_TRANSACTIONS {
+                    return Err(format!(
+                        "Invalid filter: matches {} transactions, narrow it to at most {}",
+                        ids.len(),
+                        MAX_BULK_EDIT_TRANSACTIONS
+                    ));
+                }
+                if ids.is_empty() {
+                    return Ok(Vec::new());
+                }
+                repository
+                    .get_transactions_with_account_by_ids(&user_id, &ids)
+                    .await
+                    .map_err(|e| e.to_string())
+            }
+            _ => Err("Invalid selection: give either transaction_ids or filter".to_string()),
+        }
+    }
+
+    /// Counts what each operation would change when applied in order, without
+    /// writing anything.
+    pub fn simulate(
+        selected: &[TransactionWithAccount],
+        tags: &[TransactionTag],
+        operations: &[BulkTransactionOperation],
+    ) -> Vec<u64> {
+        let mut transactions = selected.to_vec();
+        let selected_ids: HashSet<Uuid> = selected.iter().map(|t| t.id).collect();
+        let mut tagged: HashSet<(Uuid, String)> = tags
+            .iter()
+            .filter(|t| selected_ids.contains(&t.transaction_id))
+            .map(|t| (t.transaction_id, t.tag.clone()))
+            .collect();
+
+        operations
+            .iter()
+            .map(|operation| {
+                let mut affected = 0;
+                for transaction in &mut transactions {
+                    let changed = match operation {
+                        BulkTransactionOperation::SetCategory {
+                            category,
+                            category_detailed,
+                        } => {
+                            let detailed = category_detailed.as_deref().unwrap_or(category);
+                            let changed = transaction.category_primary != *category
+                                || transaction.category_detailed != detailed;
+                            transaction.category_primary = category.clone();
+                            transaction.category_detailed = detailed.to_string();
+                            changed
+                        }
+                        BulkTransactionOperation::AddTag { tag } => {
+                            tagged.insert((transaction.id, tag.clone()))
+                        }
+                        BulkTransactionOperation::RemoveTag { tag } => {
+                            tagged.remove(&(transaction.id, tag.clone()))
+                        }
+                        BulkTransactionOperation::SetReviewed { reviewed } => {
+                            std::mem::replace(&mut transaction.reviewed, *reviewed) != *reviewed
+                        }
+                        BulkTransactionOperation::SetExcludedFromAnalytics { excluded } => {
+                            std::mem::replace(&mut transaction.excluded_from_analytics, *excluded)
+                                != *excluded
+                        }
+                    };
+                    if changed {
+                        affected += 1;
+                    }
+                }
+                affected
+            })
+            .collect()
+    }
+}
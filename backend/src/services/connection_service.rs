@@ -5,16 +5,21 @@ use crate::models::{
         DataCleared, DisconnectResult, ExchangeTokenResponse, ProviderConnectRequest,
         ProviderConnectResponse, ProviderConnection,
     },
+    sync_progress::{SyncPhase, SyncProgressEvent},
     transaction::{SyncMetadata, SyncTransactionsResponse, Transaction},
+    user_settings::UserCalendar,
 };
 use crate::providers::{
     FinancialDataProvider, InstitutionInfo, ProviderCredentials, ProviderRegistry,
 };
 use crate::services::{
-    cache_service::CacheService, repository_service::DatabaseRepository, sync_service::SyncService,
+    alert_service::AlertService, cache_service::CacheService,
+    investment_service::InvestmentService, liability_service::LiabilityService,
+    repository_service::DatabaseRepository, sync_progress_service::SyncProgressService,
+    sync_service::SyncService, user_settings_service::UserSettingsService,
 };
 use anyhow::{Error, Result};
-use chrono::Utc;
+use chrono::{NaiveDate, Utc};
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use uuid::Uuid;
@@ -23,6 +28,8 @@ pub struct ConnectionService {
     db_repository: Arc<dyn DatabaseRepository>,
     cache_service: Arc<dyn CacheService>,
     provider_registry: Arc<ProviderRegistry>,
+    alert_service: Option<Arc<AlertService>>,
+    sync_progress: Option<Arc<SyncProgressService>>,
 }
 
 #[derive(Debug)]
@@ -67,6 +74,38 @@ pub enum ProviderSyncError {
     SyncFailure(Error),
 }
 
+impl ProviderSyncError {
+    /// Explanation suitable for the user when the failure is worth telling them about;
+    /// internal lookup errors return `None`.
+    pub fn user_facing_reason(&self) -> Option<&'static str> {
+        match self {
+            Self::CredentialsMissing => Some("the connection needs to be re-linked"),
+            Self::ProviderRequest(_) => Some("the institution rejected or failed the request"),
+            Self::SyncFailure(_) => Some("the sync could not be completed"),
+            _ => None,
+        }
+    }
+
+    /// Whether the user must link the institution again before syncing can resume.
+    pub fn requires_reauth(&self) -> bool {
+        matches!(self, Self::CredentialsMissing)
+    }
+}
+
+impl TellerSyncError {
+    pub fn user_facing_reason(&self) -> Option<&'static str> {
+        match self {
+            Self::CredentialsMissing => Some("the connection needs to be re-linked"),
+            Self::ProviderRequest(_) => Some("the institution rejected or failed the request"),
+            _ => None,
+        }
+    }
+
+    pub fn requires_reauth(&self) -> bool {
+        matches!(self, Self::CredentialsMissing)
+    }
+}
+
 pub struct SyncConnectionParams<'a> {
     pub provider: &'a str,
     pub user_id: &'a Uuid,
@@ -83,6 +122,182 @@ impl ConnectionService {
             db_repository,
             cache_service,
             provider_registry,
+            alert_service: None,
+            sync_progress: None,
+        }
+    }
+
+    /// Evaluates the user's alert rules after every successful sync.
+    pub fn with_alert_service(mut self, alert_service: Arc<AlertService>) -> Self {
+        self.alert_service = Some(alert_service);
+        self
+    }
+
+    /// Reports each phase of a connection sync to open progress streams.
+    pub fn with_sync_progress(mut self, sync_progress: Arc<SyncProgressService>) -> Self {
+        self.sync_progress = Some(sync_progress);
+        self
+    }
+
+    fn report_progress(&self, event: SyncProgressEvent) {
+        if let Some(sync_progress) = &self.sync_progress {
+            sync_progress.publish(event);
+        }
+    }
+
+    /// Reports the end of a sync: counts on success, the user-facing reason on failure.
+    fn report_outcome(
+        &self,
+        user_id: &Uuid,
+        connection_id: Uuid,
+        outcome: Result<&SyncTransactionsResponse, Option<&str>>,
+    ) {
+        let event = match outcome {
+            Ok(response) => SyncProgressEvent::new(*user_id, connection_id, SyncPhase::Completed)
+                .with_account_count(response.metadata.account_count.max(0) as usize)
+                .with_transaction_count(response.transactions.len()),
+            Err(reason) => SyncProgressEvent::new(*user_id, connection_id, SyncPhase::Failed)
+                .with_error(reason.unwrap_or("the sync could not be completed")),
+        };
+        self.report_progress(event);
+    }
+
+    async fn evaluate_alerts(&self, user_id: &Uuid) {
+        let Some(alert_service) = &self.alert_service else {
+            return;
+        };
+
+        if let Err(e) = alert_service
+            .evaluate_for_user(self.db_repository.as_ref(), *user_id, Utc::now())
+            .await
+        {
+            tracing::warn!("Failed to evaluate alert rules for user {}: {}", user_id, e);
+        }
+    }
+
+    /// The user's calendar for sync dates; UTC months if it cannot be read.
+    async fn user_calendar(&self, user_id: &Uuid) -> UserCalendar {
+        UserSettingsService::calendar(self.db_repository.as_ref(), *user_id)
+            .await
+            .unwrap_or_else(|e| {
+                tracing::warn!("Failed to load settings for user {}: {}", user_id, e);
+                UserCalendar::default()
+            })
+    }
+
+    /// Records today's balance for every account so net worth history reflects
+    /// the freshly synced figures.
+    async fn record_balance_snapshots(&self, user_id: &Uuid) {
+        let today = self.user_calendar(user_id).await.today();
+        if let Err(e) = self
+            .db_repository
+            .record_balance_snapshots(user_id, today)
+            .await
+        {
+            tracing::warn!(
+                "Failed to record balance snapshots for user {}: {}",
+                user_id,
+                e
+            );
+        }
+    }
+
+    /// Refreshes holdings and investment transactions for the connection's
+    /// investment accounts. Failures are logged so they never fail the sync.
+    async fn sync_investments(
+        &self,
+        credentials: &ProviderCredentials,
+        user_id: &Uuid,
+        accounts: &[Account],
+        (start_date, end_date): (NaiveDate, NaiveDate),
+    ) {
+        let Some(provider) = self
+            .resolve_provider(&credentials.provider)
+            .filter(|_| !accounts.is_empty())
+        else {
+            return;
+        };
+
+        let fetched = match provider.get_investment_holdings(credentials).await {
+            Ok(holdings) => provider
+                .get_investment_transactions(credentials, start_date, end_date)
+                .await
+                .map(|activity| (holdings, activity)),
+            Err(e) => Err(e),
+        };
+        let (holdings, activity) = match fetched {
+            Ok(fetched) => fetched,
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to fetch investments for item {} and user {}: {}",
+                    credentials.item_id,
+                    user_id,
+                    e
+                );
+                return;
+            }
+        };
+
+        if let Err(e) = InvestmentService::store_provider_data(
+            self.db_repository.as_ref(),
+            *user_id,
+            accounts,
+            holdings,
+            activity,
+        )
+        .await
+        {
+            tracing::warn!(
+                "Failed to store investments for item {} and user {}: {}",
+                credentials.item_id,
+                user_id,
+                e
+            );
+        }
+    }
+
+    /// Refreshes APR, payment and term details for the connection's credit
+    /// and loan accounts. Failures are logged so they never fail the sync.
+    async fn sync_liabilities(
+        &self,
+        credentials: &ProviderCredentials,
+        user_id: &Uuid,
+        accounts: &[Account],
+    ) {
+        let Some(provider) = self
+            .resolve_provider(&credentials.provider)
+            .filter(|_| !accounts.is_empty())
+        else {
+            return;
+        };
+
+        let liabilities = match provider.get_liabilities(credentials).await {
+            Ok(liabilities) => liabilities,
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to fetch liabilities for item {} and user {}: {}",
+                    credentials.item_id,
+                    user_id,
+                    e
+                );
+                return;
+            }
+        };
+
+        if let Err(e) = LiabilityService::store_provider_liabilities(
+            self.db_repository.as_ref(),
+            *user_id,
+            accounts,
+            liabilities,
+        )
+        .await
+        {
+            tracing::warn!(
+                "Failed to store liabilities for item {} and user {}: {}",
+                credentials.item_id,
+                user_id,
+                e
+            );
         }
     }
 
@@ -410,10 +625,36 @@ impl ConnectionService {
         params: SyncConnectionParams<'_>,
         sync_service: &SyncService,
         connection: &mut ProviderConnection,
+    ) -> Result<SyncTransactionsResponse, ProviderSyncError> {
+        let user_id = *params.user_id;
+        let connection_id = connection.id;
+        self.report_progress(SyncProgressEvent::new(
+            user_id,
+            connection_id,
+            SyncPhase::Started,
+        ));
+
+        let result = self
+            .run_provider_sync(params, sync_service, connection)
+            .await;
+        self.report_outcome(
+            &user_id,
+            connection_id,
+            result.as_ref().map_err(|e| e.user_facing_reason()),
+        );
+        result
+    }
+
+    async fn run_provider_sync(
+        &self,
+        params: SyncConnectionParams<'_>,
+        sync_service: &SyncService,
+        connection: &mut ProviderConnection,
     ) -> Result<SyncTransactionsResponse, ProviderSyncError> {
         let sync_timestamp = Utc::now();
+        let calendar = self.user_calendar(params.user_id).await;
         let (sync_start_date, sync_end_date) =
-            sync_service.calculate_sync_date_range(connection.last_sync_at);
+            sync_service.calculate_sync_date_range(connection.last_sync_at, &calendar);
 
         let credentials_record = self
             .db_repository
@@ -434,6 +675,11 @@ impl ConnectionService {
             .resolve_provider(params.provider)
             .ok_or_else(|| ProviderSyncError::ProviderUnavailable(params.provider.to_string()))?;
 
+        self.report_progress(SyncProgressEvent::new(
+            *params.user_id,
+            connection.id,
+            SyncPhase::FetchingAccounts,
+        ));
         let fetched_accounts = provider_impl
             .as_ref()
             .get_accounts(&provider_credentials)
@@ -460,11 +706,32 @@ impl ConnectionService {
             .await
             .map_err(ProviderSyncError::AccountLookup)?;
 
+        self.report_progress(
+            SyncProgressEvent::new(
+                *params.user_id,
+                connection.id,
+                SyncPhase::FetchingTransactions,
+            )
+            .with_account_count(db_accounts.len()),
+        );
         let (mut transactions, new_cursor) = sync_service
-            .sync_bank_connection_transactions(&provider_credentials, connection, &db_accounts)
+            .sync_bank_connection_transactions(
+                &provider_credentials,
+                &db_accounts,
+                (sync_start_date, sync_end_date),
+            )
             .await
             .map_err(ProviderSyncError::SyncFailure)?;
 
+        self.report_progress(
+            SyncProgressEvent::new(
+                *params.user_id,
+                connection.id,
+                SyncPhase::FetchingTransactions,
+            )
+            .with_account_count(db_accounts.len())
+            .with_transaction_count(transactions.len()),
+        );
         let existing_transactions = self
             .db_repository
             .get_transactions_for_user(params.user_id)
@@ -478,6 +745,12 @@ impl ConnectionService {
             txn.user_id = Some(*params.user_id);
         }
 
+        self.report_progress(
+            SyncProgressEvent::new(*params.user_id, connection.id, SyncPhase::Persisting)
+                .with_account_count(db_accounts.len())
+                .with_transaction_count(transactions.len()),
+        );
+
         let mut persisted_transactions = Vec::new();
 
         for transaction in &transactions {
@@ -512,6 +785,22 @@ impl ConnectionService {
 
         let transactions = persisted_transactions;
 
+        let (liability_accounts, investment_accounts): (Vec<Account>, Vec<Account>) = db_accounts
+            .iter()
+            .filter(|a| a.provider_connection_id == Some(connection.id))
+            .filter(|a| LiabilityService::is_liability_account(a) || a.account_type == "investment")
+            .cloned()
+            .partition(LiabilityService::is_liability_account);
+        self.sync_investments(
+            &provider_credentials,
+            params.user_id,
+            &investment_accounts,
+            (sync_start_date, sync_end_date),
+        )
+        .await;
+        self.sync_liabilities(&provider_credentials, params.user_id, &liability_accounts)
+            .await;
+
         let total_transactions = self
             .db_repository
             .get_transactions_for_user(params.user_id)
@@ -561,6 +850,9 @@ impl ConnectionService {
             "Transaction sync completed"
         );
 
+        self.record_balance_snapshots(params.user_id).await;
+        self.evaluate_alerts(params.user_id).await;
+
         Ok(SyncTransactionsResponse {
             transactions,
             metadata: SyncMetadata {
@@ -583,10 +875,33 @@ impl ConnectionService {
         user_id: &Uuid,
         jwt_id: &str,
         connection: &mut ProviderConnection,
+    ) -> Result<SyncTransactionsResponse, TellerSyncError> {
+        let connection_id = connection.id;
+        self.report_progress(SyncProgressEvent::new(
+            *user_id,
+            connection_id,
+            SyncPhase::Started,
+        ));
+
+        let result = self.run_teller_sync(user_id, jwt_id, connection).await;
+        self.report_outcome(
+            user_id,
+            connection_id,
+            result.as_ref().map_err(|e| e.user_facing_reason()),
+        );
+        result
+    }
+
+    async fn run_teller_sync(
+        &self,
+        user_id: &Uuid,
+        jwt_id: &str,
+        connection: &mut ProviderConnection,
     ) -> Result<SyncTransactionsResponse, TellerSyncError> {
         let sync_timestamp = Utc::now();
+        let calendar = self.user_calendar(user_id).await;
         let (sync_start_date, sync_end_date) =
-            SyncService::calculate_sync_date_range_static(connection.last_sync_at);
+            SyncService::calculate_sync_date_range_static(connection.last_sync_at, &calendar);
 
         let credentials = self
             .db_repository
@@ -609,6 +924,11 @@ impl ConnectionService {
             ))
         })?;
 
+        self.report_progress(SyncProgressEvent::new(
+            *user_id,
+            connection.id,
+            SyncPhase::FetchingAccounts,
+        ));
         let mut fetched_accounts = provider
             .as_ref()
             .get_accounts(&provider_credentials)
@@ -650,12 +970,22 @@ impl ConnectionService {
             })
             .collect();
 
+        self.report_progress(
+            SyncProgressEvent::new(*user_id, connection.id, SyncPhase::FetchingTransactions)
+                .with_account_count(accounts_for_connection.len()),
+        );
         let mut teller_transactions = provider
             .as_ref()
             .get_transactions(&provider_credentials, sync_start_date, sync_end_date)
             .await
             .map_err(TellerSyncError::ProviderRequest)?;
 
+        self.report_progress(
+            SyncProgressEvent::new(*user_id, connection.id, SyncPhase::FetchingTransactions)
+                .with_account_count(accounts_for_connection.len())
+                .with_transaction_count(teller_transactions.len()),
+        );
+
         let existing_transactions = self
             .db_repository
             .get_transactions_for_user(user_id)
@@ -674,6 +1004,11 @@ impl ConnectionService {
                 .unwrap_or(true)
         });
 
+        self.report_progress(
+            SyncProgressEvent::new(*user_id, connection.id, SyncPhase::Persisting)
+                .with_account_count(accounts_for_connection.len())
+                .with_transaction_count(teller_transactions.len()),
+        );
         let mut synced_transactions: Vec<Transaction> = Vec::new();
 
         for mut transaction in teller_transactions {
@@ -786,6 +1121,9 @@ impl ConnectionService {
             "Transaction sync completed"
         );
 
+        self.record_balance_snapshots(user_id).await;
+        self.evaluate_alerts(user_id).await;
+
         Ok(SyncTransactionsResponse {
             transactions: synced_transactions,
             metadata,
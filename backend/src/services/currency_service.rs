@@ -0,0 +1,295 @@
+use crate::models::analytics::{AggregateBucket, AggregateTotal};
+use crate::models::currency::{normalize_currency_code, FxRate, DEFAULT_CURRENCY};
+use crate::models::transaction::Transaction;
+use crate::services::repository_service::DatabaseRepository;
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::str::FromStr;
+use uuid::Uuid;
+
+/// Converts amounts into a single target currency using dated rates.
+///
+/// The rate for a day is the most recent one published on or before it; days
+/// earlier than the first published rate use the earliest rate. Pairs without
+/// a direct quote are inverted or crossed through a shared third currency,
+/// preferring USD.
+pub struct FxConverter {
+    target: String,
+    rates: HashMap<(String, String), BTreeMap<NaiveDate, Decimal>>,
+}
+
+impl FxConverter {
+    pub fn new(target: &str, rates: &[FxRate]) -> Self {
+        let mut by_pair: HashMap<(String, String), BTreeMap<NaiveDate, Decimal>> = HashMap::new();
+        for rate in rates {
+            by_pair
+                .entry((rate.base_currency.clone(), rate.quote_currency.clone()))
+                .or_default()
+                .insert(rate.rate_date, rate.rate);
+        }
+
+        Self {
+            target: target.to_string(),
+            rates: by_pair,
+        }
+    }
+
+    pub fn target(&self) -> &str {
+        &self.target
+    }
+
+    pub fn convert(&self, amount: Decimal, from: &str, on: NaiveDate) -> Option<Decimal> {
+        self.rate(from, &self.target, on).map(|rate| amount * rate)
+    }
+
+    pub fn rate(&self, from: &str, to: &str, on: NaiveDate) -> Option<Decimal> {
+        if from.eq_ignore_ascii_case(to) {
+            return Some(Decimal::ONE);
+        }
+        if let Some(rate) = self.quoted_rate(from, to, on) {
+            return Some(rate);
+        }
+
+        // USD first since most rates are quoted against it, then the rest in
+        // code order, so the same pair always crosses through the same currency.
+        let others: BTreeSet<&str> = self
+            .rates
+            .keys()
+            .flat_map(|(base, quote)| [base.as_str(), quote.as_str()])
+            .filter(|c| *c != DEFAULT_CURRENCY)
+            .collect();
+        let mut pivots = std::iter::once(DEFAULT_CURRENCY)
+            .chain(others)
+            .filter(|c| *c != from && *c != to);
+        pivots.find_map(|pivot| {
+            let first = self.quoted_rate(from, pivot, on)?;
+            let second = self.quoted_rate(pivot, to, on)?;
+            Some(first * second)
+        })
+    }
+
+    fn quoted_rate(&self, from: &str, to: &str, on: NaiveDate) -> Option<Decimal> {
+        if let Some(rate) = self.lookup(from, to, on) {
+            return Some(rate);
+        }
+        self.lookup(to, from, on)
+            .filter(|rate| !rate.is_zero())
+            .map(|rate| Decimal::ONE / rate)
+    }
+
+    fn lookup(&self, base: &str, quote: &str, on: NaiveDate) -> Option<Decimal> {
+        let series = self.rates.get(&(base.to_string(), quote.to_string()))?;
+        series
+            .range(..=on)
+            .next_back()
+            .or_else(|| series.iter().next())
+            .map(|(_, rate)| *rate)
+    }
+}
+
+pub struct CurrencyService;
+
+impl CurrencyService {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub async fn reporting_currency<R: DatabaseRepository + ?Sized>(
+        repository: &R,
+        user_id: Uuid,
+    ) -> Result<String, String> {
+        Ok(repository
+            .get_user_settings(&user_id)
+            .await
+            .map_err(|e| e.to_string())?
+            .unwrap_or_default()
+            .reporting_currency)
+    }
+
+    /// Loads only the rates needed to bring `currencies` into `target`; when
+    /// everything is already in the target currency no rates are read.
+    pub async fn load_converter<R: DatabaseRepository + ?Sized>(
+        repository: &R,
+        target: &str,
+        currencies: &HashSet<String>,
+        until: NaiveDate,
+    ) -> Result<FxConverter, String> {
+        if currencies.iter().all(|c| c.eq_ignore_ascii_case(target)) {
+            return Ok(FxConverter::new(target, &[]));
+        }
+
+        let mut wanted: Vec<String> = currencies.iter().cloned().collect();
+        wanted.push(target.to_string());
+        let rates = repository
+            .get_fx_rates(&wanted, until)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(FxConverter::new(target, &rates))
+    }
+
+    /// Restates transaction amounts in the user's reporting currency using the
+    /// rate on each transaction's date. Transactions in a currency with no
+    /// known rate are left out rather than summed as if they were comparable.
+    pub async fn convert_transactions<R: DatabaseRepository + ?Sized>(
+        repository: &R,
+        user_id: Uuid,
+        transactions: Vec<Transaction>,
+    ) -> Result<Vec<Transaction>, String> {
+        let target = Self::reporting_currency(repository, user_id).await?;
+        let currencies: HashSet<String> = transactions
+            .iter()
+            .map(|t| t.iso_currency_code.clone())
+            .collect();
+        let until = transactions
+            .iter()
+            .map(|t| t.date)
+            .max()
+            .unwrap_or_else(|| chrono::Utc::now().date_naive());
+        let converter = Self::load_converter(repository, &target, &currencies, until).await?;
+
+        Ok(Self::apply_conversion(&converter, transactions, user_id))
+    }
+
+    pub fn apply_conversion(
+        converter: &FxConverter,
+        transactions: Vec<Transaction>,
+        user_id: Uuid,
+    ) -> Vec<Transaction> {
+        let total = transactions.len();
+        let converted: Vec<Transaction> = transactions
+            .into_iter()
+            .filter_map(|mut t| {
+                t.amount = converter.convert(t.amount, &t.iso_currency_code, t.date)?;
+                t.iso_currency_code = converter.target().to_string();
+                Some(t)
+            })
+            .collect();
+
+        if converted.len() < total {
+            tracing::warn!(
+                "Skipped {} transactions for user {} with no FX rate into {}",
+                total - converted.len(),
+                user_id,
+                converter.target()
+            );
+        }
+
+        converted
+    }
+
+    /// Restates SQL aggregate buckets in `target` and merges them per key.
+    /// Buckets in a currency with no known rate are left out, as transactions are.
+    pub async fn convert_aggregates<R: DatabaseRepository + ?Sized>(
+        repository: &R,
+        target: &str,
+        buckets: Vec<AggregateBucket>,
+    ) -> Result<Vec<AggregateTotal>, String> {
+        let currencies: HashSet<String> = buckets
+            .iter()
+            .map(|b| b.iso_currency_code.clone())
+            .collect();
+        let until = buckets
+            .iter()
+            .filter_map(|b| b.rate_date)
+            .max()
+            .unwrap_or_else(|| chrono::Utc::now().date_naive());
+        let converter = Self::load_converter(repository, target, &currencies, until).await?;
+
+        Ok(Self::merge_aggregates(&converter, buckets))
+    }
+
+    pub fn merge_aggregates(
+        converter: &FxConverter,
+        buckets: Vec<AggregateBucket>,
+    ) -> Vec<AggregateTotal> {
+        let mut merged: BTreeMap<String, (Decimal, i64)> = BTreeMap::new();
+        let mut skipped = 0;
+
+        for bucket in buckets {
+            let total = match bucket.rate_date {
+                None => Some(bucket.total),
+                Some(on) => converter.convert(bucket.total, &bucket.iso_currency_code, on),
+            };
+            let Some(total) = total else {
+                skipped += bucket.count;
+                continue;
+            };
+            let entry = merged.entry(bucket.key).or_default();
+            entry.0 += total;
+            entry.1 += bucket.count;
+        }
+
+        if skipped > 0 {
+            tracing::warn!(
+                "Skipped {} aggregated transactions with no FX rate into {}",
+                skipped,
+                converter.target()
+            );
+        }
+
+        merged
+            .into_iter()
+            .map(|(key, (total, count))| AggregateTotal { key, total, count })
+            .collect()
+    }
+
+    /// Parses a rates file with one `date,base,quote,rate` row per line. A
+    /// header row, blank lines, and `#` comments are ignored.
+    pub fn parse_fx_rates_csv(content: &str) -> Result<Vec<FxRate>, String> {
+        let mut rates = Vec::new();
+
+        for (index, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+            if index == 0
+                && fields
+                    .first()
+                    .is_some_and(|f| f.eq_ignore_ascii_case("date"))
+            {
+                continue;
+            }
+
+            let invalid =
+                |reason: &str| format!("Invalid FX rate on line {}: {}", index + 1, reason);
+            let [date, base, quote, rate] = fields.as_slice() else {
+                return Err(invalid("expected date,base,quote,rate"));
+            };
+            let rate_date = NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                .map_err(|_| invalid("date must be YYYY-MM-DD"))?;
+            let base_currency =
+                normalize_currency_code(base).ok_or_else(|| invalid("unknown base currency"))?;
+            let quote_currency =
+                normalize_currency_code(quote).ok_or_else(|| invalid("unknown quote currency"))?;
+            let rate = Decimal::from_str(rate)
+                .ok()
+                .filter(|r| *r > Decimal::ZERO)
+                .ok_or_else(|| invalid("rate must be a positive number"))?;
+
+            rates.push(FxRate {
+                base_currency,
+                quote_currency,
+                rate_date,
+                rate,
+            });
+        }
+
+        Ok(rates)
+    }
+
+    pub async fn import_fx_rates<R: DatabaseRepository + ?Sized>(
+        &self,
+        repository: &R,
+        content: &str,
+    ) -> Result<u64, String> {
+        let rates = Self::parse_fx_rates_csv(content)?;
+        repository
+            .upsert_fx_rates(&rates)
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
@@ -0,0 +1,406 @@
+use crate::models::export::{ExportColumn, ExportFormat, TransactionPageCursor};
+use crate::models::transaction::{TransactionWithAccount, TransactionsQuery};
+use crate::services::analytics_service::AnalyticsService;
+use crate::services::repository_service::DatabaseRepository;
+use chrono::{DateTime, Utc};
+use futures::stream::{self, Stream};
+use rust_decimal::Decimal;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Transactions fetched from the database per streamed chunk.
+pub const EXPORT_PAGE_SIZE: i64 = 500;
+
+/// OFX limits transaction names to 32 characters.
+const OFX_NAME_MAX_CHARS: usize = 32;
+
+/// OFX limits account ids to 22 characters.
+const OFX_ACCTID_MAX_CHARS: usize = 22;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OfxMessageSet {
+    Bank,
+    CreditCard,
+}
+
+impl OfxMessageSet {
+    fn for_account_type(account_type: &str) -> Self {
+        if account_type.eq_ignore_ascii_case("credit") {
+            OfxMessageSet::CreditCard
+        } else {
+            OfxMessageSet::Bank
+        }
+    }
+
+    fn tag(&self) -> &'static str {
+        match self {
+            OfxMessageSet::Bank => "BANKMSGSRSV1",
+            OfxMessageSet::CreditCard => "CREDITCARDMSGSRSV1",
+        }
+    }
+}
+
+/// Incrementally encodes transactions so an export can be streamed chunk by
+/// chunk. Transactions must arrive grouped by account, which is the order
+/// `get_transactions_with_account_page` returns them in; OFX additionally
+/// needs credit accounts to be contiguous.
+pub struct TransactionExportWriter {
+    format: ExportFormat,
+    columns: Vec<ExportColumn>,
+    generated_at: DateTime<Utc>,
+    current_account: Option<(Uuid, OfxMessageSet)>,
+    written: usize,
+}
+
+impl TransactionExportWriter {
+    pub fn new(
+        format: ExportFormat,
+        columns: Vec<ExportColumn>,
+        generated_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            format,
+            columns,
+            generated_at,
+            current_account: None,
+            written: 0,
+        }
+    }
+
+    pub fn header(&self) -> Result<Vec<u8>, String> {
+        match self.format {
+            ExportFormat::Csv => {
+                let names = self.columns.iter().map(|column| column.name());
+                Self::csv_record(names)
+            }
+            ExportFormat::Json => Ok(b"[".to_vec()),
+            ExportFormat::Qif => Ok(Vec::new()),
+            ExportFormat::Ofx => Ok(format!(
+                "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"no\"?>\n\
+                 <?OFX OFXHEADER=\"200\" VERSION=\"220\" SECURITY=\"NONE\" OLDFILEUID=\"NONE\" NEWFILEUID=\"NONE\"?>\n\
+                 <OFX>\n\
+                 <SIGNONMSGSRSV1>\n\
+                 <SONRS>\n\
+                 <STATUS><CODE>0</CODE><SEVERITY>INFO</SEVERITY></STATUS>\n\
+                 <DTSERVER>{}</DTSERVER>\n\
+                 <LANGUAGE>ENG</LANGUAGE>\n\
+                 </SONRS>\n\
+                 </SIGNONMSGSRSV1>\n",
+                self.ofx_timestamp()
+            )
+            .into_bytes()),
+        }
+    }
+
+    pub fn write<'a>(
+        &mut self,
+        transactions: impl IntoIterator<Item = &'a TransactionWithAccount>,
+    ) -> Result<Vec<u8>, String> {
+        let mut out = Vec::new();
+        for transaction in transactions {
+            match self.format {
+                ExportFormat::Csv => {
+                    let values = self.columns.iter().map(|c| c.value(transaction));
+                    out.extend(Self::csv_record(values)?);
+                }
+                ExportFormat::Json => {
+                    if self.written > 0 {
+                        out.push(b',');
+                    }
+                    serde_json::to_writer(&mut out, transaction)
+                        .map_err(|e| format!("Failed to encode transaction: {}", e))?;
+                }
+                ExportFormat::Qif => {
+                    self.switch_qif_account(transaction, &mut out);
+                    out.extend(Self::qif_transaction(transaction).into_bytes());
+                }
+                ExportFormat::Ofx => {
+                    self.switch_ofx_account(transaction, &mut out);
+                    out.extend(Self::ofx_transaction(transaction).into_bytes());
+                }
+            }
+            self.written += 1;
+        }
+        Ok(out)
+    }
+
+    pub fn finish(&mut self) -> Vec<u8> {
+        match self.format {
+            ExportFormat::Csv | ExportFormat::Qif => Vec::new(),
+            ExportFormat::Json => b"]".to_vec(),
+            ExportFormat::Ofx => {
+                let mut out = Vec::new();
+                if let Some((_, message_set)) = self.current_account.take() {
+                    out.extend(Self::close_ofx_statement(message_set).as_bytes());
+                    out.extend(format!("</{}>\n", message_set.tag()).into_bytes());
+                }
+                out.extend(b"</OFX>\n");
+                out
+            }
+        }
+    }
+
+    fn csv_record<I, S>(values: I) -> Result<Vec<u8>, String>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<[u8]>,
+    {
+        let mut writer = csv::Writer::from_writer(Vec::new());
+        writer
+            .write_record(values)
+            .map_err(|e| format!("Failed to encode CSV row: {}", e))?;
+        writer
+            .into_inner()
+            .map_err(|e| format!("Failed to encode CSV row: {}", e))
+    }
+
+    /// Amount with inflows positive and outflows negative, as OFX and QIF expect.
+    /// Stored amounts follow the Plaid convention where spending is positive.
+    pub fn signed_amount(transaction: &TransactionWithAccount) -> Decimal {
+        if transaction.amount < Decimal::ZERO
+            || AnalyticsService::is_income_category(&transaction.category_primary)
+        {
+            transaction.amount.abs()
+        } else {
+            -transaction.amount
+        }
+    }
+
+    fn switch_qif_account(&mut self, transaction: &TransactionWithAccount, out: &mut Vec<u8>) {
+        if self.current_account.map(|(id, _)| id) == Some(transaction.account_id) {
+            return;
+        }
+        let qif_type = match transaction.account_type.to_ascii_lowercase().as_str() {
+            "credit" => "CCard",
+            "loan" => "Oth L",
+            _ => "Bank",
+        };
+        out.extend(
+            format!(
+                "!Account\nN{}\nT{}\n^\n!Type:{}\n",
+                Self::qif_text(&transaction.account_name),
+                qif_type,
+                qif_type
+            )
+            .into_bytes(),
+        );
+        self.current_account = Some((
+            transaction.account_id,
+            OfxMessageSet::for_account_type(&transaction.account_type),
+        ));
+    }
+
+    fn qif_transaction(transaction: &TransactionWithAccount) -> String {
+        let mut entry = format!(
+            "D{}\nT{:.2}\n",
+            transaction.date.format("%m/%d/%Y"),
+            Self::signed_amount(transaction)
+        );
+        if let Some(merchant) = &transaction.merchant_name {
+            entry.push_str(&format!("P{}\n", Self::qif_text(merchant)));
+        }
+        if !transaction.category_primary.is_empty() {
+            entry.push_str(&format!(
+                "L{}\n",
+                Self::qif_text(&transaction.category_primary)
+            ));
+        }
+        entry.push_str("^\n");
+        entry
+    }
+
+    /// QIF fields are line based, so embedded line breaks would start a new field.
+    fn qif_text(value: &str) -> String {
+        value.replace(['\r', '\n'], " ")
+    }
+
+    fn switch_ofx_account(&mut self, transaction: &TransactionWithAccount, out: &mut Vec<u8>) {
+        let previous = self.current_account;
+        if previous.map(|(id, _)| id) == Some(transaction.account_id) {
+            return;
+        }
+        let message_set = OfxMessageSet::for_account_type(&transaction.account_type);
+        if let Some((_, previous_set)) = previous {
+            out.extend(Self::close_ofx_statement(previous_set).as_bytes());
+            if previous_set != message_set {
+                out.extend(format!("</{}>\n", previous_set.tag()).into_bytes());
+            }
+        }
+        if previous.map(|(_, set)| set) != Some(message_set) {
+            out.extend(format!("<{}>\n", message_set.tag()).into_bytes());
+        }
+        out.extend(
+            self.open_ofx_statement(transaction, message_set)
+                .into_bytes(),
+        );
+        self.current_account = Some((transaction.account_id, message_set));
+    }
+
+    /// Statements carry no `LEDGERBAL` because exported transactions have no
+    /// balance attached.
+    fn open_ofx_statement(
+        &self,
+        transaction: &TransactionWithAccount,
+        message_set: OfxMessageSet,
+    ) -> String {
+        let account_id = transaction
+            .account_mask
+            .clone()
+            .unwrap_or_else(|| transaction.account_id.simple().to_string())
+            .chars()
+            .take(OFX_ACCTID_MAX_CHARS)
+            .collect::<String>();
+        let (wrapper, statement, account_from) = match message_set {
+            OfxMessageSet::Bank => {
+                let account_type = match transaction.account_type.to_ascii_lowercase().as_str() {
+                    "loan" => "CREDITLINE",
+                    _ => "CHECKING",
+                };
+                (
+                    "STMTTRNRS",
+                    "STMTRS",
+                    format!(
+                        "<BANKACCTFROM><BANKID>000000000</BANKID><ACCTID>{}</ACCTID><ACCTTYPE>{}</ACCTTYPE></BANKACCTFROM>",
+                        Self::ofx_text(&account_id),
+                        account_type
+                    ),
+                )
+            }
+            OfxMessageSet::CreditCard => (
+                "CCSTMTTRNRS",
+                "CCSTMTRS",
+                format!(
+                    "<CCACCTFROM><ACCTID>{}</ACCTID></CCACCTFROM>",
+                    Self::ofx_text(&account_id)
+                ),
+            ),
+        };
+        format!(
+            "<{wrapper}>\n<TRNUID>{}</TRNUID>\n<STATUS><CODE>0</CODE><SEVERITY>INFO</SEVERITY></STATUS>\n<{statement}>\n<CURDEF>{}</CURDEF>\n{account_from}\n<BANKTRANLIST>\n<DTSTART>{}</DTSTART>\n<DTEND>{}</DTEND>\n",
+            transaction.account_id,
+            Self::ofx_text(&transaction.iso_currency_code),
+            transaction.date.format("%Y%m%d"),
+            self.ofx_timestamp(),
+        )
+    }
+
+    fn close_ofx_statement(message_set: OfxMessageSet) -> &'static str {
+        match message_set {
+            OfxMessageSet::Bank => "</BANKTRANLIST>\n</STMTRS>\n</STMTTRNRS>\n",
+            OfxMessageSet::CreditCard => "</BANKTRANLIST>\n</CCSTMTRS>\n</CCSTMTTRNRS>\n",
+        }
+    }
+
+    fn ofx_transaction(transaction: &TransactionWithAccount) -> String {
+        let amount = Self::signed_amount(transaction);
+        let name = transaction
+            .merchant_name
+            .as_deref()
+            .unwrap_or(&transaction.category_primary)
+            .chars()
+            .take(OFX_NAME_MAX_CHARS)
+            .collect::<String>();
+        let fitid = transaction
+            .provider_transaction_id
+            .clone()
+            .unwrap_or_else(|| transaction.id.to_string());
+        format!(
+            "<STMTTRN>\n<TRNTYPE>{}</TRNTYPE>\n<DTPOSTED>{}</DTPOSTED>\n<TRNAMT>{:.2}</TRNAMT>\n<FITID>{}</FITID>\n<NAME>{}</NAME>\n<MEMO>{}</MEMO>\n</STMTTRN>\n",
+            if amount < Decimal::ZERO { "DEBIT" } else { "CREDIT" },
+            transaction.date.format("%Y%m%d"),
+            amount,
+            Self::ofx_text(&fitid),
+            Self::ofx_text(&name),
+            Self::ofx_text(&transaction.category_primary),
+        )
+    }
+
+    fn ofx_text(value: &str) -> String {
+        value
+            .replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+    }
+
+    fn ofx_timestamp(&self) -> String {
+        self.generated_at.format("%Y%m%d%H%M%S").to_string()
+    }
+}
+
+enum ExportStep {
+    Header,
+    Page(Option<TransactionPageCursor>),
+    Finish,
+    Done,
+}
+
+pub struct ExportService;
+
+impl ExportService {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Streams the user's transactions matching `filters`, one database page per
+    /// chunk, so large histories are never held in memory at once. A failure
+    /// part way through ends the stream with an error, aborting the response.
+    pub fn stream_transactions(
+        &self,
+        repository: Arc<dyn DatabaseRepository>,
+        user_id: Uuid,
+        filters: TransactionsQuery,
+        writer: TransactionExportWriter,
+    ) -> impl Stream<Item = Result<Vec<u8>, String>> + Send + 'static {
+        let filters = Arc::new(filters);
+        stream::unfold((ExportStep::Header, writer), move |(step, writer)| {
+            let repository = repository.clone();
+            let filters = filters.clone();
+            async move { Self::next_chunk(&*repository, user_id, &filters, step, writer).await }
+        })
+    }
+
+    async fn next_chunk(
+        repository: &dyn DatabaseRepository,
+        user_id: Uuid,
+        filters: &TransactionsQuery,
+        step: ExportStep,
+        mut writer: TransactionExportWriter,
+    ) -> Option<(
+        Result<Vec<u8>, String>,
+        (ExportStep, TransactionExportWriter),
+    )> {
+        let (chunk, next) = match step {
+            ExportStep::Header => (writer.header(), ExportStep::Page(None)),
+            ExportStep::Page(after) => {
+                match repository
+                    .get_transactions_with_account_page(&user_id, after, EXPORT_PAGE_SIZE)
+                    .await
+                {
+                    Ok(page) => {
+                        let next = if (page.len() as i64) < EXPORT_PAGE_SIZE {
+                            ExportStep::Finish
+                        } else {
+                            ExportStep::Page(page.last().map(TransactionPageCursor::from))
+                        };
+                        (
+                            writer.write(page.iter().filter(|t| filters.matches(t))),
+                            next,
+                        )
+                    }
+                    Err(e) => (
+                        Err(format!("Failed to load transactions for export: {}", e)),
+                        ExportStep::Done,
+                    ),
+                }
+            }
+            ExportStep::Finish => (Ok(writer.finish()), ExportStep::Done),
+            ExportStep::Done => return None,
+        };
+        let next = if chunk.is_err() {
+            ExportStep::Done
+        } else {
+            next
+        };
+        Some((chunk, (next, writer)))
+    }
+}
@@ -0,0 +1,252 @@
+use crate::models::analytics::BalanceCategory;
+use crate::models::forecast::{CashFlowForecastResponse, DiscretionarySpendRate, ForecastPoint};
+use crate::models::transaction::Transaction;
+use crate::services::analytics_service::AnalyticsService;
+use crate::services::currency_service::CurrencyService;
+use crate::services::recurring_service::RecurringService;
+use crate::services::repository_service::DatabaseRepository;
+use chrono::{Days, NaiveDate};
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use rust_decimal::Decimal;
+use std::collections::{BTreeMap, HashSet};
+use uuid::Uuid;
+
+/// History used to detect recurring series.
+const HISTORY_DAYS: u64 = 180;
+/// Trailing window used to average discretionary spend.
+const DISCRETIONARY_WINDOW_DAYS: u64 = 90;
+/// z-score for an 80% confidence band around the expected balance.
+const CONFIDENCE_Z: f64 = 1.2816;
+
+pub struct ForecastOptions {
+    pub today: NaiveDate,
+    pub horizon_days: u32,
+    pub low_balance_threshold: Decimal,
+    pub account_ids: Option<HashSet<Uuid>>,
+}
+
+pub struct ForecastInputs<'a> {
+    pub today: NaiveDate,
+    pub horizon_days: u32,
+    pub low_balance_threshold: Decimal,
+    pub account_ids: Vec<Uuid>,
+    pub starting_balance: Decimal,
+    pub transactions: &'a [Transaction],
+}
+
+pub struct ForecastService;
+
+impl ForecastService {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub async fn forecast_for_user<R: DatabaseRepository + ?Sized>(
+        &self,
+        repository: &R,
+        user_id: Uuid,
+        options: &ForecastOptions,
+    ) -> Result<CashFlowForecastResponse, String> {
+        let balances = repository
+            .get_latest_account_balances_for_user(&user_id)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let depository: Vec<_> = balances
+            .into_iter()
+            .filter(|row| {
+                AnalyticsService::map_account_to_balance_category(
+                    &row.account_type,
+                    row.account_subtype.as_deref(),
+                ) == BalanceCategory::Cash
+            })
+            .filter(|row| {
+                options
+                    .account_ids
+                    .as_ref()
+                    .is_none_or(|ids| ids.contains(&row.account_id))
+            })
+            .collect();
+
+        let account_ids: Vec<Uuid> = depository.iter().map(|row| row.account_id).collect();
+
+        let history_start = options
+            .today
+            .checked_sub_days(Days::new(HISTORY_DAYS))
+            .unwrap_or(options.today);
+        let transactions: Vec<Transaction> = repository
+            .get_transactions_by_date_range_for_user(&user_id, history_start, options.today)
+            .await
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .filter(|t| account_ids.contains(&t.account_id))
+            .collect();
+
+        // Balances and history are projected in the user's reporting currency.
+        let target = CurrencyService::reporting_currency(repository, user_id).await?;
+        let currencies: HashSet<String> = depository
+            .iter()
+            .map(|row| row.currency.to_uppercase())
+            .chain(transactions.iter().map(|t| t.iso_currency_code.clone()))
+            .collect();
+        let converter =
+            CurrencyService::load_converter(repository, &target, &currencies, options.today)
+                .await?;
+        let starting_balance: Decimal = depository
+            .iter()
+            .filter_map(|row| {
+                converter.convert(
+                    row.current_balance,
+                    &row.currency.to_uppercase(),
+                    options.today,
+                )
+            })
+            .sum();
+        let transactions = CurrencyService::apply_conversion(&converter, transactions, user_id);
+
+        Ok(Self::project(ForecastInputs {
+            today: options.today,
+            horizon_days: options.horizon_days,
+            low_balance_threshold: options.low_balance_threshold,
+            account_ids,
+            starting_balance,
+            transactions: &transactions,
+        }))
+    }
+
+    /// Projects balances day by day from recurring series plus the average
+    /// discretionary spend, widening the band with the square root of elapsed days.
+    pub fn project(inputs: ForecastInputs<'_>) -> CashFlowForecastResponse {
+        let recurring =
+            RecurringService::detect_recurring_series(inputs.transactions, inputs.today);
+        let recurring_ids: HashSet<Uuid> = recurring
+            .iter()
+            .flat_map(|series| series.transaction_ids.iter().copied())
+            .collect();
+
+        let (discretionary, daily_discretionary, daily_std_dev) =
+            Self::discretionary_rates(inputs.transactions, &recurring_ids, inputs.today);
+
+        let horizon_end = inputs
+            .today
+            .checked_add_days(Days::new(u64::from(inputs.horizon_days)))
+            .unwrap_or(inputs.today);
+        let first_day = inputs.today.succ_opt().unwrap_or(inputs.today);
+
+        // A charge due today has not posted yet, or the series would expect it next
+        // period, so it is not in the starting balance; book it on the first day.
+        let mut scheduled: BTreeMap<NaiveDate, (Decimal, Decimal)> = BTreeMap::new();
+        for series in &recurring {
+            for date in series.occurrences_between(inputs.today, horizon_end) {
+                let entry = scheduled
+                    .entry(date.max(first_day))
+                    .or_insert((Decimal::ZERO, Decimal::ZERO));
+                if series.is_income {
+                    entry.0 += series.average_amount;
+                } else {
+                    entry.1 += series.average_amount;
+                }
+            }
+        }
+
+        let mut balance = inputs.starting_balance;
+        let mut series = Vec::with_capacity(inputs.horizon_days as usize);
+        let mut low_balance_dates = Vec::new();
+
+        for offset in 1..=u64::from(inputs.horizon_days) {
+            let Some(date) = inputs.today.checked_add_days(Days::new(offset)) else {
+                break;
+            };
+            let (scheduled_in, scheduled_out) = scheduled
+                .get(&date)
+                .copied()
+                .unwrap_or((Decimal::ZERO, Decimal::ZERO));
+            let outflows = scheduled_out + daily_discretionary;
+            balance += scheduled_in - outflows;
+
+            let half_width =
+                Decimal::from_f64(CONFIDENCE_Z * daily_std_dev * (offset as f64).sqrt())
+                    .unwrap_or(Decimal::ZERO);
+            let low_balance = balance < inputs.low_balance_threshold;
+            if low_balance {
+                low_balance_dates.push(date);
+            }
+
+            series.push(ForecastPoint {
+                date,
+                balance: balance.round_dp(2),
+                lower: (balance - half_width).round_dp(2),
+                upper: (balance + half_width).round_dp(2),
+                inflows: scheduled_in.round_dp(2),
+                outflows: outflows.round_dp(2),
+                low_balance,
+            });
+        }
+
+        CashFlowForecastResponse {
+            start_date: inputs.today,
+            horizon_days: inputs.horizon_days,
+            account_ids: inputs.account_ids,
+            starting_balance: inputs.starting_balance.round_dp(2),
+            low_balance_threshold: inputs.low_balance_threshold,
+            series,
+            low_balance_dates,
+            recurring,
+            discretionary,
+        }
+    }
+
+    /// Returns per-category daily averages, the combined daily average, and the
+    /// standard deviation of daily discretionary totals over the trailing window.
+    fn discretionary_rates(
+        transactions: &[Transaction],
+        recurring_ids: &HashSet<Uuid>,
+        today: NaiveDate,
+    ) -> (Vec<DiscretionarySpendRate>, Decimal, f64) {
+        let window_start = today
+            .checked_sub_days(Days::new(DISCRETIONARY_WINDOW_DAYS - 1))
+            .unwrap_or(today);
+        let mut by_category: BTreeMap<String, Decimal> = BTreeMap::new();
+        let mut by_day = vec![Decimal::ZERO; DISCRETIONARY_WINDOW_DAYS as usize];
+
+        for transaction in transactions {
+            if transaction.date < window_start
+                || transaction.date > today
+                || recurring_ids.contains(&transaction.id)
+                || AnalyticsService::is_income_transaction(transaction)
+            {
+                continue;
+            }
+            let category = if transaction.category_primary.is_empty() {
+                "Uncategorized".to_string()
+            } else {
+                transaction.category_primary.clone()
+            };
+            *by_category.entry(category).or_insert(Decimal::ZERO) += transaction.amount;
+            let index = (transaction.date - window_start).num_days() as usize;
+            by_day[index] += transaction.amount;
+        }
+
+        let window = Decimal::from(DISCRETIONARY_WINDOW_DAYS);
+        let rates: Vec<DiscretionarySpendRate> = by_category
+            .into_iter()
+            .map(|(category, total)| DiscretionarySpendRate {
+                category,
+                daily_average: (total / window).round_dp(2),
+            })
+            .collect();
+        let daily_total = by_day.iter().copied().sum::<Decimal>() / window;
+
+        let mean = daily_total.to_f64().unwrap_or(0.0);
+        let variance = by_day
+            .iter()
+            .map(|d| {
+                let diff = d.to_f64().unwrap_or(0.0) - mean;
+                diff * diff
+            })
+            .sum::<f64>()
+            / DISCRETIONARY_WINDOW_DAYS as f64;
+
+        (rates, daily_total, variance.sqrt())
+    }
+}
@@ -0,0 +1,699 @@
+use crate::models::account::Account;
+use crate::models::budget::Budget;
+use crate::models::import::{
+    ImportAccountPreview, ImportBudgetPreview, ImportCategoryMapping, ImportPreview, ImportRequest,
+    ImportResult, ImportSource, ImportedBudget, ImportedTransaction,
+};
+use crate::models::transaction::Transaction;
+use crate::services::repository_service::DatabaseRepository;
+use chrono::{NaiveDate, Utc};
+use csv::StringRecord;
+use rust_decimal::Decimal;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::str::FromStr;
+use uuid::Uuid;
+
+/// Category names the rest of the app understands.
+const CATEGORIES: [&str; 17] = [
+    "INCOME",
+    "TRANSFER_IN",
+    "TRANSFER_OUT",
+    "LOAN_PAYMENTS",
+    "BANK_FEES",
+    "ENTERTAINMENT",
+    "FOOD_AND_DRINK",
+    "GENERAL_MERCHANDISE",
+    "HOME_IMPROVEMENT",
+    "MEDICAL",
+    "PERSONAL_CARE",
+    "GENERAL_SERVICES",
+    "GOVERNMENT_AND_NON_PROFIT",
+    "TRANSPORTATION",
+    "TRAVEL",
+    "RENT_AND_UTILITIES",
+    "OTHER",
+];
+
+/// Default categories of Mint and the YNAB and Actual starter budgets, keyed by
+/// `category_key`.
+const BUILT_IN_CATEGORIES: [(&str, &str); 68] = [
+    ("income", "INCOME"),
+    ("paycheck", "INCOME"),
+    ("salary", "INCOME"),
+    ("bonus", "INCOME"),
+    ("interest income", "INCOME"),
+    ("ready to assign", "INCOME"),
+    ("to be budgeted", "INCOME"),
+    ("inflow ready to assign", "INCOME"),
+    ("starting balances", "INCOME"),
+    ("transfer", "TRANSFER_OUT"),
+    ("credit card payment", "LOAN_PAYMENTS"),
+    ("loan payment", "LOAN_PAYMENTS"),
+    ("student loan", "LOAN_PAYMENTS"),
+    ("auto payment", "LOAN_PAYMENTS"),
+    ("bank fee", "BANK_FEES"),
+    ("atm fee", "BANK_FEES"),
+    ("service fee", "BANK_FEES"),
+    ("late fee", "BANK_FEES"),
+    ("fees charges", "BANK_FEES"),
+    ("entertainment", "ENTERTAINMENT"),
+    ("movies dvds", "ENTERTAINMENT"),
+    ("music", "ENTERTAINMENT"),
+    ("streaming services", "ENTERTAINMENT"),
+    ("fun money", "ENTERTAINMENT"),
+    ("food", "FOOD_AND_DRINK"),
+    ("groceries", "FOOD_AND_DRINK"),
+    ("restaurants", "FOOD_AND_DRINK"),
+    ("fast food", "FOOD_AND_DRINK"),
+    ("coffee shops", "FOOD_AND_DRINK"),
+    ("food dining", "FOOD_AND_DRINK"),
+    ("dining out", "FOOD_AND_DRINK"),
+    ("alcohol bars", "FOOD_AND_DRINK"),
+    ("shopping", "GENERAL_MERCHANDISE"),
+    ("clothing", "GENERAL_MERCHANDISE"),
+    ("electronics software", "GENERAL_MERCHANDISE"),
+    ("books", "GENERAL_MERCHANDISE"),
+    ("gifts", "GENERAL_MERCHANDISE"),
+    ("home improvement", "HOME_IMPROVEMENT"),
+    ("home maintenance", "HOME_IMPROVEMENT"),
+    ("furnishings", "HOME_IMPROVEMENT"),
+    ("lawn garden", "HOME_IMPROVEMENT"),
+    ("doctor", "MEDICAL"),
+    ("dentist", "MEDICAL"),
+    ("pharmacy", "MEDICAL"),
+    ("medical", "MEDICAL"),
+    ("gym", "PERSONAL_CARE"),
+    ("hair", "PERSONAL_CARE"),
+    ("personal care", "PERSONAL_CARE"),
+    ("insurance", "GENERAL_SERVICES"),
+    ("auto insurance", "GENERAL_SERVICES"),
+    ("education", "GENERAL_SERVICES"),
+    ("childcare", "GENERAL_SERVICES"),
+    ("charity", "GOVERNMENT_AND_NON_PROFIT"),
+    ("gifts donations", "GOVERNMENT_AND_NON_PROFIT"),
+    ("taxes", "GOVERNMENT_AND_NON_PROFIT"),
+    ("gas fuel", "TRANSPORTATION"),
+    ("auto transport", "TRANSPORTATION"),
+    ("parking", "TRANSPORTATION"),
+    ("public transportation", "TRANSPORTATION"),
+    ("air travel", "TRAVEL"),
+    ("hotel", "TRAVEL"),
+    ("vacation", "TRAVEL"),
+    ("mortgage rent", "RENT_AND_UTILITIES"),
+    ("rent mortgage", "RENT_AND_UTILITIES"),
+    ("utilities", "RENT_AND_UTILITIES"),
+    ("internet", "RENT_AND_UTILITIES"),
+    ("mobile phone", "RENT_AND_UTILITIES"),
+    ("uncategorized", "OTHER"),
+];
+
+/// Largest request accepted by the import endpoints; exports spanning many
+/// years of history run to tens of megabytes.
+pub const MAX_IMPORT_UPLOAD_BYTES: usize = 50 * 1024 * 1024;
+
+const UNCATEGORIZED: &str = "Uncategorized";
+const TRANSFER: &str = "Transfer";
+
+/// Export contents with every category resolved as far as possible.
+struct PreparedImport {
+    transactions: Vec<ImportedTransaction>,
+    budgets: Vec<ImportedBudget>,
+    mappings: BTreeMap<String, Option<String>>,
+}
+
+impl PreparedImport {
+    fn mapped_category(&self, transaction: &ImportedTransaction) -> Option<String> {
+        let category = self.mappings.get(&transaction.category)?.clone()?;
+        if category == "TRANSFER_OUT" && transaction.amount < Decimal::ZERO {
+            return Some("TRANSFER_IN".to_string());
+        }
+        Some(category)
+    }
+
+    fn unmapped(&self) -> Vec<String> {
+        self.mappings
+            .iter()
+            .filter(|(_, category)| category.is_none())
+            .map(|(source, _)| source.clone())
+            .collect()
+    }
+
+    /// Budget amounts per mapped category, summed where several source
+    /// categories land on the same one.
+    fn budget_totals(&self) -> BTreeMap<String, Decimal> {
+        let mut totals = BTreeMap::new();
+        for budget in &self.budgets {
+            if let Some(Some(category)) = self.mappings.get(&budget.category) {
+                *totals.entry(category.clone()).or_insert(Decimal::ZERO) += budget.amount;
+            }
+        }
+        totals.retain(|_, amount| *amount > Decimal::ZERO);
+        totals
+    }
+}
+
+/// A CSV file addressed by header name.
+struct CsvTable {
+    headers: HashMap<String, usize>,
+    rows: Vec<StringRecord>,
+}
+
+impl CsvTable {
+    fn parse(name: &str, content: &str) -> Result<Self, String> {
+        let mut reader = csv::ReaderBuilder::new()
+            .flexible(true)
+            .trim(csv::Trim::All)
+            .from_reader(content.trim_start_matches('\u{feff}').as_bytes());
+        let headers = reader
+            .headers()
+            .map_err(|e| format!("Invalid {}: {}", name, e))?
+            .iter()
+            .enumerate()
+            .map(|(index, header)| (header.to_lowercase(), index))
+            .collect();
+        let rows = reader
+            .records()
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("Invalid {}: {}", name, e))?;
+        Ok(Self { headers, rows })
+    }
+
+    fn column(&self, name: &str) -> Result<usize, String> {
+        self.headers
+            .get(&name.to_lowercase())
+            .copied()
+            .ok_or_else(|| format!("Invalid import file: missing column '{}'", name))
+    }
+}
+
+pub struct ImportService;
+
+impl ImportService {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Reads a transactions export into rows with spending as positive amounts.
+    pub fn parse_transactions(
+        source: ImportSource,
+        content: &str,
+    ) -> Result<Vec<ImportedTransaction>, String> {
+        let table = CsvTable::parse("transactions file", content)?;
+        match source {
+            ImportSource::Mint => Self::parse_mint(&table),
+            ImportSource::Ynab => Self::parse_ynab_register(&table),
+            ImportSource::Actual => Self::parse_actual(&table),
+        }
+    }
+
+    /// Reads a YNAB budget export, keeping the amounts budgeted in its latest
+    /// month.
+    pub fn parse_ynab_budget(content: &str) -> Result<Vec<ImportedBudget>, String> {
+        let table = CsvTable::parse("budget file", content)?;
+        let (month, category, budgeted) = (
+            table.column("Month")?,
+            table.column("Category")?,
+            table.column("Budgeted")?,
+        );
+
+        let mut rows = Vec::new();
+        for (line, row) in table.rows.iter().enumerate() {
+            let field = |index: usize| row.get(index).unwrap_or_default();
+            let month = NaiveDate::parse_from_str(&format!("1 {}", field(month)), "%d %b %Y")
+                .map_err(|_| {
+                    format!(
+                        "Invalid budget file: unreadable month '{}' on row {}",
+                        field(month),
+                        line + 2
+                    )
+                })?;
+            rows.push((
+                month,
+                field(category).to_string(),
+                Self::parse_amount(field(budgeted), line)?,
+            ));
+        }
+
+        let Some(latest) = rows.iter().map(|(month, _, _)| *month).max() else {
+            return Ok(vec![]);
+        };
+        Ok(rows
+            .into_iter()
+            .filter(|(month, category, _)| *month == latest && !category.is_empty())
+            .map(|(_, category, amount)| ImportedBudget { category, amount })
+            .collect())
+    }
+
+    /// Resolves a source category: the caller's mapping wins, then the
+    /// built-in table, then an exact match on one of our own categories.
+    pub fn map_category(
+        source_category: &str,
+        overrides: &HashMap<String, String>,
+    ) -> Result<Option<String>, String> {
+        let key = Self::category_key(source_category);
+        let mapped = overrides.get(source_category).or_else(|| {
+            overrides
+                .iter()
+                .find(|(name, _)| Self::category_key(name) == key)
+                .map(|(_, category)| category)
+        });
+        if let Some(category) = mapped {
+            let category = category.trim();
+            if category.is_empty() {
+                return Err(format!(
+                    "Invalid category mapping for '{}': category is empty",
+                    source_category
+                ));
+            }
+            return Ok(Some(
+                Self::known_category(category)
+                    .unwrap_or(category)
+                    .to_string(),
+            ));
+        }
+
+        Ok(BUILT_IN_CATEGORIES
+            .iter()
+            .find(|(name, _)| *name == key)
+            .map(|(_, category)| *category)
+            .or_else(|| Self::known_category(source_category))
+            .map(str::to_string))
+    }
+
+    pub async fn preview<R: DatabaseRepository + ?Sized>(
+        &self,
+        repository: &R,
+        user_id: Uuid,
+        request: &ImportRequest,
+    ) -> Result<ImportPreview, String> {
+        let prepared = Self::prepare(request)?;
+        let accounts = repository
+            .get_accounts_for_user(&user_id)
+            .await
+            .map_err(|e| e.to_string())?;
+        let budgets = repository
+            .get_budgets_for_user(user_id)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let mut account_counts: BTreeMap<&str, usize> = BTreeMap::new();
+        let mut category_counts: BTreeMap<&str, usize> = BTreeMap::new();
+        for transaction in &prepared.transactions {
+            *account_counts.entry(&transaction.account_name).or_default() += 1;
+            *category_counts.entry(&transaction.category).or_default() += 1;
+        }
+
+        Ok(ImportPreview {
+            source: request.source,
+            transaction_count: prepared.transactions.len(),
+            first_date: prepared.transactions.iter().map(|t| t.date).min(),
+            last_date: prepared.transactions.iter().map(|t| t.date).max(),
+            accounts: account_counts
+                .into_iter()
+                .map(|(name, transaction_count)| ImportAccountPreview {
+                    name: name.to_string(),
+                    account_type: Self::account_type(name).to_string(),
+                    transaction_count,
+                    existing_account_id: Self::existing_account(&accounts, name).map(|a| a.id),
+                })
+                .collect(),
+            categories: prepared
+                .mappings
+                .iter()
+                .map(|(source_category, category)| ImportCategoryMapping {
+                    source_category: source_category.clone(),
+                    category: category.clone(),
+                    transaction_count: category_counts
+                        .get(source_category.as_str())
+                        .copied()
+                        .unwrap_or(0),
+                })
+                .collect(),
+            unmapped_categories: prepared.unmapped(),
+            budgets: prepared
+                .budget_totals()
+                .into_iter()
+                .map(|(category, amount)| ImportBudgetPreview {
+                    exists: Self::has_budget(&budgets, &category),
+                    category,
+                    amount,
+                })
+                .collect(),
+        })
+    }
+
+    /// Writes the import. Every source category must map to one of ours; the
+    /// preview lists the ones that do not. Re-running the same import updates
+    /// the transactions it created instead of duplicating them.
+    pub async fn commit<R: DatabaseRepository + ?Sized>(
+        &self,
+        repository: &R,
+        user_id: Uuid,
+        request: &ImportRequest,
+    ) -> Result<ImportResult, String> {
+        let prepared = Self::prepare(request)?;
+        let unmapped = prepared.unmapped();
+        if !unmapped.is_empty() {
+            return Err(format!(
+                "Invalid import: map these categories first: {}",
+                unmapped.join(", ")
+            ));
+        }
+
+        let currency = repository
+            .get_user_settings(&user_id)
+            .await
+            .map_err(|e| e.to_string())?
+            .unwrap_or_default()
+            .reporting_currency;
+        let (account_ids, accounts_created) =
+            Self::resolve_accounts(repository, user_id, request.source, &prepared, &currency)
+                .await?;
+
+        let mut occurrences: HashMap<String, usize> = HashMap::new();
+        for imported in &prepared.transactions {
+            let key = Self::transaction_key(user_id, imported);
+            let occurrence = occurrences.entry(key.clone()).or_default();
+            *occurrence += 1;
+            let transaction = Transaction {
+                id: Uuid::new_v4(),
+                account_id: account_ids[&imported.account_name],
+                user_id: Some(user_id),
+                provider_account_id: None,
+                provider_transaction_id: Some(Self::stable_id(
+                    request.source,
+                    &format!("{}|{}", key, occurrence),
+                )),
+                amount: imported.amount,
+                iso_currency_code: currency.clone(),
+                date: imported.date,
+                merchant_name: imported.payee.clone(),
+                category_primary: prepared.mapped_category(imported).unwrap_or_default(),
+                category_detailed: imported.category.clone(),
+                category_confidence: String::new(),
+                payment_channel: None,
+                pending: false,
+                created_at: Some(Utc::now()),
+                reviewed: false,
+                excluded_from_analytics: false,
+            };
+            repository
+                .upsert_transaction(&transaction)
+                .await
+                .map_err(|e| e.to_string())?;
+        }
+
+        let existing_budgets = repository
+            .get_budgets_for_user(user_id)
+            .await
+            .map_err(|e| e.to_string())?;
+        let mut budgets_created = 0;
+        for (category, amount) in prepared.budget_totals() {
+            if Self::has_budget(&existing_budgets, &category) {
+                continue;
+            }
+            repository
+                .create_budget_for_user(Budget::new(user_id, category, amount))
+                .await
+                .map_err(|e| e.to_string())?;
+            budgets_created += 1;
+        }
+
+        Ok(ImportResult {
+            accounts_created,
+            transactions_imported: prepared.transactions.len(),
+            budgets_created,
+        })
+    }
+
+    fn prepare(request: &ImportRequest) -> Result<PreparedImport, String> {
+        let transactions = Self::parse_transactions(request.source, &request.transactions_csv)?;
+        let budgets = match (&request.budget_csv, request.source) {
+            (Some(content), ImportSource::Ynab) => Self::parse_ynab_budget(content)?,
+            _ => vec![],
+        };
+
+        let mut mappings = BTreeMap::new();
+        let source_categories = transactions
+            .iter()
+            .map(|t| &t.category)
+            .chain(budgets.iter().map(|b| &b.category));
+        for source_category in source_categories {
+            if !mappings.contains_key(source_category) {
+                let category = Self::map_category(source_category, &request.category_mappings)?;
+                mappings.insert(source_category.clone(), category);
+            }
+        }
+
+        Ok(PreparedImport {
+            transactions,
+            budgets,
+            mappings,
+        })
+    }
+
+    /// Finds or creates an account for every account name in the import.
+    async fn resolve_accounts<R: DatabaseRepository + ?Sized>(
+        repository: &R,
+        user_id: Uuid,
+        source: ImportSource,
+        prepared: &PreparedImport,
+        currency: &str,
+    ) -> Result<(HashMap<String, Uuid>, usize), String> {
+        let existing = repository
+            .get_accounts_for_user(&user_id)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let mut account_ids = HashMap::new();
+        let mut created = 0;
+        for transaction in &prepared.transactions {
+            let name = &transaction.account_name;
+            if account_ids.contains_key(name) {
+                continue;
+            }
+            if let Some(account) = Self::existing_account(&existing, name) {
+                account_ids.insert(name.clone(), account.id);
+                continue;
+            }
+            let account = Account {
+                id: Uuid::new_v4(),
+                user_id: Some(user_id),
+                provider_account_id: Some(Self::stable_id(
+                    source,
+                    &format!("{}|{}", user_id, name.to_lowercase()),
+                )),
+                provider_connection_id: None,
+                name: name.clone(),
+                account_type: Self::account_type(name).to_string(),
+                balance_current: None,
+                iso_currency_code: currency.to_string(),
+                mask: None,
+                institution_name: None,
+            };
+            repository
+                .upsert_account(&account)
+                .await
+                .map_err(|e| e.to_string())?;
+            account_ids.insert(name.clone(), account.id);
+            created += 1;
+        }
+        Ok((account_ids, created))
+    }
+
+    fn parse_mint(table: &CsvTable) -> Result<Vec<ImportedTransaction>, String> {
+        let (date, description, amount, kind, category, account) = (
+            table.column("Date")?,
+            table.column("Description")?,
+            table.column("Amount")?,
+            table.column("Transaction Type")?,
+            table.column("Category")?,
+            table.column("Account Name")?,
+        );
+        table
+            .rows
+            .iter()
+            .enumerate()
+            .map(|(line, row)| {
+                let field = |index: usize| row.get(index).unwrap_or_default();
+                let magnitude = Self::parse_amount(field(amount), line)?.abs();
+                Ok(ImportedTransaction {
+                    account_name: Self::account_name(field(account), line)?,
+                    date: Self::parse_date(field(date), line)?,
+                    payee: Self::non_empty(field(description)),
+                    amount: if field(kind).eq_ignore_ascii_case("credit") {
+                        -magnitude
+                    } else {
+                        magnitude
+                    },
+                    category: Self::category_or_default(field(category), field(description)),
+                })
+            })
+            .collect()
+    }
+
+    fn parse_ynab_register(table: &CsvTable) -> Result<Vec<ImportedTransaction>, String> {
+        let (account, date, payee, category, outflow, inflow) = (
+            table.column("Account")?,
+            table.column("Date")?,
+            table.column("Payee")?,
+            table.column("Category")?,
+            table.column("Outflow")?,
+            table.column("Inflow")?,
+        );
+        table
+            .rows
+            .iter()
+            .enumerate()
+            .map(|(line, row)| {
+                let field = |index: usize| row.get(index).unwrap_or_default();
+                Ok(ImportedTransaction {
+                    account_name: Self::account_name(field(account), line)?,
+                    date: Self::parse_date(field(date), line)?,
+                    payee: Self::non_empty(field(payee)),
+                    amount: Self::parse_amount(field(outflow), line)?
+                        - Self::parse_amount(field(inflow), line)?,
+                    category: Self::category_or_default(field(category), field(payee)),
+                })
+            })
+            .collect()
+    }
+
+    fn parse_actual(table: &CsvTable) -> Result<Vec<ImportedTransaction>, String> {
+        let (account, date, payee, category, amount) = (
+            table.column("Account")?,
+            table.column("Date")?,
+            table.column("Payee")?,
+            table.column("Category")?,
+            table.column("Amount")?,
+        );
+        table
+            .rows
+            .iter()
+            .enumerate()
+            .map(|(line, row)| {
+                let field = |index: usize| row.get(index).unwrap_or_default();
+                Ok(ImportedTransaction {
+                    account_name: Self::account_name(field(account), line)?,
+                    date: Self::parse_date(field(date), line)?,
+                    payee: Self::non_empty(field(payee)),
+                    // Actual records outflows as negative amounts.
+                    amount: -Self::parse_amount(field(amount), line)?,
+                    category: Self::category_or_default(field(category), field(payee)),
+                })
+            })
+            .collect()
+    }
+
+    fn parse_date(value: &str, line: usize) -> Result<NaiveDate, String> {
+        ["%Y-%m-%d", "%m/%d/%Y"]
+            .iter()
+            .find_map(|format| NaiveDate::parse_from_str(value, format).ok())
+            .ok_or_else(|| {
+                format!(
+                    "Invalid import file: unreadable date '{}' on row {}",
+                    value,
+                    line + 2
+                )
+            })
+    }
+
+    /// Parses amounts such as `1,234.56`, `-$12.00` and `($12.00)`.
+    fn parse_amount(value: &str, line: usize) -> Result<Decimal, String> {
+        let negative = value.starts_with('-') || value.starts_with('(');
+        let digits: String = value
+            .chars()
+            .filter(|c| c.is_ascii_digit() || *c == '.')
+            .collect();
+        if digits.is_empty() {
+            return Ok(Decimal::ZERO);
+        }
+        let amount = Decimal::from_str(&digits).map_err(|_| {
+            format!(
+                "Invalid import file: unreadable amount '{}' on row {}",
+                value,
+                line + 2
+            )
+        })?;
+        Ok(if negative { -amount } else { amount })
+    }
+
+    fn account_name(value: &str, line: usize) -> Result<String, String> {
+        Self::non_empty(value)
+            .ok_or_else(|| format!("Invalid import file: missing account on row {}", line + 2))
+    }
+
+    fn category_or_default(category: &str, payee: &str) -> String {
+        match Self::non_empty(category) {
+            Some(category) => category,
+            None if payee.trim_start().starts_with(TRANSFER) => TRANSFER.to_string(),
+            None => UNCATEGORIZED.to_string(),
+        }
+    }
+
+    fn non_empty(value: &str) -> Option<String> {
+        let value = value.trim();
+        (!value.is_empty()).then(|| value.to_string())
+    }
+
+    fn account_type(name: &str) -> &'static str {
+        let name = name.to_lowercase();
+        let has = |words: &[&str]| words.iter().any(|word| name.contains(word));
+        if has(&["credit", "card", "visa", "mastercard", "amex"]) {
+            "credit"
+        } else if has(&["loan", "mortgage"]) {
+            "loan"
+        } else if has(&["brokerage", "invest", "401k", "ira"]) {
+            "investment"
+        } else {
+            "depository"
+        }
+    }
+
+    fn existing_account<'a>(accounts: &'a [Account], name: &str) -> Option<&'a Account> {
+        accounts.iter().find(|a| a.name.eq_ignore_ascii_case(name))
+    }
+
+    fn has_budget(budgets: &[Budget], category: &str) -> bool {
+        budgets
+            .iter()
+            .any(|b| b.category.eq_ignore_ascii_case(category))
+    }
+
+    fn known_category(name: &str) -> Option<&'static str> {
+        let key = Self::category_key(name);
+        CATEGORIES
+            .iter()
+            .find(|category| Self::category_key(category) == key)
+            .copied()
+    }
+
+    /// Lowercase words, so that "Gas & Fuel", "gas_fuel" and "GAS FUEL" match.
+    fn category_key(name: &str) -> String {
+        name.split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .map(str::to_lowercase)
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    fn transaction_key(user_id: Uuid, transaction: &ImportedTransaction) -> String {
+        format!(
+            "{}|{}|{}|{}|{}|{}",
+            user_id,
+            transaction.account_name.to_lowercase(),
+            transaction.date,
+            transaction.amount.normalize(),
+            transaction.payee.as_deref().unwrap_or_default(),
+            transaction.category
+        )
+    }
+
+    /// Provider ids are unique across all users, so imported ids hash in the
+    /// user id along with the row contents.
+    fn stable_id(source: ImportSource, key: &str) -> String {
+        let digest = hex::encode(Sha256::digest(key.as_bytes()));
+        format!("import-{}-{}", source.as_str(), &digest[..32])
+    }
+}
@@ -0,0 +1,408 @@
+use crate::models::insight::{Insight, InsightKind, InsightsResponse};
+use crate::models::transaction::Transaction;
+use crate::services::analytics_service::AnalyticsService;
+use crate::services::currency_service::CurrencyService;
+use crate::services::recurring_service::RecurringService;
+use crate::services::repository_service::DatabaseRepository;
+use chrono::{Datelike, Days, Months, NaiveDate};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use uuid::Uuid;
+
+/// History loaded to judge what is normal for the user.
+const HISTORY_MONTHS: u32 = 12;
+/// Only charges posted within this window are flagged individually.
+const RECENT_DAYS: u64 = 30;
+
+const SPIKE_TRAILING_MONTHS: u32 = 6;
+const SPIKE_MIN_HISTORY_MONTHS: usize = 3;
+const SPIKE_RATIO: Decimal = dec!(1.5);
+const SPIKE_MIN_EXCESS: Decimal = dec!(50);
+
+const UNUSUAL_MIN_PRIOR_CHARGES: usize = 3;
+const UNUSUAL_RATIO: Decimal = dec!(2);
+const UNUSUAL_MIN_EXCESS: Decimal = dec!(20);
+
+/// Without this much history every merchant looks new.
+const NEW_MERCHANT_MIN_HISTORY_DAYS: u64 = 90;
+const NEW_MERCHANT_MIN_AMOUNT: Decimal = dec!(100);
+const NEW_MERCHANT_MEDIAN_MULTIPLE: Decimal = dec!(3);
+
+pub struct InsightsService;
+
+impl InsightsService {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub async fn list_insights<R: DatabaseRepository + ?Sized>(
+        &self,
+        repository: &R,
+        user_id: Uuid,
+        today: NaiveDate,
+    ) -> Result<InsightsResponse, String> {
+        let start_date = today
+            .checked_sub_months(Months::new(HISTORY_MONTHS))
+            .unwrap_or(today);
+        let mut transactions = repository
+            .get_transactions_by_date_range_for_user(&user_id, start_date, today)
+            .await
+            .map_err(|e| e.to_string())?;
+        transactions.retain(|t| !t.excluded_from_analytics);
+
+        let currency = CurrencyService::reporting_currency(repository, user_id).await?;
+        let currencies: HashSet<String> = transactions
+            .iter()
+            .map(|t| t.iso_currency_code.clone())
+            .collect();
+        let converter =
+            CurrencyService::load_converter(repository, &currency, &currencies, today).await?;
+        let transactions = CurrencyService::apply_conversion(&converter, transactions, user_id);
+
+        let dismissed: HashSet<String> = repository
+            .get_dismissed_insight_ids(&user_id)
+            .await
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .collect();
+
+        let insights = Self::detect(&transactions, today)
+            .into_iter()
+            .filter(|insight| !dismissed.contains(&insight.id))
+            .collect();
+
+        Ok(InsightsResponse { insights, currency })
+    }
+
+    pub async fn dismiss<R: DatabaseRepository + ?Sized>(
+        &self,
+        repository: &R,
+        user_id: Uuid,
+        insight_id: &str,
+    ) -> Result<(), String> {
+        let known_kind = insight_id
+            .split_once(':')
+            .and_then(|(kind, _)| InsightKind::parse(kind))
+            .is_some();
+        if !known_kind || insight_id.len() > 255 {
+            return Err(format!("Invalid insight id: {}", insight_id));
+        }
+
+        repository
+            .dismiss_insight(&user_id, insight_id)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    /// Flags unusual spending in `transactions`, newest first. Income and
+    /// pending transactions are ignored.
+    pub fn detect(transactions: &[Transaction], today: NaiveDate) -> Vec<Insight> {
+        let mut spending: Vec<&Transaction> = transactions
+            .iter()
+            .filter(|t| t.date <= today && !t.pending)
+            .filter(|t| !AnalyticsService::is_income_transaction(t))
+            .collect();
+        spending.sort_by_key(|t| (t.date, t.id));
+
+        let recent_start = today
+            .checked_sub_days(Days::new(RECENT_DAYS))
+            .unwrap_or(today);
+
+        let mut insights = Self::category_spikes(&spending, today);
+        insights.extend(Self::unusual_amounts(&spending, recent_start));
+        insights.extend(Self::duplicate_charges(&spending, recent_start));
+        insights.extend(Self::new_merchants(&spending, recent_start, today));
+
+        insights.sort_by(|a, b| {
+            b.date
+                .cmp(&a.date)
+                .then_with(|| a.kind.as_str().cmp(b.kind.as_str()))
+                .then_with(|| a.id.cmp(&b.id))
+        });
+        insights
+    }
+
+    /// Month-to-date spending per category against the median of the trailing
+    /// full months. Months before the user's first transaction are not counted,
+    /// but months with no spending in the category count as zero.
+    fn category_spikes(spending: &[&Transaction], today: NaiveDate) -> Vec<Insight> {
+        let Some(first_date) = spending.first().map(|t| t.date) else {
+            return Vec::new();
+        };
+        let month_start = today.with_day(1).unwrap_or(today);
+        let trailing_months: Vec<NaiveDate> = (1..=SPIKE_TRAILING_MONTHS)
+            .filter_map(|back| month_start.checked_sub_months(Months::new(back)))
+            .filter(|start| {
+                start
+                    .checked_add_months(Months::new(1))
+                    .is_some_and(|next| next > first_date)
+            })
+            .collect();
+        if trailing_months.len() < SPIKE_MIN_HISTORY_MONTHS {
+            return Vec::new();
+        }
+
+        let mut current: BTreeMap<String, Vec<&Transaction>> = BTreeMap::new();
+        let mut monthly: HashMap<(String, NaiveDate), Decimal> = HashMap::new();
+        for transaction in spending {
+            let category = Self::category_label(transaction);
+            if transaction.date >= month_start {
+                current.entry(category).or_default().push(transaction);
+            } else {
+                let start = transaction.date.with_day(1).unwrap_or(transaction.date);
+                *monthly.entry((category, start)).or_default() += transaction.amount;
+            }
+        }
+
+        current
+            .into_iter()
+            .filter_map(|(category, transactions)| {
+                let spent: Decimal = transactions.iter().map(|t| t.amount).sum();
+                let history: Vec<Decimal> = trailing_months
+                    .iter()
+                    .map(|start| {
+                        monthly
+                            .get(&(category.clone(), *start))
+                            .copied()
+                            .unwrap_or_default()
+                    })
+                    .collect();
+                let median = Self::median(history)?;
+                if median <= Decimal::ZERO
+                    || spent < median * SPIKE_RATIO
+                    || spent - median < SPIKE_MIN_EXCESS
+                {
+                    return None;
+                }
+
+                let above = ((spent - median) * Decimal::from(100) / median).round();
+                Some(Insight {
+                    id: format!(
+                        "{}:{}:{}",
+                        InsightKind::CategorySpike.as_str(),
+                        category,
+                        month_start.format("%Y-%m")
+                    ),
+                    kind: InsightKind::CategorySpike,
+                    title: format!("{} spending is running high", category),
+                    explanation: format!(
+                        "{} spending is {:.2} so far this month, {}% above its {}-month median of {:.2}.",
+                        category,
+                        spent,
+                        above,
+                        trailing_months.len(),
+                        median
+                    ),
+                    date: today,
+                    amount: spent,
+                    category: Some(category),
+                    merchant: None,
+                    transaction_ids: transactions.iter().map(|t| t.id).collect(),
+                })
+            })
+            .collect()
+    }
+
+    /// Recent charges far above what the same merchant charged before.
+    fn unusual_amounts(spending: &[&Transaction], recent_start: NaiveDate) -> Vec<Insight> {
+        let mut by_merchant: HashMap<String, Vec<&Transaction>> = HashMap::new();
+        for transaction in spending {
+            if let Some(key) = Self::merchant_key(transaction) {
+                by_merchant.entry(key).or_default().push(transaction);
+            }
+        }
+
+        let mut insights = Vec::new();
+        for transactions in by_merchant.values() {
+            for transaction in transactions.iter().filter(|t| t.date >= recent_start) {
+                let prior: Vec<Decimal> = transactions
+                    .iter()
+                    .filter(|t| t.date < transaction.date)
+                    .map(|t| t.amount)
+                    .collect();
+                if prior.len() < UNUSUAL_MIN_PRIOR_CHARGES {
+                    continue;
+                }
+                let Some(median) = Self::median(prior) else {
+                    continue;
+                };
+                if median <= Decimal::ZERO
+                    || transaction.amount < median * UNUSUAL_RATIO
+                    || transaction.amount - median < UNUSUAL_MIN_EXCESS
+                {
+                    continue;
+                }
+
+                let merchant = Self::merchant_label(transaction);
+                insights.push(Insight {
+                    id: format!("{}:{}", InsightKind::UnusualAmount.as_str(), transaction.id),
+                    kind: InsightKind::UnusualAmount,
+                    title: format!("Unusual charge at {}", merchant),
+                    explanation: format!(
+                        "{} charged {:.2} on {}, {:.1}x its usual {:.2}.",
+                        merchant,
+                        transaction.amount,
+                        transaction.date,
+                        transaction.amount / median,
+                        median
+                    ),
+                    date: transaction.date,
+                    amount: transaction.amount,
+                    category: Some(Self::category_label(transaction)),
+                    merchant: Some(merchant),
+                    transaction_ids: vec![transaction.id],
+                });
+            }
+        }
+        insights
+    }
+
+    /// Recent charges from the same merchant, for the same amount, on the same
+    /// account and day.
+    fn duplicate_charges(spending: &[&Transaction], recent_start: NaiveDate) -> Vec<Insight> {
+        let mut groups: BTreeMap<(NaiveDate, Uuid, String, Decimal), Vec<&Transaction>> =
+            BTreeMap::new();
+        for transaction in spending.iter().filter(|t| t.date >= recent_start) {
+            if let Some(key) = Self::merchant_key(transaction) {
+                groups
+                    .entry((
+                        transaction.date,
+                        transaction.account_id,
+                        key,
+                        transaction.amount,
+                    ))
+                    .or_default()
+                    .push(transaction);
+            }
+        }
+
+        groups
+            .into_values()
+            .filter(|group| group.len() > 1)
+            .map(|group| {
+                let first = group[0];
+                let merchant = Self::merchant_label(first);
+                let mut transaction_ids: Vec<Uuid> = group.iter().map(|t| t.id).collect();
+                transaction_ids.sort();
+                Insight {
+                    id: format!(
+                        "{}:{}",
+                        InsightKind::DuplicateCharge.as_str(),
+                        transaction_ids[0]
+                    ),
+                    kind: InsightKind::DuplicateCharge,
+                    title: format!("Possible duplicate charge at {}", merchant),
+                    explanation: format!(
+                        "{} charged {:.2} {} times on {}.",
+                        merchant,
+                        first.amount,
+                        group.len(),
+                        first.date
+                    ),
+                    date: first.date,
+                    amount: first.amount,
+                    category: Some(Self::category_label(first)),
+                    merchant: Some(merchant),
+                    transaction_ids,
+                }
+            })
+            .collect()
+    }
+
+    /// The largest first-day charge from merchants first seen recently, when it
+    /// is large both in absolute terms and against the user's typical charge.
+    fn new_merchants(
+        spending: &[&Transaction],
+        recent_start: NaiveDate,
+        today: NaiveDate,
+    ) -> Vec<Insight> {
+        let history_needed = today
+            .checked_sub_days(Days::new(NEW_MERCHANT_MIN_HISTORY_DAYS))
+            .unwrap_or(today);
+        if spending.first().is_none_or(|t| t.date > history_needed) {
+            return Vec::new();
+        }
+        let typical = Self::median(spending.iter().map(|t| t.amount).collect()).unwrap_or_default();
+        let threshold = std::cmp::max(
+            NEW_MERCHANT_MIN_AMOUNT,
+            typical * NEW_MERCHANT_MEDIAN_MULTIPLE,
+        );
+
+        let mut first_day: HashMap<String, Vec<&Transaction>> = HashMap::new();
+        for transaction in spending {
+            let Some(key) = Self::merchant_key(transaction) else {
+                continue;
+            };
+            let charges = first_day.entry(key).or_default();
+            if charges.first().is_none_or(|t| t.date == transaction.date) {
+                charges.push(transaction);
+            }
+        }
+
+        first_day
+            .into_values()
+            .filter_map(|charges| {
+                let largest = charges
+                    .into_iter()
+                    .filter(|t| t.date >= recent_start)
+                    .max_by_key(|t| (t.amount, t.id))?;
+                if largest.amount < threshold {
+                    return None;
+                }
+
+                let merchant = Self::merchant_label(largest);
+                Some(Insight {
+                    id: format!("{}:{}", InsightKind::NewMerchant.as_str(), largest.id),
+                    kind: InsightKind::NewMerchant,
+                    title: format!("Large first charge from {}", merchant),
+                    explanation: format!(
+                        "{} is a new merchant and charged {:.2} on {}, more than {:.2}.",
+                        merchant, largest.amount, largest.date, threshold
+                    ),
+                    date: largest.date,
+                    amount: largest.amount,
+                    category: Some(Self::category_label(largest)),
+                    merchant: Some(merchant),
+                    transaction_ids: vec![largest.id],
+                })
+            })
+            .collect()
+    }
+
+    fn median(mut values: Vec<Decimal>) -> Option<Decimal> {
+        if values.is_empty() {
+            return None;
+        }
+        values.sort();
+        let middle = values.len() / 2;
+        Some(if values.len().is_multiple_of(2) {
+            (values[middle - 1] + values[middle]) / Decimal::from(2)
+        } else {
+            values[middle]
+        })
+    }
+
+    fn merchant_key(transaction: &Transaction) -> Option<String> {
+        transaction
+            .merchant_name
+            .as_deref()
+            .map(RecurringService::normalize_merchant)
+            .filter(|k| !k.is_empty())
+    }
+
+    fn merchant_label(transaction: &Transaction) -> String {
+        transaction
+            .merchant_name
+            .clone()
+            .unwrap_or_else(|| "Unknown Merchant".to_string())
+    }
+
+    fn category_label(transaction: &Transaction) -> String {
+        if transaction.category_primary.is_empty() {
+            "Uncategorized".to_string()
+        } else {
+            transaction.category_primary.clone()
+        }
+    }
+}
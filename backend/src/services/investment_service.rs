@@ -0,0 +1,331 @@
+use crate::models::account::Account;
+use crate::models::investment::{
+    AllocationResponse, AssetAllocation, AssetClass, Holding, HoldingView, HoldingsResponse,
+    InvestmentTransaction, Security,
+};
+use crate::providers::{InvestmentActivity, InvestmentHoldings, ProviderSecurity};
+use crate::services::currency_service::{CurrencyService, FxConverter};
+use crate::services::repository_service::DatabaseRepository;
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use uuid::Uuid;
+
+pub struct InvestmentService;
+
+impl InvestmentService {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Stores what a provider reported for `accounts`, which must be the
+    /// user's persisted investment accounts for one connection. Holdings in
+    /// those accounts are replaced wholesale so sold positions disappear;
+    /// entries for unknown accounts or securities are dropped.
+    pub async fn store_provider_data<R: DatabaseRepository + ?Sized>(
+        repository: &R,
+        user_id: Uuid,
+        accounts: &[Account],
+        holdings: InvestmentHoldings,
+        activity: InvestmentActivity,
+    ) -> Result<(), String> {
+        let account_ids: HashMap<&str, Uuid> = accounts
+            .iter()
+            .filter_map(|a| Some((a.provider_account_id.as_deref()?, a.id)))
+            .collect();
+
+        let mut provider_securities: BTreeMap<String, ProviderSecurity> = BTreeMap::new();
+        for security in activity.securities.into_iter().chain(holdings.securities) {
+            provider_securities.insert(security.provider_security_id.clone(), security);
+        }
+        let securities: Vec<Security> = provider_securities
+            .into_values()
+            .map(|s| Security {
+                id: Uuid::new_v4(),
+                provider_security_id: s.provider_security_id,
+                name: s.name,
+                ticker_symbol: s.ticker_symbol,
+                security_type: s.security_type,
+                iso_currency_code: s.iso_currency_code,
+                close_price: s.close_price,
+            })
+            .collect();
+        let security_ids: HashMap<String, Uuid> = repository
+            .upsert_securities(&user_id, &securities)
+            .await
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .map(|s| (s.provider_security_id, s.id))
+            .collect();
+
+        let mut positions: BTreeMap<(Uuid, Uuid), Holding> = BTreeMap::new();
+        for h in holdings.holdings {
+            let (Some(&account_id), Some(&security_id)) = (
+                account_ids.get(h.provider_account_id.as_str()),
+                security_ids.get(&h.provider_security_id),
+            ) else {
+                continue;
+            };
+            // Institutions may report one position as several lots.
+            positions
+                .entry((account_id, security_id))
+                .and_modify(|p| {
+                    p.quantity += h.quantity;
+                    p.institution_value += h.institution_value;
+                    p.cost_basis = p.cost_basis.zip(h.cost_basis).map(|(a, b)| a + b);
+                })
+                .or_insert(Holding {
+                    account_id,
+                    security_id,
+                    quantity: h.quantity,
+                    institution_price: h.institution_price,
+                    institution_value: h.institution_value,
+                    cost_basis: h.cost_basis,
+                    iso_currency_code: h.iso_currency_code,
+                });
+        }
+        let holdings: Vec<Holding> = positions.into_values().collect();
+        let replaced: Vec<Uuid> = account_ids.values().copied().collect();
+        repository
+            .replace_investment_holdings(&user_id, &replaced, &holdings)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let transactions: Vec<InvestmentTransaction> = activity
+            .transactions
+            .into_iter()
+            .filter_map(|t| {
+                Some(InvestmentTransaction {
+                    id: Uuid::new_v4(),
+                    account_id: *account_ids.get(t.provider_account_id.as_str())?,
+                    security_id: t
+                        .provider_security_id
+                        .as_ref()
+                        .and_then(|id| security_ids.get(id).copied()),
+                    provider_investment_transaction_id: t.provider_investment_transaction_id,
+                    date: t.date,
+                    name: t.name,
+                    transaction_type: t.transaction_type,
+                    subtype: t.subtype,
+                    quantity: t.quantity,
+                    price: t.price,
+                    amount: t.amount,
+                    fees: t.fees,
+                    iso_currency_code: t.iso_currency_code,
+                })
+            })
+            .collect();
+        if transactions.is_empty() {
+            return Ok(());
+        }
+        repository
+            .upsert_investment_transactions(&user_id, &transactions)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn holdings<R: DatabaseRepository + ?Sized>(
+        &self,
+        repository: &R,
+        user_id: Uuid,
+        account_ids: &[Uuid],
+        today: NaiveDate,
+    ) -> Result<HoldingsResponse, String> {
+        let (views, converter) =
+            Self::load_holding_views(repository, user_id, account_ids, today).await?;
+        Ok(Self::summarize_holdings(views, &converter, today))
+    }
+
+    pub async fn allocation<R: DatabaseRepository + ?Sized>(
+        &self,
+        repository: &R,
+        user_id: Uuid,
+        account_ids: &[Uuid],
+        today: NaiveDate,
+    ) -> Result<AllocationResponse, String> {
+        let (views, converter) =
+            Self::load_holding_views(repository, user_id, account_ids, today).await?;
+        Ok(Self::allocate(&views, &converter, today))
+    }
+
+    pub async fn transactions<R: DatabaseRepository + ?Sized>(
+        &self,
+        repository: &R,
+        user_id: Uuid,
+        account_ids: &[Uuid],
+        (start_date, end_date): (NaiveDate, NaiveDate),
+    ) -> Result<Vec<InvestmentTransaction>, String> {
+        let mut transactions = repository
+            .get_investment_transactions_for_user(&user_id, start_date, end_date)
+            .await
+            .map_err(|e| e.to_string())?;
+        if !account_ids.is_empty() {
+            transactions.retain(|t| account_ids.contains(&t.account_id));
+        }
+        Ok(transactions)
+    }
+
+    async fn load_holding_views<R: DatabaseRepository + ?Sized>(
+        repository: &R,
+        user_id: Uuid,
+        account_ids: &[Uuid],
+        today: NaiveDate,
+    ) -> Result<(Vec<HoldingView>, FxConverter), String> {
+        let mut holdings = repository
+            .get_investment_holdings_for_user(&user_id)
+            .await
+            .map_err(|e| e.to_string())?;
+        if !account_ids.is_empty() {
+            holdings.retain(|h| account_ids.contains(&h.account_id));
+        }
+        let securities = repository
+            .get_securities_for_user(&user_id)
+            .await
+            .map_err(|e| e.to_string())?;
+        let accounts = repository
+            .get_accounts_for_user(&user_id)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let views = Self::holding_views(&holdings, &securities, &accounts);
+        let currency = CurrencyService::reporting_currency(repository, user_id).await?;
+        let currencies: HashSet<String> =
+            views.iter().map(|v| v.iso_currency_code.clone()).collect();
+        let converter =
+            CurrencyService::load_converter(repository, &currency, &currencies, today).await?;
+
+        Ok((views, converter))
+    }
+
+    /// Joins holdings with their security and account and works out cost basis
+    /// and unrealized gain, largest positions first.
+    pub fn holding_views(
+        holdings: &[Holding],
+        securities: &[Security],
+        accounts: &[Account],
+    ) -> Vec<HoldingView> {
+        let securities: HashMap<Uuid, &Security> = securities.iter().map(|s| (s.id, s)).collect();
+        let account_names: HashMap<Uuid, &str> =
+            accounts.iter().map(|a| (a.id, a.name.as_str())).collect();
+
+        let mut views: Vec<HoldingView> = holdings
+            .iter()
+            .filter_map(|h| {
+                let security = securities.get(&h.security_id)?;
+                let unrealized_gain = h.cost_basis.map(|cost| h.institution_value - cost);
+                let unrealized_gain_percent = h
+                    .cost_basis
+                    .zip(unrealized_gain)
+                    .filter(|(cost, _)| !cost.is_zero())
+                    .map(|(cost, gain)| (gain / cost * Decimal::ONE_HUNDRED).round_dp(2));
+
+                Some(HoldingView {
+                    account_id: h.account_id,
+                    account_name: account_names
+                        .get(&h.account_id)
+                        .copied()
+                        .unwrap_or("Unknown Account")
+                        .to_string(),
+                    security_id: h.security_id,
+                    name: security.name.clone(),
+                    ticker_symbol: security.ticker_symbol.clone(),
+                    asset_class: AssetClass::from_security_type(&security.security_type),
+                    quantity: h.quantity,
+                    price: h.institution_price,
+                    market_value: h.institution_value,
+                    cost_basis: h.cost_basis,
+                    unrealized_gain,
+                    unrealized_gain_percent,
+                    iso_currency_code: h.iso_currency_code.clone(),
+                })
+            })
+            .collect();
+
+        views.sort_by(|a, b| {
+            b.market_value
+                .cmp(&a.market_value)
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        views
+    }
+
+    /// Totals the holdings in the converter's currency. Holdings with no known
+    /// rate keep their own figures but are left out of the totals.
+    pub fn summarize_holdings(
+        holdings: Vec<HoldingView>,
+        converter: &FxConverter,
+        today: NaiveDate,
+    ) -> HoldingsResponse {
+        let mut total_market_value = Decimal::ZERO;
+        let mut total_cost_basis = Decimal::ZERO;
+        let mut total_unrealized_gain = Decimal::ZERO;
+        let mut mixed_currency = false;
+
+        for holding in &holdings {
+            let Some(rate) = converter.rate(&holding.iso_currency_code, converter.target(), today)
+            else {
+                mixed_currency = true;
+                continue;
+            };
+            total_market_value += holding.market_value * rate;
+            if let (Some(cost), Some(gain)) = (holding.cost_basis, holding.unrealized_gain) {
+                total_cost_basis += cost * rate;
+                total_unrealized_gain += gain * rate;
+            }
+        }
+
+        HoldingsResponse {
+            holdings,
+            currency: converter.target().to_string(),
+            total_market_value: total_market_value.round_dp(2),
+            total_cost_basis: total_cost_basis.round_dp(2),
+            total_unrealized_gain: total_unrealized_gain.round_dp(2),
+            mixed_currency,
+        }
+    }
+
+    /// Market value per asset class in the converter's currency, largest first.
+    pub fn allocate(
+        holdings: &[HoldingView],
+        converter: &FxConverter,
+        today: NaiveDate,
+    ) -> AllocationResponse {
+        let mut by_class: BTreeMap<AssetClass, (Decimal, usize)> = BTreeMap::new();
+        let mut mixed_currency = false;
+
+        for holding in holdings {
+            let Some(value) =
+                converter.convert(holding.market_value, &holding.iso_currency_code, today)
+            else {
+                mixed_currency = true;
+                continue;
+            };
+            let entry = by_class.entry(holding.asset_class).or_default();
+            entry.0 += value;
+            entry.1 += 1;
+        }
+
+        let total: Decimal = by_class.values().map(|(value, _)| *value).sum();
+        let mut allocations: Vec<AssetAllocation> = by_class
+            .into_iter()
+            .map(|(asset_class, (value, holding_count))| AssetAllocation {
+                asset_class,
+                market_value: value.round_dp(2),
+                percentage: if total.is_zero() {
+                    Decimal::ZERO
+                } else {
+                    (value / total * Decimal::ONE_HUNDRED).round_dp(2)
+                },
+                holding_count,
+            })
+            .collect();
+        allocations.sort_by_key(|a| std::cmp::Reverse(a.market_value));
+
+        AllocationResponse {
+            allocations,
+            total_market_value: total.round_dp(2),
+            currency: converter.target().to_string(),
+            mixed_currency,
+        }
+    }
+}
@@ -0,0 +1,352 @@
+use crate::models::account::Account;
+use crate::models::analytics::BalanceCategory;
+use crate::models::export::JournalFormat;
+use crate::models::transaction::Transaction;
+use crate::services::analytics_service::AnalyticsService;
+use chrono::{Days, NaiveDate};
+use rust_decimal::Decimal;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use uuid::Uuid;
+
+const OPENING_BALANCES_ACCOUNT: &str = "Equity:Opening-Balances";
+
+struct JournalAccount<'a> {
+    account: &'a Account,
+    name: String,
+    opened: NaiveDate,
+    /// Expected closing balance in journal sign: negative for amounts owed.
+    closing_balance: Option<Decimal>,
+}
+
+/// A balanced two-posting entry: the account posting takes `amount`, the
+/// counter account the opposite.
+struct JournalEntry<'a> {
+    date: NaiveDate,
+    pending: bool,
+    payee: String,
+    narration: String,
+    metadata: Option<(&'static str, String)>,
+    account: &'a str,
+    counter_account: String,
+    amount: Decimal,
+    currency: &'a str,
+}
+
+pub struct JournalService;
+
+impl JournalService {
+    /// Renders accounts and transactions as a double-entry journal. Each account
+    /// gets an opening balance so that its closing balance assertion, taken from
+    /// the provider's current balance as of `today`, holds. Output is sorted so
+    /// that exporting the same data twice produces the same file.
+    pub fn render(
+        format: JournalFormat,
+        accounts: &[Account],
+        transactions: &[Transaction],
+        operating_currency: &str,
+        today: NaiveDate,
+    ) -> String {
+        let mut transactions: Vec<&Transaction> = transactions.iter().collect();
+        transactions
+            .sort_by(|a, b| (a.date, Self::stable_id(a)).cmp(&(b.date, Self::stable_id(b))));
+        let as_of = transactions.last().map_or(today, |t| t.date.max(today));
+        let journal_accounts = Self::journal_accounts(accounts, &transactions, as_of);
+        let by_id: HashMap<Uuid, &JournalAccount> =
+            journal_accounts.iter().map(|a| (a.account.id, a)).collect();
+        let first_day = journal_accounts
+            .iter()
+            .map(|a| a.opened)
+            .min()
+            .unwrap_or(as_of);
+
+        let mut entries: Vec<JournalEntry> = journal_accounts
+            .iter()
+            .filter_map(|a| Self::opening_entry(a, &transactions))
+            .collect();
+        entries.extend(transactions.iter().filter_map(|t| {
+            let account = by_id.get(&t.account_id)?;
+            Some(Self::transaction_entry(t, &account.name))
+        }));
+
+        let mut open_accounts: BTreeSet<String> =
+            journal_accounts.iter().map(|a| a.name.clone()).collect();
+        open_accounts.extend(entries.iter().map(|e| e.counter_account.clone()));
+
+        let mut out = Self::header(format, operating_currency);
+        for name in &open_accounts {
+            out.push_str(&match format {
+                JournalFormat::Beancount => format!("{} open {}\n", first_day, name),
+                JournalFormat::Hledger | JournalFormat::Ledger => format!("account {}\n", name),
+            });
+        }
+        for entry in &entries {
+            out.push('\n');
+            out.push_str(&Self::render_entry(format, entry));
+        }
+        out.push('\n');
+        for account in &journal_accounts {
+            if let Some(balance) = account.closing_balance {
+                out.push_str(&Self::balance_assertion(format, account, balance, as_of));
+            }
+        }
+        out
+    }
+
+    /// Account path such as `Assets:Cash:Demo-Bank:Checking-1234`, rooted by the
+    /// account's balance category.
+    pub fn account_name(account: &Account) -> String {
+        let root =
+            match AnalyticsService::map_account_to_balance_category(&account.account_type, None) {
+                BalanceCategory::Cash => "Assets:Cash",
+                BalanceCategory::Investments => "Assets:Investments",
+                BalanceCategory::Credit => "Liabilities:Credit-Cards",
+                BalanceCategory::Loan => "Liabilities:Loans",
+            };
+        let mut name = root.to_string();
+        if let Some(institution) = &account.institution_name {
+            name.push(':');
+            name.push_str(&Self::component(institution, false));
+        }
+        let label = match &account.mask {
+            Some(mask) => format!("{} {}", account.name, mask),
+            None => account.name.clone(),
+        };
+        name.push(':');
+        name.push_str(&Self::component(&label, false));
+        name
+    }
+
+    /// `Income:` or `Expenses:` account for the transaction's primary category.
+    pub fn category_account(transaction: &Transaction) -> String {
+        let root = if Self::signed_amount(transaction) > Decimal::ZERO {
+            "Income"
+        } else {
+            "Expenses"
+        };
+        let leaf = match Self::component(&transaction.category_primary, true) {
+            leaf if leaf == root => "General".to_string(),
+            leaf => leaf,
+        };
+        format!("{}:{}", root, leaf)
+    }
+
+    /// Inflows positive and outflows negative, from the account's point of view.
+    pub fn signed_amount(transaction: &Transaction) -> Decimal {
+        if AnalyticsService::is_income_transaction(transaction) {
+            transaction.amount.abs()
+        } else {
+            -transaction.amount
+        }
+    }
+
+    fn journal_accounts<'a>(
+        accounts: &'a [Account],
+        transactions: &[&Transaction],
+        as_of: NaiveDate,
+    ) -> Vec<JournalAccount<'a>> {
+        let mut accounts: Vec<&Account> = accounts.iter().collect();
+        accounts.sort_by(|a, b| (&a.name, a.id).cmp(&(&b.name, b.id)));
+
+        let mut used = HashSet::new();
+        accounts
+            .into_iter()
+            .map(|account| {
+                let mut name = Self::account_name(account);
+                if !used.insert(name.clone()) {
+                    name = format!("{}-{}", name, &account.id.simple().to_string()[..8]);
+                    used.insert(name.clone());
+                }
+                let first_posting = transactions
+                    .iter()
+                    .find(|t| t.account_id == account.id)
+                    .map_or(as_of, |t| t.date);
+                JournalAccount {
+                    account,
+                    name,
+                    opened: first_posting
+                        .checked_sub_days(Days::new(1))
+                        .unwrap_or(first_posting),
+                    closing_balance: account
+                        .balance_current
+                        .map(|balance| Self::journal_balance(account, balance)),
+                }
+            })
+            .collect()
+    }
+
+    fn journal_balance(account: &Account, balance: Decimal) -> Decimal {
+        match AnalyticsService::map_account_to_balance_category(&account.account_type, None) {
+            BalanceCategory::Credit | BalanceCategory::Loan => -balance.abs(),
+            BalanceCategory::Cash | BalanceCategory::Investments => balance,
+        }
+    }
+
+    fn opening_entry<'a>(
+        account: &'a JournalAccount,
+        transactions: &[&Transaction],
+    ) -> Option<JournalEntry<'a>> {
+        let currency = account.account.iso_currency_code.as_str();
+        let movement: Decimal = transactions
+            .iter()
+            .filter(|t| t.account_id == account.account.id && t.iso_currency_code == currency)
+            .map(|t| Self::signed_amount(t))
+            .sum();
+        let opening = account.closing_balance? - movement;
+        if opening.is_zero() {
+            return None;
+        }
+        Some(JournalEntry {
+            date: account.opened,
+            pending: false,
+            payee: "Opening balance".to_string(),
+            narration: String::new(),
+            metadata: None,
+            account: &account.name,
+            counter_account: OPENING_BALANCES_ACCOUNT.to_string(),
+            amount: opening,
+            currency,
+        })
+    }
+
+    fn transaction_entry<'a>(transaction: &'a Transaction, account: &'a str) -> JournalEntry<'a> {
+        let metadata = match &transaction.provider_transaction_id {
+            Some(provider_id) => ("provider_id", provider_id.clone()),
+            None => ("id", transaction.id.to_string()),
+        };
+        JournalEntry {
+            date: transaction.date,
+            pending: transaction.pending,
+            payee: transaction
+                .merchant_name
+                .clone()
+                .unwrap_or_else(|| transaction.category_primary.clone()),
+            narration: transaction.category_detailed.clone(),
+            metadata: Some(metadata),
+            account,
+            counter_account: Self::category_account(transaction),
+            amount: Self::signed_amount(transaction),
+            currency: &transaction.iso_currency_code,
+        }
+    }
+
+    fn header(format: JournalFormat, operating_currency: &str) -> String {
+        match format {
+            JournalFormat::Beancount => format!(
+                "option \"title\" \"Sumurai\"\noption \"operating_currency\" \"{}\"\n\n",
+                Self::quoted(operating_currency)
+            ),
+            JournalFormat::Hledger | JournalFormat::Ledger => {
+                "; Sumurai journal export\n\n".to_string()
+            }
+        }
+    }
+
+    fn render_entry(format: JournalFormat, entry: &JournalEntry) -> String {
+        let flag = if entry.pending { "!" } else { "*" };
+        let mut out = match format {
+            JournalFormat::Beancount => format!(
+                "{} {} \"{}\" \"{}\"\n",
+                entry.date,
+                flag,
+                Self::quoted(&entry.payee),
+                Self::quoted(&entry.narration)
+            ),
+            JournalFormat::Hledger | JournalFormat::Ledger => {
+                let mut line = format!(
+                    "{} {} {}",
+                    entry.date,
+                    flag,
+                    Self::single_line(&entry.payee)
+                );
+                if !entry.narration.is_empty() {
+                    line.push_str(&format!("  ; {}", Self::single_line(&entry.narration)));
+                }
+                line + "\n"
+            }
+        };
+        if let Some((key, value)) = &entry.metadata {
+            out.push_str(&match format {
+                JournalFormat::Beancount => format!("  {}: \"{}\"\n", key, Self::quoted(value)),
+                JournalFormat::Hledger | JournalFormat::Ledger => {
+                    format!("    ; {}: {}\n", key, Self::single_line(value))
+                }
+            });
+        }
+        for (account, amount) in [
+            (entry.account, entry.amount),
+            (entry.counter_account.as_str(), -entry.amount),
+        ] {
+            out.push_str(&format!(
+                "  {}  {:.2} {}\n",
+                account, amount, entry.currency
+            ));
+        }
+        out
+    }
+
+    fn balance_assertion(
+        format: JournalFormat,
+        account: &JournalAccount,
+        balance: Decimal,
+        as_of: NaiveDate,
+    ) -> String {
+        let currency = &account.account.iso_currency_code;
+        match format {
+            // Beancount checks balances at the start of the day.
+            JournalFormat::Beancount => format!(
+                "{} balance {}  {:.2} {}\n",
+                as_of.checked_add_days(Days::new(1)).unwrap_or(as_of),
+                account.name,
+                balance,
+                currency
+            ),
+            JournalFormat::Hledger | JournalFormat::Ledger => format!(
+                "{} * Balance assertion\n  {}  0 {} = {:.2} {}\n",
+                as_of, account.name, currency, balance, currency
+            ),
+        }
+    }
+
+    /// One account-name component: ASCII words capitalised and joined by `-`,
+    /// which every supported dialect accepts.
+    fn component(raw: &str, lowercase_words: bool) -> String {
+        let words: Vec<String> = raw
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .map(|word| {
+                let word = if lowercase_words {
+                    word.to_ascii_lowercase()
+                } else {
+                    word.to_string()
+                };
+                let mut chars = word.chars();
+                chars
+                    .next()
+                    .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+                    .unwrap_or_default()
+            })
+            .collect();
+        if words.is_empty() {
+            "Uncategorized".to_string()
+        } else {
+            words.join("-")
+        }
+    }
+
+    fn stable_id(transaction: &Transaction) -> String {
+        transaction
+            .provider_transaction_id
+            .clone()
+            .unwrap_or_else(|| transaction.id.to_string())
+    }
+
+    fn quoted(value: &str) -> String {
+        Self::single_line(value)
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"")
+    }
+
+    fn single_line(value: &str) -> String {
+        value.replace(['\r', '\n'], " ")
+    }
+}
@@ -0,0 +1,442 @@
+use crate::models::account::Account;
+use crate::models::analytics::BalanceCategory;
+use crate::models::liability::{
+    DebtPayment, DebtPayoffSummary, Liability, LiabilitySource, LiabilityView, PayoffDebt,
+    PayoffMonth, PayoffPlan, PayoffPlanRequest, PayoffPlanResponse, PayoffStrategy,
+    UpdateLiabilityRequest, MAX_PAYOFF_MONTHS,
+};
+use crate::providers::ProviderLiability;
+use crate::services::analytics_service::AnalyticsService;
+use crate::services::currency_service::{CurrencyService, FxConverter};
+use crate::services::repository_service::DatabaseRepository;
+use chrono::{Months, NaiveDate};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+/// Minimum payment assumed when none is known: a share of the balance, with a floor.
+const DEFAULT_MINIMUM_PAYMENT_RATE: Decimal = dec!(0.02);
+const DEFAULT_MINIMUM_PAYMENT_FLOOR: Decimal = dec!(25);
+const MAX_APR: Decimal = dec!(100);
+
+pub struct LiabilityService;
+
+impl LiabilityService {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn is_liability_account(account: &Account) -> bool {
+        matches!(
+            AnalyticsService::map_account_to_balance_category(&account.account_type, None),
+            BalanceCategory::Credit | BalanceCategory::Loan
+        )
+    }
+
+    /// Stores provider liability details for `accounts`, the user's persisted
+    /// accounts for one connection. Details for unknown accounts are dropped.
+    pub async fn store_provider_liabilities<R: DatabaseRepository + ?Sized>(
+        repository: &R,
+        user_id: Uuid,
+        accounts: &[Account],
+        liabilities: Vec<ProviderLiability>,
+    ) -> Result<(), String> {
+        let account_ids: HashMap<&str, Uuid> = accounts
+            .iter()
+            .filter_map(|a| Some((a.provider_account_id.as_deref()?, a.id)))
+            .collect();
+        let liabilities: Vec<Liability> = liabilities
+            .into_iter()
+            .filter_map(|l| {
+                Some(Liability {
+                    account_id: *account_ids.get(l.provider_account_id.as_str())?,
+                    liability_type: l.liability_type,
+                    apr: l.apr,
+                    minimum_payment: l.minimum_payment,
+                    statement_balance: l.last_statement_balance,
+                    next_payment_due_date: l.next_payment_due_date,
+                    loan_term_months: l.loan_term_months,
+                    maturity_date: l.maturity_date,
+                    source: LiabilitySource::Provider.as_str().to_string(),
+                })
+            })
+            .collect();
+        if liabilities.is_empty() {
+            return Ok(());
+        }
+
+        repository
+            .upsert_provider_liabilities(&user_id, &liabilities)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    /// Every credit and loan account, with whatever details are known for it.
+    pub async fn list<R: DatabaseRepository + ?Sized>(
+        &self,
+        repository: &R,
+        user_id: Uuid,
+    ) -> Result<Vec<LiabilityView>, String> {
+        let accounts = repository
+            .get_accounts_for_user(&user_id)
+            .await
+            .map_err(|e| e.to_string())?;
+        let liabilities = repository
+            .get_liabilities_for_user(&user_id)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(Self::liability_views(&accounts, &liabilities))
+    }
+
+    pub fn liability_views(accounts: &[Account], liabilities: &[Liability]) -> Vec<LiabilityView> {
+        let details: HashMap<Uuid, &Liability> =
+            liabilities.iter().map(|l| (l.account_id, l)).collect();
+
+        let mut views: Vec<LiabilityView> = accounts
+            .iter()
+            .filter(|a| Self::is_liability_account(a))
+            .map(|account| {
+                let liability = details.get(&account.id);
+                LiabilityView {
+                    account_id: account.id,
+                    account_name: account.name.clone(),
+                    account_type: account.account_type.clone(),
+                    institution_name: account.institution_name.clone(),
+                    balance: account.balance_current.unwrap_or(Decimal::ZERO).abs(),
+                    iso_currency_code: account.iso_currency_code.clone(),
+                    liability_type: liability.map(|l| l.liability_type.clone()),
+                    apr: liability.and_then(|l| l.apr),
+                    minimum_payment: liability.and_then(|l| l.minimum_payment),
+                    statement_balance: liability.and_then(|l| l.statement_balance),
+                    next_payment_due_date: liability.and_then(|l| l.next_payment_due_date),
+                    loan_term_months: liability.and_then(|l| l.loan_term_months),
+                    maturity_date: liability.and_then(|l| l.maturity_date),
+                    source: liability.map(|l| {
+                        if l.source == LiabilitySource::Manual.as_str() {
+                            LiabilitySource::Manual
+                        } else {
+                            LiabilitySource::Provider
+                        }
+                    }),
+                }
+            })
+            .collect();
+        views.sort_by(|a, b| {
+            b.balance
+                .cmp(&a.balance)
+                .then(a.account_name.cmp(&b.account_name))
+        });
+        views
+    }
+
+    /// Records hand-entered details for a credit or loan account. Later syncs
+    /// leave them in place.
+    pub async fn update_manual<R: DatabaseRepository + ?Sized>(
+        &self,
+        repository: &R,
+        user_id: Uuid,
+        account_id: Uuid,
+        request: &UpdateLiabilityRequest,
+    ) -> Result<Liability, String> {
+        Self::validate_update(request)?;
+
+        let account = repository
+            .get_accounts_for_user(&user_id)
+            .await
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .find(|a| a.id == account_id)
+            .ok_or_else(|| "Account not found".to_string())?;
+        if !Self::is_liability_account(&account) {
+            return Err(
+                "Invalid account: liability details apply to credit and loan accounts".to_string(),
+            );
+        }
+
+        let default_type = if account.account_type.eq_ignore_ascii_case("credit") {
+            "credit"
+        } else {
+            "loan"
+        };
+        let liability = Liability {
+            account_id,
+            liability_type: request
+                .liability_type
+                .as_deref()
+                .map(|t| t.trim().to_lowercase())
+                .filter(|t| !t.is_empty())
+                .unwrap_or_else(|| default_type.to_string()),
+            apr: request.apr,
+            minimum_payment: request.minimum_payment,
+            statement_balance: request.statement_balance,
+            next_payment_due_date: request.next_payment_due_date,
+            loan_term_months: request.loan_term_months,
+            maturity_date: request.maturity_date,
+            source: LiabilitySource::Manual.as_str().to_string(),
+        };
+
+        repository
+            .upsert_liability(&user_id, &liability)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    fn validate_update(request: &UpdateLiabilityRequest) -> Result<(), String> {
+        if request
+            .apr
+            .is_some_and(|apr| apr < Decimal::ZERO || apr > MAX_APR)
+        {
+            return Err("Invalid apr: must be between 0 and 100".to_string());
+        }
+        let negative = |v: Option<Decimal>| v.is_some_and(|v| v < Decimal::ZERO);
+        if negative(request.minimum_payment) || negative(request.statement_balance) {
+            return Err("Invalid amount: payments and balances cannot be negative".to_string());
+        }
+        if request.loan_term_months.is_some_and(|m| m <= 0) {
+            return Err("Invalid loan_term_months: must be positive".to_string());
+        }
+        if request
+            .liability_type
+            .as_ref()
+            .is_some_and(|t| t.trim().len() > 32)
+        {
+            return Err("Invalid liability_type: at most 32 characters".to_string());
+        }
+        Ok(())
+    }
+
+    pub async fn payoff_plan<R: DatabaseRepository + ?Sized>(
+        &self,
+        repository: &R,
+        user_id: Uuid,
+        request: &PayoffPlanRequest,
+        today: NaiveDate,
+    ) -> Result<PayoffPlanResponse, String> {
+        if request.extra_monthly_payment < Decimal::ZERO {
+            return Err("Invalid extra_monthly_payment: cannot be negative".to_string());
+        }
+        let account_ids: Vec<Uuid> = request
+            .account_ids
+            .iter()
+            .map(|id| Uuid::parse_str(id).map_err(|_| format!("Invalid account id: {}", id)))
+            .collect::<Result<_, _>>()?;
+
+        let mut views = self.list(repository, user_id).await?;
+        if !account_ids.is_empty() {
+            views.retain(|v| account_ids.contains(&v.account_id));
+        }
+        let currency = CurrencyService::reporting_currency(repository, user_id).await?;
+        let currencies: HashSet<String> =
+            views.iter().map(|v| v.iso_currency_code.clone()).collect();
+        let converter =
+            CurrencyService::load_converter(repository, &currency, &currencies, today).await?;
+
+        let (debts, assumptions, mixed_currency) = Self::payoff_debts(&views, &converter, today);
+        let plans = [PayoffStrategy::Avalanche, PayoffStrategy::Snowball]
+            .into_iter()
+            .map(|strategy| Self::simulate(&debts, strategy, request.extra_monthly_payment, today))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(PayoffPlanResponse {
+            currency,
+            monthly_payment: debts.iter().map(|d| d.minimum_payment).sum::<Decimal>()
+                + request.extra_monthly_payment,
+            plans,
+            assumptions,
+            mixed_currency,
+        })
+    }
+
+    /// Turns liabilities with a balance into planner debts in the converter's
+    /// currency, filling in a zero APR or a default minimum payment where
+    /// nothing is known and describing each assumption made.
+    pub fn payoff_debts(
+        views: &[LiabilityView],
+        converter: &FxConverter,
+        today: NaiveDate,
+    ) -> (Vec<PayoffDebt>, Vec<String>, bool) {
+        let mut debts = Vec::new();
+        let mut assumptions = Vec::new();
+        let mut mixed_currency = false;
+
+        for view in views.iter().filter(|v| v.balance > Decimal::ZERO) {
+            let Some(rate) = converter.rate(&view.iso_currency_code, converter.target(), today)
+            else {
+                mixed_currency = true;
+                continue;
+            };
+            let balance = (view.balance * rate).round_dp(2);
+            let apr = view.apr.unwrap_or_else(|| {
+                assumptions.push(format!(
+                    "No APR known for {}; assumed 0%.",
+                    view.account_name
+                ));
+                Decimal::ZERO
+            });
+            let minimum_payment = match view.minimum_payment {
+                Some(payment) => (payment * rate).round_dp(2),
+                None => {
+                    let payment = (balance * DEFAULT_MINIMUM_PAYMENT_RATE)
+                        .round_dp(2)
+                        .max(DEFAULT_MINIMUM_PAYMENT_FLOOR)
+                        .min(balance);
+                    assumptions.push(format!(
+                        "No minimum payment known for {}; assumed {}.",
+                        view.account_name, payment
+                    ));
+                    payment
+                }
+            };
+
+            debts.push(PayoffDebt {
+                account_id: view.account_id,
+                name: view.account_name.clone(),
+                balance,
+                apr,
+                minimum_payment,
+            });
+        }
+
+        (debts, assumptions, mixed_currency)
+    }
+
+    /// Simulates paying every debt's minimum plus `extra` each month. The
+    /// monthly total stays fixed, so minimums freed by a paid-off debt roll
+    /// over to the next target: highest APR first for avalanche, smallest
+    /// balance first for snowball. Interest accrues monthly at APR / 12.
+    pub fn simulate(
+        debts: &[PayoffDebt],
+        strategy: PayoffStrategy,
+        extra: Decimal,
+        start: NaiveDate,
+    ) -> Result<PayoffPlan, String> {
+        let order = Self::target_order(debts, strategy);
+        let budget = debts.iter().map(|d| d.minimum_payment).sum::<Decimal>() + extra;
+        let mut balances: Vec<Decimal> = debts.iter().map(|d| d.balance).collect();
+        let mut interest_paid = vec![Decimal::ZERO; debts.len()];
+        let mut paid_off: Vec<Option<NaiveDate>> = vec![None; debts.len()];
+        let mut schedule = Vec::new();
+
+        for month in 1..=MAX_PAYOFF_MONTHS {
+            if balances.iter().all(|b| b.is_zero()) {
+                break;
+            }
+            let date = start
+                .checked_add_months(Months::new(month))
+                .ok_or_else(|| "Payoff date out of range".to_string())?;
+            let payments = Self::pay_month(debts, &order, &mut balances, budget);
+
+            for (i, payment) in payments.iter().enumerate() {
+                interest_paid[i] += payment.interest;
+                if paid_off[i].is_none() && balances[i].is_zero() {
+                    paid_off[i] = Some(date);
+                }
+            }
+            let remaining_balance: Decimal = balances.iter().sum();
+            if month == 1 && remaining_balance >= debts.iter().map(|d| d.balance).sum() {
+                return Err(
+                    "Monthly payments do not cover the interest; increase the extra payment"
+                        .to_string(),
+                );
+            }
+            schedule.push(PayoffMonth {
+                month,
+                date,
+                total_payment: payments.iter().map(|p| p.payment).sum(),
+                total_interest: payments.iter().map(|p| p.interest).sum(),
+                remaining_balance,
+                payments: payments
+                    .into_iter()
+                    .filter(|p| !p.payment.is_zero() || !p.balance.is_zero())
+                    .collect(),
+            });
+        }
+
+        if balances.iter().any(|b| !b.is_zero()) {
+            return Err(format!(
+                "Debts are not paid off within {} months at this payment",
+                MAX_PAYOFF_MONTHS
+            ));
+        }
+
+        let mut summaries: Vec<DebtPayoffSummary> = debts
+            .iter()
+            .zip(paid_off)
+            .zip(&interest_paid)
+            .filter_map(|((debt, date), interest)| {
+                Some(DebtPayoffSummary {
+                    account_id: debt.account_id,
+                    name: debt.name.clone(),
+                    paid_off_date: date?,
+                    interest_paid: *interest,
+                })
+            })
+            .collect();
+        summaries.sort_by_key(|s| s.paid_off_date);
+
+        let total_interest: Decimal = interest_paid.iter().sum();
+        Ok(PayoffPlan {
+            strategy,
+            debts: summaries,
+            total_paid: schedule.iter().map(|m| m.total_payment).sum(),
+            debt_free_date: schedule.last().map(|m| m.date),
+            schedule,
+            total_interest,
+        })
+    }
+
+    fn target_order(debts: &[PayoffDebt], strategy: PayoffStrategy) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..debts.len()).collect();
+        order.sort_by(|&a, &b| {
+            let (a, b) = (&debts[a], &debts[b]);
+            match strategy {
+                PayoffStrategy::Avalanche => b.apr.cmp(&a.apr).then(a.balance.cmp(&b.balance)),
+                PayoffStrategy::Snowball => a.balance.cmp(&b.balance).then(b.apr.cmp(&a.apr)),
+            }
+        });
+        order
+    }
+
+    /// Accrues one month of interest, pays each minimum, then sends what is
+    /// left of `budget` down the target order.
+    fn pay_month(
+        debts: &[PayoffDebt],
+        order: &[usize],
+        balances: &mut [Decimal],
+        budget: Decimal,
+    ) -> Vec<DebtPayment> {
+        let mut available = budget;
+        let mut payments: Vec<DebtPayment> = debts
+            .iter()
+            .zip(balances.iter_mut())
+            .map(|(debt, balance)| {
+                let interest = (*balance * debt.apr / dec!(1200)).round_dp(2);
+                *balance += interest;
+                let payment = debt.minimum_payment.min(*balance).min(available);
+                *balance -= payment;
+                available -= payment;
+                DebtPayment {
+                    account_id: debt.account_id,
+                    payment,
+                    interest,
+                    balance: *balance,
+                }
+            })
+            .collect();
+
+        for &i in order {
+            if available.is_zero() {
+                break;
+            }
+            let payment = balances[i].min(available);
+            balances[i] -= payment;
+            available -= payment;
+            payments[i].payment += payment;
+            payments[i].balance = balances[i];
+        }
+
+        payments
+    }
+}
@@ -1,18 +1,61 @@
+pub mod access_token_service;
+pub mod alert_service;
+pub mod analytics_query_service;
 pub mod analytics_service;
+pub mod archive_service;
 pub mod auth_service;
+pub mod bills_service;
 pub mod budget_service;
+pub mod bulk_edit_service;
 pub mod cache_service;
 pub mod connection_service;
+pub mod currency_service;
+pub mod export_service;
+pub mod forecast_service;
+pub mod import_service;
+pub mod insights_service;
+pub mod investment_service;
+pub mod journal_service;
+pub mod liability_service;
+pub mod net_worth_service;
+pub mod notification_service;
 pub mod plaid_service;
+pub mod recurring_service;
 pub mod repository_service;
+pub mod saved_view_service;
+pub mod sync_progress_service;
 pub mod sync_service;
+pub mod transaction_search_service;
+pub mod user_settings_service;
+pub mod webhook_service;
+pub use access_token_service::AccessTokenService;
+pub use alert_service::AlertService;
+pub use analytics_query_service::AnalyticsQueryService;
 pub use analytics_service::AnalyticsService;
+pub use archive_service::{ArchiveRestoreError, ArchiveService};
 pub use auth_service::AuthService;
+pub use bills_service::BillsService;
 pub use budget_service::BudgetService;
+pub use bulk_edit_service::BulkEditService;
 pub use cache_service::{CacheService, RedisCache};
 pub use connection_service::{
     ConnectionService, ExchangeTokenError, LinkTokenError, ProviderSyncError, SyncConnectionParams,
     TellerConnectError, TellerSyncError,
 };
+pub use currency_service::CurrencyService;
+pub use export_service::ExportService;
+pub use forecast_service::ForecastService;
+pub use import_service::ImportService;
+pub use insights_service::InsightsService;
+pub use investment_service::InvestmentService;
+pub use journal_service::JournalService;
+pub use liability_service::LiabilityService;
+pub use net_worth_service::NetWorthService;
+pub use notification_service::NotificationService;
 pub use plaid_service::{PlaidService, RealPlaidClient};
+pub use saved_view_service::SavedViewService;
+pub use sync_progress_service::SyncProgressService;
 pub use sync_service::SyncService;
+pub use transaction_search_service::TransactionSearchService;
+pub use user_settings_service::UserSettingsService;
+pub use webhook_service::WebhookService;
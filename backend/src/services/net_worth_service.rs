@@ -0,0 +1,327 @@
+use crate::models::account::Account;
+use crate::models::analytics::{BalanceCategory, NetWorthOverTimeResponse, NetWorthSeriesPoint};
+use crate::models::balance_snapshot::BalanceSnapshot;
+use crate::models::plaid::LatestAccountBalance;
+use crate::models::transaction::Transaction;
+use crate::models::user_settings::UserCalendar;
+use crate::services::analytics_service::AnalyticsService;
+use crate::services::currency_service::{CurrencyService, FxConverter};
+use crate::services::repository_service::DatabaseRepository;
+use crate::services::user_settings_service::UserSettingsService;
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use uuid::Uuid;
+
+pub struct NetWorthQuery {
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub today: NaiveDate,
+    pub account_ids: Option<HashSet<Uuid>>,
+}
+
+pub struct NetWorthInputs<'a> {
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub today: NaiveDate,
+    pub accounts: &'a [Account],
+    pub snapshots: &'a [BalanceSnapshot],
+    pub transactions: &'a [Transaction],
+    pub converter: &'a FxConverter,
+}
+
+/// Balance anchors and ledger flows for one account.
+struct AccountHistory {
+    category: BalanceCategory,
+    currency: String,
+    anchors: BTreeMap<NaiveDate, Decimal>,
+    flows: BTreeMap<NaiveDate, Decimal>,
+}
+
+impl AccountHistory {
+    fn new(
+        category: BalanceCategory,
+        currency: String,
+        current_balance: Option<Decimal>,
+        today: NaiveDate,
+    ) -> Self {
+        let mut anchors = BTreeMap::new();
+        if let Some(balance) = current_balance {
+            anchors.insert(today, balance);
+        }
+        Self {
+            category,
+            currency,
+            anchors,
+            flows: BTreeMap::new(),
+        }
+    }
+
+    /// Balance at the end of `day`: the nearest snapshot on or after it, with
+    /// the transactions posted in between rolled back. Investment balances move
+    /// with the market rather than the ledger, so they are only carried back.
+    fn balance_on(&self, day: NaiveDate) -> Option<Decimal> {
+        let (anchor_date, anchor) = self.anchors.range(day..).next()?;
+        let rolled_back: Decimal = self
+            .flows
+            .range(day..=*anchor_date)
+            .filter(|(date, _)| **date > day)
+            .map(|(_, amount)| *amount)
+            .sum();
+
+        // Positive amounts are money out: spending lowers cash and raises what is owed.
+        Some(match self.category {
+            BalanceCategory::Cash => *anchor + rolled_back,
+            BalanceCategory::Credit | BalanceCategory::Loan => *anchor - rolled_back,
+            BalanceCategory::Investments => *anchor,
+        })
+    }
+}
+
+pub struct NetWorthService;
+
+impl NetWorthService {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Snapshots every account of every user, dated by each user's local day. Run
+    /// once a day so history keeps accruing for users who do not sync.
+    pub async fn record_daily_snapshots<R: DatabaseRepository + ?Sized>(
+        &self,
+        repository: &R,
+        now: DateTime<Utc>,
+    ) -> Result<u64, String> {
+        let user_ids = repository
+            .get_all_user_ids()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let mut recorded = 0;
+        for user_id in user_ids {
+            let calendar = UserSettingsService::calendar(repository, user_id)
+                .await
+                .unwrap_or_else(|e| {
+                    tracing::warn!("Failed to load settings for user {}: {}", user_id, e);
+                    UserCalendar::default()
+                });
+            let today = calendar.local_date(now);
+            match repository.record_balance_snapshots(&user_id, today).await {
+                Ok(count) => recorded += count,
+                Err(e) => tracing::warn!(
+                    "Failed to record balance snapshots for user {}: {}",
+                    user_id,
+                    e
+                ),
+            }
+        }
+
+        Ok(recorded)
+    }
+
+    pub async fn net_worth_over_time<R: DatabaseRepository + ?Sized>(
+        &self,
+        repository: &R,
+        user_id: Uuid,
+        query: &NetWorthQuery,
+    ) -> Result<NetWorthOverTimeResponse, String> {
+        let reporting_currency = CurrencyService::reporting_currency(repository, user_id).await?;
+        let accounts: Vec<Account> = repository
+            .get_accounts_for_user(&user_id)
+            .await
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .filter(|a| {
+                query
+                    .account_ids
+                    .as_ref()
+                    .is_none_or(|ids| ids.contains(&a.id))
+            })
+            .collect();
+
+        if accounts.is_empty() {
+            return Ok(NetWorthOverTimeResponse {
+                series: Vec::new(),
+                currency: reporting_currency,
+            });
+        }
+
+        let history_end = std::cmp::max(query.start_date, query.today);
+        let snapshots = repository
+            .get_balance_snapshots_for_user(&user_id, query.start_date, history_end)
+            .await
+            .map_err(|e| e.to_string())?;
+        let transactions = repository
+            .get_transactions_by_date_range_for_user(&user_id, query.start_date, history_end)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let currencies: HashSet<String> = accounts
+            .iter()
+            .map(|a| a.iso_currency_code.clone())
+            .collect();
+        let converter = CurrencyService::load_converter(
+            repository,
+            &reporting_currency,
+            &currencies,
+            query.end_date,
+        )
+        .await?;
+
+        let series = Self::build_series(NetWorthInputs {
+            start_date: query.start_date,
+            end_date: query.end_date,
+            today: query.today,
+            accounts: &accounts,
+            snapshots: &snapshots,
+            transactions: &transactions,
+            converter: &converter,
+        });
+
+        Ok(NetWorthOverTimeResponse {
+            series,
+            currency: reporting_currency,
+        })
+    }
+
+    /// Builds a daily assets, liabilities and net worth series. Recorded
+    /// snapshots are authoritative; days between them are reconstructed from
+    /// the ledger, and days after today repeat today's figures.
+    pub fn build_series(inputs: NetWorthInputs<'_>) -> Vec<NetWorthSeriesPoint> {
+        let histories = Self::account_histories(&inputs);
+
+        let mut series = Vec::new();
+        let mut day = inputs.start_date;
+        while day <= inputs.end_date {
+            let as_of = std::cmp::min(day, inputs.today);
+            let mut assets = Decimal::ZERO;
+            let mut liabilities = Decimal::ZERO;
+
+            for history in histories.values() {
+                let Some(balance) = history
+                    .balance_on(as_of)
+                    .and_then(|b| inputs.converter.convert(b, &history.currency, as_of))
+                else {
+                    continue;
+                };
+                match history.category {
+                    BalanceCategory::Cash | BalanceCategory::Investments => assets += balance,
+                    BalanceCategory::Credit | BalanceCategory::Loan => liabilities += balance.abs(),
+                }
+            }
+
+            series.push(NetWorthSeriesPoint {
+                date: day.format("%Y-%m-%d").to_string(),
+                value: assets - liabilities,
+                assets,
+                liabilities,
+            });
+
+            let Some(next) = day.succ_opt() else {
+                break;
+            };
+            day = next;
+        }
+
+        series
+    }
+
+    /// Each account's balance at the end of `on`, in the account's own currency,
+    /// reconstructed the same way as the net worth series.
+    pub async fn balances_on<R: DatabaseRepository + ?Sized>(
+        repository: &R,
+        user_id: Uuid,
+        rows: &[LatestAccountBalance],
+        on: NaiveDate,
+        today: NaiveDate,
+    ) -> Result<HashMap<Uuid, Decimal>, String> {
+        if on >= today {
+            return Ok(rows
+                .iter()
+                .map(|row| (row.account_id, row.current_balance))
+                .collect());
+        }
+
+        let snapshots = repository
+            .get_balance_snapshots_for_user(&user_id, on, today)
+            .await
+            .map_err(|e| e.to_string())?;
+        let transactions = repository
+            .get_transactions_by_date_range_for_user(&user_id, on, today)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let mut histories: HashMap<Uuid, AccountHistory> = rows
+            .iter()
+            .map(|row| {
+                let category = AnalyticsService::map_account_to_balance_category(
+                    &row.account_type,
+                    row.account_subtype.as_deref(),
+                );
+                let history = AccountHistory::new(
+                    category,
+                    row.currency.to_uppercase(),
+                    Some(row.current_balance),
+                    today,
+                );
+                (row.account_id, history)
+            })
+            .collect();
+        Self::add_history(&mut histories, &snapshots, &transactions, today);
+
+        Ok(histories
+            .into_iter()
+            .filter_map(|(id, history)| history.balance_on(on).map(|balance| (id, balance)))
+            .collect())
+    }
+
+    fn account_histories(inputs: &NetWorthInputs<'_>) -> HashMap<Uuid, AccountHistory> {
+        let mut histories: HashMap<Uuid, AccountHistory> = inputs
+            .accounts
+            .iter()
+            .map(|account| {
+                let category =
+                    AnalyticsService::map_account_to_balance_category(&account.account_type, None);
+                let history = AccountHistory::new(
+                    category,
+                    account.iso_currency_code.clone(),
+                    account.balance_current,
+                    inputs.today,
+                );
+                (account.id, history)
+            })
+            .collect();
+        Self::add_history(
+            &mut histories,
+            inputs.snapshots,
+            inputs.transactions,
+            inputs.today,
+        );
+
+        histories
+    }
+
+    fn add_history(
+        histories: &mut HashMap<Uuid, AccountHistory>,
+        snapshots: &[BalanceSnapshot],
+        transactions: &[Transaction],
+        today: NaiveDate,
+    ) {
+        for snapshot in snapshots {
+            if let Some(history) = histories.get_mut(&snapshot.account_id) {
+                // The live balance is fresher than a snapshot taken earlier today.
+                if snapshot.snapshot_date < today {
+                    history
+                        .anchors
+                        .insert(snapshot.snapshot_date, snapshot.balance);
+                }
+            }
+        }
+
+        for transaction in transactions {
+            if let Some(history) = histories.get_mut(&transaction.account_id) {
+                *history.flows.entry(transaction.date).or_default() += transaction.amount;
+            }
+        }
+    }
+}
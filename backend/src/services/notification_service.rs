@@ -0,0 +1,235 @@
+use crate::models::notification::{
+    Notification, NotificationEvent, NotificationEventType, NotificationListResponse,
+    NotificationPreference, NotificationPreferencesResponse, UpdateNotificationPreferencesRequest,
+    IN_APP_CHANNEL,
+};
+use crate::notifications::{NotificationChannel, NotificationRecipient};
+use crate::services::repository_service::DatabaseRepository;
+use std::sync::Arc;
+use uuid::Uuid;
+
+pub struct NotificationService {
+    channels: Vec<Arc<dyn NotificationChannel>>,
+}
+
+impl NotificationService {
+    pub fn new(channels: Vec<Arc<dyn NotificationChannel>>) -> Self {
+        Self { channels }
+    }
+
+    /// The in-app feed followed by every registered external channel.
+    pub fn channel_names(&self) -> Vec<String> {
+        std::iter::once(IN_APP_CHANNEL)
+            .chain(self.channels.iter().map(|c| c.name()))
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// Stores the event in the feed and fans it out to external channels according to
+    /// the user's preferences. External delivery runs in the background and only logs
+    /// failures, so a slow or flaky mail relay never holds up or fails the operation
+    /// that raised the event.
+    pub async fn publish<R: DatabaseRepository + ?Sized>(
+        &self,
+        repository: &R,
+        event: NotificationEvent,
+    ) -> Result<Option<Notification>, String> {
+        let preferences = repository
+            .get_notification_preferences(&event.user_id)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let stored = if Self::is_enabled(&preferences, event.event_type, IN_APP_CHANNEL) {
+            Some(
+                repository
+                    .insert_notification(&event)
+                    .await
+                    .map_err(|e| e.to_string())?,
+            )
+        } else {
+            None
+        };
+
+        let channels: Vec<_> = self
+            .channels
+            .iter()
+            .filter(|c| Self::is_enabled(&preferences, event.event_type, c.name()))
+            .cloned()
+            .collect();
+        if channels.is_empty() {
+            return Ok(stored);
+        }
+
+        let recipient = match repository.get_user_by_id(&event.user_id).await {
+            Ok(Some(user)) => NotificationRecipient { email: user.email },
+            Ok(None) => return Ok(stored),
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to load notification recipient {}: {}",
+                    event.user_id,
+                    e
+                );
+                return Ok(stored);
+            }
+        };
+
+        tokio::spawn(Self::deliver(channels, recipient, event));
+
+        Ok(stored)
+    }
+
+    async fn deliver(
+        channels: Vec<Arc<dyn NotificationChannel>>,
+        recipient: NotificationRecipient,
+        event: NotificationEvent,
+    ) {
+        for channel in channels {
+            if let Err(e) = channel.deliver(&recipient, &event).await {
+                tracing::warn!(
+                    "Failed to deliver {} notification via {} to user {}: {}",
+                    event.event_type.as_str(),
+                    channel.name(),
+                    event.user_id,
+                    e
+                );
+            }
+        }
+    }
+
+    pub async fn list_notifications<R: DatabaseRepository + ?Sized>(
+        &self,
+        repository: &R,
+        user_id: Uuid,
+        unread_only: bool,
+        limit: i64,
+    ) -> Result<NotificationListResponse, String> {
+        let notifications = repository
+            .get_notifications_for_user(&user_id, unread_only, limit)
+            .await
+            .map_err(|e| e.to_string())?;
+        let unread_count = repository
+            .count_unread_notifications(&user_id)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(NotificationListResponse {
+            notifications,
+            unread_count,
+        })
+    }
+
+    pub async fn set_read<R: DatabaseRepository + ?Sized>(
+        &self,
+        repository: &R,
+        user_id: Uuid,
+        notification_id: Uuid,
+        read: bool,
+    ) -> Result<(), String> {
+        let updated = repository
+            .set_notification_read(&user_id, &notification_id, read)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        if updated {
+            Ok(())
+        } else {
+            Err("Notification not found".to_string())
+        }
+    }
+
+    pub async fn get_preferences<R: DatabaseRepository + ?Sized>(
+        &self,
+        repository: &R,
+        user_id: Uuid,
+    ) -> Result<NotificationPreferencesResponse, String> {
+        let stored = repository
+            .get_notification_preferences(&user_id)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(self.build_preferences(&stored))
+    }
+
+    pub async fn update_preferences<R: DatabaseRepository + ?Sized>(
+        &self,
+        repository: &R,
+        user_id: Uuid,
+        request: &UpdateNotificationPreferencesRequest,
+    ) -> Result<NotificationPreferencesResponse, String> {
+        let channels = self.channel_names();
+        for preference in &request.preferences {
+            if NotificationEventType::parse(&preference.event_type).is_none() {
+                return Err(format!(
+                    "Unknown notification event type: {}",
+                    preference.event_type
+                ));
+            }
+            if !channels.contains(&preference.channel) {
+                return Err(format!(
+                    "Unknown notification channel: {}",
+                    preference.channel
+                ));
+            }
+        }
+
+        repository
+            .upsert_notification_preferences(&user_id, &request.preferences)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        self.get_preferences(repository, user_id).await
+    }
+
+    /// Full event type × channel matrix with defaults filled in for anything the
+    /// user has not configured.
+    pub fn build_preferences(
+        &self,
+        stored: &[NotificationPreference],
+    ) -> NotificationPreferencesResponse {
+        let channels = self.channel_names();
+        let preferences = NotificationEventType::ALL
+            .iter()
+            .flat_map(|event_type| {
+                channels.iter().map(move |channel| NotificationPreference {
+                    event_type: event_type.as_str().to_string(),
+                    channel: channel.clone(),
+                    enabled: Self::is_enabled(stored, *event_type, channel),
+                })
+            })
+            .collect();
+
+        NotificationPreferencesResponse {
+            channels,
+            preferences,
+        }
+    }
+
+    pub fn is_enabled(
+        stored: &[NotificationPreference],
+        event_type: NotificationEventType,
+        channel: &str,
+    ) -> bool {
+        stored
+            .iter()
+            .find(|p| p.event_type == event_type.as_str() && p.channel == channel)
+            .map(|p| p.enabled)
+            .unwrap_or_else(|| event_type.enabled_by_default(channel))
+    }
+
+    pub fn sync_failure_event(
+        user_id: Uuid,
+        institution_name: Option<&str>,
+        reason: &str,
+    ) -> NotificationEvent {
+        let institution = institution_name.unwrap_or("your bank");
+        NotificationEvent {
+            user_id,
+            event_type: NotificationEventType::SyncFailure,
+            title: format!("Sync failed for {}", institution),
+            body: format!(
+                "We could not refresh {}: {}. Balances and transactions may be out of date until the next successful sync.",
+                institution, reason
+            ),
+        }
+    }
+}
@@ -6,7 +6,14 @@ use serde_json::json;
 use std::sync::Arc;
 use uuid::Uuid;
 
-use crate::models::{account::Account, transaction::Transaction};
+use crate::models::{account::Account, currency::default_currency, transaction::Transaction};
+use crate::providers::{
+    InvestmentActivity, InvestmentHoldings, ProviderHolding, ProviderInvestmentTransaction,
+    ProviderLiability, ProviderSecurity,
+};
+
+/// Page size for `/investments/transactions/get`; Plaid caps it at 500.
+const INVESTMENT_TRANSACTIONS_PAGE_SIZE: usize = 500;
 
 #[derive(Clone)]
 pub struct RealPlaidClient {
@@ -32,6 +39,12 @@ impl RealPlaidClient {
         }
     }
 
+    /// Points the client at a different Plaid host, such as a local mock server.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
     async fn get_institution_name(&self, institution_id: &str) -> Result<String> {
         let request_body = json!({
             "client_id": self.client_id,
@@ -191,6 +204,10 @@ impl RealPlaidClient {
                             .unwrap_or("other")
                             .to_string(),
                         balance_current,
+                        iso_currency_code: acc
+                            .get("balances")
+                            .map(Account::plaid_currency)
+                            .unwrap_or_else(default_currency),
                         mask,
                         institution_name: None,
                     };
@@ -296,6 +313,7 @@ impl RealPlaidClient {
                         provider_account_id,
                         provider_transaction_id,
                         amount: Decimal::from_f64(amount).unwrap_or(Decimal::ZERO),
+                        iso_currency_code: Account::plaid_currency(t),
                         date: chrono::NaiveDate::parse_from_str(&date, "%Y-%m-%d").unwrap_or_else(
                             |_| chrono::NaiveDate::from_ymd_opt(1970, 1, 1).unwrap(),
                         ),
@@ -306,6 +324,8 @@ impl RealPlaidClient {
                         payment_channel,
                         pending,
                         created_at: Some(chrono::Utc::now()),
+                        reviewed: false,
+                        excluded_from_analytics: false,
                     };
                     transactions.push(transaction);
                 }
@@ -321,6 +341,320 @@ impl RealPlaidClient {
         }
     }
 
+    pub async fn get_liabilities(&self, access_token: &str) -> Result<Vec<ProviderLiability>> {
+        let data = self.fetch_liabilities(access_token).await?;
+        Ok(Self::parse_liabilities(&data))
+    }
+
+    async fn fetch_liabilities(&self, access_token: &str) -> Result<serde_json::Value> {
+        let request_body = json!({
+            "client_id": self.client_id,
+            "secret": self.secret,
+            "access_token": access_token
+        });
+
+        let response = self
+            .http_client
+            .post(format!("{}/liabilities/get", self.base_url))
+            .header("Content-Type", "application/json")
+            .json(&request_body)
+            .send()
+            .await?;
+
+        if response.status().is_success() {
+            Ok(response.json().await?)
+        } else {
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            Err(anyhow::anyhow!("Plaid API error: {}", error_text))
+        }
+    }
+
+    /// Flattens the credit, mortgage and student loan groups of a
+    /// `/liabilities/get` response. Credit cards report the purchase APR.
+    pub fn parse_liabilities(data: &serde_json::Value) -> Vec<ProviderLiability> {
+        let group = |name: &str| {
+            data.get("liabilities")
+                .and_then(|l| l.get(name))
+                .and_then(|g| g.as_array())
+                .cloned()
+                .unwrap_or_default()
+        };
+        let account_id = |entry: &serde_json::Value| {
+            entry
+                .get("account_id")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string())
+        };
+
+        let credit = group("credit").into_iter().filter_map(|card| {
+            let aprs = card.get("aprs").and_then(|a| a.as_array());
+            let apr = aprs
+                .and_then(|aprs| {
+                    aprs.iter()
+                        .find(|a| {
+                            a.get("apr_type").and_then(|t| t.as_str()) == Some("purchase_apr")
+                        })
+                        .or_else(|| aprs.first())
+                })
+                .and_then(|a| parse_plaid_amount(a.get("apr_percentage")));
+            Some(ProviderLiability {
+                provider_account_id: account_id(&card)?,
+                liability_type: "credit".to_string(),
+                apr,
+                minimum_payment: parse_plaid_amount(card.get("minimum_payment_amount")),
+                last_statement_balance: parse_plaid_amount(card.get("last_statement_balance")),
+                next_payment_due_date: parse_plaid_date(card.get("next_payment_due_date")),
+                loan_term_months: None,
+                maturity_date: None,
+            })
+        });
+
+        let mortgage = group("mortgage").into_iter().filter_map(|loan| {
+            Some(ProviderLiability {
+                provider_account_id: account_id(&loan)?,
+                liability_type: "mortgage".to_string(),
+                apr: parse_plaid_amount(
+                    loan.get("interest_rate").and_then(|r| r.get("percentage")),
+                ),
+                minimum_payment: parse_plaid_amount(loan.get("next_monthly_payment")),
+                last_statement_balance: None,
+                next_payment_due_date: parse_plaid_date(loan.get("next_payment_due_date")),
+                loan_term_months: loan
+                    .get("loan_term")
+                    .and_then(|v| v.as_str())
+                    .and_then(parse_loan_term_months),
+                maturity_date: parse_plaid_date(loan.get("maturity_date")),
+            })
+        });
+
+        let student = group("student").into_iter().filter_map(|loan| {
+            Some(ProviderLiability {
+                provider_account_id: account_id(&loan)?,
+                liability_type: "student".to_string(),
+                apr: parse_plaid_amount(loan.get("interest_rate_percentage")),
+                minimum_payment: parse_plaid_amount(loan.get("minimum_payment_amount")),
+                last_statement_balance: parse_plaid_amount(loan.get("last_statement_balance")),
+                next_payment_due_date: parse_plaid_date(loan.get("next_payment_due_date")),
+                loan_term_months: None,
+                maturity_date: parse_plaid_date(loan.get("expected_payoff_date")),
+            })
+        });
+
+        credit.chain(mortgage).chain(student).collect()
+    }
+
+    pub async fn get_investment_holdings(&self, access_token: &str) -> Result<InvestmentHoldings> {
+        let request_body = json!({
+            "client_id": self.client_id,
+            "secret": self.secret,
+            "access_token": access_token
+        });
+
+        let response = self
+            .http_client
+            .post(format!("{}/investments/holdings/get", self.base_url))
+            .header("Content-Type", "application/json")
+            .json(&request_body)
+            .send()
+            .await?;
+
+        if response.status().is_success() {
+            let data: serde_json::Value = response.json().await?;
+            Ok(InvestmentHoldings {
+                securities: Self::parse_securities(&data),
+                holdings: Self::parse_investment_holdings(&data),
+            })
+        } else {
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            Err(anyhow::anyhow!("Plaid API error: {}", error_text))
+        }
+    }
+
+    /// Pages through `/investments/transactions/get` until every transaction in
+    /// the range has been read.
+    pub async fn get_investment_transactions(
+        &self,
+        access_token: &str,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<InvestmentActivity> {
+        let mut activity = InvestmentActivity::default();
+        // Rows Plaid has returned so far, counting any that fail to parse, so the
+        // next page starts right after the last one read.
+        let mut offset = 0;
+
+        loop {
+            let request_body = json!({
+                "client_id": self.client_id,
+                "secret": self.secret,
+                "access_token": access_token,
+                "start_date": start_date.format("%Y-%m-%d").to_string(),
+                "end_date": end_date.format("%Y-%m-%d").to_string(),
+                "options": {
+                    "count": INVESTMENT_TRANSACTIONS_PAGE_SIZE,
+                    "offset": offset
+                }
+            });
+
+            let response = self
+                .http_client
+                .post(format!("{}/investments/transactions/get", self.base_url))
+                .header("Content-Type", "application/json")
+                .json(&request_body)
+                .send()
+                .await?;
+
+            if !response.status().is_success() {
+                let error_text = response
+                    .text()
+                    .await
+                    .unwrap_or_else(|_| "Unknown error".to_string());
+                return Err(anyhow::anyhow!("Plaid API error: {}", error_text));
+            }
+
+            let data: serde_json::Value = response.json().await?;
+            let page_len = data
+                .get("investment_transactions")
+                .and_then(|t| t.as_array())
+                .map_or(0, Vec::len);
+            let total = data
+                .get("total_investment_transactions")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(0) as usize;
+            offset += page_len;
+
+            for security in Self::parse_securities(&data) {
+                if !activity
+                    .securities
+                    .iter()
+                    .any(|s| s.provider_security_id == security.provider_security_id)
+                {
+                    activity.securities.push(security);
+                }
+            }
+            activity
+                .transactions
+                .extend(Self::parse_investment_transactions(&data));
+
+            if page_len == 0 || offset >= total {
+                return Ok(activity);
+            }
+        }
+    }
+
+    pub fn parse_securities(data: &serde_json::Value) -> Vec<ProviderSecurity> {
+        data.get("securities")
+            .and_then(|s| s.as_array())
+            .map(|securities| {
+                securities
+                    .iter()
+                    .filter_map(|security| {
+                        Some(ProviderSecurity {
+                            provider_security_id: security
+                                .get("security_id")?
+                                .as_str()?
+                                .to_string(),
+                            name: security
+                                .get("name")
+                                .and_then(|v| v.as_str())
+                                .unwrap_or("Unknown Security")
+                                .to_string(),
+                            ticker_symbol: security
+                                .get("ticker_symbol")
+                                .and_then(|v| v.as_str())
+                                .map(|s| s.to_string()),
+                            security_type: security
+                                .get("type")
+                                .and_then(|v| v.as_str())
+                                .unwrap_or("other")
+                                .to_string(),
+                            iso_currency_code: Account::plaid_currency(security),
+                            close_price: parse_plaid_amount(security.get("close_price")),
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn parse_investment_holdings(data: &serde_json::Value) -> Vec<ProviderHolding> {
+        data.get("holdings")
+            .and_then(|h| h.as_array())
+            .map(|holdings| {
+                holdings
+                    .iter()
+                    .filter_map(|holding| {
+                        Some(ProviderHolding {
+                            provider_account_id: holding.get("account_id")?.as_str()?.to_string(),
+                            provider_security_id: holding.get("security_id")?.as_str()?.to_string(),
+                            quantity: parse_plaid_amount(holding.get("quantity"))?,
+                            institution_price: parse_plaid_amount(holding.get("institution_price"))
+                                .unwrap_or(Decimal::ZERO),
+                            institution_value: parse_plaid_amount(holding.get("institution_value"))
+                                .unwrap_or(Decimal::ZERO),
+                            cost_basis: parse_plaid_amount(holding.get("cost_basis")),
+                            iso_currency_code: Account::plaid_currency(holding),
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn parse_investment_transactions(
+        data: &serde_json::Value,
+    ) -> Vec<ProviderInvestmentTransaction> {
+        data.get("investment_transactions")
+            .and_then(|t| t.as_array())
+            .map(|transactions| {
+                transactions
+                    .iter()
+                    .filter_map(|t| {
+                        Some(ProviderInvestmentTransaction {
+                            provider_investment_transaction_id: t
+                                .get("investment_transaction_id")?
+                                .as_str()?
+                                .to_string(),
+                            provider_account_id: t.get("account_id")?.as_str()?.to_string(),
+                            provider_security_id: t
+                                .get("security_id")
+                                .and_then(|v| v.as_str())
+                                .map(|s| s.to_string()),
+                            date: NaiveDate::parse_from_str(t.get("date")?.as_str()?, "%Y-%m-%d")
+                                .ok()?,
+                            name: t
+                                .get("name")
+                                .and_then(|v| v.as_str())
+                                .unwrap_or("Unknown")
+                                .to_string(),
+                            transaction_type: t
+                                .get("type")
+                                .and_then(|v| v.as_str())
+                                .unwrap_or("other")
+                                .to_string(),
+                            subtype: t
+                                .get("subtype")
+                                .and_then(|v| v.as_str())
+                                .map(|s| s.to_string()),
+                            quantity: parse_plaid_amount(t.get("quantity"))
+                                .unwrap_or(Decimal::ZERO),
+                            price: parse_plaid_amount(t.get("price")).unwrap_or(Decimal::ZERO),
+                            amount: parse_plaid_amount(t.get("amount")).unwrap_or(Decimal::ZERO),
+                            fees: parse_plaid_amount(t.get("fees")),
+                            iso_currency_code: Account::plaid_currency(t),
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
     pub async fn get_item_info(
         &self,
         access_token: &str,
@@ -375,6 +709,27 @@ impl RealPlaidClient {
     }
 }
 
+fn parse_plaid_amount(value: Option<&serde_json::Value>) -> Option<Decimal> {
+    value.and_then(|v| v.as_f64()).and_then(Decimal::from_f64)
+}
+
+fn parse_plaid_date(value: Option<&serde_json::Value>) -> Option<NaiveDate> {
+    value
+        .and_then(|v| v.as_str())
+        .and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+}
+
+/// Reads Plaid loan terms such as `30 year` or `18 month`.
+fn parse_loan_term_months(term: &str) -> Option<i32> {
+    let (count, unit) = term.trim().split_once(' ')?;
+    let count: i32 = count.parse().ok()?;
+    match unit.trim().trim_end_matches('s') {
+        "year" => Some(count * 12),
+        "month" => Some(count),
+        _ => None,
+    }
+}
+
 pub struct PlaidService {
     #[allow(dead_code)]
     client: Arc<RealPlaidClient>,
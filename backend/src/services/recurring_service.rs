@@ -0,0 +1,131 @@
+use crate::models::recurring::{RecurrenceCadence, RecurringSeries};
+use crate::models::transaction::Transaction;
+use crate::services::analytics_service::AnalyticsService;
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+
+const MIN_OCCURRENCES: usize = 3;
+const MAX_AMOUNT_DEVIATION_PERCENT: i64 = 25;
+
+pub struct RecurringService;
+
+impl RecurringService {
+    /// Detects charges and deposits that repeat from the same merchant at a regular cadence
+    /// with a stable amount. Series that have not recurred for two cycles are treated as ended.
+    pub fn detect_recurring_series(
+        transactions: &[Transaction],
+        today: NaiveDate,
+    ) -> Vec<RecurringSeries> {
+        let mut groups: HashMap<(String, bool), Vec<&Transaction>> = HashMap::new();
+        for transaction in transactions.iter().filter(|t| !t.pending) {
+            let Some(key) = transaction
+                .merchant_name
+                .as_deref()
+                .map(Self::normalize_merchant)
+                .filter(|k| !k.is_empty())
+            else {
+                continue;
+            };
+            let is_income = AnalyticsService::is_income_transaction(transaction);
+            groups
+                .entry((key, is_income))
+                .or_default()
+                .push(transaction);
+        }
+
+        let mut series: Vec<RecurringSeries> = groups
+            .into_values()
+            .filter_map(|group| Self::series_from_group(group, today))
+            .collect();
+        series.sort_by(|a, b| {
+            a.next_expected_date
+                .cmp(&b.next_expected_date)
+                .then_with(|| a.merchant.cmp(&b.merchant))
+        });
+        series
+    }
+
+    fn series_from_group(
+        mut group: Vec<&Transaction>,
+        today: NaiveDate,
+    ) -> Option<RecurringSeries> {
+        if group.len() < MIN_OCCURRENCES {
+            return None;
+        }
+        group.sort_by_key(|t| t.date);
+
+        let mut intervals: Vec<i64> = group
+            .windows(2)
+            .map(|pair| (pair[1].date - pair[0].date).num_days())
+            .filter(|days| *days > 0)
+            .collect();
+        if intervals.len() + 1 < MIN_OCCURRENCES {
+            return None;
+        }
+        intervals.sort_unstable();
+        let median_interval = intervals[intervals.len() / 2];
+        let cadence = RecurrenceCadence::from_interval_days(median_interval)?;
+
+        let regular = intervals
+            .iter()
+            .filter(|days| RecurrenceCadence::from_interval_days(**days) == Some(cadence))
+            .count();
+        if regular * 4 < intervals.len() * 3 {
+            return None;
+        }
+
+        let amounts: Vec<Decimal> = group.iter().map(|t| t.amount.abs()).collect();
+        let average_amount =
+            amounts.iter().copied().sum::<Decimal>() / Decimal::from(amounts.len());
+        if average_amount <= Decimal::ZERO {
+            return None;
+        }
+        let tolerance =
+            average_amount * Decimal::from(MAX_AMOUNT_DEVIATION_PERCENT) / Decimal::from(100);
+        if amounts
+            .iter()
+            .any(|amount| (*amount - average_amount).abs() > tolerance)
+        {
+            return None;
+        }
+
+        let last = *group.last()?;
+        if (today - last.date).num_days() > cadence.nominal_days() * 2 {
+            return None;
+        }
+
+        let mut next_expected_date = cadence.advance(last.date);
+        while next_expected_date < today {
+            next_expected_date = cadence.advance(next_expected_date);
+        }
+
+        Some(RecurringSeries {
+            merchant: last
+                .merchant_name
+                .clone()
+                .unwrap_or_else(|| "Unknown Merchant".to_string()),
+            category: last.category_primary.clone(),
+            cadence,
+            average_amount: average_amount.round_dp(2),
+            is_income: AnalyticsService::is_income_transaction(last),
+            occurrences: group.len() as u32,
+            last_date: last.date,
+            next_expected_date,
+            account_id: last.account_id,
+            transaction_ids: group.iter().map(|t| t.id).collect(),
+        })
+    }
+
+    /// Collapses processor noise such as store numbers and reference codes so
+    /// "NETFLIX.COM 1234" and "Netflix.com 9876" land in the same series.
+    pub fn normalize_merchant(name: &str) -> String {
+        name.to_lowercase()
+            .chars()
+            .map(|c| if c.is_ascii_alphabetic() { c } else { ' ' })
+            .collect::<String>()
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
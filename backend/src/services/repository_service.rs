@@ -1,9 +1,29 @@
 use crate::models::{
+    access_token::{PersonalAccessToken, TokenScope},
     account::Account,
+    alert::{AlertHistoryEntry, AlertRule, FiredAlert},
+    analytics::{AggregateBucket, AggregateFilter},
+    analytics_query::{AnalyticsQueryRequest, AnalyticsQueryRow, QueryRates},
+    archive::{TransactionTag, UserArchive},
     auth::User,
-    budget::Budget,
+    balance_snapshot::BalanceSnapshot,
+    budget::{AssignBudgetFundsRequest, Budget, BudgetAllocation, MoveBudgetFundsRequest},
+    bulk_edit::BulkTransactionOperation,
+    currency::FxRate,
+    export::TransactionPageCursor,
+    investment::{Holding, InvestmentTransaction, Security},
+    liability::Liability,
+    notification::{Notification, NotificationEvent, NotificationPreference},
     plaid::{LatestAccountBalance, PlaidCredentials, ProviderConnection},
+    saved_view::SavedView,
     transaction::{Transaction, TransactionWithAccount},
+    transaction_search::TransactionSearch,
+    user_settings::UserSettings,
+    webhook::{WebhookDelivery, WebhookEndpoint, WebhookEventType},
+};
+use crate::services::analytics_query_service::AnalyticsQueryService;
+use crate::services::transaction_search_service::{
+    TransactionSearchService, FUZZY_MATCH_THRESHOLD,
 };
 use aes_gcm::{
     aead::{Aead, KeyInit},
@@ -11,7 +31,7 @@ use aes_gcm::{
 };
 use anyhow::Result;
 use async_trait::async_trait;
-use sqlx::PgPool;
+use sqlx::{PgPool, Postgres, QueryBuilder, Row};
 use uuid::Uuid;
 
 #[async_trait]
@@ -29,6 +49,26 @@ pub trait DatabaseRepository: Send + Sync {
         &self,
         user_id: &Uuid,
     ) -> Result<Vec<TransactionWithAccount>>;
+    async fn get_transactions_with_account_page(
+        &self,
+        user_id: &Uuid,
+        after: Option<TransactionPageCursor>,
+        limit: i64,
+    ) -> Result<Vec<TransactionWithAccount>>;
+    /// Transactions matching a parsed search, best matches first.
+    async fn search_transactions(
+        &self,
+        user_id: &Uuid,
+        search: &TransactionSearch,
+        account_ids: &[Uuid],
+    ) -> Result<Vec<TransactionWithAccount>>;
+    /// Ids of every transaction matching a parsed search, with no row cap.
+    async fn search_transaction_ids(
+        &self,
+        user_id: &Uuid,
+        search: &TransactionSearch,
+        account_ids: &[Uuid],
+    ) -> Result<Vec<Uuid>>;
     async fn get_transactions_by_date_range_for_user(
         &self,
         user_id: &Uuid,
@@ -74,15 +114,39 @@ pub trait DatabaseRepository: Send + Sync {
     async fn get_budgets_for_user(&self, user_id: Uuid) -> Result<Vec<Budget>>;
     async fn create_budget_for_user(&self, budget: Budget) -> Result<Budget>;
 
+    /// `saved_view_id` is left unchanged when `None` and replaced otherwise.
     async fn update_budget_for_user(
         &self,
         budget_id: Uuid,
         user_id: Uuid,
         amount: rust_decimal::Decimal,
+        saved_view_id: Option<Option<Uuid>>,
     ) -> Result<Budget>;
 
     async fn delete_budget_for_user(&self, budget_id: Uuid, user_id: Uuid) -> Result<()>;
 
+    async fn get_zero_based_budgeting_enabled(&self, user_id: Uuid) -> Result<bool>;
+
+    async fn set_zero_based_budgeting_enabled(&self, user_id: Uuid, enabled: bool) -> Result<()>;
+
+    async fn get_budget_allocations_for_user(
+        &self,
+        user_id: Uuid,
+        month: &str,
+    ) -> Result<Vec<BudgetAllocation>>;
+
+    async fn upsert_budget_allocation(
+        &self,
+        user_id: Uuid,
+        request: &AssignBudgetFundsRequest,
+    ) -> Result<BudgetAllocation>;
+
+    async fn move_budget_allocation(
+        &self,
+        user_id: Uuid,
+        request: &MoveBudgetFundsRequest,
+    ) -> Result<Vec<BudgetAllocation>>;
+
     async fn get_latest_account_balances_for_user(
         &self,
         user_id: &Uuid,
@@ -91,6 +155,281 @@ pub trait DatabaseRepository: Send + Sync {
     async fn update_user_password(&self, user_id: &Uuid, new_password_hash: &str) -> Result<()>;
 
     async fn delete_user(&self, user_id: &Uuid) -> Result<()>;
+
+    async fn upsert_calendar_feed_token(&self, user_id: &Uuid, token_hash: &str) -> Result<()>;
+
+    async fn get_user_id_by_calendar_feed_token(&self, token_hash: &str) -> Result<Option<Uuid>>;
+
+    async fn delete_calendar_feed_token(&self, user_id: &Uuid) -> Result<()>;
+
+    async fn create_personal_access_token(
+        &self,
+        token: &PersonalAccessToken,
+        token_hash: &str,
+    ) -> Result<()>;
+
+    async fn get_personal_access_tokens_for_user(
+        &self,
+        user_id: &Uuid,
+    ) -> Result<Vec<PersonalAccessToken>>;
+
+    async fn get_personal_access_token_by_hash(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<PersonalAccessToken>>;
+
+    /// Marks the token revoked; returns false when the user has no such active token.
+    async fn revoke_personal_access_token(&self, user_id: &Uuid, token_id: &Uuid) -> Result<bool>;
+
+    async fn touch_personal_access_token(&self, token_id: &Uuid) -> Result<()>;
+
+    async fn create_webhook_endpoint(&self, endpoint: &WebhookEndpoint) -> Result<()>;
+
+    async fn get_webhook_endpoints_for_user(&self, user_id: &Uuid) -> Result<Vec<WebhookEndpoint>>;
+
+    async fn get_webhook_endpoint_by_id(
+        &self,
+        endpoint_id: &Uuid,
+    ) -> Result<Option<WebhookEndpoint>>;
+
+    /// Updates everything but the secret; returns false when the user has no such endpoint.
+    async fn update_webhook_endpoint(&self, endpoint: &WebhookEndpoint) -> Result<bool>;
+
+    async fn delete_webhook_endpoint(&self, user_id: &Uuid, endpoint_id: &Uuid) -> Result<bool>;
+
+    /// Returns false when the endpoint already has a delivery with the same dedup key.
+    async fn insert_webhook_delivery(&self, delivery: &WebhookDelivery) -> Result<bool>;
+
+    async fn update_webhook_delivery(&self, delivery: &WebhookDelivery) -> Result<()>;
+
+    /// Pending deliveries whose next attempt is due, leased so that concurrent workers
+    /// skip them until `lease_until`.
+    async fn claim_due_webhook_deliveries(
+        &self,
+        now: chrono::DateTime<chrono::Utc>,
+        lease_until: chrono::DateTime<chrono::Utc>,
+        limit: i64,
+    ) -> Result<Vec<WebhookDelivery>>;
+
+    async fn get_webhook_deliveries_for_endpoint(
+        &self,
+        user_id: &Uuid,
+        endpoint_id: &Uuid,
+        limit: i64,
+    ) -> Result<Vec<WebhookDelivery>>;
+
+    async fn insert_notification(&self, event: &NotificationEvent) -> Result<Notification>;
+
+    async fn get_notifications_for_user(
+        &self,
+        user_id: &Uuid,
+        unread_only: bool,
+        limit: i64,
+    ) -> Result<Vec<Notification>>;
+
+    async fn count_unread_notifications(&self, user_id: &Uuid) -> Result<i64>;
+
+    async fn set_notification_read(
+        &self,
+        user_id: &Uuid,
+        notification_id: &Uuid,
+        read: bool,
+    ) -> Result<bool>;
+
+    async fn mark_all_notifications_read(&self, user_id: &Uuid) -> Result<u64>;
+
+    async fn get_notification_preferences(
+        &self,
+        user_id: &Uuid,
+    ) -> Result<Vec<NotificationPreference>>;
+
+    async fn upsert_notification_preferences(
+        &self,
+        user_id: &Uuid,
+        preferences: &[NotificationPreference],
+    ) -> Result<()>;
+
+    async fn get_alert_rules_for_user(&self, user_id: &Uuid) -> Result<Vec<AlertRule>>;
+
+    async fn create_alert_rule(&self, rule: &AlertRule) -> Result<AlertRule>;
+
+    async fn update_alert_rule(&self, rule: &AlertRule) -> Result<Option<AlertRule>>;
+
+    async fn delete_alert_rule(&self, user_id: &Uuid, rule_id: &Uuid) -> Result<bool>;
+
+    async fn get_saved_views_for_user(&self, user_id: &Uuid) -> Result<Vec<SavedView>>;
+
+    async fn get_saved_view(&self, user_id: &Uuid, view_id: &Uuid) -> Result<Option<SavedView>>;
+
+    /// Fails with "Saved view name already exists" when the name is taken.
+    async fn create_saved_view(&self, view: &SavedView) -> Result<SavedView>;
+
+    /// `None` when the view does not exist or belongs to another user.
+    async fn update_saved_view(&self, view: &SavedView) -> Result<Option<SavedView>>;
+
+    async fn delete_saved_view(&self, user_id: &Uuid, view_id: &Uuid) -> Result<bool>;
+
+    async fn get_alert_history_for_user(&self, user_id: &Uuid) -> Result<Vec<AlertHistoryEntry>>;
+
+    /// Appends an alert to the history unless its rule and dedup key already fired,
+    /// or fired after `refire_before` when one is given. The check runs under a lock
+    /// on the rule, so of two concurrent evaluations only one gets `true` and sends
+    /// the alert.
+    async fn insert_fired_alert(
+        &self,
+        user_id: &Uuid,
+        alert: &FiredAlert,
+        refire_before: Option<chrono::DateTime<chrono::Utc>>,
+    ) -> Result<bool>;
+
+    async fn get_fired_alerts_for_user(
+        &self,
+        user_id: &Uuid,
+        limit: i64,
+    ) -> Result<Vec<FiredAlert>>;
+
+    async fn upsert_fx_rates(&self, rates: &[FxRate]) -> Result<u64>;
+
+    async fn get_fx_rates(
+        &self,
+        currencies: &[String],
+        until: chrono::NaiveDate,
+    ) -> Result<Vec<FxRate>>;
+
+    async fn get_user_settings(&self, user_id: &Uuid) -> Result<Option<UserSettings>>;
+
+    async fn upsert_user_settings(
+        &self,
+        user_id: &Uuid,
+        settings: &UserSettings,
+    ) -> Result<UserSettings>;
+
+    async fn get_all_user_ids(&self) -> Result<Vec<Uuid>>;
+
+    async fn record_balance_snapshots(
+        &self,
+        user_id: &Uuid,
+        snapshot_date: chrono::NaiveDate,
+    ) -> Result<u64>;
+
+    async fn get_balance_snapshots_for_user(
+        &self,
+        user_id: &Uuid,
+        start_date: chrono::NaiveDate,
+        end_date: chrono::NaiveDate,
+    ) -> Result<Vec<BalanceSnapshot>>;
+
+    /// Currencies and days, outside `reporting_currency`, of the transactions an
+    /// analytics query matches.
+    async fn get_analytics_query_currency_days(
+        &self,
+        user_id: &Uuid,
+        request: &AnalyticsQueryRequest,
+        account_ids: &[Uuid],
+        reporting_currency: &str,
+    ) -> Result<Vec<(String, chrono::NaiveDate)>>;
+
+    async fn run_analytics_query(
+        &self,
+        user_id: &Uuid,
+        request: &AnalyticsQueryRequest,
+        account_ids: &[Uuid],
+        rates: &QueryRates,
+    ) -> Result<Vec<AnalyticsQueryRow>>;
+
+    async fn sum_spending_by_category(
+        &self,
+        user_id: &Uuid,
+        filter: &AggregateFilter,
+    ) -> Result<Vec<AggregateBucket>>;
+
+    async fn sum_spending_by_merchant(
+        &self,
+        user_id: &Uuid,
+        filter: &AggregateFilter,
+    ) -> Result<Vec<AggregateBucket>>;
+
+    async fn sum_by_month(
+        &self,
+        user_id: &Uuid,
+        filter: &AggregateFilter,
+    ) -> Result<Vec<AggregateBucket>>;
+
+    async fn get_dismissed_insight_ids(&self, user_id: &Uuid) -> Result<Vec<String>>;
+
+    async fn dismiss_insight(&self, user_id: &Uuid, insight_id: &str) -> Result<()>;
+
+    /// Inserts or refreshes securities by provider id and returns the stored
+    /// rows, whose ids are stable across syncs.
+    async fn upsert_securities(
+        &self,
+        user_id: &Uuid,
+        securities: &[Security],
+    ) -> Result<Vec<Security>>;
+
+    /// Replaces every holding in `account_ids` with `holdings` in one transaction.
+    async fn replace_investment_holdings(
+        &self,
+        user_id: &Uuid,
+        account_ids: &[Uuid],
+        holdings: &[Holding],
+    ) -> Result<()>;
+
+    async fn upsert_investment_transactions(
+        &self,
+        user_id: &Uuid,
+        transactions: &[InvestmentTransaction],
+    ) -> Result<()>;
+
+    async fn get_securities_for_user(&self, user_id: &Uuid) -> Result<Vec<Security>>;
+
+    async fn get_investment_holdings_for_user(&self, user_id: &Uuid) -> Result<Vec<Holding>>;
+
+    async fn get_investment_transactions_for_user(
+        &self,
+        user_id: &Uuid,
+        start_date: chrono::NaiveDate,
+        end_date: chrono::NaiveDate,
+    ) -> Result<Vec<InvestmentTransaction>>;
+
+    /// Refreshes provider-sourced liability details; rows the user entered by
+    /// hand are left untouched.
+    async fn upsert_provider_liabilities(
+        &self,
+        user_id: &Uuid,
+        liabilities: &[Liability],
+    ) -> Result<()>;
+
+    /// Saves liability details for one account, replacing whatever was stored.
+    async fn upsert_liability(&self, user_id: &Uuid, liability: &Liability) -> Result<Liability>;
+
+    async fn get_liabilities_for_user(&self, user_id: &Uuid) -> Result<Vec<Liability>>;
+
+    async fn get_all_budget_allocations_for_user(
+        &self,
+        user_id: Uuid,
+    ) -> Result<Vec<BudgetAllocation>>;
+
+    async fn get_transaction_tags_for_user(&self, user_id: &Uuid) -> Result<Vec<TransactionTag>>;
+
+    async fn get_transactions_with_account_by_ids(
+        &self,
+        user_id: &Uuid,
+        transaction_ids: &[Uuid],
+    ) -> Result<Vec<TransactionWithAccount>>;
+
+    /// Applies the operations in order inside one database transaction and returns
+    /// how many transactions each one changed.
+    async fn apply_bulk_transaction_edit(
+        &self,
+        user_id: &Uuid,
+        transaction_ids: &[Uuid],
+        operations: &[BulkTransactionOperation],
+    ) -> Result<Vec<u64>>;
+
+    /// Writes an archive into the user's account in one transaction, keeping the
+    /// archived ids. Connections are stored disconnected and without credentials.
+    async fn restore_user_archive(&self, user_id: &Uuid, archive: &UserArchive) -> Result<()>;
 }
 
 pub struct PostgresRepository {
@@ -122,6 +461,63 @@ impl PostgresRepository {
         })
     }
 
+    /// Sums transactions per `key_sql` group and currency. Amounts outside the
+    /// reporting currency are further split by date so they can be converted
+    /// at the right rate.
+    async fn aggregate_transactions(
+        &self,
+        user_id: &Uuid,
+        filter: &AggregateFilter,
+        key_sql: &str,
+        spending_only: bool,
+    ) -> Result<Vec<AggregateBucket>> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("SELECT set_config('app.current_user_id', $1, true)")
+            .bind(user_id.to_string())
+            .execute(&mut *tx)
+            .await?;
+
+        let mut builder = QueryBuilder::<Postgres>::new(format!("SELECT {} AS key, ", key_sql));
+        builder
+            .push(
+                "UPPER(iso_currency_code) AS iso_currency_code, \
+                 CASE WHEN UPPER(iso_currency_code) = UPPER(",
+            )
+            .push_bind(filter.reporting_currency.clone())
+            .push(
+                ") THEN NULL ELSE date END AS rate_date, \
+                 SUM(amount) AS total, COUNT(*) AS count \
+                 FROM transactions WHERE NOT excluded_from_analytics AND user_id = ",
+            )
+            .push_bind(*user_id);
+        if let Some((start_date, end_date)) = filter.date_range {
+            builder
+                .push(" AND date BETWEEN ")
+                .push_bind(start_date)
+                .push(" AND ")
+                .push_bind(end_date);
+        }
+        if !filter.account_ids.is_empty() {
+            builder
+                .push(" AND account_id = ANY(")
+                .push_bind(filter.account_ids.clone())
+                .push(")");
+        }
+        if spending_only {
+            builder.push(" AND amount > 0");
+        }
+        builder.push(" GROUP BY 1, 2, 3");
+
+        let buckets = builder
+            .build_query_as::<AggregateBucket>()
+            .fetch_all(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(buckets)
+    }
+
     fn encrypt_token(&self, token: &str) -> Result<Vec<u8>> {
         let key = Key::<Aes256Gcm>::from_slice(&self.encryption_key);
         let cipher = Aes256Gcm::new(key);
@@ -179,103 +575,442 @@ impl PostgresRepository {
             onboarding_completed,
         }
     }
-}
-
-#[async_trait]
-impl DatabaseRepository for PostgresRepository {
-    async fn create_user(&self, user: &User) -> Result<()> {
-        sqlx::query(
-            r#"
-            INSERT INTO users (id, email, password_hash, provider, created_at, updated_at, onboarding_completed)
-            VALUES ($1, $2, $3, $4, $5, $6, $7)
-            "#,
-        )
-        .bind(user.id)
-        .bind(&user.email)
-        .bind(&user.password_hash)
-        .bind(&user.provider)
-        .bind(user.created_at)
-        .bind(user.updated_at)
-        .bind(user.onboarding_completed)
-        .execute(&self.pool)
-        .await?;
 
-        Ok(())
+    fn map_personal_access_token_row(row: &sqlx::postgres::PgRow) -> Result<PersonalAccessToken> {
+        let scopes: Vec<String> = row.try_get("scopes")?;
+        Ok(PersonalAccessToken {
+            id: row.try_get("id")?,
+            user_id: row.try_get("user_id")?,
+            name: row.try_get("name")?,
+            token_prefix: row.try_get("token_prefix")?,
+            scopes: scopes.iter().filter_map(|s| TokenScope::parse(s)).collect(),
+            expires_at: row.try_get("expires_at")?,
+            last_used_at: row.try_get("last_used_at")?,
+            revoked_at: row.try_get("revoked_at")?,
+            created_at: row.try_get("created_at")?,
+        })
     }
 
-    async fn get_user_by_email(&self, email: &str) -> Result<Option<User>> {
-        let row = sqlx::query_as::<
-            _,
-            (
-                uuid::Uuid,
-                String,
-                String,
-                String,
-                chrono::DateTime<chrono::Utc>,
-                chrono::DateTime<chrono::Utc>,
-                bool,
-            ),
-        >(
-            "SELECT id, email, password_hash, provider, created_at, updated_at, onboarding_completed FROM users WHERE email = $1",
-        )
-        .bind(email)
-        .fetch_optional(&self.pool)
-        .await?;
-
-        Ok(row.map(Self::map_user_row))
+    fn map_webhook_endpoint_row(&self, row: &sqlx::postgres::PgRow) -> Result<WebhookEndpoint> {
+        let events: Vec<String> = row.try_get("events")?;
+        let encrypted_secret: Vec<u8> = row.try_get("encrypted_secret")?;
+        Ok(WebhookEndpoint {
+            id: row.try_get("id")?,
+            user_id: row.try_get("user_id")?,
+            url: row.try_get("url")?,
+            description: row.try_get("description")?,
+            events: events
+                .iter()
+                .filter_map(|e| WebhookEventType::parse(e))
+                .collect(),
+            secret: self.decrypt_token(&encrypted_secret)?,
+            enabled: row.try_get("enabled")?,
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+        })
     }
 
-    async fn get_user_by_id(&self, user_id: &Uuid) -> Result<Option<User>> {
-        let row = sqlx::query_as::<
-            _,
-            (
-                uuid::Uuid,
-                String,
-                String,
-                String,
-                chrono::DateTime<chrono::Utc>,
-                chrono::DateTime<chrono::Utc>,
-                bool,
-            ),
-        >(
-            "SELECT id, email, password_hash, provider, created_at, updated_at, onboarding_completed FROM users WHERE id = $1",
-        )
-        .bind(user_id)
-        .fetch_optional(&self.pool)
-        .await?;
-
-        Ok(row.map(Self::map_user_row))
+    fn map_webhook_delivery_row(row: &sqlx::postgres::PgRow) -> Result<WebhookDelivery> {
+        Ok(WebhookDelivery {
+            id: row.try_get("id")?,
+            endpoint_id: row.try_get("endpoint_id")?,
+            user_id: row.try_get("user_id")?,
+            event_id: row.try_get("event_id")?,
+            event_type: row.try_get("event_type")?,
+            dedup_key: row.try_get("dedup_key")?,
+            payload: row.try_get("payload")?,
+            status: row.try_get("status")?,
+            attempts: row.try_get("attempts")?,
+            response_status: row.try_get("response_status")?,
+            error: row.try_get("error")?,
+            next_attempt_at: row.try_get("next_attempt_at")?,
+            created_at: row.try_get("created_at")?,
+            delivered_at: row.try_get("delivered_at")?,
+        })
     }
 
-    async fn mark_onboarding_complete(&self, user_id: &Uuid) -> Result<()> {
-        sqlx::query(
-            r#"
-            UPDATE users
-            SET onboarding_completed = true, updated_at = NOW()
-            WHERE id = $1
-            "#,
-        )
-        .bind(user_id)
-        .execute(&self.pool)
-        .await?;
-
-        Ok(())
+    fn saved_view_name_error(error: sqlx::Error) -> anyhow::Error {
+        match &error {
+            sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
+                anyhow::anyhow!("Saved view name already exists")
+            }
+            _ => anyhow::anyhow!(error),
+        }
     }
 
-    async fn update_user_provider(&self, user_id: &Uuid, provider: &str) -> Result<()> {
-        sqlx::query(
-            r#"
-            UPDATE users
-            SET provider = $2, updated_at = NOW()
-            WHERE id = $1
-            "#,
-        )
-        .bind(user_id)
-        .bind(provider)
-        .execute(&self.pool)
-        .await?;
-
-        Ok(())
+    fn map_transaction_with_account_row(
+        row: &sqlx::postgres::PgRow,
+    ) -> Result<TransactionWithAccount> {
+        Ok(TransactionWithAccount {
+            id: row.try_get("id")?,
+            account_id: row.try_get("account_id")?,
+            user_id: row.try_get("user_id")?,
+            provider_account_id: None,
+            provider_transaction_id: row.try_get("provider_transaction_id")?,
+            amount: row.try_get("amount")?,
+            iso_currency_code: row.try_get("iso_currency_code")?,
+            date: row.try_get("date")?,
+            merchant_name: row.try_get("merchant_name")?,
+            category_primary: row.try_get("category_primary")?,
+            category_detailed: row.try_get("category_detailed")?,
+            category_confidence: row.try_get("category_confidence")?,
+            payment_channel: row.try_get("payment_channel")?,
+            pending: row.try_get("pending")?,
+            created_at: row.try_get("created_at")?,
+            reviewed: row.try_get("reviewed")?,
+            excluded_from_analytics: row.try_get("excluded_from_analytics")?,
+            account_name: row.try_get("account_name")?,
+            account_type: row.try_get("account_type")?,
+            account_mask: row.try_get("account_mask")?,
+        })
+    }
+
+    async fn restore_archive_ledger(
+        conn: &mut sqlx::PgConnection,
+        user_id: &Uuid,
+        archive: &UserArchive,
+    ) -> Result<()> {
+        for connection in &archive.connections {
+            sqlx::query(
+                r#"
+                INSERT INTO provider_connections
+                    (id, user_id, item_id, is_connected, last_sync_at, connected_at, disconnected_at,
+                     institution_id, institution_name, created_at, updated_at)
+                VALUES ($1, $2, $3, false, $4, $5, NOW(), $6, $7, COALESCE($8, NOW()), NOW())
+                "#,
+            )
+            .bind(connection.id)
+            .bind(user_id)
+            .bind(&connection.item_id)
+            .bind(connection.last_sync_at)
+            .bind(connection.connected_at)
+            .bind(&connection.institution_id)
+            .bind(&connection.institution_name)
+            .bind(connection.created_at)
+            .execute(&mut *conn)
+            .await?;
+        }
+
+        for account in &archive.accounts {
+            sqlx::query(
+                r#"
+                INSERT INTO accounts
+                    (id, user_id, provider_account_id, provider_connection_id, name, account_type,
+                     balance_current, mask, iso_currency_code)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                "#,
+            )
+            .bind(account.id)
+            .bind(user_id)
+            .bind(&account.provider_account_id)
+            .bind(account.provider_connection_id)
+            .bind(&account.name)
+            .bind(&account.account_type)
+            .bind(account.balance_current)
+            .bind(&account.mask)
+            .bind(&account.iso_currency_code)
+            .execute(&mut *conn)
+            .await?;
+        }
+
+        for transaction in &archive.transactions {
+            sqlx::query(
+                r#"
+                INSERT INTO transactions
+                    (id, account_id, user_id, provider_transaction_id, amount, date, merchant_name,
+                     category_primary, category_detailed, category_confidence, payment_channel,
+                     pending, created_at, iso_currency_code, reviewed, excluded_from_analytics)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, COALESCE($13, NOW()), $14,
+                        $15, $16)
+                "#,
+            )
+            .bind(transaction.id)
+            .bind(transaction.account_id)
+            .bind(user_id)
+            .bind(&transaction.provider_transaction_id)
+            .bind(transaction.amount)
+            .bind(transaction.date)
+            .bind(&transaction.merchant_name)
+            .bind(&transaction.category_primary)
+            .bind(&transaction.category_detailed)
+            .bind(&transaction.category_confidence)
+            .bind(&transaction.payment_channel)
+            .bind(transaction.pending)
+            .bind(transaction.created_at)
+            .bind(&transaction.iso_currency_code)
+            .bind(transaction.reviewed)
+            .bind(transaction.excluded_from_analytics)
+            .execute(&mut *conn)
+            .await?;
+        }
+
+        for tag in &archive.transaction_tags {
+            sqlx::query(
+                "INSERT INTO transaction_tags (transaction_id, user_id, tag) VALUES ($1, $2, $3)",
+            )
+            .bind(tag.transaction_id)
+            .bind(user_id)
+            .bind(&tag.tag)
+            .execute(&mut *conn)
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn restore_archive_budgets(
+        conn: &mut sqlx::PgConnection,
+        user_id: &Uuid,
+        archive: &UserArchive,
+    ) -> Result<()> {
+        for budget in &archive.budgets {
+            sqlx::query(
+                r#"
+                INSERT INTO budgets (id, user_id, category, amount, created_at, updated_at)
+                VALUES ($1, $2, $3, $4, $5, $6)
+                "#,
+            )
+            .bind(budget.id)
+            .bind(user_id)
+            .bind(&budget.category)
+            .bind(budget.amount)
+            .bind(budget.created_at)
+            .bind(budget.updated_at)
+            .execute(&mut *conn)
+            .await?;
+        }
+
+        for allocation in &archive.budget_allocations {
+            sqlx::query(
+                r#"
+                INSERT INTO budget_allocations
+                    (id, user_id, month, category, assigned, created_at, updated_at)
+                VALUES ($1, $2, $3, $4, $5, $6, $7)
+                "#,
+            )
+            .bind(allocation.id)
+            .bind(user_id)
+            .bind(&allocation.month)
+            .bind(&allocation.category)
+            .bind(allocation.assigned)
+            .bind(allocation.created_at)
+            .bind(allocation.updated_at)
+            .execute(&mut *conn)
+            .await?;
+        }
+
+        for liability in &archive.liabilities {
+            sqlx::query(
+                r#"
+                INSERT INTO liabilities
+                    (account_id, user_id, liability_type, apr, minimum_payment, statement_balance,
+                     next_payment_due_date, loan_term_months, maturity_date, source)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+                "#,
+            )
+            .bind(liability.account_id)
+            .bind(user_id)
+            .bind(&liability.liability_type)
+            .bind(liability.apr)
+            .bind(liability.minimum_payment)
+            .bind(liability.statement_balance)
+            .bind(liability.next_payment_due_date)
+            .bind(liability.loan_term_months)
+            .bind(liability.maturity_date)
+            .bind(&liability.source)
+            .execute(&mut *conn)
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn restore_archive_preferences(
+        conn: &mut sqlx::PgConnection,
+        user_id: &Uuid,
+        archive: &UserArchive,
+    ) -> Result<()> {
+        let profile = &archive.profile;
+        sqlx::query(
+            r#"
+            UPDATE users
+            SET onboarding_completed = $2, zero_based_budgeting = $3, updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(user_id)
+        .bind(profile.onboarding_completed)
+        .bind(profile.zero_based_budgeting)
+        .execute(&mut *conn)
+        .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO user_settings (user_id, reporting_currency, timezone, month_start_day)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (user_id)
+            DO UPDATE SET reporting_currency = EXCLUDED.reporting_currency,
+                          timezone = EXCLUDED.timezone,
+                          month_start_day = EXCLUDED.month_start_day,
+                          updated_at = NOW()
+            "#,
+        )
+        .bind(user_id)
+        .bind(&profile.settings.reporting_currency)
+        .bind(&profile.settings.timezone)
+        .bind(profile.settings.month_start_day as i16)
+        .execute(&mut *conn)
+        .await?;
+
+        for rule in &archive.alert_rules {
+            sqlx::query(
+                r#"
+                INSERT INTO alert_rules
+                    (id, user_id, rule_type, threshold, percentages, account_id, category,
+                     cooldown_hours, enabled, created_at, updated_at)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+                "#,
+            )
+            .bind(rule.id)
+            .bind(user_id)
+            .bind(&rule.rule_type)
+            .bind(rule.threshold)
+            .bind(&rule.percentages)
+            .bind(rule.account_id)
+            .bind(&rule.category)
+            .bind(rule.cooldown_hours)
+            .bind(rule.enabled)
+            .bind(rule.created_at)
+            .bind(rule.updated_at)
+            .execute(&mut *conn)
+            .await?;
+        }
+
+        for preference in &archive.notification_preferences {
+            sqlx::query(
+                r#"
+                INSERT INTO notification_preferences (user_id, event_type, channel, enabled)
+                VALUES ($1, $2, $3, $4)
+                ON CONFLICT (user_id, event_type, channel) DO UPDATE SET enabled = EXCLUDED.enabled
+                "#,
+            )
+            .bind(user_id)
+            .bind(&preference.event_type)
+            .bind(&preference.channel)
+            .bind(preference.enabled)
+            .execute(&mut *conn)
+            .await?;
+        }
+
+        for insight_id in &archive.dismissed_insights {
+            sqlx::query(
+                "INSERT INTO dismissed_insights (user_id, insight_id) VALUES ($1, $2) ON CONFLICT DO NOTHING",
+            )
+            .bind(user_id)
+            .bind(insight_id)
+            .execute(&mut *conn)
+            .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl DatabaseRepository for PostgresRepository {
+    async fn create_user(&self, user: &User) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO users (id, email, password_hash, provider, created_at, updated_at, onboarding_completed)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#,
+        )
+        .bind(user.id)
+        .bind(&user.email)
+        .bind(&user.password_hash)
+        .bind(&user.provider)
+        .bind(user.created_at)
+        .bind(user.updated_at)
+        .bind(user.onboarding_completed)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get_user_by_email(&self, email: &str) -> Result<Option<User>> {
+        let row = sqlx::query_as::<
+            _,
+            (
+                uuid::Uuid,
+                String,
+                String,
+                String,
+                chrono::DateTime<chrono::Utc>,
+                chrono::DateTime<chrono::Utc>,
+                bool,
+            ),
+        >(
+            "SELECT id, email, password_hash, provider, created_at, updated_at, onboarding_completed FROM users WHERE email = $1",
+        )
+        .bind(email)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(Self::map_user_row))
+    }
+
+    async fn get_user_by_id(&self, user_id: &Uuid) -> Result<Option<User>> {
+        let row = sqlx::query_as::<
+            _,
+            (
+                uuid::Uuid,
+                String,
+                String,
+                String,
+                chrono::DateTime<chrono::Utc>,
+                chrono::DateTime<chrono::Utc>,
+                bool,
+            ),
+        >(
+            "SELECT id, email, password_hash, provider, created_at, updated_at, onboarding_completed FROM users WHERE id = $1",
+        )
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(Self::map_user_row))
+    }
+
+    async fn mark_onboarding_complete(&self, user_id: &Uuid) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE users
+            SET onboarding_completed = true, updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(user_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn update_user_provider(&self, user_id: &Uuid, provider: &str) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE users
+            SET provider = $2, updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(user_id)
+        .bind(provider)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
     }
 
     async fn upsert_account(&self, account: &Account) -> Result<()> {
@@ -289,15 +1024,16 @@ impl DatabaseRepository for PostgresRepository {
         }
         sqlx::query(
             r#"
-            INSERT INTO accounts (id, user_id, provider_account_id, provider_connection_id, name, account_type, balance_current, mask)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            INSERT INTO accounts (id, user_id, provider_account_id, provider_connection_id, name, account_type, balance_current, mask, iso_currency_code)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
             ON CONFLICT (provider_account_id) 
             DO UPDATE SET 
                 provider_connection_id = EXCLUDED.provider_connection_id,
                 name = EXCLUDED.name,
                 account_type = EXCLUDED.account_type,
                 balance_current = EXCLUDED.balance_current,
-                mask = EXCLUDED.mask
+                mask = EXCLUDED.mask,
+                iso_currency_code = EXCLUDED.iso_currency_code
             "#
         )
         .bind(account.id)
@@ -308,6 +1044,7 @@ impl DatabaseRepository for PostgresRepository {
         .bind(&account.account_type)
         .bind(account.balance_current)
         .bind(&account.mask)
+        .bind(&account.iso_currency_code)
         .execute(&mut *tx)
             .await?;
         tx.commit().await?;
@@ -330,12 +1067,14 @@ impl DatabaseRepository for PostgresRepository {
             INSERT INTO transactions (
                 id, account_id, user_id, provider_transaction_id, amount, date,
                 merchant_name, category_primary, category_detailed,
-                category_confidence, payment_channel, pending, created_at
+                category_confidence, payment_channel, pending, created_at,
+                iso_currency_code
             )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
             ON CONFLICT (provider_transaction_id)
             DO UPDATE SET
                 amount = EXCLUDED.amount,
+                iso_currency_code = EXCLUDED.iso_currency_code,
                 merchant_name = EXCLUDED.merchant_name,
                 pending = EXCLUDED.pending
             "#,
@@ -353,6 +1092,7 @@ impl DatabaseRepository for PostgresRepository {
         .bind(&transaction.payment_channel)
         .bind(transaction.pending)
         .bind(transaction.created_at.unwrap_or_else(chrono::Utc::now))
+        .bind(&transaction.iso_currency_code)
         .execute(&mut *tx)
         .await?;
         tx.commit().await?;
@@ -742,12 +1482,16 @@ impl DatabaseRepository for PostgresRepository {
                 Option<String>,
                 bool,
                 Option<chrono::DateTime<chrono::Utc>>,
+                String,
+                bool,
+                bool,
             ),
         >(
             r#"
             SELECT id, account_id, user_id, provider_transaction_id, amount, date,
                    merchant_name, category_primary, category_detailed,
-                   category_confidence, payment_channel, pending, created_at
+                   category_confidence, payment_channel, pending, created_at,
+                   iso_currency_code, reviewed, excluded_from_analytics
             FROM transactions 
             WHERE user_id = $1
             ORDER BY date DESC, created_at DESC
@@ -777,6 +1521,9 @@ impl DatabaseRepository for PostgresRepository {
                     payment_channel,
                     pending,
                     created_at,
+                    iso_currency_code,
+                    reviewed,
+                    excluded_from_analytics,
                 )| Transaction {
                     id,
                     account_id,
@@ -784,6 +1531,7 @@ impl DatabaseRepository for PostgresRepository {
                     provider_account_id: None,
                     provider_transaction_id,
                     amount,
+                    iso_currency_code,
                     date,
                     merchant_name,
                     category_primary,
@@ -792,6 +1540,8 @@ impl DatabaseRepository for PostgresRepository {
                     payment_channel,
                     pending,
                     created_at,
+                    reviewed,
+                    excluded_from_analytics,
                 },
             )
             .collect())
@@ -807,31 +1557,13 @@ impl DatabaseRepository for PostgresRepository {
             .execute(&mut *tx)
             .await?;
 
-        let rows = sqlx::query_as::<
-            _,
-            (
-                Uuid,
-                Uuid,
-                Option<Uuid>,
-                Option<String>,
-                rust_decimal::Decimal,
-                chrono::NaiveDate,
-                Option<String>,
-                String,
-                String,
-                String,
-                Option<String>,
-                bool,
-                Option<chrono::DateTime<chrono::Utc>>,
-                String,
-                String,
-                Option<String>,
-            ),
-        >(
+        // Wider than sqlx's 16-column tuple decoding, so columns are read by name.
+        let rows = sqlx::query(
             r#"
-            SELECT t.id, t.account_id, t.user_id, t.provider_transaction_id, t.amount, t.date,
-                   t.merchant_name, t.category_primary, t.category_detailed,
-                   t.category_confidence, t.payment_channel, t.pending, t.created_at,
+            SELECT t.id, t.account_id, t.user_id, t.provider_transaction_id, t.amount,
+                   t.iso_currency_code, t.date, t.merchant_name, t.category_primary,
+                   t.category_detailed, t.category_confidence, t.payment_channel, t.pending,
+                   t.created_at, t.reviewed, t.excluded_from_analytics,
                    a.name as account_name, a.account_type, a.mask as account_mask
             FROM transactions t
             INNER JOIN accounts a ON t.account_id = a.id
@@ -845,84 +1577,149 @@ impl DatabaseRepository for PostgresRepository {
         .await?;
         tx.commit().await?;
 
-        Ok(rows
-            .into_iter()
-            .map(
-                |(
-                    id,
-                    account_id,
-                    user_id,
-                    provider_transaction_id,
-                    amount,
-                    date,
-                    merchant_name,
-                    category_primary,
-                    category_detailed,
-                    category_confidence,
-                    payment_channel,
-                    pending,
-                    created_at,
-                    account_name,
-                    account_type,
-                    account_mask,
-                )| TransactionWithAccount {
-                    id,
-                    account_id,
-                    user_id,
-                    provider_account_id: None,
-                    provider_transaction_id,
-                    amount,
-                    date,
-                    merchant_name,
-                    category_primary,
-                    category_detailed,
-                    category_confidence,
-                    payment_channel,
-                    pending,
-                    created_at,
-                    account_name,
-                    account_type,
-                    account_mask,
-                },
-            )
-            .collect())
+        rows.iter()
+            .map(Self::map_transaction_with_account_row)
+            .collect()
     }
 
-    async fn get_transactions_by_date_range_for_user(
+    async fn get_transactions_with_account_page(
         &self,
         user_id: &Uuid,
-        start_date: chrono::NaiveDate,
-        end_date: chrono::NaiveDate,
-    ) -> Result<Vec<Transaction>> {
+        after: Option<TransactionPageCursor>,
+        limit: i64,
+    ) -> Result<Vec<TransactionWithAccount>> {
         let mut tx = self.pool.begin().await?;
-
         sqlx::query("SELECT set_config('app.current_user_id', $1, true)")
             .bind(user_id.to_string())
             .execute(&mut *tx)
             .await?;
 
-        let rows = sqlx::query_as::<
-            _,
-            (
-                Uuid,
-                Uuid,
-                Option<Uuid>,
-                Option<String>,
-                rust_decimal::Decimal,
-                chrono::NaiveDate,
-                Option<String>,
-                String,
+        let mut builder: QueryBuilder<Postgres> = QueryBuilder::new(
+            r#"
+            SELECT t.id, t.account_id, t.user_id, t.provider_transaction_id, t.amount,
+                   t.iso_currency_code, t.date, t.merchant_name, t.category_primary,
+                   t.category_detailed, t.category_confidence, t.payment_channel, t.pending,
+                   t.created_at, t.reviewed, t.excluded_from_analytics,
+                   a.name as account_name, a.account_type, a.mask as account_mask
+            FROM transactions t
+            INNER JOIN accounts a ON t.account_id = a.id
+            WHERE t.user_id = "#,
+        );
+        builder.push_bind(user_id);
+        if let Some(cursor) = after {
+            builder
+                .push(" AND (a.account_type, t.account_id, t.date, t.id) > (")
+                .push_bind(cursor.account_type)
+                .push(", ")
+                .push_bind(cursor.account_id)
+                .push(", ")
+                .push_bind(cursor.date)
+                .push(", ")
+                .push_bind(cursor.id)
+                .push(")");
+        }
+        builder
+            .push(" ORDER BY a.account_type, t.account_id, t.date, t.id LIMIT ")
+            .push_bind(limit);
+
+        let rows = builder.build().fetch_all(&mut *tx).await?;
+        tx.commit().await?;
+
+        rows.iter()
+            .map(Self::map_transaction_with_account_row)
+            .collect()
+    }
+
+    async fn search_transactions(
+        &self,
+        user_id: &Uuid,
+        search: &TransactionSearch,
+        account_ids: &[Uuid],
+    ) -> Result<Vec<TransactionWithAccount>> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("SELECT set_config('app.current_user_id', $1, true)")
+            .bind(user_id.to_string())
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("SELECT set_config('pg_trgm.word_similarity_threshold', $1, true)")
+            .bind(FUZZY_MATCH_THRESHOLD)
+            .execute(&mut *tx)
+            .await?;
+
+        let mut builder = TransactionSearchService::build_sql(*user_id, search, account_ids);
+        let rows = builder.build().fetch_all(&mut *tx).await?;
+        tx.commit().await?;
+
+        rows.iter()
+            .map(Self::map_transaction_with_account_row)
+            .collect()
+    }
+
+    async fn search_transaction_ids(
+        &self,
+        user_id: &Uuid,
+        search: &TransactionSearch,
+        account_ids: &[Uuid],
+    ) -> Result<Vec<Uuid>> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("SELECT set_config('app.current_user_id', $1, true)")
+            .bind(user_id.to_string())
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("SELECT set_config('pg_trgm.word_similarity_threshold', $1, true)")
+            .bind(FUZZY_MATCH_THRESHOLD)
+            .execute(&mut *tx)
+            .await?;
+
+        let mut builder = TransactionSearchService::build_ids_sql(*user_id, search, account_ids);
+        let ids = builder
+            .build_query_scalar::<Uuid>()
+            .fetch_all(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        Ok(ids)
+    }
+
+    async fn get_transactions_by_date_range_for_user(
+        &self,
+        user_id: &Uuid,
+        start_date: chrono::NaiveDate,
+        end_date: chrono::NaiveDate,
+    ) -> Result<Vec<Transaction>> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("SELECT set_config('app.current_user_id', $1, true)")
+            .bind(user_id.to_string())
+            .execute(&mut *tx)
+            .await?;
+
+        let rows = sqlx::query_as::<
+            _,
+            (
+                Uuid,
+                Uuid,
+                Option<Uuid>,
+                Option<String>,
+                rust_decimal::Decimal,
+                chrono::NaiveDate,
+                Option<String>,
+                String,
                 String,
                 String,
                 Option<String>,
                 bool,
                 Option<chrono::DateTime<chrono::Utc>>,
+                String,
+                bool,
+                bool,
             ),
         >(
             r#"
             SELECT id, account_id, user_id, provider_transaction_id, amount, date,
                    merchant_name, category_primary, category_detailed,
-                   category_confidence, payment_channel, pending, created_at
+                   category_confidence, payment_channel, pending, created_at,
+                   iso_currency_code, reviewed, excluded_from_analytics
             FROM transactions 
             WHERE user_id = $1 AND date >= $2 AND date <= $3
             ORDER BY date DESC, created_at DESC
@@ -954,6 +1751,9 @@ impl DatabaseRepository for PostgresRepository {
                     payment_channel,
                     pending,
                     created_at,
+                    iso_currency_code,
+                    reviewed,
+                    excluded_from_analytics,
                 )| Transaction {
                     id,
                     account_id,
@@ -961,6 +1761,7 @@ impl DatabaseRepository for PostgresRepository {
                     provider_account_id: None,
                     provider_transaction_id,
                     amount,
+                    iso_currency_code,
                     date,
                     merchant_name,
                     category_primary,
@@ -969,6 +1770,8 @@ impl DatabaseRepository for PostgresRepository {
                     payment_channel,
                     pending,
                     created_at,
+                    reviewed,
+                    excluded_from_analytics,
                 },
             )
             .collect())
@@ -994,10 +1797,11 @@ impl DatabaseRepository for PostgresRepository {
                 Option<rust_decimal::Decimal>,
                 Option<String>,
                 Option<String>,
+                String,
             ),
         >(
             r#"
-            SELECT a.id, a.user_id, a.provider_account_id, a.provider_connection_id, a.name, a.account_type, a.balance_current, a.mask, pc.institution_name
+            SELECT a.id, a.user_id, a.provider_account_id, a.provider_connection_id, a.name, a.account_type, a.balance_current, a.mask, pc.institution_name, a.iso_currency_code
             FROM accounts a
             LEFT JOIN provider_connections pc ON pc.id = a.provider_connection_id
             WHERE a.user_id = $1
@@ -1023,6 +1827,7 @@ impl DatabaseRepository for PostgresRepository {
                     balance_current,
                     mask,
                     institution_name,
+                    iso_currency_code,
                 )| Account {
                     id,
                     user_id,
@@ -1031,6 +1836,7 @@ impl DatabaseRepository for PostgresRepository {
                     name,
                     account_type,
                     balance_current,
+                    iso_currency_code,
                     mask,
                     institution_name,
                 },
@@ -1075,7 +1881,7 @@ impl DatabaseRepository for PostgresRepository {
             .await?;
 
         let budgets = sqlx::query_as::<_, Budget>(
-            "SELECT id, user_id, category, amount, created_at, updated_at 
+            "SELECT id, user_id, category, amount, saved_view_id, created_at, updated_at 
              FROM budgets 
              WHERE user_id = $1 
              ORDER BY category ASC",
@@ -1097,13 +1903,14 @@ impl DatabaseRepository for PostgresRepository {
             .await?;
 
         let res = sqlx::query(
-            "INSERT INTO budgets (id, user_id, category, amount, created_at, updated_at) 
-             VALUES ($1, $2, $3, $4, $5, $6)",
+            "INSERT INTO budgets (id, user_id, category, amount, saved_view_id, created_at, updated_at) 
+             VALUES ($1, $2, $3, $4, $5, $6, $7)",
         )
         .bind(budget.id)
         .bind(budget.user_id)
         .bind(&budget.category)
         .bind(budget.amount)
+        .bind(budget.saved_view_id)
         .bind(budget.created_at)
         .bind(budget.updated_at)
         .execute(&mut *tx)
@@ -1129,6 +1936,7 @@ impl DatabaseRepository for PostgresRepository {
         budget_id: Uuid,
         user_id: Uuid,
         amount: rust_decimal::Decimal,
+        saved_view_id: Option<Option<Uuid>>,
     ) -> Result<Budget> {
         let mut tx = self.pool.begin().await?;
 
@@ -1140,18 +1948,21 @@ impl DatabaseRepository for PostgresRepository {
         let updated_at = chrono::Utc::now();
 
         sqlx::query(
-            "UPDATE budgets SET amount = $1, updated_at = $2 
+            "UPDATE budgets SET amount = $1, updated_at = $2,
+                 saved_view_id = CASE WHEN $5 THEN $6 ELSE saved_view_id END
              WHERE id = $3 AND user_id = $4",
         )
         .bind(amount)
         .bind(updated_at)
         .bind(budget_id)
         .bind(user_id)
+        .bind(saved_view_id.is_some())
+        .bind(saved_view_id.flatten())
         .execute(&mut *tx)
         .await?;
 
         let updated_budget = sqlx::query_as::<_, Budget>(
-            "SELECT id, user_id, category, amount, created_at, updated_at 
+            "SELECT id, user_id, category, amount, saved_view_id, created_at, updated_at 
              FROM budgets 
              WHERE id = $1 AND user_id = $2",
         )
@@ -1182,6 +1993,149 @@ impl DatabaseRepository for PostgresRepository {
         Ok(())
     }
 
+    async fn get_zero_based_budgeting_enabled(&self, user_id: Uuid) -> Result<bool> {
+        let enabled =
+            sqlx::query_scalar::<_, bool>("SELECT zero_based_budgeting FROM users WHERE id = $1")
+                .bind(user_id)
+                .fetch_optional(&self.pool)
+                .await?;
+
+        Ok(enabled.unwrap_or(false))
+    }
+
+    async fn set_zero_based_budgeting_enabled(&self, user_id: Uuid, enabled: bool) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE users
+            SET zero_based_budgeting = $2, updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(user_id)
+        .bind(enabled)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get_budget_allocations_for_user(
+        &self,
+        user_id: Uuid,
+        month: &str,
+    ) -> Result<Vec<BudgetAllocation>> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("SELECT set_config('app.current_user_id', $1, true)")
+            .bind(user_id.to_string())
+            .execute(&mut *tx)
+            .await?;
+
+        let allocations = sqlx::query_as::<_, BudgetAllocation>(
+            "SELECT id, user_id, month, category, assigned, created_at, updated_at
+             FROM budget_allocations
+             WHERE user_id = $1 AND month = $2
+             ORDER BY category ASC",
+        )
+        .bind(user_id)
+        .bind(month)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(allocations)
+    }
+
+    async fn upsert_budget_allocation(
+        &self,
+        user_id: Uuid,
+        request: &AssignBudgetFundsRequest,
+    ) -> Result<BudgetAllocation> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("SELECT set_config('app.current_user_id', $1, true)")
+            .bind(user_id.to_string())
+            .execute(&mut *tx)
+            .await?;
+
+        let allocation = sqlx::query_as::<_, BudgetAllocation>(
+            r#"
+            INSERT INTO budget_allocations (id, user_id, month, category, assigned)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (user_id, month, category) DO UPDATE SET
+                assigned = EXCLUDED.assigned,
+                updated_at = NOW()
+            RETURNING id, user_id, month, category, assigned, created_at, updated_at
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(user_id)
+        .bind(&request.month)
+        .bind(&request.category)
+        .bind(request.amount)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(allocation)
+    }
+
+    async fn move_budget_allocation(
+        &self,
+        user_id: Uuid,
+        request: &MoveBudgetFundsRequest,
+    ) -> Result<Vec<BudgetAllocation>> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("SELECT set_config('app.current_user_id', $1, true)")
+            .bind(user_id.to_string())
+            .execute(&mut *tx)
+            .await?;
+
+        let source = sqlx::query_as::<_, BudgetAllocation>(
+            r#"
+            UPDATE budget_allocations
+            SET assigned = assigned - $4, updated_at = NOW()
+            WHERE user_id = $1 AND month = $2 AND category = $3 AND assigned >= $4
+            RETURNING id, user_id, month, category, assigned, created_at, updated_at
+            "#,
+        )
+        .bind(user_id)
+        .bind(&request.month)
+        .bind(&request.from_category)
+        .bind(request.amount)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(source) = source else {
+            let _ = tx.rollback().await;
+            return Err(anyhow::anyhow!(
+                "Insufficient assigned funds in source category"
+            ));
+        };
+
+        let destination = sqlx::query_as::<_, BudgetAllocation>(
+            r#"
+            INSERT INTO budget_allocations (id, user_id, month, category, assigned)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (user_id, month, category) DO UPDATE SET
+                assigned = budget_allocations.assigned + EXCLUDED.assigned,
+                updated_at = NOW()
+            RETURNING id, user_id, month, category, assigned, created_at, updated_at
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(user_id)
+        .bind(&request.month)
+        .bind(&request.to_category)
+        .bind(request.amount)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(vec![source, destination])
+    }
+
     async fn get_latest_account_balances_for_user(
         &self,
         user_id: &Uuid,
@@ -1198,7 +2152,7 @@ impl DatabaseRepository for PostgresRepository {
                 COALESCE(pc.institution_name, 'unknown_institution') AS institution_id,
                 a.account_type,
                 NULL::text AS account_subtype,
-                'USD'::text AS currency,
+                a.iso_currency_code AS currency,
                 COALESCE(a.balance_current, 0) AS current_balance,
                 a.provider_connection_id,
                 pc.institution_name
@@ -1249,4 +2203,1770 @@ impl DatabaseRepository for PostgresRepository {
         tx.commit().await?;
         Ok(())
     }
+
+    async fn upsert_calendar_feed_token(&self, user_id: &Uuid, token_hash: &str) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO calendar_feed_tokens (user_id, token_hash)
+            VALUES ($1, $2)
+            ON CONFLICT (user_id)
+            DO UPDATE SET token_hash = EXCLUDED.token_hash, created_at = NOW()
+            "#,
+        )
+        .bind(user_id)
+        .bind(token_hash)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get_user_id_by_calendar_feed_token(&self, token_hash: &str) -> Result<Option<Uuid>> {
+        let user_id = sqlx::query_scalar::<_, Uuid>(
+            "SELECT user_id FROM calendar_feed_tokens WHERE token_hash = $1",
+        )
+        .bind(token_hash)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(user_id)
+    }
+
+    async fn delete_calendar_feed_token(&self, user_id: &Uuid) -> Result<()> {
+        sqlx::query("DELETE FROM calendar_feed_tokens WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn create_personal_access_token(
+        &self,
+        token: &PersonalAccessToken,
+        token_hash: &str,
+    ) -> Result<()> {
+        let scopes: Vec<&str> = token.scopes.iter().map(TokenScope::as_str).collect();
+        sqlx::query(
+            r#"
+            INSERT INTO personal_access_tokens
+                (id, user_id, name, token_prefix, token_hash, scopes, expires_at, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            "#,
+        )
+        .bind(token.id)
+        .bind(token.user_id)
+        .bind(&token.name)
+        .bind(&token.token_prefix)
+        .bind(token_hash)
+        .bind(&scopes)
+        .bind(token.expires_at)
+        .bind(token.created_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get_personal_access_tokens_for_user(
+        &self,
+        user_id: &Uuid,
+    ) -> Result<Vec<PersonalAccessToken>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, user_id, name, token_prefix, scopes, expires_at, last_used_at,
+                   revoked_at, created_at
+            FROM personal_access_tokens
+            WHERE user_id = $1
+            ORDER BY created_at DESC
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter()
+            .map(Self::map_personal_access_token_row)
+            .collect()
+    }
+
+    async fn get_personal_access_token_by_hash(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<PersonalAccessToken>> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, user_id, name, token_prefix, scopes, expires_at, last_used_at,
+                   revoked_at, created_at
+            FROM personal_access_tokens
+            WHERE token_hash = $1
+            "#,
+        )
+        .bind(token_hash)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        row.as_ref()
+            .map(Self::map_personal_access_token_row)
+            .transpose()
+    }
+
+    async fn revoke_personal_access_token(&self, user_id: &Uuid, token_id: &Uuid) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE personal_access_tokens
+            SET revoked_at = NOW()
+            WHERE id = $1 AND user_id = $2 AND revoked_at IS NULL
+            "#,
+        )
+        .bind(token_id)
+        .bind(user_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn touch_personal_access_token(&self, token_id: &Uuid) -> Result<()> {
+        sqlx::query("UPDATE personal_access_tokens SET last_used_at = NOW() WHERE id = $1")
+            .bind(token_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn create_webhook_endpoint(&self, endpoint: &WebhookEndpoint) -> Result<()> {
+        let events: Vec<&str> = endpoint
+            .events
+            .iter()
+            .map(WebhookEventType::as_str)
+            .collect();
+        let encrypted_secret = self.encrypt_token(&endpoint.secret)?;
+        sqlx::query(
+            r#"
+            INSERT INTO webhook_endpoints
+                (id, user_id, url, description, events, encrypted_secret, enabled,
+                 created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            "#,
+        )
+        .bind(endpoint.id)
+        .bind(endpoint.user_id)
+        .bind(&endpoint.url)
+        .bind(&endpoint.description)
+        .bind(&events)
+        .bind(&encrypted_secret)
+        .bind(endpoint.enabled)
+        .bind(endpoint.created_at)
+        .bind(endpoint.updated_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get_webhook_endpoints_for_user(&self, user_id: &Uuid) -> Result<Vec<WebhookEndpoint>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, user_id, url, description, events, encrypted_secret, enabled,
+                   created_at, updated_at
+            FROM webhook_endpoints
+            WHERE user_id = $1
+            ORDER BY created_at
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter()
+            .map(|row| self.map_webhook_endpoint_row(row))
+            .collect()
+    }
+
+    async fn get_webhook_endpoint_by_id(
+        &self,
+        endpoint_id: &Uuid,
+    ) -> Result<Option<WebhookEndpoint>> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, user_id, url, description, events, encrypted_secret, enabled,
+                   created_at, updated_at
+            FROM webhook_endpoints
+            WHERE id = $1
+            "#,
+        )
+        .bind(endpoint_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        row.as_ref()
+            .map(|row| self.map_webhook_endpoint_row(row))
+            .transpose()
+    }
+
+    async fn update_webhook_endpoint(&self, endpoint: &WebhookEndpoint) -> Result<bool> {
+        let events: Vec<&str> = endpoint
+            .events
+            .iter()
+            .map(WebhookEventType::as_str)
+            .collect();
+        let result = sqlx::query(
+            r#"
+            UPDATE webhook_endpoints
+            SET url = $3, description = $4, events = $5, enabled = $6, updated_at = $7
+            WHERE id = $1 AND user_id = $2
+            "#,
+        )
+        .bind(endpoint.id)
+        .bind(endpoint.user_id)
+        .bind(&endpoint.url)
+        .bind(&endpoint.description)
+        .bind(&events)
+        .bind(endpoint.enabled)
+        .bind(endpoint.updated_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn delete_webhook_endpoint(&self, user_id: &Uuid, endpoint_id: &Uuid) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM webhook_endpoints WHERE id = $1 AND user_id = $2")
+            .bind(endpoint_id)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn insert_webhook_delivery(&self, delivery: &WebhookDelivery) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO webhook_deliveries
+                (id, endpoint_id, user_id, event_id, event_type, dedup_key, payload, status,
+                 attempts, response_status, error, next_attempt_at, created_at, delivered_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
+            ON CONFLICT (endpoint_id, dedup_key) DO NOTHING
+            "#,
+        )
+        .bind(delivery.id)
+        .bind(delivery.endpoint_id)
+        .bind(delivery.user_id)
+        .bind(delivery.event_id)
+        .bind(&delivery.event_type)
+        .bind(&delivery.dedup_key)
+        .bind(&delivery.payload)
+        .bind(&delivery.status)
+        .bind(delivery.attempts)
+        .bind(delivery.response_status)
+        .bind(&delivery.error)
+        .bind(delivery.next_attempt_at)
+        .bind(delivery.created_at)
+        .bind(delivery.delivered_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn update_webhook_delivery(&self, delivery: &WebhookDelivery) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE webhook_deliveries
+            SET status = $2, attempts = $3, response_status = $4, error = $5,
+                next_attempt_at = $6, delivered_at = $7
+            WHERE id = $1
+            "#,
+        )
+        .bind(delivery.id)
+        .bind(&delivery.status)
+        .bind(delivery.attempts)
+        .bind(delivery.response_status)
+        .bind(&delivery.error)
+        .bind(delivery.next_attempt_at)
+        .bind(delivery.delivered_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn claim_due_webhook_deliveries(
+        &self,
+        now: chrono::DateTime<chrono::Utc>,
+        lease_until: chrono::DateTime<chrono::Utc>,
+        limit: i64,
+    ) -> Result<Vec<WebhookDelivery>> {
+        let rows = sqlx::query(
+            r#"
+            UPDATE webhook_deliveries
+            SET next_attempt_at = $2
+            WHERE id IN (
+                SELECT id FROM webhook_deliveries
+                WHERE status = 'pending' AND next_attempt_at <= $1
+                ORDER BY next_attempt_at
+                LIMIT $3
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING id, endpoint_id, user_id, event_id, event_type, dedup_key, payload,
+                      status, attempts, response_status, error, next_attempt_at, created_at,
+                      delivered_at
+            "#,
+        )
+        .bind(now)
+        .bind(lease_until)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(Self::map_webhook_delivery_row).collect()
+    }
+
+    async fn get_webhook_deliveries_for_endpoint(
+        &self,
+        user_id: &Uuid,
+        endpoint_id: &Uuid,
+        limit: i64,
+    ) -> Result<Vec<WebhookDelivery>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, endpoint_id, user_id, event_id, event_type, dedup_key, payload, status,
+                   attempts, response_status, error, next_attempt_at, created_at, delivered_at
+            FROM webhook_deliveries
+            WHERE endpoint_id = $1 AND user_id = $2
+            ORDER BY created_at DESC
+            LIMIT $3
+            "#,
+        )
+        .bind(endpoint_id)
+        .bind(user_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(Self::map_webhook_delivery_row).collect()
+    }
+
+    async fn insert_notification(&self, event: &NotificationEvent) -> Result<Notification> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("SELECT set_config('app.current_user_id', $1, true)")
+            .bind(event.user_id.to_string())
+            .execute(&mut *tx)
+            .await?;
+
+        let notification = sqlx::query_as::<_, Notification>(
+            r#"
+            INSERT INTO notifications (user_id, event_type, title, body)
+            VALUES ($1, $2, $3, $4)
+            RETURNING id, event_type, title, body, read_at, created_at
+            "#,
+        )
+        .bind(event.user_id)
+        .bind(event.event_type.as_str())
+        .bind(&event.title)
+        .bind(&event.body)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(notification)
+    }
+
+    async fn get_notifications_for_user(
+        &self,
+        user_id: &Uuid,
+        unread_only: bool,
+        limit: i64,
+    ) -> Result<Vec<Notification>> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("SELECT set_config('app.current_user_id', $1, true)")
+            .bind(user_id.to_string())
+            .execute(&mut *tx)
+            .await?;
+
+        let notifications = sqlx::query_as::<_, Notification>(
+            r#"
+            SELECT id, event_type, title, body, read_at, created_at
+            FROM notifications
+            WHERE user_id = $1 AND ($2 = false OR read_at IS NULL)
+            ORDER BY created_at DESC
+            LIMIT $3
+            "#,
+        )
+        .bind(user_id)
+        .bind(unread_only)
+        .bind(limit)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(notifications)
+    }
+
+    async fn count_unread_notifications(&self, user_id: &Uuid) -> Result<i64> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("SELECT set_config('app.current_user_id', $1, true)")
+            .bind(user_id.to_string())
+            .execute(&mut *tx)
+            .await?;
+
+        let count = sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM notifications WHERE user_id = $1 AND read_at IS NULL",
+        )
+        .bind(user_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(count)
+    }
+
+    async fn set_notification_read(
+        &self,
+        user_id: &Uuid,
+        notification_id: &Uuid,
+        read: bool,
+    ) -> Result<bool> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("SELECT set_config('app.current_user_id', $1, true)")
+            .bind(user_id.to_string())
+            .execute(&mut *tx)
+            .await?;
+
+        let result = sqlx::query(
+            r#"
+            UPDATE notifications
+            SET read_at = CASE WHEN $3 THEN COALESCE(read_at, NOW()) ELSE NULL END
+            WHERE id = $1 AND user_id = $2
+            "#,
+        )
+        .bind(notification_id)
+        .bind(user_id)
+        .bind(read)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn mark_all_notifications_read(&self, user_id: &Uuid) -> Result<u64> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("SELECT set_config('app.current_user_id', $1, true)")
+            .bind(user_id.to_string())
+            .execute(&mut *tx)
+            .await?;
+
+        let result = sqlx::query(
+            "UPDATE notifications SET read_at = NOW() WHERE user_id = $1 AND read_at IS NULL",
+        )
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(result.rows_affected())
+    }
+
+    async fn get_notification_preferences(
+        &self,
+        user_id: &Uuid,
+    ) -> Result<Vec<NotificationPreference>> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("SELECT set_config('app.current_user_id', $1, true)")
+            .bind(user_id.to_string())
+            .execute(&mut *tx)
+            .await?;
+
+        let preferences = sqlx::query_as::<_, NotificationPreference>(
+            r#"
+            SELECT event_type, channel, enabled
+            FROM notification_preferences
+            WHERE user_id = $1
+            ORDER BY event_type, channel
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(preferences)
+    }
+
+    async fn upsert_notification_preferences(
+        &self,
+        user_id: &Uuid,
+        preferences: &[NotificationPreference],
+    ) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("SELECT set_config('app.current_user_id', $1, true)")
+            .bind(user_id.to_string())
+            .execute(&mut *tx)
+            .await?;
+
+        for preference in preferences {
+            sqlx::query(
+                r#"
+                INSERT INTO notification_preferences (user_id, event_type, channel, enabled)
+                VALUES ($1, $2, $3, $4)
+                ON CONFLICT (user_id, event_type, channel)
+                DO UPDATE SET enabled = EXCLUDED.enabled, updated_at = NOW()
+                "#,
+            )
+            .bind(user_id)
+            .bind(&preference.event_type)
+            .bind(&preference.channel)
+            .bind(preference.enabled)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn get_alert_rules_for_user(&self, user_id: &Uuid) -> Result<Vec<AlertRule>> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("SELECT set_config('app.current_user_id', $1, true)")
+            .bind(user_id.to_string())
+            .execute(&mut *tx)
+            .await?;
+
+        let rules = sqlx::query_as::<_, AlertRule>(
+            r#"
+            SELECT id, user_id, rule_type, threshold, percentages, account_id, category,
+                   saved_view_id, cooldown_hours, enabled, created_at, updated_at
+            FROM alert_rules
+            WHERE user_id = $1
+            ORDER BY created_at ASC
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(rules)
+    }
+
+    async fn create_alert_rule(&self, rule: &AlertRule) -> Result<AlertRule> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("SELECT set_config('app.current_user_id', $1, true)")
+            .bind(rule.user_id.to_string())
+            .execute(&mut *tx)
+            .await?;
+
+        let created = sqlx::query_as::<_, AlertRule>(
+            r#"
+            INSERT INTO alert_rules
+                (id, user_id, rule_type, threshold, percentages, account_id, category,
+                 saved_view_id, cooldown_hours, enabled, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+            RETURNING id, user_id, rule_type, threshold, percentages, account_id, category,
+                      saved_view_id, cooldown_hours, enabled, created_at, updated_at
+            "#,
+        )
+        .bind(rule.id)
+        .bind(rule.user_id)
+        .bind(&rule.rule_type)
+        .bind(rule.threshold)
+        .bind(&rule.percentages)
+        .bind(rule.account_id)
+        .bind(&rule.category)
+        .bind(rule.saved_view_id)
+        .bind(rule.cooldown_hours)
+        .bind(rule.enabled)
+        .bind(rule.created_at)
+        .bind(rule.updated_at)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(created)
+    }
+
+    async fn update_alert_rule(&self, rule: &AlertRule) -> Result<Option<AlertRule>> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("SELECT set_config('app.current_user_id', $1, true)")
+            .bind(rule.user_id.to_string())
+            .execute(&mut *tx)
+            .await?;
+
+        let updated = sqlx::query_as::<_, AlertRule>(
+            r#"
+            UPDATE alert_rules
+            SET rule_type = $3, threshold = $4, percentages = $5, account_id = $6,
+                category = $7, saved_view_id = $8, cooldown_hours = $9, enabled = $10,
+                updated_at = NOW()
+            WHERE id = $1 AND user_id = $2
+            RETURNING id, user_id, rule_type, threshold, percentages, account_id, category,
+                      saved_view_id, cooldown_hours, enabled, created_at, updated_at
+            "#,
+        )
+        .bind(rule.id)
+        .bind(rule.user_id)
+        .bind(&rule.rule_type)
+        .bind(rule.threshold)
+        .bind(&rule.percentages)
+        .bind(rule.account_id)
+        .bind(&rule.category)
+        .bind(rule.saved_view_id)
+        .bind(rule.cooldown_hours)
+        .bind(rule.enabled)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(updated)
+    }
+
+    async fn delete_alert_rule(&self, user_id: &Uuid, rule_id: &Uuid) -> Result<bool> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("SELECT set_config('app.current_user_id', $1, true)")
+            .bind(user_id.to_string())
+            .execute(&mut *tx)
+            .await?;
+
+        let result = sqlx::query("DELETE FROM alert_rules WHERE id = $1 AND user_id = $2")
+            .bind(rule_id)
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn get_saved_views_for_user(&self, user_id: &Uuid) -> Result<Vec<SavedView>> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("SELECT set_config('app.current_user_id', $1, true)")
+            .bind(user_id.to_string())
+            .execute(&mut *tx)
+            .await?;
+
+        let views = sqlx::query_as::<_, SavedView>(
+            r#"
+            SELECT id, user_id, name, search, account_ids, date_preset, start_date, end_date,
+                   created_at, updated_at
+            FROM saved_views
+            WHERE user_id = $1
+            ORDER BY name ASC
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(views)
+    }
+
+    async fn get_saved_view(&self, user_id: &Uuid, view_id: &Uuid) -> Result<Option<SavedView>> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("SELECT set_config('app.current_user_id', $1, true)")
+            .bind(user_id.to_string())
+            .execute(&mut *tx)
+            .await?;
+
+        let view = sqlx::query_as::<_, SavedView>(
+            r#"
+            SELECT id, user_id, name, search, account_ids, date_preset, start_date, end_date,
+                   created_at, updated_at
+            FROM saved_views
+            WHERE id = $1 AND user_id = $2
+            "#,
+        )
+        .bind(view_id)
+        .bind(user_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(view)
+    }
+
+    async fn create_saved_view(&self, view: &SavedView) -> Result<SavedView> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("SELECT set_config('app.current_user_id', $1, true)")
+            .bind(view.user_id.to_string())
+            .execute(&mut *tx)
+            .await?;
+
+        let created = sqlx::query_as::<_, SavedView>(
+            r#"
+            INSERT INTO saved_views
+                (id, user_id, name, search, account_ids, date_preset, start_date, end_date,
+                 created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            RETURNING id, user_id, name, search, account_ids, date_preset, start_date, end_date,
+                      created_at, updated_at
+            "#,
+        )
+        .bind(view.id)
+        .bind(view.user_id)
+        .bind(&view.name)
+        .bind(&view.search)
+        .bind(&view.account_ids)
+        .bind(&view.date_preset)
+        .bind(view.start_date)
+        .bind(view.end_date)
+        .bind(view.created_at)
+        .bind(view.updated_at)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(Self::saved_view_name_error)?;
+
+        tx.commit().await?;
+        Ok(created)
+    }
+
+    async fn update_saved_view(&self, view: &SavedView) -> Result<Option<SavedView>> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("SELECT set_config('app.current_user_id', $1, true)")
+            .bind(view.user_id.to_string())
+            .execute(&mut *tx)
+            .await?;
+
+        let updated = sqlx::query_as::<_, SavedView>(
+            r#"
+            UPDATE saved_views
+            SET name = $3, search = $4, account_ids = $5, date_preset = $6,
+                start_date = $7, end_date = $8, updated_at = NOW()
+            WHERE id = $1 AND user_id = $2
+            RETURNING id, user_id, name, search, account_ids, date_preset, start_date, end_date,
+                      created_at, updated_at
+            "#,
+        )
+        .bind(view.id)
+        .bind(view.user_id)
+        .bind(&view.name)
+        .bind(&view.search)
+        .bind(&view.account_ids)
+        .bind(&view.date_preset)
+        .bind(view.start_date)
+        .bind(view.end_date)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(Self::saved_view_name_error)?;
+
+        tx.commit().await?;
+        Ok(updated)
+    }
+
+    async fn delete_saved_view(&self, user_id: &Uuid, view_id: &Uuid) -> Result<bool> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("SELECT set_config('app.current_user_id', $1, true)")
+            .bind(user_id.to_string())
+            .execute(&mut *tx)
+            .await?;
+
+        let result = sqlx::query("DELETE FROM saved_views WHERE id = $1 AND user_id = $2")
+            .bind(view_id)
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn get_alert_history_for_user(&self, user_id: &Uuid) -> Result<Vec<AlertHistoryEntry>> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("SELECT set_config('app.current_user_id', $1, true)")
+            .bind(user_id.to_string())
+            .execute(&mut *tx)
+            .await?;
+
+        let history = sqlx::query_as::<_, AlertHistoryEntry>(
+            r#"
+            SELECT rule_id, dedup_key, MAX(fired_at) AS last_fired_at
+            FROM fired_alerts
+            WHERE user_id = $1
+            GROUP BY rule_id, dedup_key
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(history)
+    }
+
+    async fn insert_fired_alert(
+        &self,
+        user_id: &Uuid,
+        alert: &FiredAlert,
+        refire_before: Option<chrono::DateTime<chrono::Utc>>,
+    ) -> Result<bool> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("SELECT set_config('app.current_user_id', $1, true)")
+            .bind(user_id.to_string())
+            .execute(&mut *tx)
+            .await?;
+
+        let rule = sqlx::query("SELECT id FROM alert_rules WHERE id = $1 FOR UPDATE")
+            .bind(alert.rule_id)
+            .fetch_optional(&mut *tx)
+            .await?;
+        if rule.is_none() {
+            return Ok(false);
+        }
+
+        let last_fired_at: Option<chrono::DateTime<chrono::Utc>> = sqlx::query_scalar(
+            "SELECT MAX(fired_at) FROM fired_alerts WHERE rule_id = $1 AND dedup_key = $2",
+        )
+        .bind(alert.rule_id)
+        .bind(&alert.dedup_key)
+        .fetch_one(&mut *tx)
+        .await?;
+        let due = match (last_fired_at, refire_before) {
+            (None, _) => true,
+            (Some(last), Some(cutoff)) => last <= cutoff,
+            (Some(_), None) => false,
+        };
+        if !due {
+            return Ok(false);
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO fired_alerts (id, user_id, rule_id, rule_type, dedup_key, title, body, fired_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            "#,
+        )
+        .bind(alert.id)
+        .bind(user_id)
+        .bind(alert.rule_id)
+        .bind(&alert.rule_type)
+        .bind(&alert.dedup_key)
+        .bind(&alert.title)
+        .bind(&alert.body)
+        .bind(alert.fired_at)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    async fn get_fired_alerts_for_user(
+        &self,
+        user_id: &Uuid,
+        limit: i64,
+    ) -> Result<Vec<FiredAlert>> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("SELECT set_config('app.current_user_id', $1, true)")
+            .bind(user_id.to_string())
+            .execute(&mut *tx)
+            .await?;
+
+        let alerts = sqlx::query_as::<_, FiredAlert>(
+            r#"
+            SELECT id, rule_id, rule_type, dedup_key, title, body, fired_at
+            FROM fired_alerts
+            WHERE user_id = $1
+            ORDER BY fired_at DESC
+            LIMIT $2
+            "#,
+        )
+        .bind(user_id)
+        .bind(limit)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(alerts)
+    }
+
+    async fn upsert_fx_rates(&self, rates: &[FxRate]) -> Result<u64> {
+        let mut tx = self.pool.begin().await?;
+        let mut written = 0;
+
+        for rate in rates {
+            written += sqlx::query(
+                r#"
+                INSERT INTO fx_rates (base_currency, quote_currency, rate_date, rate)
+                VALUES ($1, $2, $3, $4)
+                ON CONFLICT (base_currency, quote_currency, rate_date)
+                DO UPDATE SET rate = EXCLUDED.rate
+                "#,
+            )
+            .bind(&rate.base_currency)
+            .bind(&rate.quote_currency)
+            .bind(rate.rate_date)
+            .bind(rate.rate)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+        }
+
+        tx.commit().await?;
+        Ok(written)
+    }
+
+    async fn get_fx_rates(
+        &self,
+        currencies: &[String],
+        until: chrono::NaiveDate,
+    ) -> Result<Vec<FxRate>> {
+        let rates = sqlx::query_as::<_, FxRate>(
+            r#"
+            SELECT base_currency, quote_currency, rate_date, rate
+            FROM fx_rates
+            WHERE rate_date <= $2
+              AND (base_currency = ANY($1) OR quote_currency = ANY($1))
+            ORDER BY rate_date
+            "#,
+        )
+        .bind(currencies)
+        .bind(until)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rates)
+    }
+
+    async fn get_user_settings(&self, user_id: &Uuid) -> Result<Option<UserSettings>> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("SELECT set_config('app.current_user_id', $1, true)")
+            .bind(user_id.to_string())
+            .execute(&mut *tx)
+            .await?;
+
+        let settings = sqlx::query_as::<_, (String, String, i16)>(
+            "SELECT reporting_currency, timezone, month_start_day FROM user_settings WHERE user_id = $1",
+        )
+        .bind(user_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .map(
+            |(reporting_currency, timezone, month_start_day)| UserSettings {
+                reporting_currency,
+                timezone,
+                month_start_day: month_start_day as u32,
+            },
+        );
+
+        tx.commit().await?;
+        Ok(settings)
+    }
+
+    async fn upsert_user_settings(
+        &self,
+        user_id: &Uuid,
+        settings: &UserSettings,
+    ) -> Result<UserSettings> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("SELECT set_config('app.current_user_id', $1, true)")
+            .bind(user_id.to_string())
+            .execute(&mut *tx)
+            .await?;
+
+        let (reporting_currency, timezone, month_start_day) =
+            sqlx::query_as::<_, (String, String, i16)>(
+                r#"
+            INSERT INTO user_settings (user_id, reporting_currency, timezone, month_start_day)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (user_id)
+            DO UPDATE SET reporting_currency = EXCLUDED.reporting_currency,
+                          timezone = EXCLUDED.timezone,
+                          month_start_day = EXCLUDED.month_start_day,
+                          updated_at = NOW()
+            RETURNING reporting_currency, timezone, month_start_day
+            "#,
+            )
+            .bind(user_id)
+            .bind(&settings.reporting_currency)
+            .bind(&settings.timezone)
+            .bind(settings.month_start_day as i16)
+            .fetch_one(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(UserSettings {
+            reporting_currency,
+            timezone,
+            month_start_day: month_start_day as u32,
+        })
+    }
+
+    async fn get_all_user_ids(&self) -> Result<Vec<Uuid>> {
+        let ids = sqlx::query_as::<_, (Uuid,)>("SELECT id FROM users ORDER BY created_at")
+            .fetch_all(&self.pool)
+            .await?
+            .into_iter()
+            .map(|(id,)| id)
+            .collect();
+
+        Ok(ids)
+    }
+
+    async fn record_balance_snapshots(
+        &self,
+        user_id: &Uuid,
+        snapshot_date: chrono::NaiveDate,
+    ) -> Result<u64> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("SELECT set_config('app.current_user_id', $1, true)")
+            .bind(user_id.to_string())
+            .execute(&mut *tx)
+            .await?;
+
+        let written = sqlx::query(
+            r#"
+            INSERT INTO account_balance_snapshots
+                (account_id, user_id, snapshot_date, balance, iso_currency_code, account_type)
+            SELECT id, user_id, $2, balance_current, iso_currency_code, account_type
+            FROM accounts
+            WHERE user_id = $1 AND balance_current IS NOT NULL
+            ON CONFLICT (account_id, snapshot_date)
+            DO UPDATE SET
+                balance = EXCLUDED.balance,
+                iso_currency_code = EXCLUDED.iso_currency_code,
+                account_type = EXCLUDED.account_type,
+                recorded_at = NOW()
+            "#,
+        )
+        .bind(user_id)
+        .bind(snapshot_date)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        tx.commit().await?;
+        Ok(written)
+    }
+
+    async fn get_balance_snapshots_for_user(
+        &self,
+        user_id: &Uuid,
+        start_date: chrono::NaiveDate,
+        end_date: chrono::NaiveDate,
+    ) -> Result<Vec<BalanceSnapshot>> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("SELECT set_config('app.current_user_id', $1, true)")
+            .bind(user_id.to_string())
+            .execute(&mut *tx)
+            .await?;
+
+        let snapshots = sqlx::query_as::<_, BalanceSnapshot>(
+            r#"
+            SELECT account_id, snapshot_date, balance, iso_currency_code, account_type
+            FROM account_balance_snapshots
+            WHERE user_id = $1 AND snapshot_date BETWEEN $2 AND $3
+            ORDER BY snapshot_date
+            "#,
+        )
+        .bind(user_id)
+        .bind(start_date)
+        .bind(end_date)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(snapshots)
+    }
+
+    async fn get_analytics_query_currency_days(
+        &self,
+        user_id: &Uuid,
+        request: &AnalyticsQueryRequest,
+        account_ids: &[Uuid],
+        reporting_currency: &str,
+    ) -> Result<Vec<(String, chrono::NaiveDate)>> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("SELECT set_config('app.current_user_id', $1, true)")
+            .bind(user_id.to_string())
+            .execute(&mut *tx)
+            .await?;
+
+        let mut builder = AnalyticsQueryService::build_currency_days_sql(
+            *user_id,
+            request,
+            account_ids,
+            reporting_currency,
+        );
+        let days = builder
+            .build_query_as::<(String, chrono::NaiveDate)>()
+            .fetch_all(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(days)
+    }
+
+    async fn run_analytics_query(
+        &self,
+        user_id: &Uuid,
+        request: &AnalyticsQueryRequest,
+        account_ids: &[Uuid],
+        rates: &QueryRates,
+    ) -> Result<Vec<AnalyticsQueryRow>> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("SELECT set_config('app.current_user_id', $1, true)")
+            .bind(user_id.to_string())
+            .execute(&mut *tx)
+            .await?;
+
+        let mut builder = AnalyticsQueryService::build_sql(*user_id, request, account_ids, rates);
+        let rows = builder.build().fetch_all(&mut *tx).await?;
+
+        tx.commit().await?;
+
+        rows.iter()
+            .map(|row| AnalyticsQueryService::decode_row(row, request).map_err(Into::into))
+            .collect()
+    }
+
+    async fn sum_spending_by_category(
+        &self,
+        user_id: &Uuid,
+        filter: &AggregateFilter,
+    ) -> Result<Vec<AggregateBucket>> {
+        self.aggregate_transactions(
+            user_id,
+            filter,
+            "COALESCE(NULLIF(category_primary, ''), 'Uncategorized')",
+            true,
+        )
+        .await
+    }
+
+    async fn sum_spending_by_merchant(
+        &self,
+        user_id: &Uuid,
+        filter: &AggregateFilter,
+    ) -> Result<Vec<AggregateBucket>> {
+        self.aggregate_transactions(
+            user_id,
+            filter,
+            "COALESCE(merchant_name, 'Unknown Merchant')",
+            true,
+        )
+        .await
+    }
+
+    async fn sum_by_month(
+        &self,
+        user_id: &Uuid,
+        filter: &AggregateFilter,
+    ) -> Result<Vec<AggregateBucket>> {
+        // Shifting by the month start day keys each date by the month it falls in.
+        let month_key = format!(
+            "to_char(date - {}, 'YYYY-MM')",
+            filter.month_start_day.saturating_sub(1)
+        );
+        self.aggregate_transactions(user_id, filter, &month_key, false)
+            .await
+    }
+
+    async fn get_dismissed_insight_ids(&self, user_id: &Uuid) -> Result<Vec<String>> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("SELECT set_config('app.current_user_id', $1, true)")
+            .bind(user_id.to_string())
+            .execute(&mut *tx)
+            .await?;
+
+        let ids = sqlx::query_scalar::<_, String>(
+            "SELECT insight_id FROM dismissed_insights WHERE user_id = $1",
+        )
+        .bind(user_id)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(ids)
+    }
+
+    async fn dismiss_insight(&self, user_id: &Uuid, insight_id: &str) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("SELECT set_config('app.current_user_id', $1, true)")
+            .bind(user_id.to_string())
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO dismissed_insights (user_id, insight_id)
+            VALUES ($1, $2)
+            ON CONFLICT (user_id, insight_id) DO NOTHING
+            "#,
+        )
+        .bind(user_id)
+        .bind(insight_id)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn upsert_securities(
+        &self,
+        user_id: &Uuid,
+        securities: &[Security],
+    ) -> Result<Vec<Security>> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("SELECT set_config('app.current_user_id', $1, true)")
+            .bind(user_id.to_string())
+            .execute(&mut *tx)
+            .await?;
+
+        let mut stored = Vec::with_capacity(securities.len());
+        for security in securities {
+            let row = sqlx::query_as::<_, Security>(
+                r#"
+                INSERT INTO securities
+                    (id, user_id, provider_security_id, name, ticker_symbol, security_type,
+                     iso_currency_code, close_price)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                ON CONFLICT (user_id, provider_security_id)
+                DO UPDATE SET
+                    name = EXCLUDED.name,
+                    ticker_symbol = EXCLUDED.ticker_symbol,
+                    security_type = EXCLUDED.security_type,
+                    iso_currency_code = EXCLUDED.iso_currency_code,
+                    close_price = COALESCE(EXCLUDED.close_price, securities.close_price),
+                    updated_at = NOW()
+                RETURNING id, provider_security_id, name, ticker_symbol, security_type,
+                          iso_currency_code, close_price
+                "#,
+            )
+            .bind(security.id)
+            .bind(user_id)
+            .bind(&security.provider_security_id)
+            .bind(&security.name)
+            .bind(&security.ticker_symbol)
+            .bind(&security.security_type)
+            .bind(&security.iso_currency_code)
+            .bind(security.close_price)
+            .fetch_one(&mut *tx)
+            .await?;
+            stored.push(row);
+        }
+
+        tx.commit().await?;
+        Ok(stored)
+    }
+
+    async fn replace_investment_holdings(
+        &self,
+        user_id: &Uuid,
+        account_ids: &[Uuid],
+        holdings: &[Holding],
+    ) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("SELECT set_config('app.current_user_id', $1, true)")
+            .bind(user_id.to_string())
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("DELETE FROM investment_holdings WHERE user_id = $1 AND account_id = ANY($2)")
+            .bind(user_id)
+            .bind(account_ids)
+            .execute(&mut *tx)
+            .await?;
+
+        for holding in holdings {
+            sqlx::query(
+                r#"
+                INSERT INTO investment_holdings
+                    (account_id, security_id, user_id, quantity, institution_price,
+                     institution_value, cost_basis, iso_currency_code)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                "#,
+            )
+            .bind(holding.account_id)
+            .bind(holding.security_id)
+            .bind(user_id)
+            .bind(holding.quantity)
+            .bind(holding.institution_price)
+            .bind(holding.institution_value)
+            .bind(holding.cost_basis)
+            .bind(&holding.iso_currency_code)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn upsert_investment_transactions(
+        &self,
+        user_id: &Uuid,
+        transactions: &[InvestmentTransaction],
+    ) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("SELECT set_config('app.current_user_id', $1, true)")
+            .bind(user_id.to_string())
+            .execute(&mut *tx)
+            .await?;
+
+        for transaction in transactions {
+            sqlx::query(
+                r#"
+                INSERT INTO investment_transactions
+                    (id, user_id, account_id, security_id, provider_investment_transaction_id,
+                     date, name, transaction_type, subtype, quantity, price, amount, fees,
+                     iso_currency_code)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
+                ON CONFLICT (user_id, provider_investment_transaction_id)
+                DO UPDATE SET
+                    account_id = EXCLUDED.account_id,
+                    security_id = EXCLUDED.security_id,
+                    date = EXCLUDED.date,
+                    name = EXCLUDED.name,
+                    transaction_type = EXCLUDED.transaction_type,
+                    subtype = EXCLUDED.subtype,
+                    quantity = EXCLUDED.quantity,
+                    price = EXCLUDED.price,
+                    amount = EXCLUDED.amount,
+                    fees = EXCLUDED.fees,
+                    iso_currency_code = EXCLUDED.iso_currency_code
+                "#,
+            )
+            .bind(transaction.id)
+            .bind(user_id)
+            .bind(transaction.account_id)
+            .bind(transaction.security_id)
+            .bind(&transaction.provider_investment_transaction_id)
+            .bind(transaction.date)
+            .bind(&transaction.name)
+            .bind(&transaction.transaction_type)
+            .bind(&transaction.subtype)
+            .bind(transaction.quantity)
+            .bind(transaction.price)
+            .bind(transaction.amount)
+            .bind(transaction.fees)
+            .bind(&transaction.iso_currency_code)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn get_securities_for_user(&self, user_id: &Uuid) -> Result<Vec<Security>> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("SELECT set_config('app.current_user_id', $1, true)")
+            .bind(user_id.to_string())
+            .execute(&mut *tx)
+            .await?;
+
+        let securities = sqlx::query_as::<_, Security>(
+            r#"
+            SELECT id, provider_security_id, name, ticker_symbol, security_type,
+                   iso_currency_code, close_price
+            FROM securities
+            WHERE user_id = $1
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(securities)
+    }
+
+    async fn get_investment_holdings_for_user(&self, user_id: &Uuid) -> Result<Vec<Holding>> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("SELECT set_config('app.current_user_id', $1, true)")
+            .bind(user_id.to_string())
+            .execute(&mut *tx)
+            .await?;
+
+        let holdings = sqlx::query_as::<_, Holding>(
+            r#"
+            SELECT account_id, security_id, quantity, institution_price, institution_value,
+                   cost_basis, iso_currency_code
+            FROM investment_holdings
+            WHERE user_id = $1
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(holdings)
+    }
+
+    async fn get_investment_transactions_for_user(
+        &self,
+        user_id: &Uuid,
+        start_date: chrono::NaiveDate,
+        end_date: chrono::NaiveDate,
+    ) -> Result<Vec<InvestmentTransaction>> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("SELECT set_config('app.current_user_id', $1, true)")
+            .bind(user_id.to_string())
+            .execute(&mut *tx)
+            .await?;
+
+        let transactions = sqlx::query_as::<_, InvestmentTransaction>(
+            r#"
+            SELECT id, account_id, security_id, provider_investment_transaction_id, date, name,
+                   transaction_type, subtype, quantity, price, amount, fees, iso_currency_code
+            FROM investment_transactions
+            WHERE user_id = $1 AND date BETWEEN $2 AND $3
+            ORDER BY date DESC, name
+            "#,
+        )
+        .bind(user_id)
+        .bind(start_date)
+        .bind(end_date)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(transactions)
+    }
+
+    async fn upsert_provider_liabilities(
+        &self,
+        user_id: &Uuid,
+        liabilities: &[Liability],
+    ) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("SELECT set_config('app.current_user_id', $1, true)")
+            .bind(user_id.to_string())
+            .execute(&mut *tx)
+            .await?;
+
+        for liability in liabilities {
+            sqlx::query(
+                r#"
+                INSERT INTO liabilities
+                    (account_id, user_id, liability_type, apr, minimum_payment, statement_balance,
+                     next_payment_due_date, loan_term_months, maturity_date, source)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, 'provider')
+                ON CONFLICT (account_id)
+                DO UPDATE SET
+                    liability_type = EXCLUDED.liability_type,
+                    apr = EXCLUDED.apr,
+                    minimum_payment = EXCLUDED.minimum_payment,
+                    statement_balance = EXCLUDED.statement_balance,
+                    next_payment_due_date = EXCLUDED.next_payment_due_date,
+                    loan_term_months = EXCLUDED.loan_term_months,
+                    maturity_date = EXCLUDED.maturity_date,
+                    updated_at = NOW()
+                WHERE liabilities.source = 'provider'
+                "#,
+            )
+            .bind(liability.account_id)
+            .bind(user_id)
+            .bind(&liability.liability_type)
+            .bind(liability.apr)
+            .bind(liability.minimum_payment)
+            .bind(liability.statement_balance)
+            .bind(liability.next_payment_due_date)
+            .bind(liability.loan_term_months)
+            .bind(liability.maturity_date)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn upsert_liability(&self, user_id: &Uuid, liability: &Liability) -> Result<Liability> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("SELECT set_config('app.current_user_id', $1, true)")
+            .bind(user_id.to_string())
+            .execute(&mut *tx)
+            .await?;
+
+        let saved = sqlx::query_as::<_, Liability>(
+            r#"
+            INSERT INTO liabilities
+                (account_id, user_id, liability_type, apr, minimum_payment, statement_balance,
+                 next_payment_due_date, loan_term_months, maturity_date, source)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            ON CONFLICT (account_id)
+            DO UPDATE SET
+                liability_type = EXCLUDED.liability_type,
+                apr = EXCLUDED.apr,
+                minimum_payment = EXCLUDED.minimum_payment,
+                statement_balance = EXCLUDED.statement_balance,
+                next_payment_due_date = EXCLUDED.next_payment_due_date,
+                loan_term_months = EXCLUDED.loan_term_months,
+                maturity_date = EXCLUDED.maturity_date,
+                source = EXCLUDED.source,
+                updated_at = NOW()
+            RETURNING account_id, liability_type, apr, minimum_payment, statement_balance,
+                      next_payment_due_date, loan_term_months, maturity_date, source
+            "#,
+        )
+        .bind(liability.account_id)
+        .bind(user_id)
+        .bind(&liability.liability_type)
+        .bind(liability.apr)
+        .bind(liability.minimum_payment)
+        .bind(liability.statement_balance)
+        .bind(liability.next_payment_due_date)
+        .bind(liability.loan_term_months)
+        .bind(liability.maturity_date)
+        .bind(&liability.source)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(saved)
+    }
+
+    async fn get_liabilities_for_user(&self, user_id: &Uuid) -> Result<Vec<Liability>> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("SELECT set_config('app.current_user_id', $1, true)")
+            .bind(user_id.to_string())
+            .execute(&mut *tx)
+            .await?;
+
+        let liabilities = sqlx::query_as::<_, Liability>(
+            r#"
+            SELECT account_id, liability_type, apr, minimum_payment, statement_balance,
+                   next_payment_due_date, loan_term_months, maturity_date, source
+            FROM liabilities
+            WHERE user_id = $1
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(liabilities)
+    }
+
+    async fn get_all_budget_allocations_for_user(
+        &self,
+        user_id: Uuid,
+    ) -> Result<Vec<BudgetAllocation>> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("SELECT set_config('app.current_user_id', $1, true)")
+            .bind(user_id.to_string())
+            .execute(&mut *tx)
+            .await?;
+
+        let allocations = sqlx::query_as::<_, BudgetAllocation>(
+            "SELECT id, user_id, month, category, assigned, created_at, updated_at
+             FROM budget_allocations
+             WHERE user_id = $1
+             ORDER BY month ASC, category ASC",
+        )
+        .bind(user_id)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(allocations)
+    }
+
+    async fn get_transaction_tags_for_user(&self, user_id: &Uuid) -> Result<Vec<TransactionTag>> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("SELECT set_config('app.current_user_id', $1, true)")
+            .bind(user_id.to_string())
+            .execute(&mut *tx)
+            .await?;
+
+        let tags = sqlx::query_as::<_, (Uuid, String)>(
+            "SELECT transaction_id, tag FROM transaction_tags WHERE user_id = $1 ORDER BY transaction_id, tag",
+        )
+        .bind(user_id)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(tags
+            .into_iter()
+            .map(|(transaction_id, tag)| TransactionTag {
+                transaction_id,
+                tag,
+            })
+            .collect())
+    }
+
+    async fn get_transactions_with_account_by_ids(
+        &self,
+        user_id: &Uuid,
+        transaction_ids: &[Uuid],
+    ) -> Result<Vec<TransactionWithAccount>> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("SELECT set_config('app.current_user_id', $1, true)")
+            .bind(user_id.to_string())
+            .execute(&mut *tx)
+            .await?;
+
+        let rows = sqlx::query(
+            r#"
+            SELECT t.id, t.account_id, t.user_id, t.provider_transaction_id, t.amount,
+                   t.iso_currency_code, t.date, t.merchant_name, t.category_primary,
+                   t.category_detailed, t.category_confidence, t.payment_channel, t.pending,
+                   t.created_at, t.reviewed, t.excluded_from_analytics,
+                   a.name as account_name, a.account_type, a.mask as account_mask
+            FROM transactions t
+            INNER JOIN accounts a ON t.account_id = a.id
+            WHERE t.user_id = $1 AND t.id = ANY($2)
+            ORDER BY t.date DESC, t.created_at DESC
+            "#,
+        )
+        .bind(user_id)
+        .bind(transaction_ids)
+        .fetch_all(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        rows.iter()
+            .map(Self::map_transaction_with_account_row)
+            .collect()
+    }
+
+    async fn apply_bulk_transaction_edit(
+        &self,
+        user_id: &Uuid,
+        transaction_ids: &[Uuid],
+        operations: &[BulkTransactionOperation],
+    ) -> Result<Vec<u64>> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("SELECT set_config('app.current_user_id', $1, true)")
+            .bind(user_id.to_string())
+            .execute(&mut *tx)
+            .await?;
+
+        let mut affected = Vec::with_capacity(operations.len());
+        for operation in operations {
+            let result = match operation {
+                BulkTransactionOperation::SetCategory {
+                    category,
+                    category_detailed,
+                } => {
+                    sqlx::query(
+                        "UPDATE transactions SET category_primary = $3, category_detailed = $4
+                         WHERE user_id = $1 AND id = ANY($2)
+                           AND (category_primary <> $3 OR category_detailed <> $4)",
+                    )
+                    .bind(user_id)
+                    .bind(transaction_ids)
+                    .bind(category)
+                    .bind(category_detailed.as_deref().unwrap_or(category))
+                    .execute(&mut *tx)
+                    .await?
+                }
+                BulkTransactionOperation::AddTag { tag } => {
+                    sqlx::query(
+                        "INSERT INTO transaction_tags (transaction_id, user_id, tag)
+                         SELECT id, user_id, $3 FROM transactions
+                         WHERE user_id = $1 AND id = ANY($2)
+                         ON CONFLICT (transaction_id, tag) DO NOTHING",
+                    )
+                    .bind(user_id)
+                    .bind(transaction_ids)
+                    .bind(tag)
+                    .execute(&mut *tx)
+                    .await?
+                }
+                BulkTransactionOperation::RemoveTag { tag } => {
+                    sqlx::query(
+                        "DELETE FROM transaction_tags
+                         WHERE user_id = $1 AND transaction_id = ANY($2) AND tag = $3",
+                    )
+                    .bind(user_id)
+                    .bind(transaction_ids)
+                    .bind(tag)
+                    .execute(&mut *tx)
+                    .await?
+                }
+                BulkTransactionOperation::SetReviewed { reviewed } => {
+                    sqlx::query(
+                        "UPDATE transactions SET reviewed = $3
+                         WHERE user_id = $1 AND id = ANY($2) AND reviewed <> $3",
+                    )
+                    .bind(user_id)
+                    .bind(transaction_ids)
+                    .bind(reviewed)
+                    .execute(&mut *tx)
+                    .await?
+                }
+                BulkTransactionOperation::SetExcludedFromAnalytics { excluded } => {
+                    sqlx::query(
+                        "UPDATE transactions SET excluded_from_analytics = $3
+                         WHERE user_id = $1 AND id = ANY($2) AND excluded_from_analytics <> $3",
+                    )
+                    .bind(user_id)
+                    .bind(transaction_ids)
+                    .bind(excluded)
+                    .execute(&mut *tx)
+                    .await?
+                }
+            };
+            affected.push(result.rows_affected());
+        }
+
+        tx.commit().await?;
+        Ok(affected)
+    }
+
+    async fn restore_user_archive(&self, user_id: &Uuid, archive: &UserArchive) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("SELECT set_config('app.current_user_id', $1, true)")
+            .bind(user_id.to_string())
+            .execute(&mut *tx)
+            .await?;
+
+        Self::restore_archive_ledger(&mut tx, user_id, archive).await?;
+        Self::restore_archive_budgets(&mut tx, user_id, archive).await?;
+        Self::restore_archive_preferences(&mut tx, user_id, archive).await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
 }
@@ -0,0 +1,246 @@
+use crate::models::saved_view::{
+    SavedView, SavedViewRequest, SavedViewResults, ViewMatches, MAX_SAVED_VIEW_NAME_LENGTH,
+};
+use crate::models::transaction_search::{TransactionSearch, SEARCH_RESULT_LIMIT};
+use crate::models::user_settings::UserCalendar;
+use crate::services::repository_service::DatabaseRepository;
+use crate::services::transaction_search_service::TransactionSearchService;
+use crate::services::user_settings_service::UserSettingsService;
+use chrono::{DateTime, NaiveDate, Utc};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+pub struct SavedViewService;
+
+impl SavedViewService {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub async fn list<R: DatabaseRepository + ?Sized>(
+        &self,
+        repository: &R,
+        user_id: Uuid,
+    ) -> Result<Vec<SavedView>, String> {
+        repository
+            .get_saved_views_for_user(&user_id)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn get<R: DatabaseRepository + ?Sized>(
+        &self,
+        repository: &R,
+        user_id: Uuid,
+        view_id: Uuid,
+    ) -> Result<SavedView, String> {
+        repository
+            .get_saved_view(&user_id, &view_id)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "Saved view not found".to_string())
+    }
+
+    pub async fn create<R: DatabaseRepository + ?Sized>(
+        &self,
+        repository: &R,
+        user_id: Uuid,
+        request: &SavedViewRequest,
+    ) -> Result<SavedView, String> {
+        let view = Self::build_view(Uuid::new_v4(), user_id, request, Utc::now())?;
+        repository
+            .create_saved_view(&view)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn update<R: DatabaseRepository + ?Sized>(
+        &self,
+        repository: &R,
+        user_id: Uuid,
+        view_id: Uuid,
+        request: &SavedViewRequest,
+    ) -> Result<SavedView, String> {
+        let view = Self::build_view(view_id, user_id, request, Utc::now())?;
+        repository
+            .update_saved_view(&view)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "Saved view not found".to_string())
+    }
+
+    /// Budgets and alert rules using the view keep existing without the filter.
+    pub async fn delete<R: DatabaseRepository + ?Sized>(
+        &self,
+        repository: &R,
+        user_id: Uuid,
+        view_id: Uuid,
+    ) -> Result<(), String> {
+        let deleted = repository
+            .delete_saved_view(&user_id, &view_id)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        if deleted {
+            Ok(())
+        } else {
+            Err("Saved view not found".to_string())
+        }
+    }
+
+    /// Runs the view as of the user's today. Results stop at the search cap;
+    /// `truncated` says whether more transactions matched.
+    pub async fn execute<R: DatabaseRepository + ?Sized>(
+        &self,
+        repository: &R,
+        user_id: Uuid,
+        view_id: Uuid,
+    ) -> Result<SavedViewResults, String> {
+        let view = self.get(repository, user_id, view_id).await?;
+        let calendar = UserSettingsService::calendar(repository, user_id).await?;
+        let (start_date, end_date) = view.date_range(&calendar, calendar.today());
+        let search = Self::search_for(&view, start_date, end_date)?;
+        let transactions =
+            TransactionSearchService::run(repository, user_id, &search, &view.account_ids).await?;
+        let truncated = transactions.len() >= SEARCH_RESULT_LIMIT as usize
+            && repository
+                .search_transaction_ids(&user_id, &search, &view.account_ids)
+                .await
+                .map_err(|e| e.to_string())?
+                .len()
+                > transactions.len();
+
+        Ok(SavedViewResults {
+            view,
+            start_date,
+            end_date,
+            transactions,
+            truncated,
+        })
+    }
+
+    /// Validates a request into a view with the given id.
+    pub fn build_view(
+        id: Uuid,
+        user_id: Uuid,
+        request: &SavedViewRequest,
+        now: DateTime<Utc>,
+    ) -> Result<SavedView, String> {
+        let name = request.name.trim();
+        if name.is_empty() || name.chars().count() > MAX_SAVED_VIEW_NAME_LENGTH {
+            return Err(format!(
+                "Invalid name: must be 1 to {} characters",
+                MAX_SAVED_VIEW_NAME_LENGTH
+            ));
+        }
+
+        let search = request
+            .search
+            .as_deref()
+            .map(str::trim)
+            .filter(|s| !s.is_empty());
+        if let Some(search) = search {
+            TransactionSearch::parse(search)?;
+        }
+
+        if request.date_preset.is_some()
+            && (request.start_date.is_some() || request.end_date.is_some())
+        {
+            return Err(
+                "Invalid date range: give either date_preset or start_date/end_date".to_string(),
+            );
+        }
+        if let (Some(start), Some(end)) = (request.start_date, request.end_date) {
+            if start > end {
+                return Err("Invalid date range: start_date is after end_date".to_string());
+            }
+        }
+
+        let mut account_ids = request.account_ids.clone();
+        account_ids.sort();
+        account_ids.dedup();
+
+        Ok(SavedView {
+            id,
+            user_id,
+            name: name.to_string(),
+            search: search.map(str::to_string),
+            account_ids,
+            date_preset: request.date_preset.map(|p| p.as_str().to_string()),
+            start_date: request.start_date,
+            end_date: request.end_date,
+            created_at: now,
+            updated_at: now,
+        })
+    }
+
+    /// The view's search narrowed to `start..=end`.
+    pub fn search_for(
+        view: &SavedView,
+        start: Option<NaiveDate>,
+        end: Option<NaiveDate>,
+    ) -> Result<TransactionSearch, String> {
+        let mut search = TransactionSearch::parse(view.search.as_deref().unwrap_or_default())?;
+        if let Some(start) = start {
+            search.narrow_start(start);
+        }
+        if let Some(end) = end {
+            search.narrow_end(end);
+        }
+        Ok(search)
+    }
+
+    /// Fails unless `view_id` is one of the user's views. Checked before a budget
+    /// or alert rule is linked to it.
+    pub async fn ensure_owned<R: DatabaseRepository + ?Sized>(
+        repository: &R,
+        user_id: Uuid,
+        view_id: Option<Uuid>,
+    ) -> Result<(), String> {
+        let Some(view_id) = view_id else {
+            return Ok(());
+        };
+        match repository.get_saved_view(&user_id, &view_id).await {
+            Ok(Some(_)) => Ok(()),
+            Ok(None) => Err("saved_view_id must reference one of your saved views".to_string()),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
+    /// Transactions each view matches within `window`, intersected with the
+    /// view's own date range. Views that no longer exist are left out, so they
+    /// match nothing.
+    pub async fn matching_transaction_ids<R: DatabaseRepository + ?Sized>(
+        repository: &R,
+        user_id: Uuid,
+        view_ids: &[Uuid],
+        calendar: &UserCalendar,
+        window: (NaiveDate, NaiveDate),
+    ) -> Result<ViewMatches, String> {
+        let (window_start, window_end) = window;
+        let mut matches = ViewMatches::default();
+        let unique: HashSet<Uuid> = view_ids.iter().copied().collect();
+
+        for view_id in unique {
+            let Some(view) = repository
+                .get_saved_view(&user_id, &view_id)
+                .await
+                .map_err(|e| e.to_string())?
+            else {
+                continue;
+            };
+            let (start, end) = view.date_range(calendar, window_end);
+            let mut search = Self::search_for(&view, start, end)?;
+            search.narrow_start(window_start);
+            search.narrow_end(window_end);
+
+            let ids = repository
+                .search_transaction_ids(&user_id, &search, &view.account_ids)
+                .await
+                .map_err(|e| e.to_string())?;
+            matches.insert(view_id, ids.into_iter().collect());
+        }
+
+        Ok(matches)
+    }
+}
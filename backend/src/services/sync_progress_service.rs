@@ -0,0 +1,113 @@
+use crate::models::sync_progress::SyncProgressEvent;
+use futures::{Stream, StreamExt};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use tokio::sync::broadcast::{self, error::RecvError};
+use uuid::Uuid;
+
+/// Events buffered per subscriber; a subscriber that falls further behind skips ahead.
+const EVENT_BUFFER: usize = 256;
+
+/// Fans sync progress out to open event streams and tracks which connections are
+/// syncing. State is per process, matching the single backend instance.
+pub struct SyncProgressService {
+    sender: broadcast::Sender<SyncProgressEvent>,
+    running: Mutex<HashSet<Uuid>>,
+    /// Last event of each connection, replayed to streams opened after it was sent.
+    latest: Mutex<HashMap<Uuid, SyncProgressEvent>>,
+}
+
+/// Marks a connection as syncing until dropped.
+pub struct SyncGuard {
+    service: Arc<SyncProgressService>,
+    connection_id: Uuid,
+}
+
+impl Drop for SyncGuard {
+    fn drop(&mut self) {
+        self.service
+            .running
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&self.connection_id);
+    }
+}
+
+impl SyncProgressService {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(EVENT_BUFFER);
+        Self {
+            sender,
+            running: Mutex::new(HashSet::new()),
+            latest: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Sends the event to every open stream and keeps it for streams opened later.
+    pub fn publish(&self, event: SyncProgressEvent) {
+        // Held across the send so a stream opening concurrently sees the event either
+        // in its replay or on its receiver, never both or neither.
+        let mut latest = self.latest.lock().unwrap_or_else(|e| e.into_inner());
+        latest.insert(event.connection_id, event.clone());
+        let _ = self.sender.send(event);
+    }
+
+    /// Claims the connection for a sync, or `None` when one is already running.
+    pub fn try_begin(self: &Arc<Self>, connection_id: Uuid) -> Option<SyncGuard> {
+        let inserted = self
+            .running
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(connection_id);
+        inserted.then(|| SyncGuard {
+            service: self.clone(),
+            connection_id,
+        })
+    }
+
+    pub fn is_running(&self, connection_id: &Uuid) -> bool {
+        self.running
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .contains(connection_id)
+    }
+
+    /// Progress of the user's syncs, starting with the last event of each connection,
+    /// optionally for one connection only. A stream for one connection ends once its
+    /// sync completes or fails.
+    pub fn events_for(
+        &self,
+        user_id: Uuid,
+        connection_id: Option<Uuid>,
+    ) -> impl Stream<Item = SyncProgressEvent> + Send + 'static {
+        let matches = move |event: &SyncProgressEvent| {
+            event.user_id == user_id && connection_id.is_none_or(|id| id == event.connection_id)
+        };
+        let (receiver, mut replay) = {
+            let latest = self.latest.lock().unwrap_or_else(|e| e.into_inner());
+            let replay: Vec<SyncProgressEvent> =
+                latest.values().filter(|e| matches(e)).cloned().collect();
+            (self.sender.subscribe(), replay)
+        };
+        replay.sort_by_key(|e| e.timestamp);
+
+        let live = futures::stream::unfold(receiver, move |mut receiver| async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(event) if matches(&event) => return Some((event, receiver)),
+                    Ok(_) | Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => return None,
+                }
+            }
+        });
+        let events = Box::pin(futures::stream::iter(replay).chain(live));
+        futures::stream::unfold((events, false), move |(mut events, finished)| async move {
+            if finished {
+                return None;
+            }
+            let event = events.next().await?;
+            let finished = connection_id.is_some() && event.phase.is_terminal();
+            Some((event, (events, finished)))
+        })
+    }
+}
@@ -4,7 +4,8 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use uuid::Uuid;
 
-use crate::models::{account::Account, plaid::ProviderConnection, transaction::Transaction};
+use crate::models::user_settings::UserCalendar;
+use crate::models::{account::Account, transaction::Transaction};
 use crate::providers::{FinancialDataProvider, ProviderCredentials, ProviderRegistry};
 
 const MAX_SYNC_YEARS: i64 = 5;
@@ -50,11 +51,9 @@ impl SyncService {
     pub async fn sync_bank_connection_transactions(
         &self,
         credentials: &ProviderCredentials,
-        connection: &ProviderConnection,
         accounts: &[Account],
+        (start_date, end_date): (NaiveDate, NaiveDate),
     ) -> Result<(Vec<Transaction>, String)> {
-        let (start_date, end_date) = self.calculate_sync_date_range(connection.last_sync_at);
-
         let provider = self.resolve_provider(Some(&credentials.provider))?;
         let transactions = provider
             .get_transactions(credentials, start_date, end_date)
@@ -85,7 +84,8 @@ impl SyncService {
         existing_transactions: &[Transaction],
         last_sync_at: Option<DateTime<Utc>>,
     ) -> Result<Vec<Transaction>> {
-        let (start_date, end_date) = self.calculate_sync_date_range(last_sync_at);
+        let (start_date, end_date) =
+            self.calculate_sync_date_range(last_sync_at, &UserCalendar::default());
 
         let provider = self.resolve_provider(Some(&credentials.provider))?;
         let provider_transactions = provider
@@ -128,20 +128,24 @@ impl SyncService {
     pub fn calculate_sync_date_range(
         &self,
         last_sync_at: Option<DateTime<Utc>>,
+        calendar: &UserCalendar,
     ) -> (NaiveDate, NaiveDate) {
-        Self::calculate_sync_date_range_static(last_sync_at)
+        Self::calculate_sync_date_range_static(last_sync_at, calendar)
     }
 
+    /// Dates are the user's local dates, so a sync late in the evening still
+    /// asks the provider for everything up to the user's today.
     pub fn calculate_sync_date_range_static(
         last_sync_at: Option<DateTime<Utc>>,
+        calendar: &UserCalendar,
     ) -> (NaiveDate, NaiveDate) {
-        let end_date = Utc::now().date_naive();
+        let end_date = calendar.today();
         let max_lookback = end_date - Duration::days(365 * MAX_SYNC_YEARS);
 
         let start_date = match last_sync_at {
             Some(last_sync) => {
                 let last_sync_with_buffer =
-                    (last_sync - Duration::days(SAFETY_MARGIN_DAYS)).date_naive();
+                    calendar.local_date(last_sync - Duration::days(SAFETY_MARGIN_DAYS));
                 std::cmp::max(last_sync_with_buffer, max_lookback).min(end_date)
             }
             None => end_date - Duration::days(DEFAULT_FIRST_SYNC_DAYS),
@@ -0,0 +1,233 @@
+use crate::models::transaction::{TransactionWithAccount, TransactionsQuery};
+use crate::models::transaction_search::{TransactionSearch, SEARCH_RESULT_LIMIT};
+use crate::services::repository_service::DatabaseRepository;
+use sqlx::{Postgres, QueryBuilder};
+use uuid::Uuid;
+
+/// Minimum `word_similarity` for a word to fuzzily match a merchant, low enough
+/// for a one-letter typo in a short name ("amazn" for "Amazon").
+pub const FUZZY_MATCH_THRESHOLD: &str = "0.5";
+
+pub struct TransactionSearchService;
+
+impl TransactionSearchService {
+    /// Transactions matching the list filters. Plain listings keep the newest-first
+    /// order; a `search` is parsed and run in Postgres, best matches first.
+    pub async fn find<R: DatabaseRepository + ?Sized>(
+        repository: &R,
+        user_id: Uuid,
+        query: &TransactionsQuery,
+    ) -> Result<Vec<TransactionWithAccount>, String> {
+        let search = TransactionSearch::parse(query.search.as_deref().unwrap_or_default())?;
+        let Some(account_ids) = Self::account_filter(query) else {
+            return Ok(Vec::new());
+        };
+
+        Self::run(repository, user_id, &search, &account_ids).await
+    }
+
+    /// Ids of every transaction matching the list filters, without the row cap
+    /// `find` applies.
+    pub async fn find_ids<R: DatabaseRepository + ?Sized>(
+        repository: &R,
+        user_id: Uuid,
+        query: &TransactionsQuery,
+    ) -> Result<Vec<Uuid>, String> {
+        let search = TransactionSearch::parse(query.search.as_deref().unwrap_or_default())?;
+        let Some(account_ids) = Self::account_filter(query) else {
+            return Ok(Vec::new());
+        };
+
+        repository
+            .search_transaction_ids(&user_id, &search, &account_ids)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    /// The query's valid account ids, or `None` when it names accounts but none
+    /// of them parse, so nothing can match.
+    fn account_filter(query: &TransactionsQuery) -> Option<Vec<Uuid>> {
+        let account_ids: Vec<Uuid> = query
+            .account_ids
+            .iter()
+            .filter_map(|s| Uuid::parse_str(s).ok())
+            .collect();
+        (query.account_ids.is_empty() || !account_ids.is_empty()).then_some(account_ids)
+    }
+
+    /// Runs an already parsed search, limited to `account_ids` when any are given.
+    pub async fn run<R: DatabaseRepository + ?Sized>(
+        repository: &R,
+        user_id: Uuid,
+        search: &TransactionSearch,
+        account_ids: &[Uuid],
+    ) -> Result<Vec<TransactionWithAccount>, String> {
+        if search.is_empty() {
+            let mut transactions = repository
+                .get_transactions_with_account_for_user(&user_id)
+                .await
+                .map_err(|e| e.to_string())?;
+            transactions.retain(|t| account_ids.is_empty() || account_ids.contains(&t.account_id));
+            return Ok(transactions);
+        }
+
+        repository
+            .search_transactions(&user_id, search, account_ids)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    /// Builds the search. Every value from the query is bound as a parameter.
+    /// Words match the `search_vector` full-text column by prefix, merchants by
+    /// trigram similarity and account names by substring; ranking adds the
+    /// full-text rank to the merchant similarity. Each branch of an `OR` compares
+    /// an indexed `transactions` expression, so Postgres can combine the indexes;
+    /// matching account names are looked up once beforehand for that reason.
+    pub fn build_sql(
+        user_id: Uuid,
+        search: &TransactionSearch,
+        account_ids: &[Uuid],
+    ) -> QueryBuilder<'static, Postgres> {
+        let mut builder = QueryBuilder::new(
+            "SELECT t.id, t.account_id, t.user_id, t.provider_transaction_id, t.amount, \
+             t.iso_currency_code, t.date, t.merchant_name, t.category_primary, \
+             t.category_detailed, t.category_confidence, t.payment_channel, t.pending, \
+             t.created_at, t.reviewed, t.excluded_from_analytics, \
+             a.name AS account_name, a.account_type, a.mask AS account_mask ",
+        );
+        Self::push_filters(&mut builder, user_id, search, account_ids);
+
+        builder.push(" ORDER BY ");
+        if !search.text.is_empty() {
+            builder.push("(");
+            if let Some(tsquery) = Self::prefix_tsquery(&search.text, " | ") {
+                builder
+                    .push("ts_rank(t.search_vector, to_tsquery('simple', ")
+                    .push_bind(tsquery)
+                    .push(")) + ");
+            }
+            builder
+                .push("word_similarity(")
+                .push_bind(search.text.join(" "))
+                .push(", COALESCE(t.merchant_name, ''))) DESC, ");
+        }
+        builder
+            .push("t.date DESC, t.created_at DESC LIMIT ")
+            .push_bind(SEARCH_RESULT_LIMIT);
+        builder
+    }
+
+    /// Builds the same search returning only ids, unordered and uncapped.
+    pub fn build_ids_sql(
+        user_id: Uuid,
+        search: &TransactionSearch,
+        account_ids: &[Uuid],
+    ) -> QueryBuilder<'static, Postgres> {
+        let mut builder = QueryBuilder::new("SELECT t.id ");
+        Self::push_filters(&mut builder, user_id, search, account_ids);
+        builder
+    }
+
+    /// The `FROM` and `WHERE` clauses shared by both searches.
+    fn push_filters(
+        builder: &mut QueryBuilder<'static, Postgres>,
+        user_id: Uuid,
+        search: &TransactionSearch,
+        account_ids: &[Uuid],
+    ) {
+        builder.push(
+            "FROM transactions t INNER JOIN accounts a ON t.account_id = a.id \
+             WHERE t.user_id = ",
+        );
+        builder.push_bind(user_id);
+        if !account_ids.is_empty() {
+            builder
+                .push(" AND t.account_id = ANY(")
+                .push_bind(account_ids.to_vec())
+                .push(")");
+        }
+
+        for word in &search.text {
+            builder.push(" AND (");
+            if let Some(tsquery) = Self::prefix_tsquery(std::slice::from_ref(word), " & ") {
+                builder
+                    .push("t.search_vector @@ to_tsquery('simple', ")
+                    .push_bind(tsquery)
+                    .push(") OR ");
+            }
+            builder
+                .push_bind(word.clone())
+                .push(
+                    " <% t.merchant_name OR t.account_id = ANY(ARRAY(\
+                     SELECT an.id FROM accounts an WHERE an.user_id = ",
+                )
+                .push_bind(user_id)
+                .push(" AND an.name ILIKE ")
+                .push_bind(Self::contains_pattern(word))
+                .push(")))");
+        }
+        for merchant in &search.merchants {
+            builder
+                .push(" AND (t.merchant_name ILIKE ")
+                .push_bind(Self::contains_pattern(merchant))
+                .push(" OR ")
+                .push_bind(merchant.clone())
+                .push(" <% t.merchant_name)");
+        }
+        for category in &search.categories {
+            let pattern = Self::contains_pattern(&category.replace('_', " "));
+            builder
+                .push(" AND (REPLACE(t.category_primary, '_', ' ') ILIKE ")
+                .push_bind(pattern.clone())
+                .push(" OR t.category_detailed ILIKE ")
+                .push_bind(pattern)
+                .push(")");
+        }
+        for account in &search.accounts {
+            builder
+                .push(" AND a.name ILIKE ")
+                .push_bind(Self::contains_pattern(account));
+        }
+        for tag in &search.tags {
+            builder
+                .push(
+                    " AND EXISTS (SELECT 1 FROM transaction_tags tt \
+                     WHERE tt.transaction_id = t.id AND LOWER(tt.tag) = LOWER(",
+                )
+                .push_bind(tag.clone())
+                .push("))");
+        }
+        for (comparison, amount) in &search.amounts {
+            builder
+                .push(format!(" AND ABS(t.amount) {} ", comparison.sql_operator()))
+                .push_bind(*amount);
+        }
+        if let Some(start_date) = search.start_date {
+            builder.push(" AND t.date >= ").push_bind(start_date);
+        }
+        if let Some(end_date) = search.end_date {
+            builder.push(" AND t.date <= ").push_bind(end_date);
+        }
+    }
+
+    /// `term:*` prefixes joined by `operator`, keeping only letters and digits so
+    /// user input cannot form tsquery syntax. `None` when nothing is left.
+    fn prefix_tsquery(words: &[String], operator: &str) -> Option<String> {
+        let terms: Vec<String> = words
+            .iter()
+            .flat_map(|word| word.split(|c: char| !c.is_alphanumeric()))
+            .filter(|term| !term.is_empty())
+            .map(|term| format!("{}:*", term.to_lowercase()))
+            .collect();
+        (!terms.is_empty()).then(|| terms.join(operator))
+    }
+
+    /// An `ILIKE` pattern matching `value` anywhere, with wildcards in it escaped.
+    fn contains_pattern(value: &str) -> String {
+        let escaped = value
+            .replace('\\', "\\\\")
+            .replace('%', "\\%")
+            .replace('_', "\\_");
+        format!("%{}%", escaped)
+    }
+}
@@ -0,0 +1,84 @@
+use crate::models::currency::normalize_currency_code;
+use crate::models::user_settings::{
+    UpdateUserSettingsRequest, UserCalendar, UserSettings, MAX_MONTH_START_DAY,
+};
+use crate::services::repository_service::DatabaseRepository;
+use chrono_tz::Tz;
+use uuid::Uuid;
+
+pub struct UserSettingsService;
+
+impl UserSettingsService {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub async fn get_settings<R: DatabaseRepository + ?Sized>(
+        &self,
+        repository: &R,
+        user_id: Uuid,
+    ) -> Result<UserSettings, String> {
+        Self::load(repository, user_id).await
+    }
+
+    pub async fn update_settings<R: DatabaseRepository + ?Sized>(
+        &self,
+        repository: &R,
+        user_id: Uuid,
+        request: &UpdateUserSettingsRequest,
+    ) -> Result<UserSettings, String> {
+        let mut settings = Self::load(repository, user_id).await?;
+        Self::apply_update(&mut settings, request)?;
+
+        repository
+            .upsert_user_settings(&user_id, &settings)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    /// The user's calendar, falling back to UTC calendar months.
+    pub async fn calendar<R: DatabaseRepository + ?Sized>(
+        repository: &R,
+        user_id: Uuid,
+    ) -> Result<UserCalendar, String> {
+        Ok(Self::load(repository, user_id).await?.calendar())
+    }
+
+    pub fn apply_update(
+        settings: &mut UserSettings,
+        request: &UpdateUserSettingsRequest,
+    ) -> Result<(), String> {
+        if let Some(code) = &request.reporting_currency {
+            settings.reporting_currency = normalize_currency_code(code)
+                .ok_or_else(|| format!("Invalid currency code: {}", code))?;
+        }
+        if let Some(timezone) = &request.timezone {
+            let tz: Tz = timezone
+                .trim()
+                .parse()
+                .map_err(|_| format!("Invalid timezone: {}", timezone))?;
+            settings.timezone = tz.name().to_string();
+        }
+        if let Some(day) = request.month_start_day {
+            if !(1..=MAX_MONTH_START_DAY).contains(&day) {
+                return Err(format!(
+                    "Invalid month_start_day: must be between 1 and {}",
+                    MAX_MONTH_START_DAY
+                ));
+            }
+            settings.month_start_day = day;
+        }
+        Ok(())
+    }
+
+    async fn load<R: DatabaseRepository + ?Sized>(
+        repository: &R,
+        user_id: Uuid,
+    ) -> Result<UserSettings, String> {
+        Ok(repository
+            .get_user_settings(&user_id)
+            .await
+            .map_err(|e| e.to_string())?
+            .unwrap_or_default())
+    }
+}
@@ -0,0 +1,697 @@
+use crate::models::budget::Budget;
+use crate::models::plaid::ProviderConnection;
+use crate::models::saved_view::ViewMatches;
+use crate::models::transaction::{SyncTransactionsResponse, Transaction};
+use crate::models::user_settings::UserCalendar;
+use crate::models::webhook::{
+    CreateWebhookEndpointResponse, WebhookDelivery, WebhookEndpoint, WebhookEndpointRequest,
+    WebhookEvent, WebhookEventType, WEBHOOK_STATUS_FAILED, WEBHOOK_STATUS_PENDING,
+    WEBHOOK_STATUS_SUCCEEDED,
+};
+use crate::services::alert_service::AlertService;
+use crate::services::repository_service::DatabaseRepository;
+use crate::services::saved_view_service::SavedViewService;
+use crate::services::user_settings_service::UserSettingsService;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use hmac::{Hmac, Mac};
+use rust_decimal::Decimal;
+use serde_json::json;
+use sha2::Sha256;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use uuid::Uuid;
+
+pub const WEBHOOK_SIGNATURE_HEADER: &str = "Sumurai-Signature";
+pub const WEBHOOK_EVENT_HEADER: &str = "Sumurai-Event";
+pub const WEBHOOK_DELIVERY_HEADER: &str = "Sumurai-Delivery";
+
+/// Attempts made before a delivery is given up as failed.
+pub const MAX_WEBHOOK_ATTEMPTS: i32 = 8;
+/// Delay before the first retry; each later retry waits twice as long.
+const RETRY_BASE_SECONDS: i64 = 60;
+/// Longest one attempt may take, resolving the receiver's host included.
+const DELIVERY_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+/// How long a claimed delivery is hidden from other workers while it is attempted.
+const DELIVERY_LEASE_MINUTES: i64 = 5;
+/// Small enough that a batch of timed out attempts still finishes within the lease,
+/// so no delivery is claimed again while it is being sent.
+const DELIVERY_BATCH_SIZE: i64 = 20;
+const _: () = assert!(
+    DELIVERY_BATCH_SIZE * (DELIVERY_TIMEOUT.as_secs() as i64) < DELIVERY_LEASE_MINUTES * 60
+);
+const MAX_DESCRIPTION_LEN: usize = 200;
+const MAX_ERROR_LEN: usize = 500;
+const SECRET_PREFIX: &str = "whsec_";
+
+pub struct WebhookService {
+    allowed_hosts: Vec<String>,
+}
+
+impl WebhookService {
+    pub fn new() -> Self {
+        Self {
+            allowed_hosts: Vec::new(),
+        }
+    }
+
+    /// Hosts the operator trusts to resolve to loopback or private addresses, such
+    /// as a receiver on the same machine. Every other host must resolve to public
+    /// addresses only.
+    pub fn with_allowed_hosts(mut self, hosts: Vec<String>) -> Self {
+        self.allowed_hosts = hosts
+            .iter()
+            .map(|host| Self::normalize_host(host))
+            .collect();
+        self
+    }
+
+    /// Registers an endpoint with a freshly generated signing secret, returned only here.
+    pub async fn create_endpoint<R: DatabaseRepository + ?Sized>(
+        &self,
+        repository: &R,
+        user_id: Uuid,
+        request: &WebhookEndpointRequest,
+    ) -> Result<CreateWebhookEndpointResponse, String> {
+        let now = Utc::now();
+        let secret = format!(
+            "{}{}",
+            SECRET_PREFIX,
+            hex::encode(rand::random::<[u8; 32]>())
+        );
+        let endpoint = Self::build_endpoint(
+            WebhookEndpoint {
+                id: Uuid::new_v4(),
+                user_id,
+                url: String::new(),
+                description: None,
+                events: Vec::new(),
+                secret: secret.clone(),
+                enabled: true,
+                created_at: now,
+                updated_at: now,
+            },
+            request,
+        )?;
+
+        repository
+            .create_webhook_endpoint(&endpoint)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(CreateWebhookEndpointResponse { secret, endpoint })
+    }
+
+    pub async fn list_endpoints<R: DatabaseRepository + ?Sized>(
+        &self,
+        repository: &R,
+        user_id: Uuid,
+    ) -> Result<Vec<WebhookEndpoint>, String> {
+        repository
+            .get_webhook_endpoints_for_user(&user_id)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn update_endpoint<R: DatabaseRepository + ?Sized>(
+        &self,
+        repository: &R,
+        user_id: Uuid,
+        endpoint_id: Uuid,
+        request: &WebhookEndpointRequest,
+    ) -> Result<WebhookEndpoint, String> {
+        let existing = Self::owned_endpoint(repository, user_id, endpoint_id).await?;
+        let mut endpoint = Self::build_endpoint(existing, request)?;
+        endpoint.updated_at = Utc::now();
+
+        let updated = repository
+            .update_webhook_endpoint(&endpoint)
+            .await
+            .map_err(|e| e.to_string())?;
+        if updated {
+            Ok(endpoint)
+        } else {
+            Err("Webhook endpoint not found".to_string())
+        }
+    }
+
+    pub async fn delete_endpoint<R: DatabaseRepository + ?Sized>(
+        &self,
+        repository: &R,
+        user_id: Uuid,
+        endpoint_id: Uuid,
+    ) -> Result<(), String> {
+        let deleted = repository
+            .delete_webhook_endpoint(&user_id, &endpoint_id)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        if deleted {
+            Ok(())
+        } else {
+            Err("Webhook endpoint not found".to_string())
+        }
+    }
+
+    pub async fn list_deliveries<R: DatabaseRepository + ?Sized>(
+        &self,
+        repository: &R,
+        user_id: Uuid,
+        endpoint_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<WebhookDelivery>, String> {
+        Self::owned_endpoint(repository, user_id, endpoint_id).await?;
+        repository
+            .get_webhook_deliveries_for_endpoint(&user_id, &endpoint_id, limit)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    /// Sends a `webhook.test` event right away, whether or not the endpoint is enabled,
+    /// and logs the outcome. Test deliveries are not retried.
+    pub async fn send_test<R: DatabaseRepository + ?Sized>(
+        &self,
+        repository: &R,
+        user_id: Uuid,
+        endpoint_id: Uuid,
+    ) -> Result<WebhookDelivery, String> {
+        let endpoint = Self::owned_endpoint(repository, user_id, endpoint_id).await?;
+        let event = WebhookEvent::new(
+            user_id,
+            WebhookEventType::Test,
+            json!({ "endpoint_id": endpoint.id }),
+        );
+        let mut delivery = WebhookDelivery::queued(&endpoint, &event);
+        repository
+            .insert_webhook_delivery(&delivery)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let outcome = self.send(&endpoint, &delivery, Utc::now()).await;
+        delivery = Self::record_attempt(delivery, outcome, Utc::now());
+        if delivery.status == WEBHOOK_STATUS_PENDING {
+            delivery.status = WEBHOOK_STATUS_FAILED.to_string();
+            delivery.next_attempt_at = None;
+        }
+        repository
+            .update_webhook_delivery(&delivery)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(delivery)
+    }
+
+    /// Queues the event for every enabled endpoint subscribed to it; the delivery
+    /// worker sends it. Returns how many deliveries were queued.
+    pub async fn publish<R: DatabaseRepository + ?Sized>(
+        &self,
+        repository: &R,
+        event: &WebhookEvent,
+    ) -> Result<usize, String> {
+        let endpoints = repository
+            .get_webhook_endpoints_for_user(&event.user_id)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let mut queued = 0;
+        for endpoint in endpoints
+            .iter()
+            .filter(|e| e.subscribes_to(event.event_type))
+        {
+            let delivery = WebhookDelivery::queued(endpoint, event);
+            if repository
+                .insert_webhook_delivery(&delivery)
+                .await
+                .map_err(|e| e.to_string())?
+            {
+                queued += 1;
+            }
+        }
+
+        Ok(queued)
+    }
+
+    /// Publishes each event, logging failures so a webhook problem never fails the
+    /// operation that raised the events.
+    pub async fn publish_all<R: DatabaseRepository + ?Sized>(
+        &self,
+        repository: &R,
+        events: Vec<WebhookEvent>,
+    ) {
+        for event in events {
+            if let Err(e) = self.publish(repository, &event).await {
+                tracing::warn!(
+                    "Failed to queue {} webhook for user {}: {}",
+                    event.event_type.as_str(),
+                    event.user_id,
+                    e
+                );
+            }
+        }
+    }
+
+    /// Attempts every delivery that is due. Returns how many were attempted.
+    pub async fn process_due<R: DatabaseRepository + ?Sized>(
+        &self,
+        repository: &R,
+        now: DateTime<Utc>,
+    ) -> Result<usize, String> {
+        let deliveries = repository
+            .claim_due_webhook_deliveries(
+                now,
+                now + Duration::minutes(DELIVERY_LEASE_MINUTES),
+                DELIVERY_BATCH_SIZE,
+            )
+            .await
+            .map_err(|e| e.to_string())?;
+        let attempted = deliveries.len();
+
+        for delivery in deliveries {
+            // A delivery skipped here stays claimed and is picked up again once its
+            // lease runs out, so one failure never holds back the rest of the batch.
+            let endpoint = match repository
+                .get_webhook_endpoint_by_id(&delivery.endpoint_id)
+                .await
+            {
+                Ok(endpoint) => endpoint,
+                Err(e) => {
+                    tracing::warn!(
+                        "Failed to load webhook endpoint {} for delivery {}: {}",
+                        delivery.endpoint_id,
+                        delivery.id,
+                        e
+                    );
+                    continue;
+                }
+            };
+            let delivery = match endpoint.filter(|e| e.enabled) {
+                Some(endpoint) => {
+                    let outcome = tokio::time::timeout(
+                        DELIVERY_TIMEOUT,
+                        self.send(&endpoint, &delivery, now),
+                    )
+                    .await
+                    .unwrap_or_else(|_| Err("Delivery timed out".to_string()));
+                    Self::record_attempt(delivery, outcome, Utc::now())
+                }
+                None => WebhookDelivery {
+                    status: WEBHOOK_STATUS_FAILED.to_string(),
+                    error: Some("Endpoint is disabled".to_string()),
+                    next_attempt_at: None,
+                    ..delivery
+                },
+            };
+            if let Err(e) = repository.update_webhook_delivery(&delivery).await {
+                tracing::warn!("Failed to record webhook delivery {}: {}", delivery.id, e);
+            }
+        }
+
+        Ok(attempted)
+    }
+
+    /// POSTs the delivery's payload, returning the receiver's status code or why the
+    /// request could not be made. The connection goes to the addresses that were
+    /// checked, and redirects are not followed, so a receiver cannot point the
+    /// request at the internal network.
+    async fn send(
+        &self,
+        endpoint: &WebhookEndpoint,
+        delivery: &WebhookDelivery,
+        now: DateTime<Utc>,
+    ) -> Result<u16, String> {
+        let url = reqwest::Url::parse(&endpoint.url).map_err(|e| e.to_string())?;
+        let addresses = self.receiver_addresses(&url).await?;
+        let mut client = reqwest::Client::builder()
+            .redirect(reqwest::redirect::Policy::none())
+            .timeout(DELIVERY_TIMEOUT);
+        if let Some(domain) = url.domain() {
+            client = client.resolve_to_addrs(domain, &addresses);
+        }
+        let client = client.build().map_err(|e| e.to_string())?;
+
+        let body = delivery.payload.to_string();
+        let signature = Self::signature_header(&endpoint.secret, now.timestamp(), &body);
+
+        let response = client
+            .post(url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(WEBHOOK_SIGNATURE_HEADER, signature)
+            .header(WEBHOOK_EVENT_HEADER, &delivery.event_type)
+            .header(WEBHOOK_DELIVERY_HEADER, delivery.id.to_string())
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(response.status().as_u16())
+    }
+
+    /// Resolves the receiver's host, refusing loopback, private, link-local and other
+    /// non-public addresses unless the operator allowed the host.
+    async fn receiver_addresses(&self, url: &reqwest::Url) -> Result<Vec<SocketAddr>, String> {
+        let port = url
+            .port_or_known_default()
+            .ok_or_else(|| "Webhook url has no port".to_string())?;
+        let host = Self::normalize_host(
+            url.host_str()
+                .ok_or_else(|| "Webhook url has no host".to_string())?,
+        );
+        let addresses: Vec<SocketAddr> = match host.parse::<IpAddr>() {
+            Ok(ip) => vec![SocketAddr::new(ip, port)],
+            Err(_) => tokio::net::lookup_host((host.as_str(), port))
+                .await
+                .map_err(|e| format!("Could not resolve webhook host {}: {}", host, e))?
+                .collect(),
+        };
+        if addresses.is_empty() {
+            return Err("Webhook host did not resolve to any address".to_string());
+        }
+
+        if !self.allowed_hosts.contains(&host)
+            && !addresses.iter().all(|a| Self::is_public_address(a.ip()))
+        {
+            return Err(
+                "Webhook host resolves to a loopback, private or link-local address".to_string(),
+            );
+        }
+        Ok(addresses)
+    }
+
+    /// Whether an address is reachable on the public internet. Loopback, private
+    /// (RFC 1918), shared (RFC 6598), link-local (including cloud metadata services),
+    /// unique-local, unspecified, broadcast and multicast addresses are not. IPv6
+    /// addresses embedding an IPv4 one (mapped, compatible or NAT64) are judged by it.
+    pub fn is_public_address(ip: IpAddr) -> bool {
+        match ip {
+            IpAddr::V4(ip) => {
+                let [first, second, ..] = ip.octets();
+                !(ip.is_loopback()
+                    || ip.is_private()
+                    || ip.is_link_local()
+                    || ip.is_unspecified()
+                    || ip.is_broadcast()
+                    || ip.is_multicast()
+                    || ip.is_documentation()
+                    || first == 0
+                    || (first == 100 && (64..128).contains(&second)))
+            }
+            IpAddr::V6(ip) => match Self::embedded_ipv4(ip) {
+                Some(embedded) => Self::is_public_address(IpAddr::V4(embedded)),
+                None => {
+                    let first = ip.segments()[0];
+                    !(ip.is_loopback()
+                        || ip.is_unspecified()
+                        || ip.is_multicast()
+                        || (first & 0xfe00) == 0xfc00
+                        || (first & 0xffc0) == 0xfe80)
+                }
+            },
+        }
+    }
+
+    /// The IPv4 address carried by an IPv4-mapped (`::ffff:a.b.c.d`), IPv4-compatible
+    /// (`::a.b.c.d`) or NAT64 (`64:ff9b::a.b.c.d`) address.
+    fn embedded_ipv4(ip: Ipv6Addr) -> Option<Ipv4Addr> {
+        if ip.segments()[..6] == [0x64, 0xff9b, 0, 0, 0, 0] {
+            let [.., a, b, c, d] = ip.octets();
+            return Some(Ipv4Addr::new(a, b, c, d));
+        }
+        ip.to_ipv4()
+    }
+
+    fn normalize_host(host: &str) -> String {
+        host.trim()
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .to_lowercase()
+    }
+
+    /// Applies the outcome of one attempt: 2xx succeeds, anything else is retried with
+    /// exponential backoff until `MAX_WEBHOOK_ATTEMPTS` is reached.
+    pub fn record_attempt(
+        mut delivery: WebhookDelivery,
+        outcome: Result<u16, String>,
+        now: DateTime<Utc>,
+    ) -> WebhookDelivery {
+        delivery.attempts += 1;
+        let error = match outcome {
+            Ok(status) => {
+                delivery.response_status = Some(i32::from(status));
+                (!(200..300).contains(&status))
+                    .then(|| format!("Receiver responded with {}", status))
+            }
+            Err(e) => {
+                delivery.response_status = None;
+                Some(e.chars().take(MAX_ERROR_LEN).collect())
+            }
+        };
+
+        match error {
+            None => {
+                delivery.status = WEBHOOK_STATUS_SUCCEEDED.to_string();
+                delivery.error = None;
+                delivery.next_attempt_at = None;
+                delivery.delivered_at = Some(now);
+            }
+            Some(error) if delivery.attempts >= MAX_WEBHOOK_ATTEMPTS => {
+                delivery.status = WEBHOOK_STATUS_FAILED.to_string();
+                delivery.error = Some(error);
+                delivery.next_attempt_at = None;
+            }
+            Some(error) => {
+                delivery.status = WEBHOOK_STATUS_PENDING.to_string();
+                delivery.error = Some(error);
+                delivery.next_attempt_at = Some(now + Self::retry_delay(delivery.attempts));
+            }
+        }
+
+        delivery
+    }
+
+    /// Wait before the retry that follows attempt number `attempts`.
+    pub fn retry_delay(attempts: i32) -> Duration {
+        let exponent = attempts.clamp(1, MAX_WEBHOOK_ATTEMPTS) - 1;
+        Duration::seconds(RETRY_BASE_SECONDS << exponent)
+    }
+
+    /// Hex HMAC-SHA256 of `"{timestamp}.{body}"`. Including the timestamp lets
+    /// receivers reject replayed deliveries.
+    pub fn sign(secret: &str, timestamp: i64, body: &str) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+            .expect("HMAC accepts keys of any length");
+        mac.update(format!("{}.{}", timestamp, body).as_bytes());
+        hex::encode(mac.finalize().into_bytes())
+    }
+
+    pub fn signature_header(secret: &str, timestamp: i64, body: &str) -> String {
+        format!("t={},v1={}", timestamp, Self::sign(secret, timestamp, body))
+    }
+
+    pub fn sync_completed_events(
+        user_id: Uuid,
+        connection: &ProviderConnection,
+        response: &SyncTransactionsResponse,
+    ) -> Vec<WebhookEvent> {
+        let mut events = vec![WebhookEvent::new(
+            user_id,
+            WebhookEventType::SyncCompleted,
+            json!({
+                "connection_id": connection.id,
+                "institution_name": connection.institution_name,
+                "new_transaction_count": response.transactions.len(),
+                "transaction_count": response.metadata.transaction_count,
+                "account_count": response.metadata.account_count,
+                "start_date": response.metadata.start_date,
+                "end_date": response.metadata.end_date,
+            }),
+        )];
+        if !response.transactions.is_empty() {
+            events.push(WebhookEvent::new(
+                user_id,
+                WebhookEventType::TransactionCreated,
+                json!({
+                    "connection_id": connection.id,
+                    "transactions": response.transactions,
+                }),
+            ));
+        }
+        events
+    }
+
+    pub fn sync_failed_events(
+        user_id: Uuid,
+        connection: &ProviderConnection,
+        reason: Option<&str>,
+        requires_reauth: bool,
+    ) -> Vec<WebhookEvent> {
+        let data = json!({
+            "connection_id": connection.id,
+            "institution_name": connection.institution_name,
+            "reason": reason.unwrap_or("the sync could not be completed"),
+        });
+        let mut events = vec![WebhookEvent::new(
+            user_id,
+            WebhookEventType::SyncFailed,
+            data.clone(),
+        )];
+        if requires_reauth {
+            events.push(WebhookEvent::new(
+                user_id,
+                WebhookEventType::ConnectionReauthRequired,
+                data,
+            ));
+        }
+        events
+    }
+
+    /// Budgets whose spending in the user's current month is over the budgeted amount.
+    /// Each budget is reported once per month.
+    pub fn budget_exceeded_events(
+        budgets: &[Budget],
+        views: &ViewMatches,
+        transactions: &[Transaction],
+        calendar: &UserCalendar,
+        today: NaiveDate,
+    ) -> Vec<WebhookEvent> {
+        let (year, month) = calendar.month_of(today);
+        let Some((month_start, _)) = calendar.month_range(year, month) else {
+            return Vec::new();
+        };
+        let month_key = calendar.month_key(today);
+
+        budgets
+            .iter()
+            .filter(|budget| budget.amount > Decimal::ZERO)
+            .filter_map(|budget| {
+                let spent: Decimal = transactions
+                    .iter()
+                    .filter(|t| t.date >= month_start && t.date <= today)
+                    .filter(|t| AlertService::budget_includes(budget, t, views))
+                    .map(|t| t.amount)
+                    .sum();
+                (spent > budget.amount).then(|| {
+                    WebhookEvent::new(
+                        budget.user_id,
+                        WebhookEventType::BudgetExceeded,
+                        json!({
+                            "budget_id": budget.id,
+                            "category": budget.category,
+                            "month": month_key,
+                            "budgeted": budget.amount,
+                            "spent": spent,
+                        }),
+                    )
+                    .with_dedup_key(format!("budget.exceeded:{}:{}", budget.id, month_key))
+                })
+            })
+            .collect()
+    }
+
+    /// Checks the user's budgets after new data arrives. Skipped entirely when no
+    /// endpoint listens for `budget.exceeded`.
+    pub async fn publish_budget_exceeded<R: DatabaseRepository + ?Sized>(
+        &self,
+        repository: &R,
+        user_id: Uuid,
+        now: DateTime<Utc>,
+    ) -> Result<usize, String> {
+        let endpoints = repository
+            .get_webhook_endpoints_for_user(&user_id)
+            .await
+            .map_err(|e| e.to_string())?;
+        if !endpoints
+            .iter()
+            .any(|e| e.subscribes_to(WebhookEventType::BudgetExceeded))
+        {
+            return Ok(0);
+        }
+
+        let calendar = UserSettingsService::calendar(repository, user_id).await?;
+        let today = calendar.local_date(now);
+        let (year, month) = calendar.month_of(today);
+        let (month_start, _) = calendar
+            .month_range(year, month)
+            .ok_or_else(|| format!("No month range for {}", today))?;
+        let budgets = repository
+            .get_budgets_for_user(user_id)
+            .await
+            .map_err(|e| e.to_string())?;
+        let mut transactions = repository
+            .get_transactions_by_date_range_for_user(&user_id, month_start, today)
+            .await
+            .map_err(|e| e.to_string())?;
+        transactions.retain(|t| !t.excluded_from_analytics);
+        let view_ids: Vec<Uuid> = budgets.iter().filter_map(|b| b.saved_view_id).collect();
+        let views = if view_ids.is_empty() {
+            ViewMatches::default()
+        } else {
+            SavedViewService::matching_transaction_ids(
+                repository,
+                user_id,
+                &view_ids,
+                &calendar,
+                (month_start, today),
+            )
+            .await?
+        };
+
+        let mut queued = 0;
+        for event in Self::budget_exceeded_events(&budgets, &views, &transactions, &calendar, today)
+        {
+            queued += self.publish(repository, &event).await?;
+        }
+        Ok(queued)
+    }
+
+    async fn owned_endpoint<R: DatabaseRepository + ?Sized>(
+        repository: &R,
+        user_id: Uuid,
+        endpoint_id: Uuid,
+    ) -> Result<WebhookEndpoint, String> {
+        repository
+            .get_webhook_endpoint_by_id(&endpoint_id)
+            .await
+            .map_err(|e| e.to_string())?
+            .filter(|endpoint| endpoint.user_id == user_id)
+            .ok_or_else(|| "Webhook endpoint not found".to_string())
+    }
+
+    /// Validates a request and applies it to `endpoint`, keeping its id and secret.
+    pub fn build_endpoint(
+        mut endpoint: WebhookEndpoint,
+        request: &WebhookEndpointRequest,
+    ) -> Result<WebhookEndpoint, String> {
+        let url = request.url.trim();
+        let parsed = reqwest::Url::parse(url)
+            .map_err(|_| format!("Invalid webhook url: {}", request.url))?;
+        if !matches!(parsed.scheme(), "http" | "https") || parsed.host_str().is_none() {
+            return Err("Invalid webhook url: must be an http or https URL".to_string());
+        }
+
+        let description = request
+            .description
+            .as_deref()
+            .map(str::trim)
+            .filter(|d| !d.is_empty());
+        if description.is_some_and(|d| d.chars().count() > MAX_DESCRIPTION_LEN) {
+            return Err(format!(
+                "Invalid description: must be at most {} characters",
+                MAX_DESCRIPTION_LEN
+            ));
+        }
+
+        if request.events.is_empty() {
+            return Err("Invalid events: at least one event is required".to_string());
+        }
+        if request.events.contains(&WebhookEventType::Test) {
+            return Err("Invalid events: webhook.test cannot be subscribed to".to_string());
+        }
+        let mut events = request.events.clone();
+        events.sort_by_key(|event| event.as_str());
+        events.dedup();
+
+        endpoint.url = url.to_string();
+        endpoint.description = description.map(str::to_string);
+        endpoint.events = events;
+        endpoint.enabled = request.enabled.unwrap_or(endpoint.enabled);
+        Ok(endpoint)
+    }
+}
@@ -0,0 +1,205 @@
+use crate::models::access_token::{CreateAccessTokenRequest, PersonalAccessToken, TokenScope};
+use crate::services::access_token_service::{AccessTokenService, ACCESS_TOKEN_PREFIX};
+use crate::services::repository_service::MockDatabaseRepository;
+use axum::http::Method;
+use chrono::{Duration, Utc};
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+fn stored_token(scopes: Vec<TokenScope>) -> PersonalAccessToken {
+    PersonalAccessToken {
+        id: Uuid::new_v4(),
+        user_id: Uuid::new_v4(),
+        name: "Report script".to_string(),
+        token_prefix: "smr_pat_abcd".to_string(),
+        scopes,
+        expires_at: None,
+        last_used_at: None,
+        revoked_at: None,
+        created_at: Utc::now(),
+    }
+}
+
+#[test]
+fn given_endpoints_when_resolving_required_scope_then_maps_reads_writes_and_session_only_routes() {
+    assert_eq!(
+        TokenScope::required_for(&Method::GET, "/api/transactions"),
+        Some(TokenScope::ReadTransactions)
+    );
+    assert_eq!(
+        TokenScope::required_for(&Method::GET, "/api/transactions/export"),
+        Some(TokenScope::ReadTransactions)
+    );
+    assert_eq!(
+        TokenScope::required_for(&Method::GET, "/api/analytics/categories"),
+        Some(TokenScope::ReadAnalytics)
+    );
+    assert_eq!(
+        TokenScope::required_for(&Method::PUT, "/api/budgets/123"),
+        Some(TokenScope::WriteBudgets)
+    );
+    assert_eq!(
+        TokenScope::required_for(&Method::POST, "/api/providers/sync-transactions"),
+        Some(TokenScope::Sync)
+    );
+    assert_eq!(
+        TokenScope::required_for(&Method::GET, "/api/providers/sync/events"),
+        Some(TokenScope::Sync)
+    );
+    assert_eq!(
+        TokenScope::required_for(&Method::GET, "/api/transactionsx"),
+        None
+    );
+    assert_eq!(
+        TokenScope::required_for(&Method::GET, "/api/auth/tokens"),
+        None
+    );
+    assert_eq!(
+        TokenScope::required_for(&Method::DELETE, "/api/auth/account"),
+        None
+    );
+    assert_eq!(
+        TokenScope::required_for(&Method::DELETE, "/api/transactions/123"),
+        None
+    );
+}
+
+#[tokio::test]
+async fn given_valid_request_when_creating_token_then_stores_only_hash_and_dedupes_scopes() {
+    let stored_hash = Arc::new(Mutex::new(None));
+    let captured = stored_hash.clone();
+    let mut mock_db = MockDatabaseRepository::new();
+    mock_db
+        .expect_create_personal_access_token()
+        .times(1)
+        .returning(move |_, hash| {
+            *captured.lock().unwrap() = Some(hash.to_string());
+            Box::pin(async { Ok(()) })
+        });
+    let user_id = Uuid::new_v4();
+    let request = CreateAccessTokenRequest {
+        name: "  Report script ".to_string(),
+        scopes: vec![
+            TokenScope::ReadAnalytics,
+            TokenScope::ReadTransactions,
+            TokenScope::ReadAnalytics,
+        ],
+        expires_in_days: Some(30),
+    };
+
+    let created = AccessTokenService::new()
+        .create(&mock_db, user_id, &request)
+        .await
+        .unwrap();
+
+    assert!(created.token.starts_with(ACCESS_TOKEN_PREFIX));
+    assert!(created
+        .token
+        .starts_with(&created.access_token.token_prefix));
+    assert_eq!(
+        stored_hash.lock().unwrap().as_deref(),
+        Some(AccessTokenService::hash_token(&created.token).as_str())
+    );
+    assert_ne!(
+        stored_hash.lock().unwrap().as_deref(),
+        Some(created.token.as_str())
+    );
+    assert_eq!(created.access_token.user_id, user_id);
+    assert_eq!(created.access_token.name, "Report script");
+    assert_eq!(
+        created.access_token.scopes,
+        vec![TokenScope::ReadAnalytics, TokenScope::ReadTransactions]
+    );
+    let expires_at = created.access_token.expires_at.unwrap();
+    assert!(expires_at > Utc::now() + Duration::days(29));
+}
+
+#[tokio::test]
+async fn given_missing_scopes_or_name_when_creating_token_then_rejects_without_storing() {
+    let mut mock_db = MockDatabaseRepository::new();
+    mock_db.expect_create_personal_access_token().never();
+    let service = AccessTokenService::new();
+
+    let no_scopes = CreateAccessTokenRequest {
+        name: "Script".to_string(),
+        scopes: vec![],
+        expires_in_days: None,
+    };
+    let blank_name = CreateAccessTokenRequest {
+        name: "   ".to_string(),
+        scopes: vec![TokenScope::Sync],
+        expires_in_days: None,
+    };
+    let zero_days = CreateAccessTokenRequest {
+        name: "Script".to_string(),
+        scopes: vec![TokenScope::Sync],
+        expires_in_days: Some(0),
+    };
+    let too_many_days = CreateAccessTokenRequest {
+        name: "Script".to_string(),
+        scopes: vec![TokenScope::Sync],
+        expires_in_days: Some(u32::MAX),
+    };
+
+    for request in [no_scopes, blank_name, zero_days, too_many_days] {
+        let error = service
+            .create(&mock_db, Uuid::new_v4(), &request)
+            .await
+            .unwrap_err();
+        assert!(error.starts_with("Invalid"), "{}", error);
+    }
+}
+
+#[tokio::test]
+async fn given_revoked_or_expired_token_when_authenticating_then_returns_none() {
+    let mut revoked = stored_token(vec![TokenScope::Sync]);
+    revoked.revoked_at = Some(Utc::now() - Duration::hours(1));
+    let mut expired = stored_token(vec![TokenScope::Sync]);
+    expired.expires_at = Some(Utc::now() - Duration::minutes(1));
+
+    for token in [revoked, expired] {
+        let mut mock_db = MockDatabaseRepository::new();
+        mock_db
+            .expect_get_personal_access_token_by_hash()
+            .returning(move |_| {
+                let token = token.clone();
+                Box::pin(async move { Ok(Some(token)) })
+            });
+        mock_db.expect_touch_personal_access_token().never();
+
+        let result = AccessTokenService::new()
+            .authenticate(&mock_db, "smr_pat_whatever")
+            .await
+            .unwrap();
+
+        assert!(result.is_none());
+    }
+}
+
+#[tokio::test]
+async fn given_active_token_when_authenticating_then_looks_up_by_hash_and_records_use() {
+    let token = stored_token(vec![TokenScope::ReadTransactions]);
+    let token_id = token.id;
+    let plain = "smr_pat_0123456789";
+    let expected_hash = AccessTokenService::hash_token(plain);
+    let mut mock_db = MockDatabaseRepository::new();
+    mock_db
+        .expect_get_personal_access_token_by_hash()
+        .withf(move |hash| hash == expected_hash)
+        .returning(move |_| {
+            let token = token.clone();
+            Box::pin(async move { Ok(Some(token)) })
+        });
+    mock_db
+        .expect_touch_personal_access_token()
+        .withf(move |id| *id == token_id)
+        .times(1)
+        .returning(|_| Box::pin(async { Ok(()) }));
+
+    let result = AccessTokenService::new()
+        .authenticate(&mock_db, plain)
+        .await
+        .unwrap();
+
+    assert_eq!(result.map(|t| t.id), Some(token_id));
+}
@@ -0,0 +1,436 @@
+use crate::models::account::Account;
+use crate::models::alert::{
+    AlertHistoryEntry, AlertRule, AlertRuleRequest, AlertRuleType, FiredAlert,
+};
+use crate::models::budget::Budget;
+use crate::models::notification::Notification;
+use crate::models::saved_view::ViewMatches;
+use crate::models::user_settings::UserCalendar;
+use crate::services::alert_service::{AlertCandidate, AlertInputs, AlertService};
+use crate::services::repository_service::MockDatabaseRepository;
+use crate::services::NotificationService;
+use crate::test_fixtures::TestFixtures;
+use chrono::{Duration, NaiveDate, TimeZone, Utc};
+use chrono_tz::Tz;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+fn rule(rule_type: AlertRuleType, threshold: Option<Decimal>, percentages: Vec<i32>) -> AlertRule {
+    AlertRule {
+        id: Uuid::new_v4(),
+        user_id: Uuid::new_v4(),
+        rule_type: rule_type.as_str().to_string(),
+        threshold,
+        percentages,
+        account_id: None,
+        category: None,
+        saved_view_id: None,
+        cooldown_hours: 24,
+        enabled: true,
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+    }
+}
+
+fn budget(category: &str, amount: Decimal) -> Budget {
+    Budget {
+        id: Uuid::new_v4(),
+        user_id: Uuid::new_v4(),
+        category: category.to_string(),
+        amount,
+        saved_view_id: None,
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+    }
+}
+
+fn account(name: &str, account_type: &str, balance: Decimal) -> Account {
+    Account {
+        id: Uuid::new_v4(),
+        user_id: None,
+        provider_account_id: None,
+        provider_connection_id: None,
+        name: name.to_string(),
+        account_type: account_type.to_string(),
+        balance_current: Some(balance),
+        iso_currency_code: "USD".to_string(),
+        mask: None,
+        institution_name: None,
+    }
+}
+
+fn candidate(rule_id: Uuid, repeatable: bool) -> AlertCandidate {
+    AlertCandidate {
+        rule_id,
+        rule_type: AlertRuleType::LowBalance,
+        dedup_key: "balance:checking".to_string(),
+        repeatable,
+        title: "Low balance".to_string(),
+        body: "Below threshold".to_string(),
+    }
+}
+
+#[test]
+fn given_spending_past_several_percentages_when_evaluating_budget_rule_then_reports_highest_only() {
+    let groceries = budget("Groceries", dec!(200));
+    let budgets = vec![groceries.clone()];
+    let transactions = vec![
+        TestFixtures::transaction_on(date(2024, 3, 2), dec!(120), "GROCERIES", "Market"),
+        TestFixtures::transaction_on(date(2024, 3, 9), dec!(90), "GROCERIES", "Market"),
+        TestFixtures::transaction_on(date(2024, 2, 20), dec!(500), "GROCERIES", "Market"),
+    ];
+    let inputs = AlertInputs {
+        today: date(2024, 3, 10),
+        calendar: UserCalendar::default(),
+        transactions: &transactions,
+        budgets: &budgets,
+        accounts: &[],
+        views: &ViewMatches::default(),
+    };
+    let rules = vec![rule(
+        AlertRuleType::BudgetThreshold,
+        None,
+        vec![50, 80, 100],
+    )];
+
+    let candidates = AlertService::evaluate_rules(&rules, &inputs);
+
+    assert_eq!(candidates.len(), 1);
+    assert_eq!(
+        candidates[0].dedup_key,
+        format!("budget:{}:2024-03:100", groceries.id)
+    );
+    assert_eq!(candidates[0].title, "Groceries budget at 100%");
+    assert!(!candidates[0].repeatable);
+}
+
+#[test]
+fn given_month_starting_on_the_fifteenth_when_evaluating_budget_rule_then_counts_the_users_month() {
+    let rent = budget("Rent", dec!(1000));
+    let budgets = vec![rent.clone()];
+    let transactions = vec![
+        TestFixtures::transaction_on(date(2024, 2, 20), dec!(600), "RENT", "Landlord"),
+        TestFixtures::transaction_on(date(2024, 3, 10), dec!(300), "RENT", "Landlord"),
+        TestFixtures::transaction_on(date(2024, 2, 10), dec!(900), "RENT", "Landlord"),
+    ];
+    let inputs = AlertInputs {
+        today: date(2024, 3, 10),
+        calendar: UserCalendar::new(Tz::UTC, 15),
+        transactions: &transactions,
+        budgets: &budgets,
+        accounts: &[],
+        views: &ViewMatches::default(),
+    };
+    let rules = vec![rule(AlertRuleType::BudgetThreshold, None, vec![50, 80])];
+
+    let candidates = AlertService::evaluate_rules(&rules, &inputs);
+
+    assert_eq!(candidates.len(), 1);
+    assert_eq!(
+        candidates[0].dedup_key,
+        format!("budget:{}:2024-02:80", rent.id)
+    );
+    assert!(candidates[0]
+        .body
+        .contains("900.00 of your 1000.00 Rent budget for 2024-02"));
+}
+
+#[test]
+fn given_recent_and_old_transactions_when_evaluating_large_transaction_rule_then_flags_recent_spending_above_threshold(
+) {
+    let large = TestFixtures::transaction_on(date(2024, 3, 8), dec!(900), "SHOPPING", "Apple");
+    let mut refund =
+        TestFixtures::transaction_on(date(2024, 3, 8), dec!(-900), "SHOPPING", "Apple");
+    refund.category_primary = "TRANSFER_IN".to_string();
+    let transactions = vec![
+        large.clone(),
+        refund,
+        TestFixtures::transaction_on(date(2024, 3, 8), dec!(40), "SHOPPING", "Target"),
+        TestFixtures::transaction_on(date(2024, 2, 1), dec!(1200), "SHOPPING", "Apple"),
+    ];
+    let inputs = AlertInputs {
+        today: date(2024, 3, 10),
+        calendar: UserCalendar::default(),
+        transactions: &transactions,
+        budgets: &[],
+        accounts: &[],
+        views: &ViewMatches::default(),
+    };
+    let rules = vec![rule(
+        AlertRuleType::LargeTransaction,
+        Some(dec!(500)),
+        vec![],
+    )];
+
+    let candidates = AlertService::evaluate_rules(&rules, &inputs);
+
+    assert_eq!(candidates.len(), 1);
+    assert_eq!(candidates[0].dedup_key, format!("transaction:{}", large.id));
+    assert_eq!(candidates[0].title, "Large transaction at Apple");
+}
+
+#[test]
+fn given_known_and_unknown_merchants_when_evaluating_new_merchant_rule_then_flags_first_charge_only(
+) {
+    let first_charge =
+        TestFixtures::transaction_on(date(2024, 3, 9), dec!(250), "TRAVEL", "Harbor Hotel");
+    let transactions = vec![
+        TestFixtures::transaction_on(date(2023, 11, 4), dec!(60), "SHOPPING", "Hardware Co"),
+        TestFixtures::transaction_on(date(2024, 3, 9), dec!(300), "SHOPPING", "Hardware Co"),
+        first_charge.clone(),
+        TestFixtures::transaction_on(date(2024, 3, 9), dec!(12), "FOOD", "Corner Cafe"),
+    ];
+    let inputs = AlertInputs {
+        today: date(2024, 3, 10),
+        calendar: UserCalendar::default(),
+        transactions: &transactions,
+        budgets: &[],
+        accounts: &[],
+        views: &ViewMatches::default(),
+    };
+    let rules = vec![rule(AlertRuleType::NewMerchant, Some(dec!(100)), vec![])];
+
+    let candidates = AlertService::evaluate_rules(&rules, &inputs);
+
+    assert_eq!(candidates.len(), 1);
+    assert_eq!(
+        candidates[0].dedup_key,
+        format!("transaction:{}", first_charge.id)
+    );
+}
+
+#[test]
+fn given_cash_and_credit_accounts_when_evaluating_low_balance_rule_then_checks_cash_accounts() {
+    let checking = account("Everyday Checking", "depository", dec!(150));
+    let accounts = vec![
+        checking.clone(),
+        account("Savings", "depository", dec!(5000)),
+        account("Sapphire Card", "credit", dec!(20)),
+    ];
+    let inputs = AlertInputs {
+        today: date(2024, 3, 10),
+        calendar: UserCalendar::default(),
+        transactions: &[],
+        budgets: &[],
+        accounts: &accounts,
+        views: &ViewMatches::default(),
+    };
+    let rules = vec![rule(AlertRuleType::LowBalance, Some(dec!(500)), vec![])];
+
+    let candidates = AlertService::evaluate_rules(&rules, &inputs);
+
+    assert_eq!(candidates.len(), 1);
+    assert_eq!(candidates[0].dedup_key, format!("balance:{}", checking.id));
+    assert_eq!(candidates[0].title, "Low balance in Everyday Checking");
+    assert!(candidates[0].repeatable);
+}
+
+#[test]
+fn given_previous_firing_when_checking_cooldown_then_only_repeatable_alerts_refire_after_cooldown()
+{
+    let rule_id = Uuid::new_v4();
+    let now = Utc.with_ymd_and_hms(2024, 3, 10, 12, 0, 0).unwrap();
+    let history = vec![AlertHistoryEntry {
+        rule_id,
+        dedup_key: "balance:checking".to_string(),
+        last_fired_at: now - Duration::hours(6),
+    }];
+
+    assert!(!AlertService::should_fire(
+        &candidate(rule_id, true),
+        24,
+        &history,
+        now
+    ));
+    assert!(AlertService::should_fire(
+        &candidate(rule_id, true),
+        6,
+        &history,
+        now
+    ));
+    assert!(!AlertService::should_fire(
+        &candidate(rule_id, false),
+        0,
+        &history,
+        now
+    ));
+    assert!(AlertService::should_fire(
+        &candidate(Uuid::new_v4(), false),
+        24,
+        &history,
+        now
+    ));
+}
+
+#[test]
+fn given_invalid_requests_when_building_rule_then_rejects_with_reason() {
+    let request = |rule_type, threshold, percentages| AlertRuleRequest {
+        rule_type,
+        threshold,
+        percentages,
+        account_id: None,
+        category: None,
+        saved_view_id: None,
+        cooldown_hours: None,
+        enabled: None,
+    };
+    let build = |r: &AlertRuleRequest| {
+        AlertService::build_rule(Uuid::new_v4(), Uuid::new_v4(), r, Utc::now())
+    };
+
+    assert_eq!(
+        build(&request(AlertRuleType::LargeTransaction, None, None)).unwrap_err(),
+        "threshold must be greater than zero"
+    );
+    assert_eq!(
+        build(&request(
+            AlertRuleType::BudgetThreshold,
+            None,
+            Some(vec![0])
+        ))
+        .unwrap_err(),
+        "percentages must be between 1 and 1000"
+    );
+    let defaults = build(&request(AlertRuleType::BudgetThreshold, None, None)).unwrap();
+    assert_eq!(defaults.percentages, vec![80, 100]);
+    assert_eq!(defaults.cooldown_hours, 24);
+    assert!(defaults.enabled);
+}
+
+#[tokio::test]
+async fn given_large_transaction_already_alerted_when_evaluating_after_sync_then_fires_only_new_ones(
+) {
+    let user_id = Uuid::new_v4();
+    let now = Utc::now();
+    let today = now.date_naive();
+    let large_rule = rule(AlertRuleType::LargeTransaction, Some(dec!(500)), vec![]);
+    let rule_id = large_rule.id;
+    let seen = TestFixtures::transaction_on(today, dec!(800), "SHOPPING", "Apple");
+    let fresh = TestFixtures::transaction_on(today, dec!(650), "TRAVEL", "Airline");
+    let seen_key = format!("transaction:{}", seen.id);
+    let fresh_key = format!("transaction:{}", fresh.id);
+    let transactions = vec![seen, fresh];
+
+    let mut mock_db = MockDatabaseRepository::new();
+    mock_db
+        .expect_get_alert_rules_for_user()
+        .returning(move |_| {
+            let rules = vec![large_rule.clone()];
+            Box::pin(async move { Ok(rules) })
+        });
+    mock_db
+        .expect_get_user_settings()
+        .returning(|_| Box::pin(async { Ok(None) }));
+    mock_db
+        .expect_get_transactions_by_date_range_for_user()
+        .returning(move |_, _, _| {
+            let transactions = transactions.clone();
+            Box::pin(async move { Ok(transactions) })
+        });
+    mock_db.expect_get_budgets_for_user().never();
+    mock_db.expect_get_accounts_for_user().never();
+    mock_db
+        .expect_get_alert_history_for_user()
+        .returning(move |_| {
+            let history = vec![AlertHistoryEntry {
+                rule_id,
+                dedup_key: seen_key.clone(),
+                last_fired_at: now - Duration::days(1),
+            }];
+            Box::pin(async move { Ok(history) })
+        });
+    let inserted: Arc<Mutex<Vec<FiredAlert>>> = Arc::default();
+    let recorded = inserted.clone();
+    mock_db
+        .expect_insert_fired_alert()
+        .times(1)
+        .withf(|_, _, refire_before| refire_before.is_none())
+        .returning(move |_, alert, _| {
+            recorded.lock().unwrap().push(alert.clone());
+            Box::pin(async { Ok(true) })
+        });
+    mock_db
+        .expect_get_notification_preferences()
+        .returning(|_| Box::pin(async { Ok(vec![]) }));
+    mock_db
+        .expect_insert_notification()
+        .times(1)
+        .returning(|event| {
+            let stored = Notification {
+                id: Uuid::new_v4(),
+                event_type: event.event_type.as_str().to_string(),
+                title: event.title.clone(),
+                body: event.body.clone(),
+                read_at: None,
+                created_at: Utc::now(),
+            };
+            Box::pin(async move { Ok(stored) })
+        });
+    let service = AlertService::new(Arc::new(NotificationService::new(Vec::new())));
+
+    let fired = service
+        .evaluate_for_user(&mock_db, user_id, now)
+        .await
+        .unwrap();
+
+    assert_eq!(fired.len(), 1);
+    assert_eq!(fired[0].dedup_key, fresh_key);
+    assert_eq!(
+        inserted.lock().unwrap()[0].title,
+        "Large transaction at Airline"
+    );
+}
+
+#[tokio::test]
+async fn given_concurrent_evaluation_already_fired_alert_when_inserting_then_does_not_notify_again()
+{
+    let user_id = Uuid::new_v4();
+    let now = Utc::now();
+    let large_rule = rule(AlertRuleType::LargeTransaction, Some(dec!(500)), vec![]);
+    let transactions = vec![TestFixtures::transaction_on(
+        now.date_naive(),
+        dec!(650),
+        "TRAVEL",
+        "Airline",
+    )];
+
+    let mut mock_db = MockDatabaseRepository::new();
+    mock_db
+        .expect_get_alert_rules_for_user()
+        .returning(move |_| {
+            let rules = vec![large_rule.clone()];
+            Box::pin(async move { Ok(rules) })
+        });
+    mock_db
+        .expect_get_user_settings()
+        .returning(|_| Box::pin(async { Ok(None) }));
+    mock_db
+        .expect_get_transactions_by_date_range_for_user()
+        .returning(move |_, _, _| {
+            let transactions = transactions.clone();
+            Box::pin(async move { Ok(transactions) })
+        });
+    mock_db
+        .expect_get_alert_history_for_user()
+        .returning(|_| Box::pin(async { Ok(vec![]) }));
+    mock_db
+        .expect_insert_fired_alert()
+        .times(1)
+        .returning(|_, _, _| Box::pin(async { Ok(false) }));
+    mock_db.expect_get_notification_preferences().never();
+    mock_db.expect_insert_notification().never();
+    let service = AlertService::new(Arc::new(NotificationService::new(Vec::new())));
+
+    let fired = service
+        .evaluate_for_user(&mock_db, user_id, now)
+        .await
+        .unwrap();
+
+    assert!(fired.is_empty());
+}
@@ -0,0 +1,241 @@
+use crate::models::analytics_query::{
+    AnalyticsQueryFilters, AnalyticsQueryRequest, AnalyticsQueryRow, QueryDimension, QueryFlow,
+    QueryMeasure, QueryRates,
+};
+use crate::models::currency::FxRate;
+use crate::models::user_settings::UserSettings;
+use crate::services::analytics_query_service::AnalyticsQueryService;
+use crate::services::currency_service::FxConverter;
+use crate::services::repository_service::MockDatabaseRepository;
+use chrono::NaiveDate;
+use rust_decimal_macros::dec;
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+fn request(group_by: Vec<QueryDimension>, measures: Vec<QueryMeasure>) -> AnalyticsQueryRequest {
+    AnalyticsQueryRequest {
+        group_by,
+        measures,
+        filters: AnalyticsQueryFilters::default(),
+        limit: None,
+    }
+}
+
+#[test]
+fn given_invalid_shapes_when_validating_then_rejects_with_reason() {
+    let too_many = request(
+        vec![
+            QueryDimension::Category,
+            QueryDimension::Merchant,
+            QueryDimension::Month,
+            QueryDimension::Tag,
+        ],
+        vec![],
+    );
+    assert!(too_many.validate().unwrap_err().contains("At most 3"));
+
+    let duplicate = request(
+        vec![QueryDimension::Month, QueryDimension::Month],
+        vec![QueryMeasure::Sum],
+    );
+    assert_eq!(
+        duplicate.validate().unwrap_err(),
+        "Duplicate dimension: month"
+    );
+
+    let mut reversed = request(vec![QueryDimension::Category], vec![]);
+    reversed.filters.start_date = chrono::NaiveDate::from_ymd_opt(2024, 3, 1);
+    reversed.filters.end_date = chrono::NaiveDate::from_ymd_opt(2024, 2, 1);
+    assert!(reversed.validate().is_err());
+
+    let mut unbounded = request(vec![QueryDimension::Category], vec![]);
+    unbounded.limit = Some(0);
+    assert!(unbounded.validate().is_err());
+}
+
+#[test]
+fn given_json_body_when_deserializing_then_defaults_measures_and_filters() {
+    let parsed: AnalyticsQueryRequest = serde_json::from_str(
+        r#"{"group_by": ["weekday", "tag"], "filters": {"flow": "spending", "tags": ["trip"]}}"#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        parsed.group_by,
+        vec![QueryDimension::Weekday, QueryDimension::Tag]
+    );
+    assert_eq!(parsed.effective_measures(), vec![QueryMeasure::Sum]);
+    assert_eq!(parsed.filters.flow, Some(QueryFlow::Spending));
+    assert_eq!(parsed.effective_limit(), 500);
+    assert!(serde_json::from_str::<AnalyticsQueryRequest>(r#"{"group_by": ["payee"]}"#).is_err());
+}
+
+#[test]
+fn given_filters_when_building_sql_then_binds_values_instead_of_splicing_them() {
+    let mut query = request(
+        vec![QueryDimension::Category, QueryDimension::Institution],
+        vec![QueryMeasure::Sum, QueryMeasure::Median],
+    );
+    query.filters.categories = vec!["FOOD'; DROP TABLE transactions; --".to_string()];
+    query.filters.merchants = vec!["Cafe".to_string()];
+    query.filters.min_amount = Some(dec!(5));
+    query.filters.flow = Some(QueryFlow::Income);
+
+    let builder = AnalyticsQueryService::build_sql(
+        Uuid::new_v4(),
+        &query,
+        &[Uuid::new_v4()],
+        &QueryRates::new("USD"),
+    );
+    let sql = builder.sql();
+
+    assert!(!sql.contains("DROP TABLE"));
+    assert!(!sql.contains("Cafe"));
+    assert!(sql.contains("t.category_primary = ANY($"));
+    assert!(sql.contains("t.account_id = ANY($"));
+    assert!(sql.contains("LEFT JOIN provider_connections pc"));
+    assert!(!sql.contains("transaction_tags"));
+    assert!(sql.contains("percentile_cont(0.5)"));
+    assert!(sql.contains("GROUP BY 1, 2 "));
+    assert!(sql.contains("ORDER BY sum DESC NULLS LAST, 1, 2 "));
+    assert!(sql.contains("AND (t.amount < 0 OR"));
+    assert!(sql.contains("AND (t.amount * COALESCE(fx.rate, 1)) >= $"));
+    assert!(sql.contains("ORDER BY (t.amount * COALESCE(fx.rate, 1))"));
+}
+
+#[test]
+fn given_time_dimension_first_when_building_sql_then_orders_chronologically() {
+    let monthly = request(
+        vec![QueryDimension::Month, QueryDimension::Tag],
+        vec![QueryMeasure::Count],
+    );
+    let rates = QueryRates::new("USD");
+    let sql = AnalyticsQueryService::build_sql(Uuid::new_v4(), &monthly, &[], &rates).into_sql();
+    assert!(sql.contains("ORDER BY 1, count DESC NULLS LAST, 2 "));
+    assert!(sql.contains("LEFT JOIN transaction_tags tt"));
+
+    let weekly = request(vec![QueryDimension::Weekday], vec![]);
+    let sql = AnalyticsQueryService::build_sql(Uuid::new_v4(), &weekly, &[], &rates).into_sql();
+    assert!(sql.contains("ORDER BY MIN(EXTRACT(ISODOW FROM t.date)), sum DESC NULLS LAST "));
+
+    let overall = request(vec![], vec![]);
+    let sql = AnalyticsQueryService::build_sql(Uuid::new_v4(), &overall, &[], &rates).into_sql();
+    assert!(!sql.contains("GROUP BY"));
+    assert!(sql.contains("HAVING COUNT(*) > 0"));
+}
+
+#[tokio::test]
+async fn given_more_rows_than_limit_when_running_then_truncates_and_flags_it() {
+    let mut query = request(vec![QueryDimension::Merchant], vec![QueryMeasure::Sum]);
+    query.limit = Some(2);
+
+    let mut mock_db = MockDatabaseRepository::new();
+    mock_db
+        .expect_get_user_settings()
+        .returning(|_| Box::pin(async { Ok(None) }));
+    mock_db
+        .expect_get_analytics_query_currency_days()
+        .returning(|_, _, _, _| Box::pin(async { Ok(vec![]) }));
+    mock_db.expect_get_fx_rates().never();
+    mock_db
+        .expect_run_analytics_query()
+        .times(1)
+        .returning(|_, _, _, _| {
+            let rows = ["A", "B", "C"]
+                .iter()
+                .map(|merchant| AnalyticsQueryRow {
+                    group: BTreeMap::from([("merchant".to_string(), merchant.to_string())]),
+                    currency: "USD".to_string(),
+                    sum: Some(dec!(10)),
+                    count: None,
+                    avg: None,
+                    median: None,
+                })
+                .collect();
+            Box::pin(async move { Ok(rows) })
+        });
+
+    let response = AnalyticsQueryService::run(&mock_db, Uuid::new_v4(), &query, &[])
+        .await
+        .unwrap();
+
+    assert_eq!(response.rows.len(), 2);
+    assert!(response.truncated);
+    let body = serde_json::to_value(&response.rows[0]).unwrap();
+    assert_eq!(body["group"]["merchant"], "A");
+    assert!(body.get("count").is_none());
+}
+
+#[test]
+fn given_currency_days_when_building_rates_then_keeps_only_days_with_a_rate() {
+    let converter = FxConverter::new(
+        "EUR",
+        &[FxRate {
+            rate_date: date(2024, 3, 1),
+            base_currency: "EUR".to_string(),
+            quote_currency: "USD".to_string(),
+            rate: dec!(1.25),
+        }],
+    );
+    let days = vec![
+        ("USD".to_string(), date(2024, 3, 4)),
+        ("JPY".to_string(), date(2024, 3, 4)),
+    ];
+
+    let rates = AnalyticsQueryService::query_rates(&converter, &days);
+
+    assert_eq!(rates.reporting_currency, "EUR");
+    assert_eq!(rates.currencies, vec!["USD".to_string()]);
+    assert_eq!(rates.dates, vec![date(2024, 3, 4)]);
+    assert_eq!(rates.rates, vec![dec!(0.8)]);
+}
+
+#[tokio::test]
+async fn given_transactions_in_other_currencies_when_running_then_queries_with_rates_into_reporting_currency(
+) {
+    let query = request(vec![QueryDimension::Category], vec![QueryMeasure::Median]);
+
+    let mut mock_db = MockDatabaseRepository::new();
+    mock_db.expect_get_user_settings().returning(|_| {
+        let settings = UserSettings {
+            reporting_currency: "GBP".to_string(),
+            ..UserSettings::default()
+        };
+        Box::pin(async move { Ok(Some(settings)) })
+    });
+    mock_db
+        .expect_get_analytics_query_currency_days()
+        .withf(|_, _, _, reporting_currency| reporting_currency == "GBP")
+        .returning(|_, _, _, _| {
+            Box::pin(async { Ok(vec![("USD".to_string(), date(2024, 3, 4))]) })
+        });
+    mock_db.expect_get_fx_rates().returning(|_, _| {
+        let rates = vec![FxRate {
+            rate_date: date(2024, 3, 1),
+            base_currency: "USD".to_string(),
+            quote_currency: "GBP".to_string(),
+            rate: dec!(0.79),
+        }];
+        Box::pin(async move { Ok(rates) })
+    });
+    mock_db
+        .expect_run_analytics_query()
+        .times(1)
+        .withf(|_, _, _, rates| {
+            rates.reporting_currency == "GBP"
+                && rates.currencies == ["USD"]
+                && rates.rates == [dec!(0.79)]
+        })
+        .returning(|_, _, _, _| Box::pin(async { Ok(vec![]) }));
+
+    let response = AnalyticsQueryService::run(&mock_db, Uuid::new_v4(), &query, &[])
+        .await
+        .unwrap();
+
+    assert!(response.rows.is_empty());
+    assert!(!response.truncated);
+}
@@ -1,5 +1,6 @@
 use crate::models::analytics::CategorySpending;
 use crate::models::transaction::Transaction;
+use crate::models::user_settings::UserCalendar;
 use crate::services::analytics_service::AnalyticsService;
 use chrono::{Datelike, NaiveDate};
 use rust_decimal::Decimal;
@@ -21,6 +22,7 @@ fn create_test_transaction(
         provider_account_id: None,
         provider_transaction_id: None,
         amount,
+        iso_currency_code: "USD".to_string(),
         date,
         merchant_name: Some("Test Merchant".to_string()),
         category_primary: category_primary.to_string(),
@@ -29,6 +31,8 @@ fn create_test_transaction(
         payment_channel: Some("online".to_string()),
         pending: false,
         created_at: Some(Utc::now()),
+        reviewed: false,
+        excluded_from_analytics: false,
     }
 }
 
@@ -121,7 +125,7 @@ fn group_by_category(transactions: &[Transaction], period: &str) -> Vec<Category
 }
 
 fn limit_categories_to_ten(mut categories: Vec<CategorySpending>) -> Vec<CategorySpending> {
-    categories.sort_by(|a, b| b.value.cmp(&a.value));
+    categories.sort_by_key(|c| std::cmp::Reverse(c.value));
     if categories.len() <= 10 {
         return categories;
     }
@@ -478,3 +482,174 @@ fn given_transactions_when_getting_top_merchants_with_date_range_then_filters_an
     assert_eq!(merchant.amount, dec!(325.00));
     assert_eq!(merchant.count, 3);
 }
+
+#[test]
+fn given_month_and_arbitrary_ranges_when_resolving_baseline_then_matches_calendar_months_or_length()
+{
+    use crate::models::comparison::{ComparisonBaseline, PeriodRange};
+
+    let range = |start: (i32, u32, u32), end: (i32, u32, u32)| PeriodRange {
+        start_date: NaiveDate::from_ymd_opt(start.0, start.1, start.2).unwrap(),
+        end_date: NaiveDate::from_ymd_opt(end.0, end.1, end.2).unwrap(),
+    };
+    let march = range((2024, 3, 1), (2024, 3, 31));
+    let calendar = UserCalendar::default();
+
+    assert_eq!(
+        AnalyticsService::baseline_range(march, ComparisonBaseline::PreviousPeriod, &calendar),
+        Some(range((2024, 2, 1), (2024, 2, 29)))
+    );
+    assert_eq!(
+        AnalyticsService::baseline_range(
+            range((2024, 2, 1), (2024, 2, 29)),
+            ComparisonBaseline::YearAgo,
+            &calendar
+        ),
+        Some(range((2023, 2, 1), (2023, 2, 28)))
+    );
+    assert_eq!(
+        AnalyticsService::baseline_range(
+            range((2024, 3, 11), (2024, 3, 20)),
+            ComparisonBaseline::PreviousPeriod,
+            &calendar
+        ),
+        Some(range((2024, 3, 1), (2024, 3, 10)))
+    );
+}
+
+#[test]
+fn given_spending_in_two_periods_when_comparing_then_reports_changes_largest_movers_first() {
+    use crate::models::comparison::PeriodRange;
+
+    let date = |m: u32, d: u32| NaiveDate::from_ymd_opt(2024, m, d).unwrap();
+    let with_merchant = |amount: Decimal, date: NaiveDate, category: &str, merchant: &str| {
+        let mut transaction = create_test_transaction(amount, date, category);
+        transaction.merchant_name = Some(merchant.to_string());
+        transaction
+    };
+    let transactions = vec![
+        with_merchant(dec!(100), date(2, 5), "Groceries", "Market"),
+        with_merchant(dec!(150), date(3, 5), "Groceries", "Market"),
+        with_merchant(dec!(80), date(2, 10), "Dining", "Bistro"),
+        with_merchant(dec!(300), date(3, 12), "Travel", "Airline"),
+        with_merchant(dec!(-2000), date(3, 1), "Income", "Employer"),
+        with_merchant(dec!(999), date(1, 15), "Travel", "Airline"),
+    ];
+    let current = PeriodRange {
+        start_date: date(3, 1),
+        end_date: date(3, 31),
+    };
+    let previous = PeriodRange {
+        start_date: date(2, 1),
+        end_date: date(2, 29),
+    };
+
+    let comparison = AnalyticsService::compare_periods(&transactions, current, previous);
+
+    assert_eq!(comparison.total.current, dec!(450));
+    assert_eq!(comparison.total.previous, dec!(180));
+    assert_eq!(comparison.total.change_percent, Some(dec!(150.0)));
+
+    let names: Vec<&str> = comparison
+        .categories
+        .iter()
+        .map(|c| c.name.as_str())
+        .collect();
+    assert_eq!(names, vec!["Travel", "Dining", "Groceries"]);
+    assert_eq!(comparison.categories[0].change_percent, None);
+    assert_eq!(comparison.categories[1].change, dec!(-80));
+    assert_eq!(comparison.categories[1].change_percent, Some(dec!(-100.0)));
+    assert_eq!(comparison.categories[2].change_percent, Some(dec!(50.0)));
+    assert_eq!(comparison.merchants[0].name, "Airline");
+}
+
+#[test]
+fn given_incomplete_query_when_resolving_comparison_then_rejects_with_reason() {
+    use crate::models::comparison::PeriodComparisonQuery;
+
+    let query = |period: Option<&str>, baseline: Option<&str>, current_start: Option<&str>| {
+        PeriodComparisonQuery {
+            period: period.map(str::to_string),
+            baseline: baseline.map(str::to_string),
+            current_start: current_start.map(str::to_string),
+            current_end: None,
+            previous_start: None,
+            previous_end: None,
+            account_ids: Vec::new(),
+        }
+    };
+    let calendar = UserCalendar::default();
+    let today = NaiveDate::from_ymd_opt(2024, 3, 18).unwrap();
+
+    assert_eq!(
+        AnalyticsService::resolve_comparison_ranges(
+            &query(None, None, Some("2024-03-01")),
+            &calendar,
+            today
+        )
+        .unwrap_err(),
+        "Either period or both current_start and current_end are required"
+    );
+    assert_eq!(
+        AnalyticsService::resolve_comparison_ranges(
+            &query(Some("last-decade"), None, None),
+            &calendar,
+            today
+        )
+        .unwrap_err(),
+        "Unknown period: last-decade"
+    );
+    assert_eq!(
+        AnalyticsService::resolve_comparison_ranges(
+            &query(Some("current-month"), Some("week_ago"), None),
+            &calendar,
+            today
+        )
+        .unwrap_err(),
+        "Unknown baseline: week_ago"
+    );
+    assert!(AnalyticsService::resolve_comparison_ranges(
+        &query(Some("current-month"), Some("year_ago"), None),
+        &calendar,
+        today
+    )
+    .is_ok());
+}
+
+#[test]
+fn given_sql_aggregates_when_shaping_analytics_then_matches_in_memory_rules() {
+    use crate::models::analytics::AggregateTotal;
+
+    let analytics = AnalyticsService::new();
+    let total = |key: &str, total: Decimal, count: i64| AggregateTotal {
+        key: key.to_string(),
+        total,
+        count,
+    };
+
+    let merchants = analytics.top_merchants_from_aggregates(
+        &[
+            total("Cafe", dec!(25.004), 2),
+            total("Grocer", dec!(75), 3),
+            total("Bookshop", dec!(10), 1),
+        ],
+        2,
+    );
+    assert_eq!(merchants.len(), 2);
+    assert_eq!(merchants[0].name, "Grocer");
+    assert_eq!(merchants[0].percentage, dec!(68.2));
+    assert_eq!(merchants[1].amount, dec!(25.00));
+    assert_eq!(merchants[1].count, 2);
+
+    let months = analytics.monthly_totals_from_aggregates(
+        &[
+            total("2024-03", dec!(30), 1),
+            total("2024-01", dec!(10), 1),
+            total("2024-02", dec!(-5), 1),
+        ],
+        2,
+    );
+    let keys: Vec<&str> = months.iter().map(|m| m.month.as_str()).collect();
+    assert_eq!(keys, vec!["2024-01", "2024-02"]);
+    assert_eq!(months[1].total, dec!(-5));
+}
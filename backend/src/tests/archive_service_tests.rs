@@ -0,0 +1,429 @@
+use crate::models::account::Account;
+use crate::models::alert::AlertRule;
+use crate::models::archive::{
+    ArchiveManifest, ArchiveProfile, ArchivedConnection, TransactionTag, UserArchive,
+};
+use crate::models::budget::{Budget, BudgetAllocation};
+use crate::models::liability::Liability;
+use crate::models::notification::NotificationPreference;
+use crate::models::plaid::ProviderConnection;
+use crate::models::user_settings::UserSettings;
+use crate::services::archive_service::{ArchiveRestoreError, ArchiveService};
+use crate::services::repository_service::MockDatabaseRepository;
+use crate::test_fixtures::TestFixtures;
+use axum::body::{to_bytes, Body};
+use axum::http::{header::AUTHORIZATION, Method, Request};
+use chrono::{NaiveDate, TimeZone, Utc};
+use rust_decimal_macros::dec;
+use std::collections::BTreeMap;
+use std::io::{Cursor, Write};
+use tower::ServiceExt;
+use uuid::Uuid;
+use zip::write::SimpleFileOptions;
+use zip::{ZipArchive, ZipWriter};
+
+fn sample_archive() -> UserArchive {
+    let created_at = Utc.with_ymd_and_hms(2024, 1, 1, 9, 0, 0).unwrap();
+    let connection_id = Uuid::new_v4();
+    let account = Account {
+        id: Uuid::new_v4(),
+        user_id: None,
+        provider_account_id: Some("acc-1".to_string()),
+        provider_connection_id: Some(connection_id),
+        name: "Everyday Checking".to_string(),
+        account_type: "depository".to_string(),
+        balance_current: Some(dec!(1250.40)),
+        iso_currency_code: "USD".to_string(),
+        mask: Some("1234".to_string()),
+        institution_name: Some("Demo Bank".to_string()),
+    };
+    let mut transaction = TestFixtures::transaction_on(
+        NaiveDate::from_ymd_opt(2024, 1, 15).unwrap(),
+        dec!(12.50),
+        "FOOD_AND_DRINK",
+        "Cafe",
+    );
+    transaction.account_id = account.id;
+    let budget = Budget {
+        id: Uuid::new_v4(),
+        user_id: Uuid::new_v4(),
+        category: "FOOD_AND_DRINK".to_string(),
+        amount: dec!(300),
+        saved_view_id: None,
+        created_at,
+        updated_at: created_at,
+    };
+
+    UserArchive {
+        profile: ArchiveProfile {
+            email: "owner@example.com".to_string(),
+            provider: "plaid".to_string(),
+            created_at,
+            onboarding_completed: true,
+            zero_based_budgeting: false,
+            settings: UserSettings::default(),
+        },
+        connections: vec![ArchivedConnection {
+            id: connection_id,
+            item_id: "item-1".to_string(),
+            institution_id: Some("ins_1".to_string()),
+            institution_name: Some("Demo Bank".to_string()),
+            connected_at: Some(created_at),
+            last_sync_at: None,
+            created_at: Some(created_at),
+        }],
+        transaction_tags: vec![TransactionTag {
+            transaction_id: transaction.id,
+            tag: "coffee".to_string(),
+        }],
+        budget_allocations: vec![BudgetAllocation {
+            id: Uuid::new_v4(),
+            user_id: budget.user_id,
+            month: "2024-01".to_string(),
+            category: budget.category.clone(),
+            assigned: dec!(300),
+            created_at,
+            updated_at: created_at,
+        }],
+        liabilities: vec![Liability {
+            account_id: account.id,
+            liability_type: "credit".to_string(),
+            apr: Some(dec!(19.99)),
+            minimum_payment: None,
+            statement_balance: None,
+            next_payment_due_date: None,
+            loan_term_months: None,
+            maturity_date: None,
+            source: "manual".to_string(),
+        }],
+        alert_rules: vec![AlertRule {
+            id: Uuid::new_v4(),
+            // Not archived: restored rules take the importing user's id.
+            user_id: Uuid::nil(),
+            rule_type: "low_balance".to_string(),
+            threshold: Some(dec!(100)),
+            percentages: vec![],
+            account_id: Some(account.id),
+            category: None,
+            saved_view_id: None,
+            cooldown_hours: 24,
+            enabled: true,
+            created_at,
+            updated_at: created_at,
+        }],
+        notification_preferences: vec![NotificationPreference {
+            event_type: "budget_threshold".to_string(),
+            channel: "email".to_string(),
+            enabled: false,
+        }],
+        dismissed_insights: vec!["spending-spike-2024-01".to_string()],
+        accounts: vec![account],
+        transactions: vec![transaction],
+        budgets: vec![budget],
+    }
+}
+
+/// `restored` with the ids of the single-record `original` put back, for
+/// comparing everything but the ids a restore assigns.
+fn with_original_ids(mut restored: UserArchive, original: &UserArchive) -> UserArchive {
+    let connection_id = original.connections[0].id;
+    let account_id = original.accounts[0].id;
+    let transaction_id = original.transactions[0].id;
+    restored.connections[0].id = connection_id;
+    restored.accounts[0].id = account_id;
+    restored.accounts[0].provider_connection_id = Some(connection_id);
+    restored.transactions[0].id = transaction_id;
+    restored.transactions[0].account_id = account_id;
+    restored.transaction_tags[0].transaction_id = transaction_id;
+    restored.liabilities[0].account_id = account_id;
+    restored.budgets[0].id = original.budgets[0].id;
+    restored.budget_allocations[0].id = original.budget_allocations[0].id;
+    restored.alert_rules[0].id = original.alert_rules[0].id;
+    restored.alert_rules[0].account_id = Some(account_id);
+    restored
+}
+
+fn zip_with_manifest(version: u32) -> Vec<u8> {
+    let manifest = ArchiveManifest {
+        format: "sumurai-archive".to_string(),
+        version,
+        exported_at: Utc::now(),
+        counts: BTreeMap::new(),
+    };
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    zip.start_file("manifest.json", SimpleFileOptions::default())
+        .unwrap();
+    zip.write_all(&serde_json::to_vec(&manifest).unwrap())
+        .unwrap();
+    zip.finish().unwrap().into_inner()
+}
+
+fn archive_upload_request(token: &str, body: Vec<u8>) -> Request<Body> {
+    Request::builder()
+        .method(Method::POST)
+        .uri("/api/import/archive")
+        .header(AUTHORIZATION, format!("Bearer {}", token))
+        .header("content-type", "application/zip")
+        .body(Body::from(body))
+        .unwrap()
+}
+
+#[test]
+fn given_archive_when_writing_and_reading_zip_then_round_trips_with_csv_copies() {
+    let service = ArchiveService::new();
+    let archive = sample_archive();
+
+    let bytes = service.write_zip(&archive, Utc::now()).unwrap();
+    let restored = service.read_zip(&bytes).unwrap();
+
+    assert_eq!(restored, archive);
+    let mut zip = ZipArchive::new(Cursor::new(bytes.as_slice())).unwrap();
+    for name in ["accounts.csv", "transactions.csv", "budgets.csv"] {
+        assert!(zip.by_name(name).is_ok(), "{} missing", name);
+    }
+    let manifest: ArchiveManifest =
+        serde_json::from_reader(zip.by_name("manifest.json").unwrap()).unwrap();
+    assert_eq!(manifest.version, 1);
+    assert_eq!(manifest.counts["transactions"], 1);
+    assert!(ArchiveService::validate(&restored).is_ok());
+}
+
+#[test]
+fn given_newer_or_partial_archive_when_reading_then_rejects_version_and_requires_profile() {
+    let service = ArchiveService::new();
+
+    assert_eq!(
+        service.read_zip(&zip_with_manifest(2)).unwrap_err(),
+        "Invalid archive: version 2 is not supported (latest is 1)"
+    );
+    assert_eq!(
+        service.read_zip(&zip_with_manifest(1)).unwrap_err(),
+        "Invalid archive: profile.json is missing"
+    );
+    assert!(service
+        .read_zip(b"not a zip")
+        .unwrap_err()
+        .starts_with("Invalid archive: not a zip file"));
+}
+
+/// A valid archive whose `padded` files are empty JSON lists padded with
+/// `padding` bytes of whitespace, which compresses to almost nothing.
+fn zip_with_padded_lists(padded: &[&str], padding: usize) -> Vec<u8> {
+    let source = ArchiveService::new()
+        .write_zip(&sample_archive(), Utc::now())
+        .unwrap();
+    let mut source = ZipArchive::new(Cursor::new(source.as_slice())).unwrap();
+    let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    for name in ["manifest.json", "profile.json"] {
+        zip.raw_copy_file(source.by_name(name).unwrap()).unwrap();
+    }
+    for name in padded {
+        zip.start_file(*name, options).unwrap();
+        zip.write_all(b"[").unwrap();
+        zip.write_all(&vec![b' '; padding]).unwrap();
+        zip.write_all(b"]").unwrap();
+    }
+    zip.finish().unwrap().into_inner()
+}
+
+#[test]
+fn given_highly_compressible_file_when_reading_then_stops_at_decompressed_budget() {
+    let service = ArchiveService::new().with_max_decompressed_bytes(1024 * 1024);
+    let bomb = zip_with_padded_lists(&["transactions.json"], 4 * 1024 * 1024);
+    assert!(bomb.len() < 64 * 1024);
+
+    assert_eq!(
+        service.read_zip(&bomb).unwrap_err(),
+        "Invalid archive: contents are too large when decompressed"
+    );
+    assert!(service
+        .read_zip(&zip_with_padded_lists(&["transactions.json"], 512 * 1024))
+        .is_ok());
+}
+
+#[test]
+fn given_files_each_under_budget_when_reading_then_their_total_is_capped() {
+    let service = ArchiveService::new().with_max_decompressed_bytes(1024 * 1024);
+    let archive = zip_with_padded_lists(&["accounts.json", "transactions.json"], 600 * 1024);
+
+    assert_eq!(
+        service.read_zip(&archive).unwrap_err(),
+        "Invalid archive: contents are too large when decompressed"
+    );
+}
+
+#[test]
+fn given_dangling_references_when_validating_then_names_the_missing_record() {
+    let mut archive = sample_archive();
+    let missing_account = Uuid::new_v4();
+    archive.transactions[0].account_id = missing_account;
+
+    assert_eq!(
+        ArchiveService::validate(&archive).unwrap_err(),
+        format!("Invalid archive: unknown account {}", missing_account)
+    );
+
+    let mut archive = sample_archive();
+    archive.accounts.push(archive.accounts[0].clone());
+    assert!(ArchiveService::validate(&archive)
+        .unwrap_err()
+        .starts_with("Invalid archive: duplicate account"));
+
+    let mut archive = sample_archive();
+    archive.profile.settings.month_start_day = 31;
+    assert_eq!(
+        ArchiveService::validate(&archive).unwrap_err(),
+        "Invalid archive: Invalid month_start_day: must be between 1 and 28"
+    );
+}
+
+#[tokio::test]
+async fn given_account_with_connections_when_restoring_then_refuses_without_writing() {
+    let bytes = ArchiveService::new()
+        .write_zip(&sample_archive(), Utc::now())
+        .unwrap();
+    let mut mock_db = MockDatabaseRepository::new();
+    mock_db
+        .expect_get_all_provider_connections_by_user()
+        .returning(|_| {
+            Box::pin(async { Ok(vec![ProviderConnection::new(Uuid::new_v4(), "item-9")]) })
+        });
+    mock_db
+        .expect_get_accounts_for_user()
+        .returning(|_| Box::pin(async { Ok(vec![]) }));
+    mock_db
+        .expect_get_budgets_for_user()
+        .returning(|_| Box::pin(async { Ok(vec![]) }));
+    mock_db.expect_restore_user_archive().never();
+
+    let result = ArchiveService::new()
+        .restore(&mock_db, Uuid::new_v4(), &bytes)
+        .await;
+
+    assert!(matches!(result, Err(ArchiveRestoreError::AccountNotEmpty)));
+}
+
+#[tokio::test]
+async fn given_fresh_account_when_uploading_archive_then_restores_under_caller() {
+    let (user, token) = TestFixtures::create_authenticated_user_with_token();
+    let archive = sample_archive();
+    let bytes = ArchiveService::new()
+        .write_zip(&archive, Utc::now())
+        .unwrap();
+    let mut mock_db = MockDatabaseRepository::new();
+    mock_db
+        .expect_get_all_provider_connections_by_user()
+        .returning(|_| Box::pin(async { Ok(vec![]) }));
+    mock_db
+        .expect_get_accounts_for_user()
+        .returning(|_| Box::pin(async { Ok(vec![]) }));
+    mock_db
+        .expect_get_budgets_for_user()
+        .returning(|_| Box::pin(async { Ok(vec![]) }));
+    mock_db
+        .expect_restore_user_archive()
+        .times(1)
+        .returning(move |user_id, restored| {
+            assert_eq!(*user_id, user.id);
+            assert_eq!(with_original_ids(restored.clone(), &archive), archive);
+            Box::pin(async { Ok(()) })
+        });
+    let app = TestFixtures::create_test_app_with_db(mock_db)
+        .await
+        .unwrap();
+
+    let response = app
+        .oneshot(archive_upload_request(&token, bytes))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 200);
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let counts: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(
+        counts,
+        serde_json::json!({"connections": 1, "accounts": 1, "transactions": 1, "budgets": 1})
+    );
+}
+
+#[tokio::test]
+async fn given_exported_archive_when_restoring_on_same_server_then_records_get_new_linked_ids() {
+    let user_id = Uuid::new_v4();
+    let exported = sample_archive();
+    let bytes = ArchiveService::new()
+        .write_zip(&exported, Utc::now())
+        .unwrap();
+    let original = exported.clone();
+    let mut mock_db = MockDatabaseRepository::new();
+    mock_db
+        .expect_get_all_provider_connections_by_user()
+        .returning(|_| Box::pin(async { Ok(vec![]) }));
+    mock_db
+        .expect_get_accounts_for_user()
+        .returning(|_| Box::pin(async { Ok(vec![]) }));
+    mock_db
+        .expect_get_budgets_for_user()
+        .returning(|_| Box::pin(async { Ok(vec![]) }));
+    mock_db
+        .expect_restore_user_archive()
+        .times(1)
+        .returning(move |_, restored| {
+            let old_ids: Vec<Uuid> = vec![
+                original.connections[0].id,
+                original.accounts[0].id,
+                original.transactions[0].id,
+                original.budgets[0].id,
+                original.budget_allocations[0].id,
+                original.alert_rules[0].id,
+            ];
+            let new_ids = [
+                restored.connections[0].id,
+                restored.accounts[0].id,
+                restored.transactions[0].id,
+                restored.budgets[0].id,
+                restored.budget_allocations[0].id,
+                restored.alert_rules[0].id,
+            ];
+            assert!(new_ids.iter().all(|id| !old_ids.contains(id)));
+
+            let account_id = restored.accounts[0].id;
+            assert_eq!(
+                restored.accounts[0].provider_connection_id,
+                Some(restored.connections[0].id)
+            );
+            assert_eq!(restored.transactions[0].account_id, account_id);
+            assert_eq!(
+                restored.transaction_tags[0].transaction_id,
+                restored.transactions[0].id
+            );
+            assert_eq!(restored.liabilities[0].account_id, account_id);
+            assert_eq!(restored.alert_rules[0].account_id, Some(account_id));
+            assert!(ArchiveService::validate(restored).is_ok());
+            Box::pin(async { Ok(()) })
+        });
+
+    let restored = ArchiveService::new()
+        .restore(&mock_db, user_id, &bytes)
+        .await
+        .unwrap();
+
+    assert_eq!(restored.transactions, 1);
+}
+
+#[tokio::test]
+async fn given_corrupt_upload_when_importing_then_returns_bad_request() {
+    let (_user, token) = TestFixtures::create_authenticated_user_with_token();
+    let mut mock_db = MockDatabaseRepository::new();
+    mock_db.expect_restore_user_archive().never();
+    let app = TestFixtures::create_test_app_with_db(mock_db)
+        .await
+        .unwrap();
+
+    let response = app
+        .oneshot(archive_upload_request(&token, b"garbage".to_vec()))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 400);
+}
@@ -1,4 +1,4 @@
-use crate::models::{account::Account, plaid::ProviderConnection};
+use crate::models::{account::Account, plaid::ProviderConnection, user_settings::UserCalendar};
 use crate::providers::{PlaidProvider, ProviderRegistry};
 
 use crate::services::{plaid_service::RealPlaidClient, sync_service::SyncService};
@@ -25,6 +25,7 @@ fn create_test_accounts_for_bank(connection_id: Uuid, user_id: Uuid) -> Vec<Acco
             name: "Primary Checking".to_string(),
             account_type: "depository".to_string(),
             balance_current: Some(dec!(1500.00)),
+            iso_currency_code: "USD".to_string(),
             mask: Some("1234".to_string()),
             institution_name: None,
         },
@@ -36,6 +37,7 @@ fn create_test_accounts_for_bank(connection_id: Uuid, user_id: Uuid) -> Vec<Acco
             name: "Savings Account".to_string(),
             account_type: "depository".to_string(),
             balance_current: Some(dec!(5000.00)),
+            iso_currency_code: "USD".to_string(),
             mask: Some("5678".to_string()),
             institution_name: None,
         },
@@ -47,6 +49,7 @@ fn create_test_accounts_for_bank(connection_id: Uuid, user_id: Uuid) -> Vec<Acco
             name: "Credit Card".to_string(),
             account_type: "credit".to_string(),
             balance_current: Some(dec!(-250.00)),
+            iso_currency_code: "USD".to_string(),
             mask: Some("9012".to_string()),
             institution_name: None,
         },
@@ -100,7 +103,8 @@ fn given_connection_with_no_cursor_when_calculating_date_ranges_then_uses_defaul
     ));
     let sync_service = build_sync_service(plaid_client);
 
-    let (start_date, end_date) = sync_service.calculate_sync_date_range(connection.last_sync_at);
+    let (start_date, end_date) =
+        sync_service.calculate_sync_date_range(connection.last_sync_at, &UserCalendar::default());
     let expected_start = Utc::now().date_naive() - Duration::days(90);
     let expected_end = Utc::now().date_naive();
 
@@ -130,7 +134,14 @@ async fn given_bank_connection_when_sync_fails_then_returns_error_without_updati
     };
 
     let result = sync_service
-        .sync_bank_connection_transactions(&credentials, &connection, &accounts)
+        .sync_bank_connection_transactions(
+            &credentials,
+            &accounts,
+            SyncService::calculate_sync_date_range_static(
+                connection.last_sync_at,
+                &UserCalendar::default(),
+            ),
+        )
         .await;
 
     assert!(result.is_err());
@@ -150,6 +161,7 @@ async fn given_bank_connection_when_upserting_account_then_assigns_connection_id
         name: "Test Account".to_string(),
         account_type: "checking".to_string(),
         balance_current: Some(dec!(1000.00)),
+        iso_currency_code: "USD".to_string(),
         mask: Some("1234".to_string()),
         institution_name: None,
     };
@@ -0,0 +1,218 @@
+use crate::models::account::Account;
+use crate::models::bill::{BillSource, UpcomingBill};
+use crate::models::liability::Liability;
+use crate::models::recurring::{RecurrenceCadence, RecurringSeries};
+use crate::services::bills_service::{BillWindow, BillsService};
+use crate::services::repository_service::MockDatabaseRepository;
+use crate::test_fixtures::TestFixtures;
+use chrono::{NaiveDate, TimeZone, Utc};
+use rust_decimal_macros::dec;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+fn series(merchant: &str, next: NaiveDate, is_income: bool) -> RecurringSeries {
+    RecurringSeries {
+        merchant: merchant.to_string(),
+        category: "ENTERTAINMENT".to_string(),
+        cadence: RecurrenceCadence::Weekly,
+        average_amount: dec!(10.00),
+        is_income,
+        occurrences: 4,
+        last_date: next - chrono::Days::new(7),
+        next_expected_date: next,
+        account_id: Uuid::new_v4(),
+        transaction_ids: vec![],
+    }
+}
+
+fn statement(account_id: Uuid, due: NaiveDate, balance: rust_decimal::Decimal) -> Liability {
+    Liability {
+        account_id,
+        liability_type: "credit".to_string(),
+        apr: None,
+        minimum_payment: None,
+        statement_balance: Some(balance),
+        next_payment_due_date: Some(due),
+        loan_term_months: None,
+        maturity_date: None,
+        source: "provider".to_string(),
+    }
+}
+
+fn credit_card(provider_account_id: &str) -> Account {
+    Account {
+        id: Uuid::new_v4(),
+        user_id: None,
+        provider_account_id: Some(provider_account_id.to_string()),
+        provider_connection_id: None,
+        name: "Sapphire Card".to_string(),
+        account_type: "credit".to_string(),
+        balance_current: Some(dec!(812.40)),
+        iso_currency_code: "USD".to_string(),
+        mask: Some("4242".to_string()),
+        institution_name: None,
+    }
+}
+
+#[test]
+fn given_recurring_series_and_statement_when_building_bills_then_merges_by_due_date() {
+    let window = BillWindow::from_today(date(2024, 3, 1), 14);
+    let card = credit_card("plaid-card-1");
+    let mortgage = Liability {
+        liability_type: "mortgage".to_string(),
+        ..statement(Uuid::new_v4(), date(2024, 3, 6), dec!(1500.00))
+    };
+    let statements = vec![
+        Liability {
+            minimum_payment: Some(dec!(35.00)),
+            ..statement(card.id, date(2024, 3, 9), dec!(812.40))
+        },
+        statement(card.id, date(2024, 4, 9), dec!(100.00)),
+        mortgage,
+    ];
+    let recurring = vec![
+        series("Gym", date(2024, 3, 4), false),
+        series("Payroll", date(2024, 3, 5), true),
+    ];
+
+    let response = BillsService::build_upcoming_bills(
+        window,
+        &recurring,
+        &statements,
+        std::slice::from_ref(&card),
+    );
+
+    let summary: Vec<_> = response
+        .bills
+        .iter()
+        .map(|b| (b.name.as_str(), b.due_date, b.source))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("Gym", date(2024, 3, 4), BillSource::Recurring),
+            (
+                "Sapphire Card payment",
+                date(2024, 3, 9),
+                BillSource::CreditCardStatement
+            ),
+            ("Gym", date(2024, 3, 11), BillSource::Recurring),
+        ]
+    );
+    assert_eq!(response.bills[1].account_id, Some(card.id));
+    assert_eq!(response.bills[1].minimum_payment, Some(dec!(35.00)));
+    assert_eq!(response.total, dec!(832.40));
+    assert_eq!(response.end_date, date(2024, 3, 15));
+}
+
+#[test]
+fn given_bills_when_rendering_icalendar_then_emits_all_day_events_with_escaped_text() {
+    let bills = vec![UpcomingBill {
+        name: "Water, Sewer; City".to_string(),
+        category: Some("RENT_AND_UTILITIES".to_string()),
+        due_date: date(2024, 3, 31),
+        amount: dec!(64.5),
+        minimum_payment: None,
+        source: BillSource::Recurring,
+        account_id: None,
+        cadence: Some(RecurrenceCadence::Monthly),
+    }];
+
+    let ics =
+        BillsService::render_icalendar(&bills, Utc.with_ymd_and_hms(2024, 3, 1, 8, 30, 0).unwrap());
+
+    assert!(ics.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+    assert!(ics.ends_with("END:VCALENDAR\r\n"));
+    assert!(ics.contains("DTSTAMP:20240301T083000Z\r\n"));
+    assert!(ics.contains("DTSTART;VALUE=DATE:20240331\r\n"));
+    assert!(ics.contains("DTEND;VALUE=DATE:20240401\r\n"));
+    assert!(ics.contains("SUMMARY:Water\\, Sewer\\; City (64.50)\r\n"));
+    assert!(ics.contains("DESCRIPTION:Expected amount: 64.50\\nCategory: RENT_AND_UTILITIES"));
+    assert!(!ics.replace("\r\n", "").contains('\n'));
+}
+
+#[test]
+fn given_long_summary_when_rendering_icalendar_then_folds_lines_at_75_octets() {
+    let bills = vec![UpcomingBill {
+        name: "Ünïcödé streaming service with an exceptionally long descriptive name".to_string(),
+        category: None,
+        due_date: date(2024, 3, 31),
+        amount: dec!(9.99),
+        minimum_payment: None,
+        source: BillSource::Recurring,
+        account_id: None,
+        cadence: None,
+    }];
+
+    let ics = BillsService::render_icalendar(&bills, Utc::now());
+
+    assert!(ics.split("\r\n").all(|line| line.len() <= 75));
+    let unfolded = ics.replace("\r\n ", "");
+    assert!(unfolded.contains(
+        "SUMMARY:Ünïcödé streaming service with an exceptionally long descriptive name (9.99)"
+    ));
+}
+
+#[tokio::test]
+async fn given_unknown_feed_token_when_fetching_calendar_then_returns_not_found() {
+    let mut mock_db = MockDatabaseRepository::new();
+    mock_db
+        .expect_get_user_id_by_calendar_feed_token()
+        .withf(|hash| hash == BillsService::hash_feed_token("not-a-real-token"))
+        .returning(|_| Box::pin(async { Ok(None) }));
+    let app = TestFixtures::create_test_app_with_db(mock_db)
+        .await
+        .unwrap();
+
+    let request = axum::http::Request::builder()
+        .uri("/api/bills/calendar/not-a-real-token/feed.ics")
+        .body(axum::body::Body::empty())
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+
+    assert_eq!(response.status(), axum::http::StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn given_valid_feed_token_when_fetching_calendar_then_serves_stored_data_without_bearer_token(
+) {
+    let user_id = Uuid::new_v4();
+    let mut mock_db = MockDatabaseRepository::new();
+    mock_db
+        .expect_get_user_id_by_calendar_feed_token()
+        .returning(move |_| Box::pin(async move { Ok(Some(user_id)) }));
+    mock_db
+        .expect_get_liabilities_for_user()
+        .times(1)
+        .returning(|_| Box::pin(async { Ok(vec![]) }));
+    mock_db.expect_get_provider_credentials_for_user().never();
+    mock_db
+        .expect_get_transactions_by_date_range_for_user()
+        .returning(|_, _, _| Box::pin(async { Ok(vec![]) }));
+    mock_db
+        .expect_get_accounts_for_user()
+        .returning(|_| Box::pin(async { Ok(vec![]) }));
+    let app = TestFixtures::create_test_app_with_db(mock_db)
+        .await
+        .unwrap();
+
+    let request = axum::http::Request::builder()
+        .uri("/api/bills/calendar/secret-token/feed.ics")
+        .body(axum::body::Body::empty())
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    assert_eq!(
+        response.headers()["content-type"],
+        "text/calendar; charset=utf-8"
+    );
+    let body = axum::body::to_bytes(response.into_body(), 1024 * 1024)
+        .await
+        .unwrap();
+    assert!(String::from_utf8_lossy(&body).starts_with("BEGIN:VCALENDAR"));
+}
@@ -1,7 +1,12 @@
-use crate::models::budget::Budget;
+use crate::models::budget::{
+    Budget, BudgetAllocation, MoveBudgetFundsRequest, UpdateBudgetRequest,
+};
 use crate::services::budget_service::BudgetService;
 use crate::services::repository_service::MockDatabaseRepository;
+use crate::test_fixtures::TestFixtures;
+use chrono::NaiveDate;
 use rust_decimal_macros::dec;
+use std::collections::HashSet;
 use uuid::Uuid;
 
 #[tokio::test]
@@ -26,11 +31,9 @@ async fn given_valid_budget_data_when_creating_budget_then_succeeds_with_user_is
         .returning(move |budget| Box::pin(async move { Ok(budget.clone()) }));
 
     let result = service
-        .create_budget_for_user(
+        .create_budget(
             &repository,
-            user_id,
-            "FOOD_AND_DRINK".to_string(),
-            dec!(250.00),
+            Budget::new(user_id, "FOOD_AND_DRINK".to_string(), dec!(250.00)),
         )
         .await;
 
@@ -48,11 +51,9 @@ async fn given_invalid_budget_amount_when_creating_budget_then_fails() {
     let service = BudgetService::new();
 
     let result = service
-        .create_budget_for_user(
+        .create_budget(
             &repository,
-            user_id,
-            "FOOD_AND_DRINK".to_string(),
-            dec!(0.00),
+            Budget::new(user_id, "FOOD_AND_DRINK".to_string(), dec!(0.00)),
         )
         .await;
 
@@ -82,22 +83,28 @@ async fn given_budgets_and_user_isolation_when_updating_then_respects_access_con
     repository
         .expect_update_budget_for_user()
         .times(1)
-        .returning(|_, _, _| {
+        .returning(|_, _, _, _| {
             Box::pin(async { Err(anyhow::anyhow!("Budget not found or access denied")) })
         });
 
     let budget = service
-        .create_budget_for_user(
+        .create_budget(
             &repository,
-            user1_id,
-            "FOOD_AND_DRINK".to_string(),
-            dec!(250.00),
+            Budget::new(user1_id, "FOOD_AND_DRINK".to_string(), dec!(250.00)),
         )
         .await
         .unwrap();
 
     let result = service
-        .update_budget_for_user(&repository, budget.id, user2_id, dec!(300.00))
+        .update_budget_for_user(
+            &repository,
+            budget.id,
+            user2_id,
+            &UpdateBudgetRequest {
+                amount: dec!(300.00),
+                saved_view_id: None,
+            },
+        )
         .await;
 
     assert!(result.is_err());
@@ -127,11 +134,9 @@ async fn given_existing_budget_when_deleting_then_succeeds_with_user_isolation()
         .returning(|_, _| Box::pin(async { Ok(()) }));
 
     let budget = service
-        .create_budget_for_user(
+        .create_budget(
             &repository,
-            user_id,
-            "FOOD_AND_DRINK".to_string(),
-            dec!(250.00),
+            Budget::new(user_id, "FOOD_AND_DRINK".to_string(), dec!(250.00)),
         )
         .await
         .unwrap();
@@ -170,11 +175,9 @@ async fn given_budget_from_different_user_when_deleting_then_fails() {
         });
 
     let budget = service
-        .create_budget_for_user(
+        .create_budget(
             &repository,
-            user1_id,
-            "FOOD_AND_DRINK".to_string(),
-            dec!(250.00),
+            Budget::new(user1_id, "FOOD_AND_DRINK".to_string(), dec!(250.00)),
         )
         .await
         .unwrap();
@@ -221,31 +224,25 @@ async fn given_user_with_budgets_when_getting_budgets_then_returns_only_user_bud
         });
 
     let _budget1 = service
-        .create_budget_for_user(
+        .create_budget(
             &repository,
-            user1_id,
-            "FOOD_AND_DRINK".to_string(),
-            dec!(250.00),
+            Budget::new(user1_id, "FOOD_AND_DRINK".to_string(), dec!(250.00)),
         )
         .await
         .unwrap();
 
     let _budget2 = service
-        .create_budget_for_user(
+        .create_budget(
             &repository,
-            user1_id,
-            "TRANSPORTATION".to_string(),
-            dec!(150.00),
+            Budget::new(user1_id, "TRANSPORTATION".to_string(), dec!(150.00)),
         )
         .await
         .unwrap();
 
     let _budget3 = service
-        .create_budget_for_user(
+        .create_budget(
             &repository,
-            user2_id,
-            "FOOD_AND_DRINK".to_string(),
-            dec!(300.00),
+            Budget::new(user2_id, "FOOD_AND_DRINK".to_string(), dec!(300.00)),
         )
         .await
         .unwrap();
@@ -277,11 +274,9 @@ async fn given_existing_category_when_creating_budget_then_returns_conflict_erro
         });
 
     let result = service
-        .create_budget_for_user(
+        .create_budget(
             &repository,
-            user_id,
-            "FOOD_AND_DRINK".to_string(),
-            dec!(250.00),
+            Budget::new(user_id, "FOOD_AND_DRINK".to_string(), dec!(250.00)),
         )
         .await;
 
@@ -311,11 +306,9 @@ async fn given_unique_constraint_violation_when_creating_budget_then_returns_con
         });
 
     let result = service
-        .create_budget_for_user(
+        .create_budget(
             &repository,
-            user_id,
-            "TRANSPORTATION".to_string(),
-            dec!(100.00),
+            Budget::new(user_id, "TRANSPORTATION".to_string(), dec!(100.00)),
         )
         .await;
 
@@ -325,3 +318,285 @@ async fn given_unique_constraint_violation_when_creating_budget_then_returns_con
         .to_lowercase()
         .contains("category already exists"));
 }
+
+fn allocation(user_id: Uuid, category: &str, assigned: rust_decimal::Decimal) -> BudgetAllocation {
+    BudgetAllocation {
+        id: Uuid::new_v4(),
+        user_id,
+        month: "2024-01".to_string(),
+        category: category.to_string(),
+        assigned,
+        created_at: chrono::Utc::now(),
+        updated_at: chrono::Utc::now(),
+    }
+}
+
+#[test]
+fn given_income_and_allocations_when_building_zero_based_summary_then_reports_left_to_assign() {
+    let user_id = Uuid::new_v4();
+    let day = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+    let transactions = vec![
+        TestFixtures::transaction_on(day, dec!(-3000.00), "INCOME", "Employer Payroll"),
+        TestFixtures::transaction_on(day, dec!(120.00), "FOOD_AND_DRINK", "Whole Foods"),
+        TestFixtures::transaction_on(day, dec!(45.50), "ENTERTAINMENT", "Cinema"),
+    ];
+    let allocations = vec![
+        allocation(user_id, "FOOD_AND_DRINK", dec!(400.00)),
+        allocation(user_id, "RENT_AND_UTILITIES", dec!(1500.00)),
+    ];
+
+    let summary = BudgetService::build_zero_based_summary(
+        "2024-01",
+        &transactions,
+        &allocations,
+        &HashSet::new(),
+    );
+
+    assert_eq!(summary.income, dec!(3000.00));
+    assert_eq!(summary.assigned, dec!(1900.00));
+    assert_eq!(summary.left_to_assign, dec!(1100.00));
+    assert!(!summary.over_assigned);
+
+    let food = summary
+        .categories
+        .iter()
+        .find(|c| c.category == "FOOD_AND_DRINK")
+        .unwrap();
+    assert_eq!(food.available, dec!(280.00));
+
+    let entertainment = summary
+        .categories
+        .iter()
+        .find(|c| c.category == "ENTERTAINMENT")
+        .unwrap();
+    assert_eq!(entertainment.assigned, dec!(0));
+    assert_eq!(entertainment.available, dec!(-45.50));
+    assert!(!summary.categories.iter().any(|c| c.category == "INCOME"));
+}
+
+#[test]
+fn given_assignments_exceeding_income_when_building_summary_then_flags_over_assignment() {
+    let user_id = Uuid::new_v4();
+    let day = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+    let transactions = vec![TestFixtures::transaction_on(
+        day,
+        dec!(1000.00),
+        "INCOME",
+        "Employer Payroll",
+    )];
+    let allocations = vec![allocation(user_id, "RENT_AND_UTILITIES", dec!(1200.00))];
+
+    let summary = BudgetService::build_zero_based_summary(
+        "2024-01",
+        &transactions,
+        &allocations,
+        &HashSet::new(),
+    );
+
+    assert_eq!(summary.left_to_assign, dec!(-200.00));
+    assert!(summary.over_assigned);
+}
+
+#[test]
+fn given_transfer_and_card_refund_when_building_zero_based_summary_then_only_paycheck_is_income() {
+    let user_id = Uuid::new_v4();
+    let day = NaiveDate::from_ymd_opt(2024, 1, 10).unwrap();
+    let card_id = Uuid::new_v4();
+    let mut refund =
+        TestFixtures::transaction_on(day, dec!(-40.00), "GENERAL_MERCHANDISE", "Target");
+    refund.account_id = card_id;
+    let mut card_payment =
+        TestFixtures::transaction_on(day, dec!(-500.00), "LOAN_PAYMENTS", "Card Payment");
+    card_payment.account_id = card_id;
+    let transactions = vec![
+        TestFixtures::transaction_on(day, dec!(-2500.00), "INCOME", "Employer Payroll"),
+        TestFixtures::transaction_on(day, dec!(-800.00), "TRANSFER_IN", "From Savings"),
+        TestFixtures::transaction_on(day, dec!(100.00), "GENERAL_MERCHANDISE", "Target"),
+        refund,
+        card_payment,
+    ];
+    let allocations = vec![allocation(user_id, "GENERAL_MERCHANDISE", dec!(200.00))];
+
+    let summary = BudgetService::build_zero_based_summary(
+        "2024-01",
+        &transactions,
+        &allocations,
+        &HashSet::from([card_id]),
+    );
+
+    assert_eq!(summary.income, dec!(2500.00));
+    assert_eq!(summary.left_to_assign, dec!(2300.00));
+    let merchandise = summary
+        .categories
+        .iter()
+        .find(|c| c.category == "GENERAL_MERCHANDISE")
+        .unwrap();
+    assert_eq!(merchandise.activity, dec!(60.00));
+    assert_eq!(merchandise.available, dec!(140.00));
+    assert!(!summary
+        .categories
+        .iter()
+        .any(|c| c.category == "TRANSFER_IN" || c.category == "LOAN_PAYMENTS"));
+}
+
+#[test]
+fn given_card_purchase_and_card_payment_from_checking_when_building_summary_then_counts_spend_once()
+{
+    let user_id = Uuid::new_v4();
+    let day = NaiveDate::from_ymd_opt(2024, 1, 20).unwrap();
+    let card_id = Uuid::new_v4();
+    let mut purchase =
+        TestFixtures::transaction_on(day, dec!(300.00), "GENERAL_MERCHANDISE", "Best Buy");
+    purchase.account_id = card_id;
+    let transactions = vec![
+        purchase,
+        TestFixtures::transaction_on(day, dec!(300.00), "LOAN_PAYMENTS", "Card Payment"),
+        TestFixtures::transaction_on(day, dec!(150.00), "TRANSFER_OUT", "To Savings"),
+    ];
+    let allocations = vec![allocation(user_id, "GENERAL_MERCHANDISE", dec!(400.00))];
+
+    let summary = BudgetService::build_zero_based_summary(
+        "2024-01",
+        &transactions,
+        &allocations,
+        &HashSet::from([card_id]),
+    );
+
+    assert_eq!(summary.activity, dec!(300.00));
+    assert_eq!(summary.categories.len(), 1);
+    assert_eq!(summary.categories[0].available, dec!(100.00));
+}
+
+#[tokio::test]
+async fn given_month_beyond_supported_years_when_getting_summary_then_rejects_before_loading() {
+    let mut repository = MockDatabaseRepository::new();
+    repository.expect_get_zero_based_budgeting_enabled().never();
+    repository
+        .expect_get_transactions_by_date_range_for_user()
+        .never();
+
+    let error = BudgetService::new()
+        .get_zero_based_summary(&repository, Uuid::new_v4(), "300000-01")
+        .await
+        .unwrap_err();
+
+    assert_eq!(error, "Invalid budget month, expected YYYY-MM");
+}
+
+#[tokio::test]
+async fn given_zero_based_mode_disabled_when_getting_summary_then_fails() {
+    let user_id = Uuid::new_v4();
+    let mut repository = MockDatabaseRepository::new();
+    let service = BudgetService::new();
+
+    repository
+        .expect_get_zero_based_budgeting_enabled()
+        .times(1)
+        .returning(|_| Box::pin(async { Ok(false) }));
+
+    let result = service
+        .get_zero_based_summary(&repository, user_id, "2024-01")
+        .await;
+
+    assert!(result.unwrap_err().contains("not enabled"));
+}
+
+#[tokio::test]
+async fn given_same_source_and_destination_when_moving_funds_then_fails_without_repository_call() {
+    let repository = MockDatabaseRepository::new();
+    let service = BudgetService::new();
+    let request = MoveBudgetFundsRequest {
+        month: "2024-01".to_string(),
+        from_category: "FOOD_AND_DRINK".to_string(),
+        to_category: "food_and_drink".to_string(),
+        amount: dec!(25.00),
+    };
+
+    let result = service
+        .move_funds(&repository, Uuid::new_v4(), &request)
+        .await;
+
+    assert!(result.unwrap_err().contains("must differ"));
+}
+
+#[tokio::test]
+async fn given_enabled_mode_when_moving_funds_then_delegates_to_repository() {
+    let user_id = Uuid::new_v4();
+    let mut repository = MockDatabaseRepository::new();
+    let service = BudgetService::new();
+
+    repository
+        .expect_get_zero_based_budgeting_enabled()
+        .times(1)
+        .returning(|_| Box::pin(async { Ok(true) }));
+    repository
+        .expect_move_budget_allocation()
+        .withf(|_, request| {
+            request.from_category == "ENTERTAINMENT" && request.amount == dec!(50.00)
+        })
+        .times(1)
+        .returning(move |uid, request| {
+            let moved = vec![
+                allocation(uid, &request.from_category, dec!(0)),
+                allocation(uid, &request.to_category, request.amount),
+            ];
+            Box::pin(async move { Ok(moved) })
+        });
+
+    let request = MoveBudgetFundsRequest {
+        month: "2024-01".to_string(),
+        from_category: "ENTERTAINMENT".to_string(),
+        to_category: "FOOD_AND_DRINK".to_string(),
+        amount: dec!(50.00),
+    };
+
+    let moved = service
+        .move_funds(&repository, user_id, &request)
+        .await
+        .unwrap();
+
+    assert_eq!(moved.len(), 2);
+    assert_eq!(moved[1].assigned, dec!(50.00));
+}
+
+#[tokio::test]
+async fn given_mid_month_start_day_when_getting_summary_then_reads_the_shifted_month() {
+    use crate::models::user_settings::UserSettings;
+
+    let user_id = Uuid::new_v4();
+    let mut repository = MockDatabaseRepository::new();
+    let service = BudgetService::new();
+
+    repository
+        .expect_get_zero_based_budgeting_enabled()
+        .returning(|_| Box::pin(async { Ok(true) }));
+    repository.expect_get_user_settings().returning(|_| {
+        Box::pin(async {
+            Ok(Some(UserSettings {
+                month_start_day: 15,
+                ..UserSettings::default()
+            }))
+        })
+    });
+    repository
+        .expect_get_budget_allocations_for_user()
+        .returning(|_, _| Box::pin(async { Ok(vec![]) }));
+    repository
+        .expect_get_transactions_by_date_range_for_user()
+        .times(1)
+        .returning(|_, start, end| {
+            assert_eq!(start, NaiveDate::from_ymd_opt(2024, 1, 15).unwrap());
+            assert_eq!(end, NaiveDate::from_ymd_opt(2024, 2, 14).unwrap());
+            Box::pin(async { Ok(vec![]) })
+        });
+    repository
+        .expect_get_accounts_for_user()
+        .returning(|_| Box::pin(async { Ok(vec![]) }));
+
+    let summary = service
+        .get_zero_based_summary(&repository, user_id, "2024-01")
+        .await
+        .unwrap();
+
+    assert_eq!(summary.month, "2024-01");
+}
@@ -0,0 +1,319 @@
+use crate::models::archive::TransactionTag;
+use crate::models::bulk_edit::{
+    BulkTransactionEditRequest, BulkTransactionOperation, MAX_BULK_EDIT_TRANSACTIONS,
+};
+use crate::models::transaction::{TransactionWithAccount, TransactionsQuery};
+use crate::services::bulk_edit_service::BulkEditService;
+use crate::services::repository_service::MockDatabaseRepository;
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+fn listed(merchant: &str, category: &str) -> TransactionWithAccount {
+    TransactionWithAccount {
+        id: Uuid::new_v4(),
+        account_id: Uuid::new_v4(),
+        user_id: None,
+        provider_account_id: None,
+        provider_transaction_id: None,
+        amount: Decimal::new(1999, 2),
+        iso_currency_code: "USD".to_string(),
+        date: NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(),
+        merchant_name: Some(merchant.to_string()),
+        category_primary: category.to_string(),
+        category_detailed: category.to_string(),
+        category_confidence: "high".to_string(),
+        payment_channel: None,
+        pending: false,
+        created_at: None,
+        reviewed: false,
+        excluded_from_analytics: false,
+        account_name: "Checking".to_string(),
+        account_type: "depository".to_string(),
+        account_mask: None,
+    }
+}
+
+fn search(text: &str) -> TransactionsQuery {
+    TransactionsQuery {
+        search: Some(text.to_string()),
+        account_ids: vec![],
+    }
+}
+
+#[tokio::test]
+async fn given_filter_and_dry_run_when_applying_then_counts_changes_without_writing() {
+    let amazon = listed("Amazon", "GENERAL_MERCHANDISE");
+    let mut amazon_shopping = listed("Amazon Marketplace", "SHOPPING");
+    amazon_shopping.reviewed = true;
+    let tagged_id = amazon.id;
+    let matched_ids = vec![amazon.id, amazon_shopping.id];
+    let matches = vec![amazon, amazon_shopping];
+    let mut mock_db = MockDatabaseRepository::new();
+    mock_db
+        .expect_search_transaction_ids()
+        .withf(|_, search, _| search.text == ["amazon"])
+        .returning(move |_, _, _| {
+            let ids = matched_ids.clone();
+            Box::pin(async move { Ok(ids) })
+        });
+    mock_db
+        .expect_get_transactions_with_account_by_ids()
+        .withf(|_, ids| ids.len() == 2)
+        .returning(move |_, _| {
+            let matches = matches.clone();
+            Box::pin(async move { Ok(matches) })
+        });
+    mock_db
+        .expect_get_transaction_tags_for_user()
+        .returning(move |_| {
+            let tags = vec![TransactionTag {
+                transaction_id: tagged_id,
+                tag: "household".to_string(),
+            }];
+            Box::pin(async move { Ok(tags) })
+        });
+    mock_db.expect_apply_bulk_transaction_edit().never();
+    let request = BulkTransactionEditRequest {
+        transaction_ids: None,
+        filter: Some(search("amazon")),
+        operations: vec![
+            BulkTransactionOperation::SetCategory {
+                category: "GENERAL_MERCHANDISE".to_string(),
+                category_detailed: None,
+            },
+            BulkTransactionOperation::AddTag {
+                tag: " household ".to_string(),
+            },
+            BulkTransactionOperation::SetReviewed { reviewed: true },
+        ],
+        dry_run: true,
+    };
+
+    let response = BulkEditService::new()
+        .apply(&mock_db, Uuid::new_v4(), &request)
+        .await
+        .unwrap();
+
+    assert!(response.dry_run);
+    assert_eq!(response.matched, 2);
+    let affected: Vec<u64> = response.results.iter().map(|r| r.affected).collect();
+    assert_eq!(affected, vec![1, 1, 1]);
+    assert_eq!(
+        response.results[1].operation,
+        BulkTransactionOperation::AddTag {
+            tag: "household".to_string()
+        }
+    );
+    assert_eq!(response.preview.len(), 2);
+}
+
+#[tokio::test]
+async fn given_filter_matching_more_than_the_cap_when_applying_then_rejects_without_writing() {
+    let mut mock_db = MockDatabaseRepository::new();
+    mock_db
+        .expect_search_transaction_ids()
+        .returning(|_, _, _| {
+            let ids = (0..=MAX_BULK_EDIT_TRANSACTIONS)
+                .map(|_| Uuid::new_v4())
+                .collect();
+            Box::pin(async move { Ok(ids) })
+        });
+    mock_db
+        .expect_get_transactions_with_account_by_ids()
+        .never();
+    mock_db.expect_apply_bulk_transaction_edit().never();
+    let request = BulkTransactionEditRequest {
+        transaction_ids: None,
+        filter: Some(search("amazon")),
+        operations: vec![BulkTransactionOperation::SetReviewed { reviewed: true }],
+        dry_run: false,
+    };
+
+    let error = BulkEditService::new()
+        .apply(&mock_db, Uuid::new_v4(), &request)
+        .await
+        .unwrap_err();
+
+    assert_eq!(
+        error,
+        format!(
+            "Invalid filter: matches {} transactions, narrow it to at most {}",
+            MAX_BULK_EDIT_TRANSACTIONS + 1,
+            MAX_BULK_EDIT_TRANSACTIONS
+        )
+    );
+}
+
+#[tokio::test]
+async fn given_transaction_ids_when_applying_then_writes_selected_ids_in_one_call() {
+    let first = listed("Amazon", "SHOPPING");
+    let second = listed("Target", "SHOPPING");
+    let selected = vec![first.clone(), second.clone()];
+    let captured = Arc::new(Mutex::new(Vec::new()));
+    let captured_ids = captured.clone();
+    let mut mock_db = MockDatabaseRepository::new();
+    mock_db
+        .expect_get_transactions_with_account_by_ids()
+        .returning(move |_, _| {
+            let selected = selected.clone();
+            Box::pin(async move { Ok(selected) })
+        });
+    mock_db
+        .expect_apply_bulk_transaction_edit()
+        .times(1)
+        .withf(|_, _, operations| {
+            operations == [BulkTransactionOperation::SetExcludedFromAnalytics { excluded: true }]
+        })
+        .returning(move |_, ids, _| {
+            *captured_ids.lock().unwrap() = ids.to_vec();
+            Box::pin(async { Ok(vec![2]) })
+        });
+    let request = BulkTransactionEditRequest {
+        transaction_ids: Some(vec![first.id, second.id, first.id]),
+        filter: None,
+        operations: vec![BulkTransactionOperation::SetExcludedFromAnalytics { excluded: true }],
+        dry_run: false,
+    };
+
+    let response = BulkEditService::new()
+        .apply(&mock_db, Uuid::new_v4(), &request)
+        .await
+        .unwrap();
+
+    assert_eq!(response.matched, 2);
+    assert_eq!(response.results[0].affected, 2);
+    assert!(response.preview.is_empty());
+    let mut ids = captured.lock().unwrap().clone();
+    ids.sort();
+    let mut expected = vec![first.id, second.id];
+    expected.sort();
+    assert_eq!(ids, expected);
+}
+
+#[tokio::test]
+async fn given_ids_of_other_users_when_applying_then_rejects_without_writing() {
+    let mine = listed("Amazon", "SHOPPING");
+    let returned = vec![mine.clone()];
+    let mut mock_db = MockDatabaseRepository::new();
+    mock_db
+        .expect_get_transactions_with_account_by_ids()
+        .returning(move |_, _| {
+            let returned = returned.clone();
+            Box::pin(async move { Ok(returned) })
+        });
+    mock_db.expect_apply_bulk_transaction_edit().never();
+    let request = BulkTransactionEditRequest {
+        transaction_ids: Some(vec![mine.id, Uuid::new_v4()]),
+        filter: None,
+        operations: vec![BulkTransactionOperation::SetReviewed { reviewed: true }],
+        dry_run: false,
+    };
+
+    let error = BulkEditService::new()
+        .apply(&mock_db, Uuid::new_v4(), &request)
+        .await
+        .unwrap_err();
+
+    assert_eq!(error, "Invalid transaction_ids: 1 do not exist");
+}
+
+#[tokio::test]
+async fn given_bad_selection_or_operations_when_applying_then_rejects_before_loading() {
+    let mut mock_db = MockDatabaseRepository::new();
+    mock_db
+        .expect_get_transactions_with_account_by_ids()
+        .never();
+    mock_db
+        .expect_get_transactions_with_account_for_user()
+        .never();
+    let reviewed = || vec![BulkTransactionOperation::SetReviewed { reviewed: true }];
+    let requests = [
+        BulkTransactionEditRequest {
+            transaction_ids: Some(vec![Uuid::new_v4()]),
+            filter: Some(search("amazon")),
+            operations: reviewed(),
+            dry_run: false,
+        },
+        BulkTransactionEditRequest {
+            transaction_ids: None,
+            filter: None,
+            operations: reviewed(),
+            dry_run: false,
+        },
+        BulkTransactionEditRequest {
+            transaction_ids: Some(vec![]),
+            filter: None,
+            operations: reviewed(),
+            dry_run: false,
+        },
+        BulkTransactionEditRequest {
+            transaction_ids: Some(vec![Uuid::new_v4()]),
+            filter: None,
+            operations: vec![],
+            dry_run: false,
+        },
+        BulkTransactionEditRequest {
+            transaction_ids: Some(vec![Uuid::new_v4()]),
+            filter: None,
+            operations: vec![BulkTransactionOperation::RemoveTag {
+                tag: "x".repeat(65),
+            }],
+            dry_run: false,
+        },
+    ];
+
+    for request in requests {
+        let error = BulkEditService::new()
+            .apply(&mock_db, Uuid::new_v4(), &request)
+            .await
+            .unwrap_err();
+        assert!(error.starts_with("Invalid"), "{}", error);
+    }
+}
+
+#[test]
+fn given_operations_that_undo_each_other_when_simulating_then_counts_each_in_order() {
+    let untagged = listed("Amazon", "SHOPPING");
+    let tagged = listed("Target", "SHOPPING");
+    let tags = vec![TransactionTag {
+        transaction_id: tagged.id,
+        tag: "gifts".to_string(),
+    }];
+    let gifts = || "gifts".to_string();
+    let operations = vec![
+        BulkTransactionOperation::AddTag { tag: gifts() },
+        BulkTransactionOperation::RemoveTag { tag: gifts() },
+        BulkTransactionOperation::RemoveTag { tag: gifts() },
+        BulkTransactionOperation::SetExcludedFromAnalytics { excluded: false },
+    ];
+
+    let affected = BulkEditService::simulate(&[untagged, tagged], &tags, &operations);
+
+    assert_eq!(affected, vec![1, 2, 0, 0]);
+}
+
+#[test]
+fn given_bulk_request_json_when_deserializing_then_accepts_transactions_query_filter() {
+    let request: BulkTransactionEditRequest = serde_json::from_value(serde_json::json!({
+        "filter": {"search": "amazon", "account_ids": ["aaaaaaaa-bbbb-cccc-dddd-eeeeeeeeeeee"]},
+        "operations": [
+            {"op": "set_category", "category": "SHOPPING"},
+            {"op": "set_excluded_from_analytics", "excluded": true}
+        ]
+    }))
+    .unwrap();
+
+    let filter = request.filter.unwrap();
+    assert_eq!(filter.search.as_deref(), Some("amazon"));
+    assert_eq!(filter.account_ids.len(), 1);
+    assert!(!request.dry_run);
+    assert_eq!(
+        request.operations[0],
+        BulkTransactionOperation::SetCategory {
+            category: "SHOPPING".to_string(),
+            category_detailed: None
+        }
+    );
+}
@@ -71,6 +71,7 @@ async fn given_bank_accounts_when_caching_with_jwt_scope_then_stores_with_correc
             name: "Checking Account".to_string(),
             account_type: "depository".to_string(),
             balance_current: Some(Decimal::new(150000, 2)),
+            iso_currency_code: "USD".to_string(),
             mask: Some("1234".to_string()),
             institution_name: None,
         },
@@ -82,6 +83,7 @@ async fn given_bank_accounts_when_caching_with_jwt_scope_then_stores_with_correc
             name: "Savings Account".to_string(),
             account_type: "depository".to_string(),
             balance_current: Some(Decimal::new(300000, 2)),
+            iso_currency_code: "USD".to_string(),
             mask: Some("5678".to_string()),
             institution_name: None,
         },
@@ -190,6 +192,7 @@ fn given_cached_transaction_when_serializing_then_includes_timestamp() {
         provider_account_id: None,
         provider_transaction_id: None,
         amount: Decimal::new(1234, 2),
+        iso_currency_code: "USD".to_string(),
         date: Utc::now().date_naive(),
         merchant_name: Some("Demo".to_string()),
         category_primary: "Misc".to_string(),
@@ -198,6 +201,8 @@ fn given_cached_transaction_when_serializing_then_includes_timestamp() {
         payment_channel: Some("online".to_string()),
         pending: false,
         created_at: Some(Utc::now()),
+        reviewed: false,
+        excluded_from_analytics: false,
     }];
 
     let cached = CachedTransaction {
@@ -74,3 +74,48 @@ fn given_teller_environment_when_from_env_provider_then_uses_value() {
 
     assert_eq!(config.get_teller_environment(), "sandbox");
 }
+
+#[test]
+fn given_smtp_host_without_port_when_from_env_provider_then_defaults_to_starttls_submission() {
+    let mut env = MockEnvironment::new();
+    env.set("TELLER_ENV", "development");
+    env.set("SMTP_HOST", "smtp.example.com");
+    env.set("SMTP_FROM", "alerts@example.com");
+
+    let config = Config::from_env_provider(&env).unwrap();
+
+    let smtp = config.get_smtp_settings().unwrap();
+    assert_eq!(smtp.port, 587);
+    assert_eq!(smtp.security, crate::config::SmtpSecurity::StartTls);
+}
+
+#[test]
+fn given_smtp_host_without_from_when_from_env_provider_then_returns_error() {
+    let mut env = MockEnvironment::new();
+    env.set("TELLER_ENV", "development");
+    env.set("SMTP_HOST", "smtp.example.com");
+
+    assert!(Config::from_env_provider(&env).is_err());
+    assert!(Config::from_env_provider(&{
+        let mut env = MockEnvironment::new();
+        env.set("TELLER_ENV", "development");
+        env
+    })
+    .unwrap()
+    .get_smtp_settings()
+    .is_none());
+}
+
+#[test]
+fn given_webhook_allowed_hosts_when_from_env_provider_then_splits_and_lowercases() {
+    let mut env = MockEnvironment::new();
+    env.set("TELLER_ENV", "development");
+    env.set("WEBHOOK_ALLOWED_HOSTS", " Receiver.local, ,127.0.0.1 ");
+
+    let config = Config::from_env_provider(&env).unwrap();
+
+    assert_eq!(
+        config.get_webhook_allowed_hosts(),
+        ["receiver.local", "127.0.0.1"]
+    );
+}
@@ -43,6 +43,7 @@ fn create_test_accounts() -> Vec<Account> {
             name: "Checking".to_string(),
             account_type: "depository".to_string(),
             balance_current: Some(Decimal::new(150000, 2)),
+            iso_currency_code: "USD".to_string(),
             mask: Some("1234".to_string()),
             institution_name: None,
         },
@@ -54,6 +55,7 @@ fn create_test_accounts() -> Vec<Account> {
             name: "Savings".to_string(),
             account_type: "depository".to_string(),
             balance_current: Some(Decimal::new(300000, 2)),
+            iso_currency_code: "USD".to_string(),
             mask: Some("5678".to_string()),
             institution_name: None,
         },
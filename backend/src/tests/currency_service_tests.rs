@@ -0,0 +1,185 @@
+use crate::models::currency::FxRate;
+use crate::models::user_settings::UserSettings;
+use crate::services::currency_service::{CurrencyService, FxConverter};
+use crate::services::repository_service::MockDatabaseRepository;
+use crate::test_fixtures::TestFixtures;
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use uuid::Uuid;
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+fn fx(base: &str, quote: &str, on: NaiveDate, rate: Decimal) -> FxRate {
+    FxRate {
+        base_currency: base.to_string(),
+        quote_currency: quote.to_string(),
+        rate_date: on,
+        rate,
+    }
+}
+
+#[test]
+fn given_dated_rates_when_converting_then_uses_latest_rate_on_or_before_the_day() {
+    let converter = FxConverter::new(
+        "USD",
+        &[
+            fx("EUR", "USD", date(2024, 3, 1), dec!(1.10)),
+            fx("EUR", "USD", date(2024, 3, 15), dec!(1.20)),
+        ],
+    );
+
+    assert_eq!(
+        converter.convert(dec!(100), "EUR", date(2024, 3, 10)),
+        Some(dec!(110.00))
+    );
+    assert_eq!(
+        converter.convert(dec!(100), "EUR", date(2024, 3, 20)),
+        Some(dec!(120.00))
+    );
+    assert_eq!(
+        converter.convert(dec!(100), "EUR", date(2024, 2, 1)),
+        Some(dec!(110.00))
+    );
+    assert_eq!(
+        converter.convert(dec!(100), "USD", date(2024, 2, 1)),
+        Some(dec!(100))
+    );
+    assert_eq!(converter.convert(dec!(100), "JPY", date(2024, 3, 10)), None);
+}
+
+#[test]
+fn given_only_inverse_or_pivot_quotes_when_converting_then_derives_the_rate() {
+    let on = date(2024, 3, 1);
+    let converter = FxConverter::new(
+        "EUR",
+        &[
+            fx("EUR", "USD", on, dec!(1.25)),
+            fx("USD", "CAD", on, dec!(1.50)),
+        ],
+    );
+
+    assert_eq!(converter.convert(dec!(125), "USD", on), Some(dec!(100)));
+    assert_eq!(
+        converter
+            .convert(dec!(150), "CAD", on)
+            .map(|amount| amount.round_dp(6)),
+        Some(dec!(80))
+    );
+}
+
+#[test]
+fn given_two_possible_pivots_when_crossing_then_always_goes_through_usd() {
+    let on = date(2024, 3, 1);
+    let via_euro = [
+        fx("CHF", "EUR", on, dec!(1.05)),
+        fx("EUR", "JPY", on, dec!(160)),
+    ];
+    let mut both = vec![
+        fx("CHF", "USD", on, dec!(1.10)),
+        fx("USD", "JPY", on, dec!(150)),
+    ];
+    both.extend(via_euro.iter().cloned());
+
+    // Each converter hashes with a fresh seed, so repeat to cover many orders.
+    for _ in 0..20 {
+        let converter = FxConverter::new("JPY", &both);
+        assert_eq!(converter.rate("CHF", "JPY", on), Some(dec!(165.00)));
+    }
+    assert_eq!(
+        FxConverter::new("JPY", &via_euro).rate("CHF", "JPY", on),
+        Some(dec!(168.00))
+    );
+}
+
+#[test]
+fn given_rates_file_when_parsing_then_skips_header_and_comments_and_reports_bad_lines() {
+    let content = "date,base,quote,rate\n# ECB reference\n\n2024-03-01, eur, usd, 1.0842\n";
+
+    let rates = CurrencyService::parse_fx_rates_csv(content).unwrap();
+
+    assert_eq!(
+        rates,
+        vec![fx("EUR", "USD", date(2024, 3, 1), dec!(1.0842))]
+    );
+    assert_eq!(
+        CurrencyService::parse_fx_rates_csv("2024-03-01,EUR,USD,0").unwrap_err(),
+        "Invalid FX rate on line 1: rate must be a positive number"
+    );
+    assert_eq!(
+        CurrencyService::parse_fx_rates_csv("date,base,quote,rate\n03/01/2024,EUR,USD,1.1")
+            .unwrap_err(),
+        "Invalid FX rate on line 2: date must be YYYY-MM-DD"
+    );
+}
+
+#[tokio::test]
+async fn given_euro_reporting_currency_when_converting_transactions_then_restates_and_drops_unpriced(
+) {
+    let user_id = Uuid::new_v4();
+    let on = date(2024, 3, 5);
+    let usd = TestFixtures::transaction_on(on, dec!(110), "FOOD_AND_DRINK", "Cafe");
+    let mut yen = TestFixtures::transaction_on(on, dec!(5000), "TRAVEL", "Rail");
+    yen.iso_currency_code = "JPY".to_string();
+
+    let mut mock_db = MockDatabaseRepository::new();
+    mock_db.expect_get_user_settings().returning(|_| {
+        Box::pin(async {
+            Ok(Some(UserSettings {
+                reporting_currency: "EUR".to_string(),
+                ..UserSettings::default()
+            }))
+        })
+    });
+    mock_db
+        .expect_get_fx_rates()
+        .times(1)
+        .returning(move |_, _| {
+            Box::pin(async move { Ok(vec![fx("EUR", "USD", date(2024, 3, 1), dec!(1.10))]) })
+        });
+
+    let converted = CurrencyService::convert_transactions(&mock_db, user_id, vec![usd, yen])
+        .await
+        .unwrap();
+
+    assert_eq!(converted.len(), 1);
+    assert_eq!(converted[0].amount, dec!(100));
+    assert_eq!(converted[0].iso_currency_code, "EUR");
+}
+
+#[test]
+fn given_aggregate_buckets_when_merging_then_converts_dated_buckets_and_drops_unpriced() {
+    use crate::models::analytics::AggregateBucket;
+
+    let bucket = |key: &str, currency: &str, rate_date, total, count| AggregateBucket {
+        key: key.to_string(),
+        iso_currency_code: currency.to_string(),
+        rate_date,
+        total,
+        count,
+    };
+    let converter = FxConverter::new(
+        "USD",
+        &[
+            fx("EUR", "USD", date(2024, 3, 1), dec!(1.10)),
+            fx("EUR", "USD", date(2024, 3, 10), dec!(1.20)),
+        ],
+    );
+
+    let merged = CurrencyService::merge_aggregates(
+        &converter,
+        vec![
+            bucket("Groceries", "USD", None, dec!(300), 4),
+            bucket("Groceries", "EUR", Some(date(2024, 3, 2)), dec!(100), 1),
+            bucket("Groceries", "EUR", Some(date(2024, 3, 12)), dec!(50), 2),
+            bucket("Travel", "JPY", Some(date(2024, 3, 5)), dec!(9000), 1),
+        ],
+    );
+
+    assert_eq!(merged.len(), 1);
+    assert_eq!(merged[0].key, "Groceries");
+    assert_eq!(merged[0].total, dec!(470.00));
+    assert_eq!(merged[0].count, 7);
+}
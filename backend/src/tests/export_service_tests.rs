@@ -0,0 +1,292 @@
+use crate::models::export::{ExportColumn, ExportFormat, TransactionPageCursor};
+use crate::models::transaction::{TransactionWithAccount, TransactionsQuery};
+use crate::services::export_service::{ExportService, TransactionExportWriter, EXPORT_PAGE_SIZE};
+use crate::services::repository_service::{DatabaseRepository, MockDatabaseRepository};
+use crate::test_fixtures::TestFixtures;
+use axum::body::to_bytes;
+use chrono::{NaiveDate, TimeZone, Utc};
+use futures::StreamExt;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::sync::Arc;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+fn transaction(
+    account_id: Uuid,
+    account_type: &str,
+    date: NaiveDate,
+    amount: Decimal,
+    merchant: &str,
+) -> TransactionWithAccount {
+    TransactionWithAccount {
+        id: Uuid::new_v4(),
+        account_id,
+        user_id: None,
+        provider_account_id: None,
+        provider_transaction_id: Some(format!("txn-{}", merchant.to_lowercase())),
+        amount,
+        iso_currency_code: "USD".to_string(),
+        date,
+        merchant_name: Some(merchant.to_string()),
+        category_primary: "FOOD_AND_DRINK".to_string(),
+        category_detailed: String::new(),
+        category_confidence: String::new(),
+        payment_channel: None,
+        pending: false,
+        created_at: None,
+        reviewed: false,
+        excluded_from_analytics: false,
+        account_name: format!("{} account", account_type),
+        account_type: account_type.to_string(),
+        account_mask: Some("1234".to_string()),
+    }
+}
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+fn render(
+    format: ExportFormat,
+    columns: Vec<ExportColumn>,
+    transactions: &[TransactionWithAccount],
+) -> String {
+    let generated_at = Utc.with_ymd_and_hms(2024, 3, 1, 12, 0, 0).unwrap();
+    let mut writer = TransactionExportWriter::new(format, columns, generated_at);
+    let mut out = writer.header().unwrap();
+    out.extend(writer.write(transactions).unwrap());
+    out.extend(writer.finish());
+    String::from_utf8(out).unwrap()
+}
+
+fn no_filters() -> TransactionsQuery {
+    TransactionsQuery {
+        search: None,
+        account_ids: vec![],
+    }
+}
+
+#[test]
+fn given_column_list_when_exporting_csv_then_writes_requested_columns_in_order() {
+    let columns = ExportColumn::parse_list(Some("merchant, Amount,date")).unwrap();
+    let rows = vec![transaction(
+        Uuid::new_v4(),
+        "depository",
+        date(2024, 1, 15),
+        dec!(42.75),
+        "Smith, Jones & Co",
+    )];
+
+    let csv = render(ExportFormat::Csv, columns, &rows);
+
+    assert_eq!(
+        csv,
+        "merchant,amount,date\n\"Smith, Jones & Co\",42.75,2024-01-15\n"
+    );
+    assert_eq!(
+        ExportColumn::parse_list(None).unwrap(),
+        ExportColumn::DEFAULT.to_vec()
+    );
+    assert_eq!(
+        ExportColumn::parse_list(Some("date,balance")).unwrap_err(),
+        "Invalid export column: balance"
+    );
+}
+
+#[test]
+fn given_bank_and_credit_accounts_when_exporting_ofx_then_writes_one_statement_per_account() {
+    let card = Uuid::new_v4();
+    let checking = Uuid::new_v4();
+    let savings = Uuid::new_v4();
+    let mut salary = transaction(checking, "depository", date(2024, 1, 2), dec!(2500), "Acme");
+    salary.category_primary = "INCOME".to_string();
+    let rows = vec![
+        transaction(card, "credit", date(2024, 1, 5), dec!(12.5), "Cafe <Bar>"),
+        salary,
+        transaction(checking, "depository", date(2024, 1, 9), dec!(80), "Grocer"),
+        transaction(
+            savings,
+            "depository",
+            date(2024, 1, 3),
+            dec!(-10),
+            "Interest",
+        ),
+    ];
+
+    let ofx = render(ExportFormat::Ofx, vec![], &rows);
+
+    assert_eq!(ofx.matches("<CREDITCARDMSGSRSV1>").count(), 1);
+    assert_eq!(ofx.matches("<BANKMSGSRSV1>").count(), 1);
+    assert_eq!(ofx.matches("<CCSTMTRS>").count(), 1);
+    assert_eq!(ofx.matches("<STMTRS>").count(), 2);
+    assert_eq!(ofx.matches("<STMTTRN>").count(), 4);
+    assert!(ofx.find("</CREDITCARDMSGSRSV1>") < ofx.find("<BANKMSGSRSV1>"));
+    assert!(ofx.contains(
+        "<TRNTYPE>DEBIT</TRNTYPE>\n<DTPOSTED>20240105</DTPOSTED>\n<TRNAMT>-12.50</TRNAMT>"
+    ));
+    assert!(ofx.contains("<NAME>Cafe &lt;Bar&gt;</NAME>"));
+    assert!(ofx.contains(
+        "<TRNTYPE>CREDIT</TRNTYPE>\n<DTPOSTED>20240102</DTPOSTED>\n<TRNAMT>2500.00</TRNAMT>"
+    ));
+    assert!(ofx.contains("<TRNAMT>10.00</TRNAMT>"));
+    assert!(ofx.contains("<DTEND>20240301120000</DTEND>"));
+    assert!(ofx.ends_with("</BANKTRANLIST>\n</STMTRS>\n</STMTTRNRS>\n</BANKMSGSRSV1>\n</OFX>\n"));
+}
+
+#[test]
+fn given_two_accounts_when_exporting_qif_then_switches_account_before_each_group() {
+    let card = Uuid::new_v4();
+    let checking = Uuid::new_v4();
+    let rows = vec![
+        transaction(card, "credit", date(2024, 1, 5), dec!(12.5), "Cafe"),
+        transaction(card, "credit", date(2024, 1, 6), dec!(3), "Bakery"),
+        transaction(checking, "depository", date(2024, 1, 9), dec!(80), "Grocer"),
+    ];
+
+    let qif = render(ExportFormat::Qif, vec![], &rows);
+
+    assert_eq!(
+        qif,
+        "!Account\nNcredit account\nTCCard\n^\n!Type:CCard\n\
+         D01/05/2024\nT-12.50\nPCafe\nLFOOD_AND_DRINK\n^\n\
+         D01/06/2024\nT-3.00\nPBakery\nLFOOD_AND_DRINK\n^\n\
+         !Account\nNdepository account\nTBank\n^\n!Type:Bank\n\
+         D01/09/2024\nT-80.00\nPGrocer\nLFOOD_AND_DRINK\n^\n"
+    );
+}
+
+#[tokio::test]
+async fn given_more_than_one_page_when_streaming_json_then_resumes_after_last_row_and_filters() {
+    let account_id = Uuid::new_v4();
+    let first_page: Vec<TransactionWithAccount> = (0..EXPORT_PAGE_SIZE)
+        .map(|_| transaction(account_id, "depository", date(2024, 1, 1), dec!(5), "Cafe"))
+        .collect();
+    let expected_cursor = TransactionPageCursor::from(first_page.last().unwrap());
+    let second_page = vec![
+        transaction(account_id, "depository", date(2024, 2, 1), dec!(9), "Cafe"),
+        transaction(
+            account_id,
+            "depository",
+            date(2024, 2, 2),
+            dec!(7),
+            "Bookshop",
+        ),
+    ];
+
+    let mut mock_db = MockDatabaseRepository::new();
+    mock_db
+        .expect_get_transactions_with_account_page()
+        .times(2)
+        .returning(move |_, after, limit| {
+            assert_eq!(limit, EXPORT_PAGE_SIZE);
+            let page = match after {
+                None => first_page.clone(),
+                Some(cursor) => {
+                    assert_eq!(cursor, expected_cursor);
+                    second_page.clone()
+                }
+            };
+            Box::pin(async move { Ok(page) })
+        });
+    let repository: Arc<dyn DatabaseRepository> = Arc::new(mock_db);
+    let filters = TransactionsQuery {
+        search: Some(" cafe ".to_string()),
+        account_ids: vec![],
+    };
+    let writer = TransactionExportWriter::new(ExportFormat::Json, vec![], Utc::now());
+
+    let chunks: Vec<Vec<u8>> = ExportService::new()
+        .stream_transactions(repository, Uuid::new_v4(), filters, writer)
+        .map(|chunk| chunk.unwrap())
+        .collect()
+        .await;
+
+    assert_eq!(chunks.len(), 4);
+    let exported: Vec<TransactionWithAccount> = serde_json::from_slice(&chunks.concat()).unwrap();
+    assert_eq!(exported.len(), EXPORT_PAGE_SIZE as usize + 1);
+    assert!(exported
+        .iter()
+        .all(|t| t.merchant_name.as_deref() == Some("Cafe")));
+}
+
+#[tokio::test]
+async fn given_repository_failure_when_streaming_then_ends_with_error() {
+    let mut mock_db = MockDatabaseRepository::new();
+    mock_db
+        .expect_get_transactions_with_account_page()
+        .returning(|_, _, _| Box::pin(async { Err(anyhow::anyhow!("connection reset")) }));
+    let repository: Arc<dyn DatabaseRepository> = Arc::new(mock_db);
+    let writer = TransactionExportWriter::new(ExportFormat::Csv, vec![], Utc::now());
+
+    let chunks: Vec<Result<Vec<u8>, String>> = ExportService::new()
+        .stream_transactions(repository, Uuid::new_v4(), no_filters(), writer)
+        .collect()
+        .await;
+
+    assert_eq!(chunks.len(), 2);
+    assert_eq!(
+        chunks[1].clone().unwrap_err(),
+        "Failed to load transactions for export: connection reset"
+    );
+}
+
+#[tokio::test]
+async fn given_csv_format_when_requesting_export_then_returns_attachment() {
+    let (_user, token) = TestFixtures::create_authenticated_user_with_token();
+    let mut mock_db = MockDatabaseRepository::new();
+    mock_db
+        .expect_get_transactions_with_account_page()
+        .returning(|_, _, _| {
+            let rows = vec![transaction(
+                Uuid::new_v4(),
+                "depository",
+                NaiveDate::from_ymd_opt(2024, 1, 15).unwrap(),
+                dec!(42.75),
+                "Cafe",
+            )];
+            Box::pin(async move { Ok(rows) })
+        });
+    let app = TestFixtures::create_test_app_with_db(mock_db)
+        .await
+        .unwrap();
+
+    let request = TestFixtures::create_authenticated_get_request(
+        "/api/transactions/export?format=csv&columns=date,amount,merchant",
+        &token,
+    );
+    let response = app.oneshot(request).await.unwrap();
+
+    assert_eq!(response.status(), 200);
+    assert_eq!(
+        response.headers()["content-type"],
+        "text/csv; charset=utf-8"
+    );
+    assert!(response.headers()["content-disposition"]
+        .to_str()
+        .unwrap()
+        .ends_with(".csv\""));
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    assert_eq!(
+        String::from_utf8(body.to_vec()).unwrap(),
+        "date,amount,merchant\n2024-01-15,42.75,Cafe\n"
+    );
+}
+
+#[tokio::test]
+async fn given_unknown_column_when_requesting_export_then_returns_bad_request() {
+    let (_user, token) = TestFixtures::create_authenticated_user_with_token();
+    let mut mock_db = MockDatabaseRepository::new();
+    mock_db.expect_get_transactions_with_account_page().never();
+    let app = TestFixtures::create_test_app_with_db(mock_db)
+        .await
+        .unwrap();
+
+    let request = TestFixtures::create_authenticated_get_request(
+        "/api/transactions/export?format=csv&columns=date,balance",
+        &token,
+    );
+    let response = app.oneshot(request).await.unwrap();
+
+    assert_eq!(response.status(), 400);
+}
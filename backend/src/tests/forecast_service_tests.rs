@@ -0,0 +1,186 @@
+use crate::models::plaid::LatestAccountBalance;
+use crate::services::forecast_service::{ForecastInputs, ForecastOptions, ForecastService};
+use crate::services::repository_service::MockDatabaseRepository;
+use crate::test_fixtures::TestFixtures;
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use uuid::Uuid;
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+fn balance_row(account_type: &str, balance: Decimal) -> LatestAccountBalance {
+    LatestAccountBalance {
+        account_id: Uuid::new_v4(),
+        institution_id: "bank".to_string(),
+        account_type: account_type.to_string(),
+        account_subtype: None,
+        currency: "USD".to_string(),
+        current_balance: balance,
+        provider_connection_id: None,
+        institution_name: Some("Bank".to_string()),
+    }
+}
+
+#[test]
+fn given_rent_due_before_payday_when_projecting_then_flags_low_balance_dates() {
+    let today = date(2024, 3, 20);
+    let transactions = vec![
+        TestFixtures::transaction_on(
+            date(2024, 1, 25),
+            dec!(1200.00),
+            "RENT_AND_UTILITIES",
+            "Landlord LLC",
+        ),
+        TestFixtures::transaction_on(
+            date(2024, 2, 25),
+            dec!(1200.00),
+            "RENT_AND_UTILITIES",
+            "Landlord LLC",
+        ),
+        TestFixtures::transaction_on(
+            date(2023, 12, 25),
+            dec!(1200.00),
+            "RENT_AND_UTILITIES",
+            "Landlord LLC",
+        ),
+        TestFixtures::transaction_on(date(2024, 1, 1), dec!(-3000.00), "INCOME", "ACME PAYROLL"),
+        TestFixtures::transaction_on(date(2024, 2, 1), dec!(-3000.00), "INCOME", "ACME PAYROLL"),
+        TestFixtures::transaction_on(date(2024, 3, 1), dec!(-3000.00), "INCOME", "ACME PAYROLL"),
+    ];
+
+    let forecast = ForecastService::project(ForecastInputs {
+        today,
+        horizon_days: 30,
+        low_balance_threshold: Decimal::ZERO,
+        account_ids: vec![],
+        starting_balance: dec!(800.00),
+        transactions: &transactions,
+    });
+
+    assert_eq!(forecast.series.len(), 30);
+    assert_eq!(forecast.series[0].date, date(2024, 3, 21));
+
+    let rent_day = forecast
+        .series
+        .iter()
+        .find(|p| p.date == date(2024, 3, 25))
+        .unwrap();
+    assert_eq!(rent_day.balance, dec!(-400.00));
+    assert!(rent_day.low_balance);
+
+    let payday = forecast
+        .series
+        .iter()
+        .find(|p| p.date == date(2024, 4, 1))
+        .unwrap();
+    assert_eq!(payday.inflows, dec!(3000.00));
+    assert!(!payday.low_balance);
+    assert_eq!(
+        forecast.low_balance_dates,
+        (25..=31).map(|d| date(2024, 3, d)).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn given_rent_due_today_when_projecting_then_books_it_on_the_first_day() {
+    let today = date(2024, 3, 25);
+    let transactions: Vec<_> = [date(2023, 12, 25), date(2024, 1, 25), date(2024, 2, 25)]
+        .into_iter()
+        .map(|day| {
+            TestFixtures::transaction_on(day, dec!(1200.00), "RENT_AND_UTILITIES", "Landlord LLC")
+        })
+        .collect();
+
+    let forecast = ForecastService::project(ForecastInputs {
+        today,
+        horizon_days: 10,
+        low_balance_threshold: Decimal::ZERO,
+        account_ids: vec![],
+        starting_balance: dec!(800.00),
+        transactions: &transactions,
+    });
+
+    assert_eq!(forecast.recurring[0].next_expected_date, today);
+    assert_eq!(forecast.series[0].date, date(2024, 3, 26));
+    assert_eq!(forecast.series[0].outflows, dec!(1200.00));
+    assert_eq!(forecast.series[0].balance, dec!(-400.00));
+    assert_eq!(forecast.series[9].balance, dec!(-400.00));
+}
+
+#[test]
+fn given_discretionary_spend_when_projecting_then_applies_daily_average_and_widens_band() {
+    let today = date(2024, 3, 31);
+    let transactions = vec![
+        TestFixtures::transaction_on(date(2024, 3, 10), dec!(450.00), "FOOD_AND_DRINK", "Grocer"),
+        TestFixtures::transaction_on(
+            date(2024, 2, 10),
+            dec!(450.00),
+            "FOOD_AND_DRINK",
+            "Farmers Market",
+        ),
+    ];
+
+    let forecast = ForecastService::project(ForecastInputs {
+        today,
+        horizon_days: 30,
+        low_balance_threshold: Decimal::ZERO,
+        account_ids: vec![],
+        starting_balance: dec!(1000.00),
+        transactions: &transactions,
+    });
+
+    assert_eq!(forecast.discretionary.len(), 1);
+    assert_eq!(forecast.discretionary[0].daily_average, dec!(10.00));
+    assert_eq!(forecast.series[0].balance, dec!(990.00));
+    assert_eq!(forecast.series[29].balance, dec!(700.00));
+
+    let first_width = forecast.series[0].upper - forecast.series[0].lower;
+    let last_width = forecast.series[29].upper - forecast.series[29].lower;
+    assert!(first_width > Decimal::ZERO);
+    assert!(last_width > first_width);
+}
+
+#[tokio::test]
+async fn given_mixed_account_types_when_forecasting_for_user_then_starts_from_depository_only() {
+    let checking = balance_row("depository", dec!(1500.00));
+    let savings = balance_row("depository", dec!(4000.00));
+    let credit = balance_row("credit", dec!(-900.00));
+    let checking_id = checking.account_id;
+
+    let mut repository = MockDatabaseRepository::new();
+    let rows = vec![checking, savings, credit];
+    repository
+        .expect_get_user_settings()
+        .returning(|_| Box::pin(async { Ok(None) }));
+    repository
+        .expect_get_latest_account_balances_for_user()
+        .times(1)
+        .returning(move |_| {
+            let rows = rows.clone();
+            Box::pin(async move { Ok(rows) })
+        });
+    repository
+        .expect_get_transactions_by_date_range_for_user()
+        .times(1)
+        .returning(|_, _, _| Box::pin(async { Ok(vec![]) }));
+
+    let options = ForecastOptions {
+        today: date(2024, 3, 1),
+        horizon_days: 45,
+        low_balance_threshold: dec!(100),
+        account_ids: Some([checking_id].into_iter().collect()),
+    };
+
+    let forecast = ForecastService::new()
+        .forecast_for_user(&repository, Uuid::new_v4(), &options)
+        .await
+        .unwrap();
+
+    assert_eq!(forecast.account_ids, vec![checking_id]);
+    assert_eq!(forecast.starting_balance, dec!(1500.00));
+    assert_eq!(forecast.series.len(), 45);
+    assert!(forecast.low_balance_dates.is_empty());
+}
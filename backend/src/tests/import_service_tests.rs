@@ -0,0 +1,286 @@
+use crate::models::account::Account;
+use crate::models::budget::Budget;
+use crate::models::import::{ImportRequest, ImportSource, ImportedBudget};
+use crate::models::transaction::Transaction;
+use crate::services::import_service::ImportService;
+use crate::services::repository_service::MockDatabaseRepository;
+use crate::test_fixtures::TestFixtures;
+use axum::body::to_bytes;
+use chrono::NaiveDate;
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+const MINT_CSV: &str = "\"Date\",\"Description\",\"Original Description\",\"Amount\",\"Transaction Type\",\"Category\",\"Account Name\",\"Labels\",\"Notes\"\n\
+\"1/15/2024\",\"Blue Bottle\",\"BLUE BOTTLE #12\",\"4.50\",\"debit\",\"Coffee Shops\",\"Everyday Checking\",\"\",\"\"\n\
+\"1/31/2024\",\"Acme Corp\",\"ACME PAYROLL\",\"2,500.00\",\"credit\",\"Paycheck\",\"Everyday Checking\",\"\",\"\"\n\
+\"2/02/2024\",\"Pet Palace\",\"PET PALACE\",\"60.00\",\"debit\",\"Pet Food & Supplies\",\"Rewards Visa\",\"\",\"\"\n";
+
+const YNAB_REGISTER_CSV: &str = "\u{feff}\"Account\",\"Flag\",\"Date\",\"Payee\",\"Category Group/Category\",\"Category Group\",\"Category\",\"Memo\",\"Outflow\",\"Inflow\",\"Cleared\"\n\
+\"Checking\",\"\",\"01/03/2024\",\"Landlord\",\"Bills: Rent/Mortgage\",\"Bills\",\"Rent/Mortgage\",\"\",\"$1,450.00\",\"$0.00\",\"Cleared\"\n\
+\"Checking\",\"\",\"01/05/2024\",\"Transfer : Savings\",\"\",\"\",\"\",\"\",\"$200.00\",\"$0.00\",\"Cleared\"\n\
+\"Checking\",\"\",\"01/06/2024\",\"Employer\",\"Inflow: Ready to Assign\",\"Inflow\",\"Ready to Assign\",\"\",\"$0.00\",\"$3,000.00\",\"Cleared\"\n";
+
+const YNAB_BUDGET_CSV: &str = "\"Month\",\"Category Group/Category\",\"Category Group\",\"Category\",\"Budgeted\",\"Activity\",\"Available\"\n\
+\"Dec 2023\",\"Bills: Rent/Mortgage\",\"Bills\",\"Rent/Mortgage\",\"$1,400.00\",\"-$1,400.00\",\"$0.00\"\n\
+\"Jan 2024\",\"Bills: Rent/Mortgage\",\"Bills\",\"Rent/Mortgage\",\"$1,450.00\",\"-$1,450.00\",\"$0.00\"\n\
+\"Jan 2024\",\"Food: Groceries\",\"Food\",\"Groceries\",\"$400.00\",\"$0.00\",\"$400.00\"\n\
+\"Jan 2024\",\"Food: Dining Out\",\"Food\",\"Dining Out\",\"$0.00\",\"$0.00\",\"$0.00\"\n";
+
+const ACTUAL_CSV: &str = "Account,Date,Payee,Notes,Category,Amount,Split_Amount,Cleared\n\
+Checking,2024-03-01,Grocer,,Food,-82.15,0,Cleared\n\
+Checking,2024-03-02,Transfer: Savings,,,-100,0,Cleared\n\
+Checking,2024-03-03,Refund Co,,Food,12.00,0,Cleared\n";
+
+fn request(source: ImportSource, transactions_csv: &str) -> ImportRequest {
+    ImportRequest {
+        source,
+        transactions_csv: transactions_csv.to_string(),
+        budget_csv: None,
+        category_mappings: HashMap::new(),
+    }
+}
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+fn existing_account(name: &str) -> Account {
+    Account {
+        id: Uuid::new_v4(),
+        user_id: None,
+        provider_account_id: Some("plaid-acc".to_string()),
+        provider_connection_id: None,
+        name: name.to_string(),
+        account_type: "depository".to_string(),
+        balance_current: None,
+        iso_currency_code: "USD".to_string(),
+        mask: None,
+        institution_name: None,
+    }
+}
+
+#[test]
+fn given_mint_export_when_parsing_then_credits_become_inflows() {
+    let rows = ImportService::parse_transactions(ImportSource::Mint, MINT_CSV).unwrap();
+
+    assert_eq!(rows.len(), 3);
+    assert_eq!(rows[0].date, date(2024, 1, 15));
+    assert_eq!(rows[0].amount, dec!(4.50));
+    assert_eq!(rows[0].payee.as_deref(), Some("Blue Bottle"));
+    assert_eq!(rows[0].category, "Coffee Shops");
+    assert_eq!(rows[1].amount, dec!(-2500.00));
+    assert_eq!(rows[2].account_name, "Rewards Visa");
+}
+
+#[test]
+fn given_ynab_register_and_budget_when_parsing_then_nets_flows_and_keeps_latest_month() {
+    let rows = ImportService::parse_transactions(ImportSource::Ynab, YNAB_REGISTER_CSV).unwrap();
+    let budgets = ImportService::parse_ynab_budget(YNAB_BUDGET_CSV).unwrap();
+
+    assert_eq!(rows[0].amount, dec!(1450.00));
+    assert_eq!(rows[1].category, "Transfer");
+    assert_eq!(rows[2].amount, dec!(-3000.00));
+    assert_eq!(rows[2].category, "Ready to Assign");
+    assert_eq!(
+        budgets,
+        vec![
+            ImportedBudget {
+                category: "Rent/Mortgage".to_string(),
+                amount: dec!(1450.00),
+            },
+            ImportedBudget {
+                category: "Groceries".to_string(),
+                amount: dec!(400.00),
+            },
+            ImportedBudget {
+                category: "Dining Out".to_string(),
+                amount: dec!(0.00),
+            },
+        ]
+    );
+}
+
+#[test]
+fn given_actual_export_when_parsing_then_flips_sign_and_rejects_bad_rows() {
+    let rows = ImportService::parse_transactions(ImportSource::Actual, ACTUAL_CSV).unwrap();
+
+    assert_eq!(rows[0].amount, dec!(82.15));
+    assert_eq!(rows[1].category, "Transfer");
+    assert_eq!(rows[2].amount, dec!(-12.00));
+
+    let bad_date = "Account,Date,Payee,Category,Amount\nChecking,03/32/2024,Grocer,Food,-1\n";
+    assert_eq!(
+        ImportService::parse_transactions(ImportSource::Actual, bad_date).unwrap_err(),
+        "Invalid import file: unreadable date '03/32/2024' on row 2"
+    );
+    assert_eq!(
+        ImportService::parse_transactions(ImportSource::Mint, ACTUAL_CSV).unwrap_err(),
+        "Invalid import file: missing column 'Description'"
+    );
+}
+
+#[test]
+fn given_category_names_when_mapping_then_overrides_win_over_built_in_table() {
+    let overrides = HashMap::from([
+        ("coffee shops".to_string(), "ENTERTAINMENT".to_string()),
+        ("Pets".to_string(), " Pet care ".to_string()),
+    ]);
+
+    let map = |name: &str| ImportService::map_category(name, &overrides).unwrap();
+
+    assert_eq!(map("Coffee Shops").as_deref(), Some("ENTERTAINMENT"));
+    assert_eq!(map("Gas & Fuel").as_deref(), Some("TRANSPORTATION"));
+    assert_eq!(map("Food and drink").as_deref(), Some("FOOD_AND_DRINK"));
+    assert_eq!(map("Pets").as_deref(), Some("Pet care"));
+    assert_eq!(map("Pet Food & Supplies"), None);
+    assert!(ImportService::map_category(
+        "Pets",
+        &HashMap::from([("Pets".to_string(), " ".to_string())])
+    )
+    .unwrap_err()
+    .starts_with("Invalid category mapping for 'Pets'"));
+}
+
+#[tokio::test]
+async fn given_mint_export_when_previewing_then_reports_accounts_and_unmapped_categories() {
+    let user_id = Uuid::new_v4();
+    let checking = existing_account("everyday checking");
+    let checking_id = checking.id;
+    let mut mock_db = MockDatabaseRepository::new();
+    mock_db.expect_get_accounts_for_user().returning(move |_| {
+        let accounts = vec![checking.clone()];
+        Box::pin(async move { Ok(accounts) })
+    });
+    mock_db
+        .expect_get_budgets_for_user()
+        .returning(|_| Box::pin(async { Ok(vec![]) }));
+    mock_db.expect_upsert_transaction().never();
+
+    let preview = ImportService::new()
+        .preview(&mock_db, user_id, &request(ImportSource::Mint, MINT_CSV))
+        .await
+        .unwrap();
+
+    assert_eq!(preview.transaction_count, 3);
+    assert_eq!(preview.first_date, Some(date(2024, 1, 15)));
+    assert_eq!(preview.last_date, Some(date(2024, 2, 2)));
+    assert_eq!(preview.accounts.len(), 2);
+    assert_eq!(preview.accounts[0].existing_account_id, Some(checking_id));
+    assert_eq!(preview.accounts[1].account_type, "credit");
+    assert_eq!(preview.accounts[1].existing_account_id, None);
+    assert_eq!(preview.unmapped_categories, vec!["Pet Food & Supplies"]);
+    assert_eq!(
+        preview.categories[0].category.as_deref(),
+        Some("FOOD_AND_DRINK")
+    );
+}
+
+#[tokio::test]
+async fn given_unmapped_categories_when_committing_then_rejects_without_writing() {
+    let mut mock_db = MockDatabaseRepository::new();
+    mock_db.expect_upsert_account().never();
+    mock_db.expect_upsert_transaction().never();
+
+    let error = ImportService::new()
+        .commit(
+            &mock_db,
+            Uuid::new_v4(),
+            &request(ImportSource::Mint, MINT_CSV),
+        )
+        .await
+        .unwrap_err();
+
+    assert_eq!(
+        error,
+        "Invalid import: map these categories first: Pet Food & Supplies"
+    );
+}
+
+#[tokio::test]
+async fn given_ynab_export_when_committing_twice_then_reuses_transaction_ids_and_skips_budgets() {
+    let user_id = Uuid::new_v4();
+    let stored: Arc<Mutex<Vec<Transaction>>> = Arc::new(Mutex::new(vec![]));
+    let mut mock_db = MockDatabaseRepository::new();
+    mock_db
+        .expect_get_user_settings()
+        .returning(|_| Box::pin(async { Ok(None) }));
+    mock_db
+        .expect_get_accounts_for_user()
+        .returning(|_| Box::pin(async { Ok(vec![]) }));
+    mock_db
+        .expect_upsert_account()
+        .times(2)
+        .returning(|account| {
+            assert_eq!(account.name, "Checking");
+            assert!(account
+                .provider_account_id
+                .as_deref()
+                .unwrap()
+                .starts_with("import-ynab-"));
+            Box::pin(async { Ok(()) })
+        });
+    let sink = stored.clone();
+    mock_db.expect_upsert_transaction().returning(move |t| {
+        sink.lock().unwrap().push(t.clone());
+        Box::pin(async { Ok(()) })
+    });
+    mock_db.expect_get_budgets_for_user().returning(move |_| {
+        let rent = Budget::new(user_id, "rent_and_utilities".to_string(), dec!(1400));
+        Box::pin(async move { Ok(vec![rent]) })
+    });
+    mock_db
+        .expect_create_budget_for_user()
+        .times(2)
+        .returning(|budget| {
+            assert_eq!(budget.category, "FOOD_AND_DRINK");
+            assert_eq!(budget.amount, dec!(400.00));
+            Box::pin(async move { Ok(budget) })
+        });
+    let mut import = request(ImportSource::Ynab, YNAB_REGISTER_CSV);
+    import.budget_csv = Some(YNAB_BUDGET_CSV.to_string());
+    let service = ImportService::new();
+
+    let first = service.commit(&mock_db, user_id, &import).await.unwrap();
+    service.commit(&mock_db, user_id, &import).await.unwrap();
+
+    assert_eq!(first.accounts_created, 1);
+    assert_eq!(first.transactions_imported, 3);
+    assert_eq!(first.budgets_created, 1);
+    let stored = stored.lock().unwrap();
+    let categories: Vec<&str> = stored[..3]
+        .iter()
+        .map(|t| t.category_primary.as_str())
+        .collect();
+    assert_eq!(categories, ["RENT_AND_UTILITIES", "TRANSFER_OUT", "INCOME"]);
+    assert_eq!(stored[0].category_detailed, "Rent/Mortgage");
+    assert_eq!(stored[0].iso_currency_code, "USD");
+    let ids = |range: std::ops::Range<usize>| -> Vec<Option<String>> {
+        stored[range]
+            .iter()
+            .map(|t| t.provider_transaction_id.clone())
+            .collect()
+    };
+    assert_eq!(ids(0..3), ids(3..6));
+}
+
+#[tokio::test]
+async fn given_unreadable_file_when_requesting_preview_then_returns_bad_request() {
+    let (_user, token) = TestFixtures::create_authenticated_user_with_token();
+    let app = TestFixtures::create_test_app_with_db(MockDatabaseRepository::new())
+        .await
+        .unwrap();
+
+    let request = TestFixtures::create_authenticated_post_request(
+        "/api/import/preview",
+        &token,
+        serde_json::json!({"source": "actual", "transactions_csv": "Date,Amount\n2024-01-01,5\n"}),
+    );
+    let response = app.oneshot(request).await.unwrap();
+
+    assert_eq!(response.status(), 400);
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    assert!(String::from_utf8_lossy(&body).contains("missing column 'Account'"));
+}
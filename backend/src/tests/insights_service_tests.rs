@@ -0,0 +1,214 @@
+use crate::models::insight::InsightKind;
+use crate::models::transaction::Transaction;
+use crate::services::insights_service::InsightsService;
+use crate::services::repository_service::MockDatabaseRepository;
+use crate::test_fixtures::TestFixtures;
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use uuid::Uuid;
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+fn monthly(
+    months: std::ops::RangeInclusive<u32>,
+    amount: Decimal,
+    category: &str,
+    merchant: &str,
+) -> Vec<Transaction> {
+    months
+        .map(|m| TestFixtures::transaction_on(date(2024, m, 10), amount, category, merchant))
+        .collect()
+}
+
+#[test]
+fn given_category_well_above_trailing_median_when_detecting_then_flags_spike_only() {
+    let today = date(2024, 6, 20);
+    let mut transactions = monthly(1..=5, dec!(200), "GROCERIES", "Grocer");
+    transactions.extend(monthly(1..=5, dec!(100), "DINING", "Bistro"));
+    transactions.push(TestFixtures::transaction_on(
+        date(2024, 6, 3),
+        dec!(300),
+        "GROCERIES",
+        "Grocer",
+    ));
+    transactions.push(TestFixtures::transaction_on(
+        date(2024, 6, 12),
+        dec!(150),
+        "GROCERIES",
+        "Grocer",
+    ));
+    transactions.push(TestFixtures::transaction_on(
+        date(2024, 6, 14),
+        dec!(120),
+        "DINING",
+        "Bistro",
+    ));
+
+    let insights = InsightsService::detect(&transactions, today);
+
+    assert_eq!(insights.len(), 1);
+    let spike = &insights[0];
+    assert_eq!(spike.kind, InsightKind::CategorySpike);
+    assert_eq!(spike.id, "category_spike:GROCERIES:2024-06");
+    assert_eq!(spike.amount, dec!(450));
+    assert_eq!(spike.transaction_ids.len(), 2);
+    assert_eq!(
+        spike.explanation,
+        "GROCERIES spending is 450.00 so far this month, 125% above its 5-month median of 200.00."
+    );
+}
+
+#[test]
+fn given_repeat_and_outsized_charges_when_detecting_then_flags_duplicate_and_unusual_amount() {
+    let today = date(2024, 6, 20);
+    let mut transactions = monthly(1..=5, dec!(15.49), "ENTERTAINMENT", "Netflix");
+    transactions.extend(monthly(2..=5, dec!(40), "PERSONAL_CARE", "Gym"));
+    let first =
+        TestFixtures::transaction_on(date(2024, 6, 4), dec!(15.49), "ENTERTAINMENT", "Netflix");
+    let second =
+        TestFixtures::transaction_on(date(2024, 6, 4), dec!(15.49), "ENTERTAINMENT", "Netflix");
+    let gym = TestFixtures::transaction_on(date(2024, 6, 15), dec!(120), "PERSONAL_CARE", "Gym");
+    transactions.extend([first.clone(), second.clone(), gym.clone()]);
+
+    let insights = InsightsService::detect(&transactions, today);
+
+    let duplicate = insights
+        .iter()
+        .find(|i| i.kind == InsightKind::DuplicateCharge)
+        .unwrap();
+    let mut expected_ids = vec![first.id, second.id];
+    expected_ids.sort();
+    assert_eq!(duplicate.transaction_ids, expected_ids);
+    assert_eq!(
+        duplicate.explanation,
+        "Netflix charged 15.49 2 times on 2024-06-04."
+    );
+
+    let unusual: Vec<_> = insights
+        .iter()
+        .filter(|i| i.kind == InsightKind::UnusualAmount)
+        .collect();
+    assert_eq!(unusual.len(), 1);
+    assert_eq!(unusual[0].transaction_ids, vec![gym.id]);
+    assert_eq!(
+        unusual[0].explanation,
+        "Gym charged 120.00 on 2024-06-15, 3.0x its usual 40.00."
+    );
+    assert_eq!(insights[0].date, today);
+}
+
+#[test]
+fn given_large_first_charge_when_detecting_then_flags_only_with_enough_history() {
+    let today = date(2024, 6, 20);
+    let mut transactions = monthly(1..=5, dec!(30), "GENERAL", "Corner Shop");
+    let furniture =
+        TestFixtures::transaction_on(date(2024, 6, 18), dec!(900), "HOME", "Furniture Co");
+    let kiosk = TestFixtures::transaction_on(date(2024, 6, 18), dec!(20), "GENERAL", "Kiosk");
+    transactions.extend([furniture.clone(), kiosk]);
+
+    let insights = InsightsService::detect(&transactions, today);
+    let new_merchants: Vec<_> = insights
+        .iter()
+        .filter(|i| i.kind == InsightKind::NewMerchant)
+        .collect();
+    assert_eq!(new_merchants.len(), 1);
+    assert_eq!(
+        new_merchants[0].id,
+        format!("new_merchant:{}", furniture.id)
+    );
+
+    let recent_only: Vec<Transaction> = transactions
+        .into_iter()
+        .filter(|t| t.date >= date(2024, 5, 1))
+        .collect();
+    assert!(InsightsService::detect(&recent_only, today)
+        .iter()
+        .all(|i| i.kind != InsightKind::NewMerchant));
+}
+
+#[tokio::test]
+async fn given_dismissed_insight_when_listing_then_leaves_it_out() {
+    let today = date(2024, 6, 20);
+    let mut transactions = monthly(1..=5, dec!(15.49), "ENTERTAINMENT", "Netflix");
+    transactions.push(TestFixtures::transaction_on(
+        date(2024, 6, 4),
+        dec!(15.49),
+        "ENTERTAINMENT",
+        "Netflix",
+    ));
+    transactions.push(TestFixtures::transaction_on(
+        date(2024, 6, 4),
+        dec!(15.49),
+        "ENTERTAINMENT",
+        "Netflix",
+    ));
+    transactions.push(TestFixtures::transaction_on(
+        date(2024, 6, 9),
+        dec!(50),
+        "ENTERTAINMENT",
+        "Netflix",
+    ));
+    let detected = InsightsService::detect(&transactions, today);
+    assert_eq!(detected.len(), 3);
+    let dismissed = detected
+        .iter()
+        .find(|i| i.kind == InsightKind::DuplicateCharge)
+        .unwrap()
+        .id
+        .clone();
+
+    let mut mock_db = MockDatabaseRepository::new();
+    mock_db
+        .expect_get_transactions_by_date_range_for_user()
+        .returning(move |_, start, end| {
+            assert_eq!((start, end), (date(2023, 6, 20), date(2024, 6, 20)));
+            let transactions = transactions.clone();
+            Box::pin(async move { Ok(transactions) })
+        });
+    mock_db
+        .expect_get_user_settings()
+        .returning(|_| Box::pin(async { Ok(None) }));
+    mock_db
+        .expect_get_dismissed_insight_ids()
+        .returning(move |_| {
+            let ids = vec![dismissed.clone()];
+            Box::pin(async move { Ok(ids) })
+        });
+
+    let response = InsightsService::new()
+        .list_insights(&mock_db, Uuid::new_v4(), today)
+        .await
+        .unwrap();
+
+    assert_eq!(response.currency, "USD");
+    assert_eq!(response.insights.len(), 2);
+    assert!(response
+        .insights
+        .iter()
+        .all(|i| i.kind != InsightKind::DuplicateCharge));
+}
+
+#[tokio::test]
+async fn given_unknown_insight_id_when_dismissing_then_rejects_without_saving() {
+    let user_id = Uuid::new_v4();
+    let mut mock_db = MockDatabaseRepository::new();
+    mock_db
+        .expect_dismiss_insight()
+        .times(1)
+        .returning(|_, insight_id| {
+            assert_eq!(insight_id, "category_spike:DINING:2024-06");
+            Box::pin(async { Ok(()) })
+        });
+    let service = InsightsService::new();
+
+    let rejected = service.dismiss(&mock_db, user_id, "weather:sunny").await;
+    assert_eq!(rejected.unwrap_err(), "Invalid insight id: weather:sunny");
+
+    service
+        .dismiss(&mock_db, user_id, "category_spike:DINING:2024-06")
+        .await
+        .unwrap();
+}
@@ -167,6 +167,7 @@ async fn given_authenticated_user_when_get_transactions_with_account_ids_then_re
                 name: "Test Account 1".to_string(),
                 account_type: "checking".to_string(),
                 balance_current: Some(rust_decimal_macros::dec!(1000.00)),
+                iso_currency_code: "USD".to_string(),
                 mask: Some("0001".to_string()),
                 institution_name: Some("Test Bank".to_string()),
             },
@@ -178,6 +179,7 @@ async fn given_authenticated_user_when_get_transactions_with_account_ids_then_re
                 name: "Test Account 2".to_string(),
                 account_type: "savings".to_string(),
                 balance_current: Some(rust_decimal_macros::dec!(5000.00)),
+                iso_currency_code: "USD".to_string(),
                 mask: Some("0002".to_string()),
                 institution_name: Some("Test Bank".to_string()),
             },
@@ -195,6 +197,7 @@ async fn given_authenticated_user_when_get_transactions_with_account_ids_then_re
                 provider_account_id: None,
                 provider_transaction_id: Some("txn_001".to_string()),
                 amount: dec!(-50.00),
+                iso_currency_code: "USD".to_string(),
                 date: NaiveDate::from_ymd_opt(2024, 1, 15).unwrap(),
                 merchant_name: Some("Test Merchant".to_string()),
                 category_primary: "Food and Drink".to_string(),
@@ -203,6 +206,8 @@ async fn given_authenticated_user_when_get_transactions_with_account_ids_then_re
                 payment_channel: Some("in_store".to_string()),
                 pending: false,
                 created_at: Some(chrono::Utc::now()),
+                reviewed: false,
+                excluded_from_analytics: false,
                 account_name: "Test Account 1".to_string(),
                 account_type: "checking".to_string(),
                 account_mask: Some("0001".to_string()),
@@ -289,6 +294,9 @@ async fn given_authenticated_user_when_get_spending_with_account_ids_then_return
     use uuid::Uuid;
 
     let mut mock_db = MockDatabaseRepository::new();
+    mock_db
+        .expect_get_user_settings()
+        .returning(|_| Box::pin(async { Ok(None) }));
     let (_user, token) = TestFixtures::create_authenticated_user_with_token();
 
     let account_id_1 = Uuid::parse_str("550e8400-e29b-41d4-a716-446655440001").unwrap();
@@ -306,6 +314,7 @@ async fn given_authenticated_user_when_get_spending_with_account_ids_then_return
                 name: "Test Account 1".to_string(),
                 account_type: "checking".to_string(),
                 balance_current: Some(rust_decimal_macros::dec!(1000.00)),
+                iso_currency_code: "USD".to_string(),
                 mask: Some("0001".to_string()),
                 institution_name: Some("Test Bank".to_string()),
             },
@@ -317,6 +326,7 @@ async fn given_authenticated_user_when_get_spending_with_account_ids_then_return
                 name: "Test Account 2".to_string(),
                 account_type: "savings".to_string(),
                 balance_current: Some(rust_decimal_macros::dec!(5000.00)),
+                iso_currency_code: "USD".to_string(),
                 mask: Some("0002".to_string()),
                 institution_name: Some("Test Bank".to_string()),
             },
@@ -335,6 +345,7 @@ async fn given_authenticated_user_when_get_spending_with_account_ids_then_return
                     provider_account_id: Some("plaid_acc_1".to_string()),
                     provider_transaction_id: Some("txn_001".to_string()),
                     amount: dec!(-50.00),
+                    iso_currency_code: "USD".to_string(),
                     date: NaiveDate::from_ymd_opt(2024, 1, 15).unwrap(),
                     merchant_name: Some("Test Merchant 1".to_string()),
                     category_primary: "Food and Drink".to_string(),
@@ -343,6 +354,8 @@ async fn given_authenticated_user_when_get_spending_with_account_ids_then_return
                     payment_channel: Some("in_store".to_string()),
                     pending: false,
                     created_at: Some(chrono::Utc::now()),
+                    reviewed: false,
+                    excluded_from_analytics: false,
                 },
                 Transaction {
                     id: Uuid::new_v4(),
@@ -351,6 +364,7 @@ async fn given_authenticated_user_when_get_spending_with_account_ids_then_return
                     provider_account_id: Some("plaid_acc_2".to_string()),
                     provider_transaction_id: Some("txn_002".to_string()),
                     amount: dec!(-25.00),
+                    iso_currency_code: "USD".to_string(),
                     date: NaiveDate::from_ymd_opt(2024, 1, 16).unwrap(),
                     merchant_name: Some("Test Merchant 2".to_string()),
                     category_primary: "Food and Drink".to_string(),
@@ -359,6 +373,8 @@ async fn given_authenticated_user_when_get_spending_with_account_ids_then_return
                     payment_channel: Some("in_store".to_string()),
                     pending: false,
                     created_at: Some(chrono::Utc::now()),
+                    reviewed: false,
+                    excluded_from_analytics: false,
                 },
             ];
             Box::pin(async { Ok(transactions) })
@@ -435,13 +451,15 @@ async fn given_authenticated_user_when_get_spending_with_foreign_account_ids_the
 #[tokio::test]
 async fn given_authenticated_user_when_get_categories_with_account_ids_then_returns_filtered_categories(
 ) {
-    use crate::models::transaction::Transaction;
+    use crate::models::analytics::AggregateBucket;
     use crate::services::repository_service::MockDatabaseRepository;
-    use chrono::NaiveDate;
     use rust_decimal_macros::dec;
     use uuid::Uuid;
 
     let mut mock_db = MockDatabaseRepository::new();
+    mock_db
+        .expect_get_user_settings()
+        .returning(|_| Box::pin(async { Ok(None) }));
     let (_user, token) = TestFixtures::create_authenticated_user_with_token();
 
     let account_id_1 = Uuid::parse_str("550e8400-e29b-41d4-a716-446655440001").unwrap();
@@ -459,6 +477,7 @@ async fn given_authenticated_user_when_get_categories_with_account_ids_then_retu
                 name: "Test Account 1".to_string(),
                 account_type: "checking".to_string(),
                 balance_current: Some(rust_decimal_macros::dec!(1000.00)),
+                iso_currency_code: "USD".to_string(),
                 mask: Some("0001".to_string()),
                 institution_name: None,
             },
@@ -470,6 +489,7 @@ async fn given_authenticated_user_when_get_categories_with_account_ids_then_retu
                 name: "Test Account 2".to_string(),
                 account_type: "savings".to_string(),
                 balance_current: Some(rust_decimal_macros::dec!(5000.00)),
+                iso_currency_code: "USD".to_string(),
                 mask: Some("0002".to_string()),
                 institution_name: None,
             },
@@ -478,43 +498,19 @@ async fn given_authenticated_user_when_get_categories_with_account_ids_then_retu
     });
 
     mock_db
-        .expect_get_transactions_for_user()
-        .returning(move |_| {
-            let transactions = vec![
-                Transaction {
-                    id: Uuid::new_v4(),
-                    account_id: account_id_1,
-                    user_id: Some(user_id),
-                    provider_account_id: Some("plaid_acc_1".to_string()),
-                    provider_transaction_id: Some("txn_001".to_string()),
-                    amount: dec!(-50.00),
-                    date: NaiveDate::from_ymd_opt(2024, 1, 15).unwrap(),
-                    merchant_name: Some("Test Merchant 1".to_string()),
-                    category_primary: "Food and Drink".to_string(),
-                    category_detailed: "Restaurant".to_string(),
-                    category_confidence: "HIGH".to_string(),
-                    payment_channel: Some("in_store".to_string()),
-                    pending: false,
-                    created_at: Some(chrono::Utc::now()),
-                },
-                Transaction {
-                    id: Uuid::new_v4(),
-                    account_id: account_id_2,
-                    user_id: Some(user_id),
-                    provider_account_id: Some("plaid_acc_2".to_string()),
-                    provider_transaction_id: Some("txn_002".to_string()),
-                    amount: dec!(-25.00),
-                    date: NaiveDate::from_ymd_opt(2024, 1, 16).unwrap(),
-                    merchant_name: Some("Test Merchant 2".to_string()),
-                    category_primary: "Transportation".to_string(),
-                    category_detailed: "Gas".to_string(),
-                    category_confidence: "HIGH".to_string(),
-                    payment_channel: Some("in_store".to_string()),
-                    pending: false,
-                    created_at: Some(chrono::Utc::now()),
-                },
-            ];
-            Box::pin(async { Ok(transactions) })
+        .expect_sum_spending_by_category()
+        .times(1)
+        .returning(move |_, filter| {
+            assert_eq!(filter.account_ids, vec![account_id_1]);
+            assert_eq!(filter.date_range, None);
+            let buckets = vec![AggregateBucket {
+                key: "Food and Drink".to_string(),
+                iso_currency_code: "USD".to_string(),
+                rate_date: None,
+                total: dec!(50.00),
+                count: 1,
+            }];
+            Box::pin(async { Ok(buckets) })
         });
 
     mock_db
@@ -540,6 +536,12 @@ async fn given_authenticated_user_when_get_categories_with_account_ids_then_retu
 
     let response = app.oneshot(request).await.unwrap();
     assert_eq!(response.status(), 200);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let categories: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(categories[0]["name"], "Food and Drink");
+    assert_eq!(categories[0]["value"], "50.00");
 }
 
 #[tokio::test]
@@ -596,6 +598,9 @@ async fn given_authenticated_user_when_get_balances_with_account_ids_then_return
     use uuid::Uuid;
 
     let mut mock_db = MockDatabaseRepository::new();
+    mock_db
+        .expect_get_user_settings()
+        .returning(|_| Box::pin(async { Ok(None) }));
     let (user, token) = TestFixtures::create_authenticated_user_with_token();
 
     let account_id_1 = Uuid::parse_str("550e8400-e29b-41d4-a716-446655440001").unwrap();
@@ -610,6 +615,7 @@ async fn given_authenticated_user_when_get_balances_with_account_ids_then_return
             name: "Account 1".to_string(),
             account_type: "checking".to_string(),
             balance_current: Some(rust_decimal_macros::dec!(1000.00)),
+            iso_currency_code: "USD".to_string(),
             mask: Some("0001".to_string()),
             institution_name: None,
         },
@@ -621,6 +627,7 @@ async fn given_authenticated_user_when_get_balances_with_account_ids_then_return
             name: "Account 2".to_string(),
             account_type: "savings".to_string(),
             balance_current: Some(rust_decimal_macros::dec!(5000.00)),
+            iso_currency_code: "USD".to_string(),
             mask: Some("0002".to_string()),
             institution_name: None,
         },
@@ -687,6 +694,10 @@ async fn given_authenticated_user_when_get_balances_with_foreign_account_ids_the
 
     let foreign_account_id = Uuid::parse_str("550e8400-e29b-41d4-a716-446655440999").unwrap();
 
+    mock_db
+        .expect_get_user_settings()
+        .returning(|_| Box::pin(async { Ok(None) }));
+
     mock_db
         .expect_get_accounts_for_user()
         .returning(move |_| Box::pin(async { Ok(vec![]) }));
@@ -743,6 +754,9 @@ async fn given_different_account_filters_when_caching_then_uses_different_cache_
     use uuid::Uuid;
 
     let mut mock_db = MockDatabaseRepository::new();
+    mock_db
+        .expect_get_user_settings()
+        .returning(|_| Box::pin(async { Ok(None) }));
     let (user, token) = TestFixtures::create_authenticated_user_with_token();
 
     let account_id_1 = Uuid::parse_str("550e8400-e29b-41d4-a716-446655440001").unwrap();
@@ -757,6 +771,7 @@ async fn given_different_account_filters_when_caching_then_uses_different_cache_
             name: "Account 1".to_string(),
             account_type: "checking".to_string(),
             balance_current: Some(rust_decimal_macros::dec!(1000.00)),
+            iso_currency_code: "USD".to_string(),
             mask: Some("0001".to_string()),
             institution_name: None,
         },
@@ -768,6 +783,7 @@ async fn given_different_account_filters_when_caching_then_uses_different_cache_
             name: "Account 2".to_string(),
             account_type: "savings".to_string(),
             balance_current: Some(rust_decimal_macros::dec!(5000.00)),
+            iso_currency_code: "USD".to_string(),
             mask: Some("0002".to_string()),
             institution_name: None,
         },
@@ -890,6 +906,7 @@ async fn given_user_with_multiple_banks_when_get_accounts_then_returns_all_accou
             name: "Chase Checking".to_string(),
             account_type: "checking".to_string(),
             balance_current: Some(dec!(1000.00)),
+            iso_currency_code: "USD".to_string(),
             mask: Some("1234".to_string()),
             institution_name: Some("Chase".to_string()),
         },
@@ -901,6 +918,7 @@ async fn given_user_with_multiple_banks_when_get_accounts_then_returns_all_accou
             name: "BofA Savings".to_string(),
             account_type: "savings".to_string(),
             balance_current: Some(dec!(5000.00)),
+            iso_currency_code: "USD".to_string(),
             mask: Some("5678".to_string()),
             institution_name: Some("Bank of America".to_string()),
         },
@@ -979,6 +997,10 @@ async fn given_connection_id_when_sync_then_uses_get_provider_connection_by_id()
         .expect_get_provider_credentials_for_user()
         .returning(|_, _| Box::pin(async { Ok(None) }));
 
+    mock_db
+        .expect_get_user_settings()
+        .returning(|_| Box::pin(async { Ok(None) }));
+
     mock_db
         .expect_get_accounts_for_user()
         .returning(|_| Box::pin(async { Ok(vec![]) }));
@@ -999,12 +1021,40 @@ async fn given_connection_id_when_sync_then_uses_get_provider_connection_by_id()
         .expect_get_latest_account_balances_for_user()
         .returning(|_| Box::pin(async { Ok(vec![]) }));
 
+    mock_db
+        .expect_get_notification_preferences()
+        .returning(|_| Box::pin(async { Ok(vec![]) }));
+
+    mock_db
+        .expect_get_webhook_endpoints_for_user()
+        .returning(|_| Box::pin(async { Ok(vec![]) }));
+
+    mock_db
+        .expect_insert_notification()
+        .withf(|event| {
+            event.event_type == crate::models::notification::NotificationEventType::SyncFailure
+                && event.title == "Sync failed for Chase"
+        })
+        .times(1)
+        .returning(|event| {
+            let notification = crate::models::notification::Notification {
+                id: Uuid::new_v4(),
+                event_type: event.event_type.as_str().to_string(),
+                title: event.title.clone(),
+                body: event.body.clone(),
+                read_at: None,
+                created_at: chrono::Utc::now(),
+            };
+            Box::pin(async move { Ok(notification) })
+        });
+
     let app = TestFixtures::create_test_app_with_db(mock_db)
         .await
         .unwrap();
 
     let sync_request = SyncTransactionsRequest {
         connection_id: Some(connection_id.to_string()),
+        detach: None,
     };
 
     let request = TestFixtures::create_authenticated_post_request(
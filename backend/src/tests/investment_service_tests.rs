@@ -0,0 +1,412 @@
+use crate::models::account::Account;
+use crate::models::currency::FxRate;
+use crate::models::investment::{AssetClass, Holding, Security};
+use crate::providers::{FinancialDataProvider, PlaidProvider, ProviderCredentials};
+use crate::services::currency_service::FxConverter;
+use crate::services::investment_service::InvestmentService;
+use crate::services::plaid_service::RealPlaidClient;
+use crate::services::repository_service::MockDatabaseRepository;
+use axum::{extract::State, routing::post, Json, Router};
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde_json::{json, Value};
+use std::sync::{Arc, Mutex};
+use tokio::net::TcpListener;
+use uuid::Uuid;
+
+type RecordedRequests = Arc<Mutex<Vec<Value>>>;
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+fn brokerage(provider_account_id: &str) -> Account {
+    Account {
+        id: Uuid::new_v4(),
+        user_id: None,
+        provider_account_id: Some(provider_account_id.to_string()),
+        provider_connection_id: None,
+        name: "Brokerage".to_string(),
+        account_type: "investment".to_string(),
+        balance_current: Some(dec!(0)),
+        iso_currency_code: "USD".to_string(),
+        mask: None,
+        institution_name: None,
+    }
+}
+
+fn security(name: &str, security_type: &str) -> Security {
+    Security {
+        id: Uuid::new_v4(),
+        provider_security_id: format!("sec_{}", name),
+        name: name.to_string(),
+        ticker_symbol: Some(name.to_uppercase()),
+        security_type: security_type.to_string(),
+        iso_currency_code: "USD".to_string(),
+        close_price: None,
+    }
+}
+
+fn holding(
+    account: &Account,
+    security: &Security,
+    value: Decimal,
+    cost_basis: Option<Decimal>,
+    currency: &str,
+) -> Holding {
+    Holding {
+        account_id: account.id,
+        security_id: security.id,
+        quantity: dec!(10),
+        institution_price: value / dec!(10),
+        institution_value: value,
+        cost_basis,
+        iso_currency_code: currency.to_string(),
+    }
+}
+
+fn securities_json() -> Value {
+    json!([
+        {"security_id": "sec_vti", "name": "Vanguard Total Stock Market ETF", "ticker_symbol": "VTI",
+         "type": "etf", "close_price": 250.0, "iso_currency_code": "USD"},
+        {"security_id": "sec_aapl", "name": "Apple Inc.", "ticker_symbol": "AAPL",
+         "type": "equity", "close_price": 180.0, "iso_currency_code": "USD"}
+    ])
+}
+
+async fn holdings_endpoint(
+    State(requests): State<RecordedRequests>,
+    Json(body): Json<Value>,
+) -> Json<Value> {
+    requests.lock().unwrap().push(body);
+    Json(json!({
+        "accounts": [],
+        "securities": securities_json(),
+        "holdings": [
+            {"account_id": "acc_brokerage", "security_id": "sec_vti", "quantity": 10.0,
+             "institution_price": 250.0, "institution_value": 2500.0, "cost_basis": 2000.0,
+             "iso_currency_code": "USD"},
+            {"account_id": "acc_brokerage", "security_id": "sec_aapl", "quantity": 5.0,
+             "institution_price": 180.0, "institution_value": 900.0, "cost_basis": null,
+             "iso_currency_code": "USD"}
+        ]
+    }))
+}
+
+/// Serves four transactions two at a time so the client has to page. The second
+/// has no id, so the client drops it but must still page past it.
+async fn transactions_endpoint(
+    State(requests): State<RecordedRequests>,
+    Json(body): Json<Value>,
+) -> Json<Value> {
+    let offset = body["options"]["offset"].as_u64().unwrap_or(0) as usize;
+    requests.lock().unwrap().push(body);
+    let all = [
+        json!({"investment_transaction_id": "itx_1", "account_id": "acc_brokerage",
+               "security_id": "sec_vti", "date": "2024-03-04", "name": "BUY VTI",
+               "type": "buy", "subtype": "buy", "quantity": 2.5, "price": 240.0,
+               "amount": 600.0, "fees": 0.0, "iso_currency_code": "USD"}),
+        json!({"account_id": "acc_brokerage", "date": "2024-03-05", "name": "PENDING",
+               "type": "cash", "amount": 1.0, "iso_currency_code": "USD"}),
+        json!({"investment_transaction_id": "itx_2", "account_id": "acc_brokerage",
+               "security_id": "sec_aapl", "date": "2024-03-10", "name": "DIVIDEND AAPL",
+               "type": "cash", "subtype": "dividend", "quantity": 0.0, "price": 0.0,
+               "amount": -12.5, "iso_currency_code": "USD"}),
+        json!({"investment_transaction_id": "itx_3", "account_id": "acc_brokerage",
+               "security_id": null, "date": "2024-03-15", "name": "ACCOUNT FEE",
+               "type": "fee", "subtype": "account fee", "quantity": 0.0, "price": 0.0,
+               "amount": 5.0, "iso_currency_code": "USD"}),
+    ];
+    let page: Vec<Value> = all.iter().skip(offset).take(2).cloned().collect();
+    Json(json!({
+        "investment_transactions": page,
+        "securities": securities_json(),
+        "total_investment_transactions": all.len()
+    }))
+}
+
+async fn spawn_mock_plaid() -> (String, RecordedRequests) {
+    let requests = RecordedRequests::default();
+    let app = Router::new()
+        .route("/investments/holdings/get", post(holdings_endpoint))
+        .route("/investments/transactions/get", post(transactions_endpoint))
+        .with_state(requests.clone());
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    (format!("http://{}", address), requests)
+}
+
+fn mock_plaid_provider(base_url: &str) -> PlaidProvider {
+    let client = RealPlaidClient::new(
+        "client".to_string(),
+        "secret".to_string(),
+        "sandbox".to_string(),
+    )
+    .with_base_url(base_url);
+    PlaidProvider::new(Arc::new(client))
+}
+
+fn credentials() -> ProviderCredentials {
+    ProviderCredentials {
+        provider: "plaid".to_string(),
+        access_token: "access-sandbox-123".to_string(),
+        item_id: "item-1".to_string(),
+        certificate: None,
+        private_key: None,
+    }
+}
+
+#[tokio::test]
+async fn given_mock_plaid_server_when_fetching_investments_then_parses_holdings_and_pages_transactions(
+) {
+    let (base_url, requests) = spawn_mock_plaid().await;
+    let provider = mock_plaid_provider(&base_url);
+
+    let holdings = provider
+        .get_investment_holdings(&credentials())
+        .await
+        .unwrap();
+    assert_eq!(holdings.securities.len(), 2);
+    assert_eq!(holdings.holdings.len(), 2);
+    let vti = &holdings.holdings[0];
+    assert_eq!(vti.provider_security_id, "sec_vti");
+    assert_eq!(vti.institution_value, dec!(2500));
+    assert_eq!(vti.cost_basis, Some(dec!(2000)));
+    assert_eq!(holdings.holdings[1].cost_basis, None);
+
+    let activity = provider
+        .get_investment_transactions(&credentials(), date(2024, 3, 1), date(2024, 3, 31))
+        .await
+        .unwrap();
+    let ids: Vec<_> = activity
+        .transactions
+        .iter()
+        .map(|t| t.provider_investment_transaction_id.as_str())
+        .collect();
+    assert_eq!(ids, vec!["itx_1", "itx_2", "itx_3"]);
+    assert_eq!(activity.securities.len(), 2);
+    assert_eq!(activity.transactions[1].amount, dec!(-12.5));
+    assert_eq!(activity.transactions[2].provider_security_id, None);
+
+    let requests = requests.lock().unwrap();
+    assert_eq!(requests.len(), 3);
+    assert!(requests
+        .iter()
+        .all(|r| r["access_token"] == "access-sandbox-123"));
+    assert_eq!(requests[1]["start_date"], "2024-03-01");
+    assert_eq!(requests[2]["options"]["offset"], 2);
+}
+
+#[tokio::test]
+async fn given_plaid_error_response_when_fetching_holdings_then_returns_error() {
+    let app = Router::new().route(
+        "/investments/holdings/get",
+        post(|| async {
+            (
+                axum::http::StatusCode::BAD_REQUEST,
+                r#"{"error_code":"PRODUCTS_NOT_SUPPORTED"}"#,
+            )
+        }),
+    );
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let provider = mock_plaid_provider(&format!("http://{}/", address));
+    let error = provider
+        .get_investment_holdings(&credentials())
+        .await
+        .unwrap_err();
+
+    assert!(error.to_string().contains("PRODUCTS_NOT_SUPPORTED"));
+}
+
+#[tokio::test]
+async fn given_provider_investments_when_storing_then_maps_ids_and_replaces_account_holdings() {
+    let (base_url, _) = spawn_mock_plaid().await;
+    let provider = mock_plaid_provider(&base_url);
+    let mut holdings = provider
+        .get_investment_holdings(&credentials())
+        .await
+        .unwrap();
+    // A second lot of VTI and a position in an account we do not know about.
+    let mut lot = holdings.holdings[0].clone();
+    lot.cost_basis = Some(dec!(450));
+    lot.institution_value = dec!(500);
+    lot.quantity = dec!(2);
+    let mut stray = lot.clone();
+    stray.provider_account_id = "acc_unknown".to_string();
+    holdings.holdings.extend([lot, stray]);
+    let activity = provider
+        .get_investment_transactions(&credentials(), date(2024, 3, 1), date(2024, 3, 31))
+        .await
+        .unwrap();
+
+    let account = brokerage("acc_brokerage");
+    let account_id = account.id;
+    let stored_securities: Arc<Mutex<Vec<Security>>> = Arc::default();
+    let mut mock_db = MockDatabaseRepository::new();
+    let recorded = stored_securities.clone();
+    mock_db
+        .expect_upsert_securities()
+        .times(1)
+        .returning(move |_, securities| {
+            *recorded.lock().unwrap() = securities.to_vec();
+            let securities = securities.to_vec();
+            Box::pin(async move { Ok(securities) })
+        });
+    let known = stored_securities.clone();
+    mock_db
+        .expect_replace_investment_holdings()
+        .times(1)
+        .returning(move |_, account_ids, holdings| {
+            assert_eq!(account_ids, &[account_id]);
+            let securities = known.lock().unwrap();
+            let vti = securities
+                .iter()
+                .find(|s| s.provider_security_id == "sec_vti")
+                .unwrap();
+            assert_eq!(holdings.len(), 2);
+            let merged = holdings.iter().find(|h| h.security_id == vti.id).unwrap();
+            assert_eq!(merged.quantity, dec!(12));
+            assert_eq!(merged.institution_value, dec!(3000));
+            assert_eq!(merged.cost_basis, Some(dec!(2450)));
+            Box::pin(async { Ok(()) })
+        });
+    mock_db
+        .expect_upsert_investment_transactions()
+        .times(1)
+        .returning(move |_, transactions| {
+            assert_eq!(transactions.len(), 3);
+            assert!(transactions.iter().all(|t| t.account_id == account_id));
+            assert!(transactions[2].security_id.is_none());
+            Box::pin(async { Ok(()) })
+        });
+
+    InvestmentService::store_provider_data(
+        &mock_db,
+        Uuid::new_v4(),
+        &[account],
+        holdings,
+        activity,
+    )
+    .await
+    .unwrap();
+    assert_eq!(stored_securities.lock().unwrap().len(), 2);
+}
+
+#[test]
+fn given_holdings_with_and_without_cost_when_summarizing_then_reports_gains_and_totals() {
+    let today = date(2024, 3, 31);
+    let account = brokerage("acc");
+    let vti = security("vti", "etf");
+    let aapl = security("aapl", "equity");
+    let shop = security("shop", "equity");
+    let holdings = vec![
+        holding(&account, &aapl, dec!(900), None, "USD"),
+        holding(&account, &vti, dec!(2500), Some(dec!(2000)), "USD"),
+        holding(&account, &shop, dec!(1000), Some(dec!(1250)), "CAD"),
+    ];
+    let views = InvestmentService::holding_views(
+        &holdings,
+        &[vti.clone(), aapl, shop],
+        std::slice::from_ref(&account),
+    );
+
+    assert_eq!(views[0].security_id, vti.id);
+    assert_eq!(views[0].asset_class, AssetClass::Fund);
+    assert_eq!(views[0].account_name, "Brokerage");
+    assert_eq!(views[0].unrealized_gain, Some(dec!(500)));
+    assert_eq!(views[0].unrealized_gain_percent, Some(dec!(25)));
+    assert_eq!(views[1].unrealized_gain_percent, Some(dec!(-20)));
+    assert_eq!(views[2].unrealized_gain, None);
+
+    let converter = FxConverter::new(
+        "USD",
+        &[FxRate {
+            base_currency: "CAD".to_string(),
+            quote_currency: "USD".to_string(),
+            rate_date: date(2024, 3, 1),
+            rate: dec!(0.75),
+        }],
+    );
+    let summary = InvestmentService::summarize_holdings(views.clone(), &converter, today);
+    assert_eq!(summary.total_market_value, dec!(4150));
+    assert_eq!(summary.total_cost_basis, dec!(2937.5));
+    assert_eq!(summary.total_unrealized_gain, dec!(312.5));
+    assert!(!summary.mixed_currency);
+
+    let without_rates = FxConverter::new("USD", &[]);
+    let summary = InvestmentService::summarize_holdings(views, &without_rates, today);
+    assert_eq!(summary.total_market_value, dec!(3400));
+    assert!(summary.mixed_currency);
+}
+
+#[tokio::test]
+async fn given_filtered_accounts_when_computing_allocation_then_groups_by_asset_class() {
+    let today = date(2024, 3, 31);
+    let taxable = brokerage("acc_taxable");
+    let retirement = brokerage("acc_retirement");
+    let vti = security("vti", "etf");
+    let aapl = security("aapl", "equity");
+    let bond = security("bnd", "fixed income");
+    let cash = security("usd", "cash");
+    let holdings = vec![
+        holding(&taxable, &vti, dec!(3000), Some(dec!(2500)), "USD"),
+        holding(&taxable, &aapl, dec!(900), None, "USD"),
+        holding(&taxable, &bond, dec!(1500), Some(dec!(1600)), "USD"),
+        holding(&taxable, &cash, dec!(600), None, "USD"),
+        holding(&retirement, &vti, dec!(9000), None, "USD"),
+    ];
+    let securities = vec![vti, aapl, bond, cash];
+    let accounts = vec![taxable.clone(), retirement];
+
+    let mut mock_db = MockDatabaseRepository::new();
+    mock_db
+        .expect_get_investment_holdings_for_user()
+        .returning(move |_| {
+            let holdings = holdings.clone();
+            Box::pin(async move { Ok(holdings) })
+        });
+    mock_db
+        .expect_get_securities_for_user()
+        .returning(move |_| {
+            let securities = securities.clone();
+            Box::pin(async move { Ok(securities) })
+        });
+    mock_db.expect_get_accounts_for_user().returning(move |_| {
+        let accounts = accounts.clone();
+        Box::pin(async move { Ok(accounts) })
+    });
+    mock_db
+        .expect_get_user_settings()
+        .returning(|_| Box::pin(async { Ok(None) }));
+
+    let allocation = InvestmentService::new()
+        .allocation(&mock_db, Uuid::new_v4(), &[taxable.id], today)
+        .await
+        .unwrap();
+
+    assert_eq!(allocation.currency, "USD");
+    assert_eq!(allocation.total_market_value, dec!(6000));
+    let breakdown: Vec<_> = allocation
+        .allocations
+        .iter()
+        .map(|a| (a.asset_class, a.percentage, a.holding_count))
+        .collect();
+    assert_eq!(
+        breakdown,
+        vec![
+            (AssetClass::Fund, dec!(50), 1),
+            (AssetClass::FixedIncome, dec!(25), 1),
+            (AssetClass::Equity, dec!(15), 1),
+            (AssetClass::Cash, dec!(10), 1),
+        ]
+    );
+}
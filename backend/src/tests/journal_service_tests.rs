@@ -0,0 +1,210 @@
+use crate::models::account::Account;
+use crate::models::export::JournalFormat;
+use crate::models::transaction::Transaction;
+use crate::services::journal_service::JournalService;
+use crate::services::repository_service::MockDatabaseRepository;
+use crate::test_fixtures::TestFixtures;
+use axum::body::to_bytes;
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+fn account(name: &str, account_type: &str, balance: Decimal, mask: &str) -> Account {
+    Account {
+        id: Uuid::new_v4(),
+        user_id: None,
+        provider_account_id: None,
+        provider_connection_id: None,
+        name: name.to_string(),
+        account_type: account_type.to_string(),
+        balance_current: Some(balance),
+        iso_currency_code: "USD".to_string(),
+        mask: Some(mask.to_string()),
+        institution_name: Some("Demo Bank".to_string()),
+    }
+}
+
+fn transaction(
+    account: &Account,
+    provider_id: &str,
+    date: NaiveDate,
+    amount: Decimal,
+    merchant: &str,
+) -> Transaction {
+    let mut transaction = TestFixtures::transaction_on(date, amount, "FOOD_AND_DRINK", merchant);
+    transaction.account_id = account.id;
+    transaction.provider_transaction_id = Some(provider_id.to_string());
+    transaction.iso_currency_code = "USD".to_string();
+    transaction.category_detailed = String::new();
+    transaction.pending = false;
+    transaction
+}
+
+fn sample_book() -> (Vec<Account>, Vec<Transaction>) {
+    let checking = account("Everyday Checking", "depository", dec!(3000), "1234");
+    let card = account("Rewards Card", "credit", dec!(120), "9876");
+    let mut salary = transaction(&checking, "txn-2", date(2024, 1, 10), dec!(2000), "Acme");
+    salary.category_primary = "INCOME".to_string();
+    let transactions = vec![
+        transaction(&card, "txn-3", date(2024, 1, 12), dec!(50), "Bistro \"42\""),
+        transaction(&checking, "txn-1", date(2024, 1, 10), dec!(5), "Cafe"),
+        salary,
+    ];
+    (vec![checking, card], transactions)
+}
+
+#[test]
+fn given_accounts_and_transactions_when_rendering_beancount_then_writes_balanced_journal() {
+    let (accounts, transactions) = sample_book();
+
+    let journal = JournalService::render(
+        JournalFormat::Beancount,
+        &accounts,
+        &transactions,
+        "USD",
+        date(2024, 1, 31),
+    );
+
+    assert!(journal
+        .starts_with("option \"title\" \"Sumurai\"\noption \"operating_currency\" \"USD\"\n\n"));
+    assert!(journal.contains("2024-01-09 open Assets:Cash:Demo-Bank:Everyday-Checking-1234\n"));
+    assert!(
+        journal.contains("2024-01-09 open Liabilities:Credit-Cards:Demo-Bank:Rewards-Card-9876\n")
+    );
+    assert!(journal.contains("2024-01-09 open Income:General\n"));
+    assert!(journal.contains("2024-01-09 open Equity:Opening-Balances\n"));
+    assert!(journal.contains(
+        "2024-01-09 * \"Opening balance\" \"\"\n  Assets:Cash:Demo-Bank:Everyday-Checking-1234  1005.00 USD\n  Equity:Opening-Balances  -1005.00 USD\n"
+    ));
+    assert!(journal.contains(
+        "2024-01-11 * \"Opening balance\" \"\"\n  Liabilities:Credit-Cards:Demo-Bank:Rewards-Card-9876  -70.00 USD\n"
+    ));
+    assert!(journal.contains(
+        "2024-01-12 * \"Bistro \\\"42\\\"\" \"\"\n  provider_id: \"txn-3\"\n  Liabilities:Credit-Cards:Demo-Bank:Rewards-Card-9876  -50.00 USD\n  Expenses:Food-And-Drink  50.00 USD\n"
+    ));
+    assert!(journal.contains("  Assets:Cash:Demo-Bank:Everyday-Checking-1234  2000.00 USD\n  Income:General  -2000.00 USD\n"));
+    assert!(journal.find("txn-1") < journal.find("txn-2"));
+    assert!(journal.ends_with(
+        "2024-02-01 balance Assets:Cash:Demo-Bank:Everyday-Checking-1234  3000.00 USD\n\
+         2024-02-01 balance Liabilities:Credit-Cards:Demo-Bank:Rewards-Card-9876  -120.00 USD\n"
+    ));
+}
+
+#[test]
+fn given_shuffled_input_when_rendering_ledger_then_output_is_identical_and_uses_tags() {
+    let (accounts, mut transactions) = sample_book();
+    let today = date(2024, 1, 31);
+
+    let first = JournalService::render(
+        JournalFormat::Ledger,
+        &accounts,
+        &transactions,
+        "USD",
+        today,
+    );
+    transactions.reverse();
+    let reversed: Vec<Account> = accounts.iter().rev().cloned().collect();
+    let second = JournalService::render(
+        JournalFormat::Hledger,
+        &reversed,
+        &transactions,
+        "USD",
+        today,
+    );
+
+    assert_eq!(first, second);
+    assert!(first.starts_with(
+        "; Sumurai journal export\n\naccount Assets:Cash:Demo-Bank:Everyday-Checking-1234\n"
+    ));
+    assert!(first.contains("2024-01-10 * Cafe\n    ; provider_id: txn-1\n"));
+    assert!(first.contains(
+        "2024-01-31 * Balance assertion\n  Liabilities:Credit-Cards:Demo-Bank:Rewards-Card-9876  0 USD = -120.00 USD\n"
+    ));
+}
+
+#[test]
+fn given_duplicate_account_names_when_naming_then_disambiguates_and_maps_categories() {
+    let mut first = account("Savings", "depository", dec!(10), "0001");
+    first.mask = None;
+    first.institution_name = None;
+    let mut second = first.clone();
+    second.id = Uuid::new_v4();
+    let loan = account("Car Loan", "loan", dec!(8000), "77");
+
+    let journal = JournalService::render(
+        JournalFormat::Beancount,
+        &[first.clone(), second.clone(), loan.clone()],
+        &[],
+        "USD",
+        date(2024, 1, 31),
+    );
+
+    assert_eq!(JournalService::account_name(&first), "Assets:Cash:Savings");
+    assert_eq!(
+        JournalService::account_name(&loan),
+        "Liabilities:Loans:Demo-Bank:Car-Loan-77"
+    );
+    assert_eq!(journal.matches(" open Assets:Cash:Savings").count(), 2);
+    assert!(journal.contains("balance Liabilities:Loans:Demo-Bank:Car-Loan-77  -8000.00 USD"));
+
+    let refund =
+        TestFixtures::transaction_on(date(2024, 1, 2), dec!(-15), "GENERAL_MERCHANDISE", "Shop");
+    let rent = TestFixtures::transaction_on(
+        date(2024, 1, 2),
+        dec!(900),
+        "RENT_AND_UTILITIES",
+        "Landlord",
+    );
+    assert_eq!(
+        JournalService::category_account(&refund),
+        "Income:General-Merchandise"
+    );
+    assert_eq!(
+        JournalService::category_account(&rent),
+        "Expenses:Rent-And-Utilities"
+    );
+}
+
+#[tokio::test]
+async fn given_beancount_format_when_requesting_journal_then_returns_attachment() {
+    let (_user, token) = TestFixtures::create_authenticated_user_with_token();
+    let (accounts, transactions) = sample_book();
+    let mut mock_db = MockDatabaseRepository::new();
+    mock_db
+        .expect_get_user_settings()
+        .returning(|_| Box::pin(async { Ok(None) }));
+    mock_db.expect_get_accounts_for_user().returning(move |_| {
+        let accounts = accounts.clone();
+        Box::pin(async move { Ok(accounts) })
+    });
+    mock_db
+        .expect_get_transactions_for_user()
+        .returning(move |_| {
+            let transactions = transactions.clone();
+            Box::pin(async move { Ok(transactions) })
+        });
+    let app = TestFixtures::create_test_app_with_db(mock_db)
+        .await
+        .unwrap();
+
+    let request = TestFixtures::create_authenticated_get_request(
+        "/api/export/journal?format=beancount",
+        &token,
+    );
+    let response = app.oneshot(request).await.unwrap();
+
+    assert_eq!(response.status(), 200);
+    assert_eq!(
+        response.headers()["content-disposition"],
+        "attachment; filename=\"sumurai.beancount\""
+    );
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let journal = String::from_utf8(body.to_vec()).unwrap();
+    assert!(journal.contains("provider_id: \"txn-2\""));
+}
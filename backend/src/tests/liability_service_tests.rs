@@ -0,0 +1,257 @@
+use crate::models::account::Account;
+use crate::models::liability::{
+    Liability, LiabilitySource, PayoffDebt, PayoffStrategy, UpdateLiabilityRequest,
+};
+use crate::services::currency_service::FxConverter;
+use crate::services::liability_service::LiabilityService;
+use crate::services::plaid_service::RealPlaidClient;
+use crate::services::repository_service::MockDatabaseRepository;
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde_json::json;
+use uuid::Uuid;
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+fn account(name: &str, account_type: &str, balance: Decimal) -> Account {
+    Account {
+        id: Uuid::new_v4(),
+        user_id: None,
+        provider_account_id: Some(format!("acc_{}", name)),
+        provider_connection_id: None,
+        name: name.to_string(),
+        account_type: account_type.to_string(),
+        balance_current: Some(balance),
+        iso_currency_code: "USD".to_string(),
+        mask: None,
+        institution_name: None,
+    }
+}
+
+fn debt(name: &str, balance: Decimal, apr: Decimal, minimum_payment: Decimal) -> PayoffDebt {
+    PayoffDebt {
+        account_id: Uuid::new_v4(),
+        name: name.to_string(),
+        balance,
+        apr,
+        minimum_payment,
+    }
+}
+
+fn empty_update() -> UpdateLiabilityRequest {
+    UpdateLiabilityRequest {
+        liability_type: None,
+        apr: None,
+        minimum_payment: None,
+        statement_balance: None,
+        next_payment_due_date: None,
+        loan_term_months: None,
+        maturity_date: None,
+    }
+}
+
+#[test]
+fn given_plaid_liabilities_response_when_parsing_then_flattens_credit_mortgage_and_student() {
+    let data = json!({
+        "liabilities": {
+            "credit": [{
+                "account_id": "acc_card",
+                "aprs": [
+                    {"apr_type": "cash_apr", "apr_percentage": 27.99},
+                    {"apr_type": "purchase_apr", "apr_percentage": 22.99}
+                ],
+                "minimum_payment_amount": 35.0,
+                "last_statement_balance": 1250.5,
+                "next_payment_due_date": "2024-04-12"
+            }],
+            "mortgage": [{
+                "account_id": "acc_home",
+                "interest_rate": {"percentage": 6.25, "type": "fixed"},
+                "next_monthly_payment": 2100.0,
+                "next_payment_due_date": "2024-04-01",
+                "loan_term": "30 year",
+                "maturity_date": "2052-05-01"
+            }],
+            "student": [{
+                "account_id": "acc_school",
+                "interest_rate_percentage": 4.5,
+                "minimum_payment_amount": 180.0,
+                "expected_payoff_date": "2031-09-01"
+            }]
+        }
+    });
+
+    let liabilities = RealPlaidClient::parse_liabilities(&data);
+
+    assert_eq!(liabilities.len(), 3);
+    let card = &liabilities[0];
+    assert_eq!(card.liability_type, "credit");
+    assert_eq!(card.apr, Some(dec!(22.99)));
+    assert_eq!(card.last_statement_balance, Some(dec!(1250.5)));
+    assert_eq!(card.next_payment_due_date, Some(date(2024, 4, 12)));
+    let mortgage = &liabilities[1];
+    assert_eq!(mortgage.apr, Some(dec!(6.25)));
+    assert_eq!(mortgage.minimum_payment, Some(dec!(2100)));
+    assert_eq!(mortgage.loan_term_months, Some(360));
+    let student = &liabilities[2];
+    assert_eq!(student.liability_type, "student");
+    assert_eq!(student.maturity_date, Some(date(2031, 9, 1)));
+}
+
+#[test]
+fn given_two_debts_when_simulating_then_avalanche_and_snowball_target_different_debts() {
+    let card = debt("Card", dec!(1000), dec!(24), dec!(50));
+    let loan = debt("Loan", dec!(500), dec!(12), dec!(50));
+    let debts = vec![card.clone(), loan.clone()];
+    let start = date(2024, 1, 15);
+
+    let avalanche =
+        LiabilityService::simulate(&debts, PayoffStrategy::Avalanche, dec!(100), start).unwrap();
+    let snowball =
+        LiabilityService::simulate(&debts, PayoffStrategy::Snowball, dec!(100), start).unwrap();
+
+    let first = &avalanche.schedule[0];
+    assert_eq!(first.date, date(2024, 2, 15));
+    assert_eq!(first.total_payment, dec!(200));
+    assert_eq!(first.total_interest, dec!(25));
+    assert_eq!(first.payments[0].balance, dec!(870));
+    assert_eq!(first.payments[1].balance, dec!(455));
+    assert_eq!(snowball.schedule[0].payments[0].balance, dec!(970));
+    assert_eq!(snowball.schedule[0].payments[1].balance, dec!(355));
+
+    assert_eq!(avalanche.debts[0].account_id, card.account_id);
+    assert_eq!(snowball.debts[0].account_id, loan.account_id);
+    assert!(avalanche.total_interest < snowball.total_interest);
+    for plan in [&avalanche, &snowball] {
+        assert_eq!(plan.total_paid, dec!(1500) + plan.total_interest);
+        assert_eq!(plan.debt_free_date, plan.schedule.last().map(|m| m.date));
+        assert_eq!(
+            plan.schedule.last().unwrap().remaining_balance,
+            Decimal::ZERO
+        );
+        assert!(plan.schedule.iter().all(|m| m.total_payment <= dec!(200)));
+    }
+}
+
+#[test]
+fn given_payments_below_interest_when_simulating_then_rejects_plan() {
+    let debts = vec![debt("Card", dec!(10000), dec!(30), dec!(100))];
+
+    let error = LiabilityService::simulate(
+        &debts,
+        PayoffStrategy::Avalanche,
+        Decimal::ZERO,
+        date(2024, 1, 1),
+    )
+    .unwrap_err();
+
+    assert_eq!(
+        error,
+        "Monthly payments do not cover the interest; increase the extra payment"
+    );
+    let empty =
+        LiabilityService::simulate(&[], PayoffStrategy::Snowball, dec!(50), date(2024, 1, 1))
+            .unwrap();
+    assert!(empty.schedule.is_empty());
+    assert_eq!(empty.debt_free_date, None);
+}
+
+#[test]
+fn given_liabilities_missing_details_when_building_debts_then_fills_defaults_and_notes_them() {
+    let card = account("Card", "credit", dec!(-2000));
+    let loan = account("Car Loan", "loan", dec!(8000));
+    let mut euro_loan = account("Euro Loan", "loan", dec!(1000));
+    euro_loan.iso_currency_code = "EUR".to_string();
+    let checking = account("Checking", "depository", dec!(500));
+    let details = vec![Liability {
+        account_id: loan.id,
+        liability_type: "auto".to_string(),
+        apr: Some(dec!(6.5)),
+        minimum_payment: Some(dec!(300)),
+        statement_balance: None,
+        next_payment_due_date: None,
+        loan_term_months: Some(60),
+        maturity_date: None,
+        source: LiabilitySource::Manual.as_str().to_string(),
+    }];
+
+    let views = LiabilityService::liability_views(&[card, loan, euro_loan, checking], &details);
+    assert_eq!(views.len(), 3);
+    assert_eq!(views[0].account_name, "Car Loan");
+    assert_eq!(views[0].source, Some(LiabilitySource::Manual));
+    assert_eq!(views[1].balance, dec!(2000));
+
+    let converter = FxConverter::new("USD", &[]);
+    let (debts, assumptions, mixed_currency) =
+        LiabilityService::payoff_debts(&views, &converter, date(2024, 1, 1));
+
+    assert!(mixed_currency);
+    assert_eq!(debts.len(), 2);
+    assert_eq!(debts[1].name, "Card");
+    assert_eq!(debts[1].apr, Decimal::ZERO);
+    assert_eq!(debts[1].minimum_payment, dec!(40));
+    assert_eq!(
+        assumptions,
+        vec![
+            "No APR known for Card; assumed 0%.".to_string(),
+            "No minimum payment known for Card; assumed 40.00.".to_string(),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn given_manual_liability_update_when_saving_then_validates_account_and_marks_manual() {
+    let user_id = Uuid::new_v4();
+    let loan = account("Car Loan", "loan", dec!(8000));
+    let checking = account("Checking", "depository", dec!(500));
+    let loan_id = loan.id;
+    let accounts = vec![loan, checking.clone()];
+
+    let mut mock_db = MockDatabaseRepository::new();
+    mock_db.expect_get_accounts_for_user().returning(move |_| {
+        let accounts = accounts.clone();
+        Box::pin(async move { Ok(accounts) })
+    });
+    mock_db
+        .expect_upsert_liability()
+        .times(1)
+        .returning(move |_, liability| {
+            assert_eq!(liability.account_id, loan_id);
+            assert_eq!(liability.liability_type, "loan");
+            assert_eq!(liability.source, "manual");
+            let liability = liability.clone();
+            Box::pin(async move { Ok(liability) })
+        });
+    let service = LiabilityService::new();
+
+    let mut request = empty_update();
+    request.apr = Some(dec!(6.49));
+    request.minimum_payment = Some(dec!(410));
+    let saved = service
+        .update_manual(&mock_db, user_id, loan_id, &request)
+        .await
+        .unwrap();
+    assert_eq!(saved.apr, Some(dec!(6.49)));
+
+    let rejected = service
+        .update_manual(&mock_db, user_id, checking.id, &request)
+        .await
+        .unwrap_err();
+    assert!(rejected.starts_with("Invalid account"));
+
+    request.apr = Some(dec!(150));
+    let rejected = service
+        .update_manual(&mock_db, user_id, loan_id, &request)
+        .await
+        .unwrap_err();
+    assert_eq!(rejected, "Invalid apr: must be between 0 and 100");
+
+    let missing = service
+        .update_manual(&mock_db, user_id, Uuid::new_v4(), &empty_update())
+        .await
+        .unwrap_err();
+    assert_eq!(missing, "Account not found");
+}
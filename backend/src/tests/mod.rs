@@ -1,26 +1,49 @@
+mod access_token_service_tests;
 mod account_validation_tests;
+mod alert_service_tests;
+mod analytics_query_tests;
 mod analytics_service_tests;
+mod archive_service_tests;
 mod auth_handlers_integration_tests;
 mod auth_middleware_tests;
 mod auth_service_tests;
 mod bank_level_sync_tests;
+mod bills_service_tests;
 mod budget_api_integration_tests;
 mod budget_service_tests;
+mod bulk_edit_service_tests;
 mod cache_keys_tests;
 mod cache_service_tests;
 mod config_tests;
 mod connection_cache_integration_tests;
 mod connection_service_tests;
+mod currency_service_tests;
+mod export_service_tests;
+mod forecast_service_tests;
+mod import_service_tests;
+mod insights_service_tests;
 mod integration_tests;
+mod investment_service_tests;
+mod journal_service_tests;
+mod liability_service_tests;
 mod migration_tests;
 mod models_tests;
+mod net_worth_service_tests;
+mod notification_service_tests;
 mod plaid_provider_tests;
 mod plaid_service_tests;
+mod recurring_service_tests;
 mod repository_service_tests;
+mod saved_view_service_tests;
 mod security_resilience_edge_cases_tests;
+mod sync_progress_service_tests;
 mod sync_service_tests;
 mod sync_service_with_provider_tests;
+mod telemetry_middleware_tests;
 mod teller_model_tests;
 mod teller_provider_tests;
 pub mod test_fixtures;
+mod transaction_search_tests;
 mod user_model_tests;
+mod user_settings_service_tests;
+mod webhook_service_tests;
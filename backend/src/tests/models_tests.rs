@@ -69,3 +69,54 @@ fn given_plaid_connection_when_serializing_then_preserves_all_fields() {
     assert!(json_str.contains("\"transaction_count\":5"));
     assert!(json_str.contains("\"account_count\":1"));
 }
+
+#[test]
+fn given_balances_on_two_dates_when_comparing_then_reports_change_per_bank_and_overall() {
+    use crate::models::analytics::{
+        finalize_totals, BalancesComparison, BalancesOverviewResponse, BankTotals,
+    };
+    use rust_decimal::Decimal;
+    use rust_decimal_macros::dec;
+
+    let overview = |as_of: &str, banks: Vec<(&str, Decimal, Decimal)>| {
+        let banks: Vec<BankTotals> = banks
+            .into_iter()
+            .map(|(id, cash, credit)| BankTotals {
+                bank_id: id.to_string(),
+                bank_name: id.to_string(),
+                totals: finalize_totals(cash, credit, Decimal::ZERO, Decimal::ZERO),
+            })
+            .collect();
+        let cash = banks.iter().map(|b| b.totals.cash).sum();
+        let credit = banks.iter().map(|b| b.totals.credit).sum();
+        BalancesOverviewResponse {
+            as_of: as_of.to_string(),
+            overall: finalize_totals(cash, credit, Decimal::ZERO, Decimal::ZERO),
+            banks,
+            mixed_currency: false,
+            currency: "USD".to_string(),
+            comparison: None,
+        }
+    };
+    let previous = overview(
+        "2024-01-31",
+        vec![
+            ("chase", dec!(1000), dec!(-200)),
+            ("ally", dec!(500), dec!(0)),
+        ],
+    );
+    let current = overview("2024-02-29", vec![("chase", dec!(1400), dec!(-350))]);
+
+    let comparison = BalancesComparison::between(&previous, &current);
+
+    assert_eq!(comparison.compare_to, "2024-01-31");
+    assert_eq!(comparison.overall, previous.overall);
+    assert_eq!(comparison.overall_change.cash, dec!(-100));
+    assert_eq!(comparison.overall_change.credit, dec!(-150));
+    assert_eq!(comparison.overall_change.net, dec!(-250));
+    assert_eq!(comparison.bank_changes.len(), 2);
+    assert_eq!(comparison.bank_changes[0].bank_id, "chase");
+    assert_eq!(comparison.bank_changes[0].change.net, dec!(250));
+    assert_eq!(comparison.bank_changes[1].bank_id, "ally");
+    assert_eq!(comparison.bank_changes[1].change.cash, dec!(-500));
+}
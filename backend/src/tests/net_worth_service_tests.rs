@@ -0,0 +1,223 @@
+use crate::models::account::Account;
+use crate::models::balance_snapshot::BalanceSnapshot;
+use crate::models::currency::FxRate;
+use crate::models::transaction::Transaction;
+use crate::models::user_settings::UserSettings;
+use crate::services::currency_service::FxConverter;
+use crate::services::net_worth_service::{NetWorthInputs, NetWorthService};
+use crate::services::repository_service::MockDatabaseRepository;
+use crate::test_fixtures::TestFixtures;
+use chrono::{NaiveDate, TimeZone, Utc};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use uuid::Uuid;
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+fn account(account_type: &str, balance: Decimal, currency: &str) -> Account {
+    Account {
+        id: Uuid::new_v4(),
+        user_id: None,
+        provider_account_id: None,
+        provider_connection_id: None,
+        name: format!("{} account", account_type),
+        account_type: account_type.to_string(),
+        balance_current: Some(balance),
+        iso_currency_code: currency.to_string(),
+        mask: None,
+        institution_name: None,
+    }
+}
+
+fn snapshot(account: &Account, on: NaiveDate, balance: Decimal) -> BalanceSnapshot {
+    BalanceSnapshot {
+        account_id: account.id,
+        snapshot_date: on,
+        balance,
+        iso_currency_code: account.iso_currency_code.clone(),
+        account_type: account.account_type.clone(),
+    }
+}
+
+fn spend(account: &Account, on: NaiveDate, amount: Decimal) -> Transaction {
+    let mut transaction = TestFixtures::transaction_on(on, amount, "GENERAL", "Store");
+    transaction.account_id = account.id;
+    transaction
+}
+
+#[test]
+fn given_snapshots_and_ledger_when_building_series_then_snapshots_win_and_ledger_fills_gaps() {
+    let today = date(2024, 3, 5);
+    let checking = account("depository", dec!(1000), "USD");
+    let card = account("credit", dec!(300), "USD");
+    let accounts = vec![checking.clone(), card.clone()];
+    let snapshots = vec![
+        snapshot(&checking, date(2024, 3, 2), dec!(1500)),
+        snapshot(&card, date(2024, 3, 2), dec!(100)),
+    ];
+    let transactions = vec![
+        spend(&checking, date(2024, 3, 2), dec!(40)),
+        spend(&checking, date(2024, 3, 4), dec!(200)),
+        spend(&card, date(2024, 3, 3), dec!(200)),
+    ];
+    let converter = FxConverter::new("USD", &[]);
+
+    let series = NetWorthService::build_series(NetWorthInputs {
+        start_date: date(2024, 3, 1),
+        end_date: date(2024, 3, 6),
+        today,
+        accounts: &accounts,
+        snapshots: &snapshots,
+        transactions: &transactions,
+        converter: &converter,
+    });
+
+    let on = |day: &str| series.iter().find(|p| p.date == day).unwrap();
+    assert_eq!(series.len(), 6);
+    assert_eq!(on("2024-03-01").assets, dec!(1540));
+    assert_eq!(on("2024-03-02").assets, dec!(1500));
+    assert_eq!(on("2024-03-02").liabilities, dec!(100));
+    assert_eq!(on("2024-03-03").assets, dec!(1200));
+    assert_eq!(on("2024-03-03").liabilities, dec!(300));
+    assert_eq!(on("2024-03-05").value, dec!(700));
+    assert_eq!(on("2024-03-06"), &{
+        let mut carried = on("2024-03-05").clone();
+        carried.date = "2024-03-06".to_string();
+        carried
+    });
+}
+
+#[test]
+fn given_foreign_and_investment_accounts_when_building_series_then_converts_and_skips_unpriced() {
+    let today = date(2024, 3, 2);
+    let brokerage = account("investment", dec!(5000), "EUR");
+    let mortgage = account("loan", dec!(-2000), "USD");
+    let yen_savings = account("depository", dec!(100000), "JPY");
+    let accounts = vec![brokerage.clone(), mortgage, yen_savings];
+    let snapshots = vec![snapshot(&brokerage, date(2024, 3, 1), dec!(4000))];
+    let transactions = vec![spend(&brokerage, date(2024, 3, 2), dec!(999))];
+    let converter = FxConverter::new(
+        "USD",
+        &[FxRate {
+            base_currency: "EUR".to_string(),
+            quote_currency: "USD".to_string(),
+            rate_date: date(2024, 3, 1),
+            rate: dec!(1.10),
+        }],
+    );
+
+    let series = NetWorthService::build_series(NetWorthInputs {
+        start_date: date(2024, 3, 1),
+        end_date: today,
+        today,
+        accounts: &accounts,
+        snapshots: &snapshots,
+        transactions: &transactions,
+        converter: &converter,
+    });
+
+    assert_eq!(series[0].assets, dec!(4400.00));
+    assert_eq!(series[0].liabilities, dec!(2000));
+    assert_eq!(series[1].assets, dec!(5500.00));
+    assert_eq!(series[1].value, dec!(3500.00));
+}
+
+#[tokio::test]
+async fn given_several_users_when_recording_daily_snapshots_then_dates_each_by_their_day_and_continues_past_failures(
+) {
+    let users = vec![Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4()];
+    let failing = users[1];
+    let new_yorker = users[2];
+    let now = Utc.with_ymd_and_hms(2024, 3, 5, 3, 0, 0).unwrap();
+
+    let mut mock_db = MockDatabaseRepository::new();
+    mock_db.expect_get_all_user_ids().returning(move || {
+        let users = users.clone();
+        Box::pin(async move { Ok(users) })
+    });
+    mock_db
+        .expect_get_user_settings()
+        .returning(move |user_id| {
+            let settings = (*user_id == new_yorker).then(|| UserSettings {
+                timezone: "America/New_York".to_string(),
+                ..UserSettings::default()
+            });
+            Box::pin(async move { Ok(settings) })
+        });
+    mock_db
+        .expect_record_balance_snapshots()
+        .times(3)
+        .returning(move |user_id, on| {
+            let expected = if *user_id == new_yorker {
+                date(2024, 3, 4)
+            } else {
+                date(2024, 3, 5)
+            };
+            assert_eq!(on, expected);
+            let failed = *user_id == failing;
+            Box::pin(async move {
+                if failed {
+                    Err(anyhow::anyhow!("connection reset"))
+                } else {
+                    Ok(2)
+                }
+            })
+        });
+
+    let recorded = NetWorthService::new()
+        .record_daily_snapshots(&mock_db, now)
+        .await
+        .unwrap();
+
+    assert_eq!(recorded, 4);
+}
+
+#[tokio::test]
+async fn given_past_date_when_reconstructing_balances_then_rolls_back_from_nearest_snapshot() {
+    use crate::models::plaid::LatestAccountBalance;
+
+    let user_id = Uuid::new_v4();
+    let today = date(2024, 3, 10);
+    let checking = account("depository", dec!(800), "USD");
+    let card = account("credit", dec!(250), "USD");
+    let row = |account: &Account, balance: Decimal| LatestAccountBalance {
+        account_id: account.id,
+        institution_id: "bank".to_string(),
+        account_type: account.account_type.clone(),
+        account_subtype: None,
+        currency: "usd".to_string(),
+        current_balance: balance,
+        provider_connection_id: None,
+        institution_name: None,
+    };
+    let rows = vec![row(&checking, dec!(800)), row(&card, dec!(250))];
+    let snapshots = vec![snapshot(&checking, date(2024, 3, 5), dec!(1000))];
+    let transactions = vec![
+        spend(&checking, date(2024, 3, 4), dec!(60)),
+        spend(&checking, date(2024, 3, 8), dec!(200)),
+        spend(&card, date(2024, 3, 6), dec!(50)),
+    ];
+
+    let mut mock_db = MockDatabaseRepository::new();
+    mock_db
+        .expect_get_balance_snapshots_for_user()
+        .returning(move |_, _, _| {
+            let snapshots = snapshots.clone();
+            Box::pin(async move { Ok(snapshots) })
+        });
+    mock_db
+        .expect_get_transactions_by_date_range_for_user()
+        .returning(move |_, _, _| {
+            let transactions = transactions.clone();
+            Box::pin(async move { Ok(transactions) })
+        });
+
+    let balances = NetWorthService::balances_on(&mock_db, user_id, &rows, date(2024, 3, 3), today)
+        .await
+        .unwrap();
+
+    assert_eq!(balances[&checking.id], dec!(1060));
+    assert_eq!(balances[&card.id], dec!(200));
+}
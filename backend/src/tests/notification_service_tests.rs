@@ -0,0 +1,329 @@
+use crate::config::{SmtpSecurity, SmtpSettings};
+use crate::models::auth::User;
+use crate::models::notification::{
+    Notification, NotificationEvent, NotificationEventType, NotificationPreference,
+    UpdateNotificationPreferencesRequest,
+};
+use crate::notifications::{NotificationChannel, NotificationRecipient, SmtpEmailChannel};
+use crate::services::repository_service::MockDatabaseRepository;
+use crate::services::NotificationService;
+use async_trait::async_trait;
+use chrono::Utc;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpListener;
+use tokio::sync::Notify;
+use uuid::Uuid;
+
+#[derive(Default)]
+struct RecordingChannel {
+    delivered: Mutex<Vec<(String, String)>>,
+    arrived: Notify,
+    stalled: bool,
+}
+
+impl RecordingChannel {
+    /// A channel whose deliveries never finish, like a relay that stops responding.
+    fn stalled() -> Self {
+        Self {
+            stalled: true,
+            ..Self::default()
+        }
+    }
+
+    async fn wait_for_delivery(&self) {
+        tokio::time::timeout(Duration::from_secs(5), self.arrived.notified())
+            .await
+            .expect("notification was not delivered");
+    }
+}
+
+#[async_trait]
+impl NotificationChannel for RecordingChannel {
+    fn name(&self) -> &'static str {
+        "email"
+    }
+
+    async fn deliver(
+        &self,
+        recipient: &NotificationRecipient,
+        event: &NotificationEvent,
+    ) -> anyhow::Result<()> {
+        if self.stalled {
+            std::future::pending::<()>().await;
+        }
+        self.delivered
+            .lock()
+            .unwrap()
+            .push((recipient.email.clone(), event.title.clone()));
+        self.arrived.notify_one();
+        Ok(())
+    }
+}
+
+fn user(id: Uuid) -> User {
+    User {
+        id,
+        email: "casey@example.com".to_string(),
+        password_hash: "hash".to_string(),
+        provider: "plaid".to_string(),
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+        onboarding_completed: true,
+    }
+}
+
+fn event(user_id: Uuid, event_type: NotificationEventType) -> NotificationEvent {
+    NotificationEvent {
+        user_id,
+        event_type,
+        title: "Heads up".to_string(),
+        body: "Something happened".to_string(),
+    }
+}
+
+fn stored_from(event: &NotificationEvent) -> Notification {
+    Notification {
+        id: Uuid::new_v4(),
+        event_type: event.event_type.as_str().to_string(),
+        title: event.title.clone(),
+        body: event.body.clone(),
+        read_at: None,
+        created_at: Utc::now(),
+    }
+}
+
+#[tokio::test]
+async fn given_default_preferences_when_publishing_sync_failure_then_stores_and_emails() {
+    let user_id = Uuid::new_v4();
+    let mut mock_db = MockDatabaseRepository::new();
+    mock_db
+        .expect_get_notification_preferences()
+        .returning(|_| Box::pin(async { Ok(vec![]) }));
+    mock_db
+        .expect_insert_notification()
+        .times(1)
+        .returning(|e| {
+            let stored = stored_from(e);
+            Box::pin(async move { Ok(stored) })
+        });
+    mock_db.expect_get_user_by_id().returning(move |id| {
+        let found = user(*id);
+        Box::pin(async move { Ok(Some(found)) })
+    });
+    let channel = Arc::new(RecordingChannel::default());
+    let service = NotificationService::new(vec![channel.clone()]);
+
+    let stored = service
+        .publish(&mock_db, event(user_id, NotificationEventType::SyncFailure))
+        .await
+        .unwrap();
+
+    assert!(stored.is_some());
+    channel.wait_for_delivery().await;
+    assert_eq!(
+        *channel.delivered.lock().unwrap(),
+        vec![("casey@example.com".to_string(), "Heads up".to_string())]
+    );
+}
+
+#[tokio::test]
+async fn given_unresponsive_channel_when_publishing_then_returns_without_waiting_for_delivery() {
+    let user_id = Uuid::new_v4();
+    let mut mock_db = MockDatabaseRepository::new();
+    mock_db
+        .expect_get_notification_preferences()
+        .returning(|_| Box::pin(async { Ok(vec![]) }));
+    mock_db
+        .expect_insert_notification()
+        .times(1)
+        .returning(|e| {
+            let stored = stored_from(e);
+            Box::pin(async move { Ok(stored) })
+        });
+    mock_db.expect_get_user_by_id().returning(move |id| {
+        let found = user(*id);
+        Box::pin(async move { Ok(Some(found)) })
+    });
+    let channel = Arc::new(RecordingChannel::stalled());
+    let service = NotificationService::new(vec![channel.clone()]);
+
+    let stored = tokio::time::timeout(
+        Duration::from_secs(5),
+        service.publish(&mock_db, event(user_id, NotificationEventType::SyncFailure)),
+    )
+    .await
+    .expect("publish waited for the channel")
+    .unwrap();
+
+    assert!(stored.is_some());
+    assert!(channel.delivered.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn given_in_app_disabled_and_email_default_off_when_publishing_then_nothing_is_delivered() {
+    let user_id = Uuid::new_v4();
+    let mut mock_db = MockDatabaseRepository::new();
+    mock_db
+        .expect_get_notification_preferences()
+        .returning(|_| {
+            Box::pin(async {
+                Ok(vec![NotificationPreference {
+                    event_type: "large_transaction".to_string(),
+                    channel: "in_app".to_string(),
+                    enabled: false,
+                }])
+            })
+        });
+    mock_db.expect_insert_notification().never();
+    mock_db.expect_get_user_by_id().never();
+    let channel = Arc::new(RecordingChannel::default());
+    let service = NotificationService::new(vec![channel.clone()]);
+
+    let stored = service
+        .publish(
+            &mock_db,
+            event(user_id, NotificationEventType::LargeTransaction),
+        )
+        .await
+        .unwrap();
+
+    assert!(stored.is_none());
+    assert!(channel.delivered.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn given_unregistered_channel_when_updating_preferences_then_rejects() {
+    let mut mock_db = MockDatabaseRepository::new();
+    mock_db.expect_upsert_notification_preferences().never();
+    let service = NotificationService::new(vec![]);
+    let request = UpdateNotificationPreferencesRequest {
+        preferences: vec![NotificationPreference {
+            event_type: "budget_threshold".to_string(),
+            channel: "email".to_string(),
+            enabled: true,
+        }],
+    };
+
+    let result = service
+        .update_preferences(&mock_db, Uuid::new_v4(), &request)
+        .await;
+
+    assert_eq!(
+        result.unwrap_err(),
+        "Unknown notification channel: email".to_string()
+    );
+}
+
+#[test]
+fn given_partial_preferences_when_building_matrix_then_fills_defaults_per_channel() {
+    let service = NotificationService::new(vec![Arc::new(RecordingChannel::default())]);
+    let stored = vec![NotificationPreference {
+        event_type: "budget_threshold".to_string(),
+        channel: "email".to_string(),
+        enabled: true,
+    }];
+
+    let response = service.build_preferences(&stored);
+
+    assert_eq!(response.channels, vec!["in_app", "email"]);
+    assert_eq!(
+        response.preferences.len(),
+        NotificationEventType::ALL.len() * 2
+    );
+    let enabled = |event_type: &str, channel: &str| {
+        response
+            .preferences
+            .iter()
+            .find(|p| p.event_type == event_type && p.channel == channel)
+            .unwrap()
+            .enabled
+    };
+    assert!(enabled("budget_threshold", "email"));
+    assert!(enabled("sync_failure", "email"));
+    assert!(!enabled("large_transaction", "email"));
+    assert!(enabled("large_transaction", "in_app"));
+}
+
+/// Minimal SMTP sink that accepts a single message and hands back the DATA section.
+async fn start_smtp_sink() -> (u16, tokio::task::JoinHandle<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let handle = tokio::spawn(async move {
+        let (socket, _) = listener.accept().await.unwrap();
+        let (read_half, mut write_half) = socket.into_split();
+        let mut reader = BufReader::new(read_half);
+        write_half.write_all(b"220 sink ESMTP\r\n").await.unwrap();
+
+        let mut data = String::new();
+        let mut in_data = false;
+        let mut line = String::new();
+        loop {
+            line.clear();
+            if reader.read_line(&mut line).await.unwrap() == 0 {
+                break;
+            }
+            if in_data {
+                if line == ".\r\n" {
+                    in_data = false;
+                    write_half.write_all(b"250 queued\r\n").await.unwrap();
+                } else {
+                    data.push_str(&line);
+                }
+                continue;
+            }
+            let command = line.to_ascii_uppercase();
+            let reply: &[u8] = if command.starts_with("EHLO") || command.starts_with("HELO") {
+                b"250 sink\r\n"
+            } else if command.starts_with("DATA") {
+                in_data = true;
+                b"354 go ahead\r\n"
+            } else if command.starts_with("QUIT") {
+                write_half.write_all(b"221 bye\r\n").await.unwrap();
+                break;
+            } else {
+                b"250 ok\r\n"
+            };
+            write_half.write_all(reply).await.unwrap();
+        }
+        data
+    });
+    (port, handle)
+}
+
+#[tokio::test]
+async fn given_local_smtp_sink_when_delivering_email_then_sink_receives_message() {
+    let (port, sink) = start_smtp_sink().await;
+    let channel = SmtpEmailChannel::new(&SmtpSettings {
+        host: "127.0.0.1".to_string(),
+        port,
+        username: None,
+        password: None,
+        from: "Sumurai <alerts@sumurai.test>".to_string(),
+        security: SmtpSecurity::None,
+    })
+    .unwrap();
+    let recipient = NotificationRecipient {
+        email: "casey@example.com".to_string(),
+    };
+    let event = NotificationService::sync_failure_event(
+        Uuid::new_v4(),
+        Some("Demo Bank"),
+        "the connection needs to be re-linked",
+    );
+
+    channel.deliver(&recipient, &event).await.unwrap();
+    drop(channel);
+    let message = tokio::time::timeout(std::time::Duration::from_secs(5), sink)
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert!(message.contains("To: casey@example.com"));
+    assert!(message.contains("Subject: Sync failed for Demo Bank"));
+    // Long body lines may be quoted-printable encoded with soft line breaks.
+    assert!(message
+        .replace("=\r\n", "")
+        .contains("the connection needs to be re-linked"));
+}
@@ -0,0 +1,120 @@
+use crate::models::recurring::RecurrenceCadence;
+use crate::services::recurring_service::RecurringService;
+use crate::test_fixtures::TestFixtures;
+use chrono::NaiveDate;
+use rust_decimal_macros::dec;
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+#[test]
+fn given_monthly_charges_from_same_merchant_when_detecting_then_returns_monthly_series() {
+    let transactions = vec![
+        TestFixtures::transaction_on(
+            date(2024, 1, 12),
+            dec!(15.49),
+            "ENTERTAINMENT",
+            "NETFLIX.COM 1234",
+        ),
+        TestFixtures::transaction_on(
+            date(2024, 2, 12),
+            dec!(15.49),
+            "ENTERTAINMENT",
+            "Netflix.com 9876",
+        ),
+        TestFixtures::transaction_on(
+            date(2024, 3, 12),
+            dec!(15.49),
+            "ENTERTAINMENT",
+            "NETFLIX.COM",
+        ),
+        TestFixtures::transaction_on(
+            date(2024, 3, 3),
+            dec!(72.10),
+            "FOOD_AND_DRINK",
+            "Corner Bistro",
+        ),
+    ];
+
+    let series = RecurringService::detect_recurring_series(&transactions, date(2024, 3, 20));
+
+    assert_eq!(series.len(), 1);
+    let netflix = &series[0];
+    assert_eq!(netflix.cadence, RecurrenceCadence::Monthly);
+    assert_eq!(netflix.average_amount, dec!(15.49));
+    assert_eq!(netflix.occurrences, 3);
+    assert!(!netflix.is_income);
+    assert_eq!(netflix.next_expected_date, date(2024, 4, 12));
+    assert_eq!(netflix.transaction_ids.len(), 3);
+}
+
+#[test]
+fn given_biweekly_paychecks_when_detecting_then_marks_series_as_income() {
+    let transactions: Vec<_> = (0..4)
+        .map(|i| {
+            TestFixtures::transaction_on(
+                date(2024, 1, 5) + chrono::Days::new(14 * i),
+                dec!(-2100.00),
+                "INCOME",
+                "ACME PAYROLL",
+            )
+        })
+        .collect();
+
+    let series = RecurringService::detect_recurring_series(&transactions, date(2024, 2, 20));
+
+    assert_eq!(series.len(), 1);
+    assert!(series[0].is_income);
+    assert_eq!(series[0].cadence, RecurrenceCadence::Biweekly);
+    assert_eq!(series[0].average_amount, dec!(2100.00));
+}
+
+#[test]
+fn given_wildly_varying_amounts_when_detecting_then_ignores_merchant() {
+    let transactions = vec![
+        TestFixtures::transaction_on(
+            date(2024, 1, 1),
+            dec!(20.00),
+            "GENERAL_MERCHANDISE",
+            "Amazon",
+        ),
+        TestFixtures::transaction_on(
+            date(2024, 2, 1),
+            dec!(250.00),
+            "GENERAL_MERCHANDISE",
+            "Amazon",
+        ),
+        TestFixtures::transaction_on(
+            date(2024, 3, 1),
+            dec!(75.00),
+            "GENERAL_MERCHANDISE",
+            "Amazon",
+        ),
+    ];
+
+    let series = RecurringService::detect_recurring_series(&transactions, date(2024, 3, 10));
+
+    assert!(series.is_empty());
+}
+
+#[test]
+fn given_series_that_stopped_recurring_when_detecting_then_treats_it_as_ended() {
+    let transactions = vec![
+        TestFixtures::transaction_on(date(2023, 1, 5), dec!(9.99), "ENTERTAINMENT", "Old Gym"),
+        TestFixtures::transaction_on(date(2023, 2, 5), dec!(9.99), "ENTERTAINMENT", "Old Gym"),
+        TestFixtures::transaction_on(date(2023, 3, 5), dec!(9.99), "ENTERTAINMENT", "Old Gym"),
+    ];
+
+    let series = RecurringService::detect_recurring_series(&transactions, date(2024, 1, 1));
+
+    assert!(series.is_empty());
+}
+
+#[test]
+fn given_merchant_with_reference_codes_when_normalizing_then_strips_noise() {
+    assert_eq!(
+        RecurringService::normalize_merchant("SPOTIFY USA #123-456"),
+        "spotify usa"
+    );
+}
@@ -74,6 +74,7 @@ async fn given_user_with_budgets_when_deleting_then_budgets_cascade() {
         user_id: user.id,
         category: "Food".to_string(),
         amount: rust_decimal_macros::dec!(500.00),
+        saved_view_id: None,
         created_at: Utc::now(),
         updated_at: Utc::now(),
     };
@@ -0,0 +1,405 @@
+use crate::models::alert::{AlertRule, AlertRuleRequest, AlertRuleType};
+use crate::models::budget::{Budget, UpdateBudgetRequest};
+use crate::models::saved_view::{DatePreset, SavedView, SavedViewRequest, ViewMatches};
+use crate::models::transaction::TransactionWithAccount;
+use crate::models::transaction_search::SEARCH_RESULT_LIMIT;
+use crate::models::user_settings::UserCalendar;
+use crate::services::alert_service::{AlertInputs, AlertService};
+use crate::services::budget_service::BudgetService;
+use crate::services::repository_service::MockDatabaseRepository;
+use crate::services::saved_view_service::SavedViewService;
+use crate::test_fixtures::TestFixtures;
+use chrono::{NaiveDate, Utc};
+use chrono_tz::Tz;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::HashSet;
+use uuid::Uuid;
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+fn listed(merchant: &str) -> TransactionWithAccount {
+    TransactionWithAccount {
+        id: Uuid::new_v4(),
+        account_id: Uuid::new_v4(),
+        user_id: None,
+        provider_account_id: None,
+        provider_transaction_id: None,
+        amount: Decimal::new(31200, 2),
+        iso_currency_code: "USD".to_string(),
+        date: date(2024, 3, 4),
+        merchant_name: Some(merchant.to_string()),
+        category_primary: "TRAVEL".to_string(),
+        category_detailed: "Flights".to_string(),
+        category_confidence: "high".to_string(),
+        payment_channel: None,
+        pending: false,
+        created_at: None,
+        reviewed: false,
+        excluded_from_analytics: false,
+        account_name: "Checking".to_string(),
+        account_type: "depository".to_string(),
+        account_mask: None,
+    }
+}
+
+fn request(name: &str) -> SavedViewRequest {
+    SavedViewRequest {
+        name: name.to_string(),
+        search: None,
+        account_ids: vec![],
+        date_preset: None,
+        start_date: None,
+        end_date: None,
+    }
+}
+
+fn view(search: Option<&str>, date_preset: Option<DatePreset>) -> SavedView {
+    let mut request = request("Business travel");
+    request.search = search.map(str::to_string);
+    request.date_preset = date_preset;
+    SavedViewService::build_view(Uuid::new_v4(), Uuid::new_v4(), &request, Utc::now()).unwrap()
+}
+
+#[test]
+fn given_presets_when_resolving_then_ranges_follow_the_user_calendar() {
+    let calendar = UserCalendar::default();
+    let today = date(2024, 5, 20);
+    let resolve = |preset: DatePreset| preset.resolve(&calendar, today);
+
+    assert_eq!(resolve(DatePreset::Last7Days), (date(2024, 5, 14), today));
+    assert_eq!(resolve(DatePreset::ThisMonth), (date(2024, 5, 1), today));
+    assert_eq!(
+        resolve(DatePreset::LastMonth),
+        (date(2024, 4, 1), date(2024, 4, 30))
+    );
+    assert_eq!(resolve(DatePreset::ThisQuarter), (date(2024, 4, 1), today));
+    assert_eq!(
+        resolve(DatePreset::LastQuarter),
+        (date(2024, 1, 1), date(2024, 3, 31))
+    );
+    assert_eq!(
+        resolve(DatePreset::LastYear),
+        (date(2023, 1, 1), date(2023, 12, 31))
+    );
+    assert_eq!(
+        DatePreset::LastQuarter.resolve(&calendar, date(2024, 2, 10)),
+        (date(2023, 10, 1), date(2023, 12, 31))
+    );
+
+    let mid_month = UserCalendar::new(Tz::UTC, 15);
+    assert_eq!(
+        DatePreset::ThisMonth.resolve(&mid_month, date(2024, 5, 10)),
+        (date(2024, 4, 15), date(2024, 5, 10))
+    );
+    assert_eq!(
+        DatePreset::LastMonth.resolve(&mid_month, date(2024, 5, 10)),
+        (date(2024, 3, 15), date(2024, 4, 14))
+    );
+}
+
+#[test]
+fn given_requests_when_building_view_then_validates_and_normalizes() {
+    let account = Uuid::new_v4();
+    let mut valid = request("  Travel  ");
+    valid.search = Some("  category:travel  ".to_string());
+    valid.account_ids = vec![account, account];
+    valid.date_preset = Some(DatePreset::ThisQuarter);
+    let built =
+        SavedViewService::build_view(Uuid::new_v4(), Uuid::new_v4(), &valid, Utc::now()).unwrap();
+    assert_eq!(built.name, "Travel");
+    assert_eq!(built.search.as_deref(), Some("category:travel"));
+    assert_eq!(built.account_ids, vec![account]);
+    assert_eq!(built.date_preset.as_deref(), Some("this_quarter"));
+    assert_eq!(built.preset(), Some(DatePreset::ThisQuarter));
+
+    let mut bad_search = request("Bad");
+    bad_search.search = Some("before:soon".to_string());
+    let mut preset_and_dates = request("Both");
+    preset_and_dates.date_preset = Some(DatePreset::LastMonth);
+    preset_and_dates.start_date = Some(date(2024, 1, 1));
+    let mut reversed = request("Reversed");
+    reversed.start_date = Some(date(2024, 2, 1));
+    reversed.end_date = Some(date(2024, 1, 1));
+
+    for invalid in [
+        request("   "),
+        request(&"x".repeat(101)),
+        bad_search,
+        preset_and_dates,
+        reversed,
+    ] {
+        let error =
+            SavedViewService::build_view(Uuid::new_v4(), Uuid::new_v4(), &invalid, Utc::now())
+                .unwrap_err();
+        assert!(error.starts_with("Invalid"), "{}", error);
+    }
+}
+
+#[tokio::test]
+async fn given_relative_view_when_executing_then_searches_resolved_range_and_accounts() {
+    let account = Uuid::new_v4();
+    let mut saved = view(Some("category:travel"), Some(DatePreset::Last30Days));
+    saved.account_ids = vec![account];
+    let view_id = saved.id;
+    let today = UserCalendar::default().today();
+    let expected_start = today - chrono::Days::new(29);
+
+    let mut mock_db = MockDatabaseRepository::new();
+    mock_db.expect_get_saved_view().returning(move |_, _| {
+        let saved = saved.clone();
+        Box::pin(async move { Ok(Some(saved)) })
+    });
+    mock_db
+        .expect_get_user_settings()
+        .returning(|_| Box::pin(async { Ok(None) }));
+    mock_db
+        .expect_search_transactions()
+        .times(1)
+        .withf(move |_, search, account_ids| {
+            search.categories == ["travel"]
+                && search.start_date == Some(expected_start)
+                && search.end_date == Some(today)
+                && account_ids == [account]
+        })
+        .returning(|_, _, _| Box::pin(async { Ok(vec![]) }));
+
+    let results = SavedViewService::new()
+        .execute(&mock_db, Uuid::new_v4(), view_id)
+        .await
+        .unwrap();
+
+    assert_eq!(results.start_date, Some(expected_start));
+    assert_eq!(results.end_date, Some(today));
+    assert_eq!(results.view.id, view_id);
+    assert!(!results.truncated);
+}
+
+#[tokio::test]
+async fn given_view_matching_more_than_the_search_cap_when_executing_then_reports_truncation() {
+    let saved = view(Some("category:travel"), None);
+    let view_id = saved.id;
+    let limit = SEARCH_RESULT_LIMIT as usize;
+    let mut mock_db = MockDatabaseRepository::new();
+    mock_db.expect_get_saved_view().returning(move |_, _| {
+        let saved = saved.clone();
+        Box::pin(async move { Ok(Some(saved)) })
+    });
+    mock_db
+        .expect_get_user_settings()
+        .returning(|_| Box::pin(async { Ok(None) }));
+    mock_db
+        .expect_search_transactions()
+        .times(1)
+        .returning(move |_, _, _| {
+            let capped = (0..limit).map(|_| listed("Delta")).collect();
+            Box::pin(async move { Ok(capped) })
+        });
+    mock_db
+        .expect_search_transaction_ids()
+        .times(1)
+        .returning(move |_, _, _| {
+            let ids = (0..=limit).map(|_| Uuid::new_v4()).collect();
+            Box::pin(async move { Ok(ids) })
+        });
+
+    let results = SavedViewService::new()
+        .execute(&mock_db, Uuid::new_v4(), view_id)
+        .await
+        .unwrap();
+
+    assert_eq!(results.transactions.len(), limit);
+    assert!(results.truncated);
+}
+
+#[tokio::test]
+async fn given_deleted_view_when_matching_then_it_matches_nothing() {
+    let saved = view(Some("merchant:delta"), Some(DatePreset::ThisYear));
+    let live_id = saved.id;
+    let deleted_id = Uuid::new_v4();
+    let flight_id = Uuid::new_v4();
+    let mut mock_db = MockDatabaseRepository::new();
+    mock_db
+        .expect_get_saved_view()
+        .returning(move |_, view_id| {
+            let found = (*view_id == live_id).then(|| saved.clone());
+            Box::pin(async move { Ok(found) })
+        });
+    mock_db
+        .expect_search_transaction_ids()
+        .times(1)
+        .withf(|_, search, _| {
+            search.start_date == Some(date(2024, 3, 1))
+                && search.end_date == Some(date(2024, 3, 10))
+        })
+        .returning(move |_, _, _| Box::pin(async move { Ok(vec![flight_id]) }));
+
+    let matches = SavedViewService::matching_transaction_ids(
+        &mock_db,
+        Uuid::new_v4(),
+        &[live_id, deleted_id, live_id],
+        &UserCalendar::default(),
+        (date(2024, 3, 1), date(2024, 3, 10)),
+    )
+    .await
+    .unwrap();
+
+    assert!(matches.allows(Some(live_id), flight_id));
+    assert!(!matches.allows(Some(live_id), Uuid::new_v4()));
+    assert!(!matches.allows(Some(deleted_id), flight_id));
+    assert!(matches.allows(None, Uuid::new_v4()));
+}
+
+#[tokio::test]
+async fn given_view_matching_more_than_the_search_cap_when_matching_then_keeps_every_match() {
+    let saved = view(Some("merchant:delta"), None);
+    let view_id = saved.id;
+    let matched: Vec<Uuid> = (0..SEARCH_RESULT_LIMIT + 500)
+        .map(|_| Uuid::new_v4())
+        .collect();
+    let returned = matched.clone();
+    let mut mock_db = MockDatabaseRepository::new();
+    mock_db.expect_get_saved_view().returning(move |_, _| {
+        let saved = saved.clone();
+        Box::pin(async move { Ok(Some(saved)) })
+    });
+    mock_db.expect_search_transactions().never();
+    mock_db
+        .expect_search_transaction_ids()
+        .returning(move |_, _, _| {
+            let ids = returned.clone();
+            Box::pin(async move { Ok(ids) })
+        });
+
+    let matches = SavedViewService::matching_transaction_ids(
+        &mock_db,
+        Uuid::new_v4(),
+        &[view_id],
+        &UserCalendar::default(),
+        (date(2024, 3, 1), date(2024, 3, 31)),
+    )
+    .await
+    .unwrap();
+
+    assert!(matched.iter().all(|id| matches.allows(Some(view_id), *id)));
+}
+
+#[test]
+fn given_budget_and_rule_with_views_when_evaluating_then_only_view_matches_count() {
+    let today = date(2024, 3, 10);
+    let hotel = TestFixtures::transaction_on(date(2024, 3, 4), dec!(400), "TRAVEL", "Hotel");
+    let flight = TestFixtures::transaction_on(date(2024, 3, 5), dec!(700), "TRAVEL", "Airline");
+    let groceries =
+        TestFixtures::transaction_on(date(2024, 3, 6), dec!(900), "GROCERIES", "Market");
+    let view_id = Uuid::new_v4();
+    let mut views = ViewMatches::default();
+    views.insert(view_id, HashSet::from([hotel.id, flight.id]));
+
+    let trips = Budget::new(Uuid::new_v4(), "Business trips".to_string(), dec!(1000))
+        .with_saved_view(Some(view_id));
+    let transactions = vec![hotel, flight, groceries];
+    let budgets = vec![trips];
+    let inputs = AlertInputs {
+        today,
+        calendar: UserCalendar::default(),
+        transactions: &transactions,
+        budgets: &budgets,
+        accounts: &[],
+        views: &views,
+    };
+    let rule = |rule_type: AlertRuleType, saved_view_id| {
+        let request = AlertRuleRequest {
+            rule_type,
+            threshold: Some(dec!(500)),
+            percentages: Some(vec![100]),
+            account_id: None,
+            category: None,
+            saved_view_id,
+            cooldown_hours: None,
+            enabled: None,
+        };
+        AlertService::build_rule(Uuid::new_v4(), Uuid::new_v4(), &request, Utc::now()).unwrap()
+    };
+    let rules: Vec<AlertRule> = vec![
+        rule(AlertRuleType::BudgetThreshold, None),
+        rule(AlertRuleType::LargeTransaction, Some(view_id)),
+        rule(AlertRuleType::LargeTransaction, Some(Uuid::new_v4())),
+    ];
+
+    let candidates = AlertService::evaluate_rules(&rules, &inputs);
+
+    let titles: Vec<&str> = candidates.iter().map(|c| c.title.as_str()).collect();
+    assert_eq!(
+        titles,
+        vec![
+            "Business trips budget at 100%",
+            "Large transaction at Airline"
+        ]
+    );
+}
+
+#[tokio::test]
+async fn given_view_of_another_user_when_linking_budget_or_rule_then_rejects() {
+    let mut mock_db = MockDatabaseRepository::new();
+    mock_db
+        .expect_get_saved_view()
+        .returning(|_, _| Box::pin(async { Ok(None) }));
+    mock_db.expect_create_budget_for_user().never();
+    mock_db.expect_update_budget_for_user().never();
+    mock_db.expect_create_alert_rule().never();
+    let user_id = Uuid::new_v4();
+    let foreign = Some(Uuid::new_v4());
+
+    let budget = Budget::new(user_id, "Travel".to_string(), dec!(100)).with_saved_view(foreign);
+    let error = BudgetService::new()
+        .create_budget(&mock_db, budget)
+        .await
+        .unwrap_err();
+    assert!(error.contains("saved_view_id"), "{}", error);
+
+    let update = UpdateBudgetRequest {
+        amount: dec!(100),
+        saved_view_id: Some(foreign),
+    };
+    let error = BudgetService::new()
+        .update_budget_for_user(&mock_db, Uuid::new_v4(), user_id, &update)
+        .await
+        .unwrap_err();
+    assert!(error.contains("saved_view_id"), "{}", error);
+
+    let low_balance = AlertRuleRequest {
+        rule_type: AlertRuleType::LowBalance,
+        threshold: Some(dec!(100)),
+        percentages: None,
+        account_id: None,
+        category: None,
+        saved_view_id: foreign,
+        cooldown_hours: None,
+        enabled: None,
+    };
+    let error =
+        AlertService::build_rule(Uuid::new_v4(), user_id, &low_balance, Utc::now()).unwrap_err();
+    assert!(error.contains(" must "), "{}", error);
+}
+
+#[test]
+fn given_budget_update_json_when_deserializing_then_distinguishes_absent_and_null_view() {
+    let view_id = Uuid::new_v4();
+    let parse =
+        |body: serde_json::Value| -> UpdateBudgetRequest { serde_json::from_value(body).unwrap() };
+
+    assert_eq!(
+        parse(serde_json::json!({"amount": "10"})).saved_view_id,
+        None
+    );
+    assert_eq!(
+        parse(serde_json::json!({"amount": "10", "saved_view_id": null})).saved_view_id,
+        Some(None)
+    );
+    assert_eq!(
+        parse(serde_json::json!({"amount": "10", "saved_view_id": view_id})).saved_view_id,
+        Some(Some(view_id))
+    );
+}
@@ -0,0 +1,180 @@
+use crate::models::plaid::ProviderConnection;
+use crate::models::sync_progress::{SyncPhase, SyncProgressEvent};
+use crate::providers::ProviderRegistry;
+use crate::services::cache_service::MockCacheService;
+use crate::services::connection_service::{ConnectionService, TellerSyncError};
+use crate::services::repository_service::MockDatabaseRepository;
+use crate::services::sync_progress_service::SyncProgressService;
+use futures::StreamExt;
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+async fn next_event(
+    stream: &mut (impl futures::Stream<Item = SyncProgressEvent> + Unpin),
+) -> SyncProgressEvent {
+    tokio::time::timeout(Duration::from_secs(1), stream.next())
+        .await
+        .expect("timed out waiting for sync event")
+        .expect("sync event stream ended")
+}
+
+#[tokio::test]
+async fn given_events_for_several_users_when_streaming_then_only_yields_matching_user_and_connection(
+) {
+    let service = SyncProgressService::new();
+    let user_id = Uuid::new_v4();
+    let connection_id = Uuid::new_v4();
+    let mut stream = Box::pin(service.events_for(user_id, Some(connection_id)));
+
+    service.publish(SyncProgressEvent::new(
+        Uuid::new_v4(),
+        connection_id,
+        SyncPhase::Started,
+    ));
+    service.publish(SyncProgressEvent::new(
+        user_id,
+        Uuid::new_v4(),
+        SyncPhase::Started,
+    ));
+    service.publish(
+        SyncProgressEvent::new(user_id, connection_id, SyncPhase::FetchingTransactions)
+            .with_account_count(2)
+            .with_transaction_count(40),
+    );
+
+    let event = next_event(&mut stream).await;
+
+    assert_eq!(event.user_id, user_id);
+    assert_eq!(event.connection_id, connection_id);
+    assert_eq!(event.phase, SyncPhase::FetchingTransactions);
+    assert_eq!(event.transaction_count, Some(40));
+}
+
+#[tokio::test]
+async fn given_sync_finished_before_subscribing_when_streaming_connection_then_replays_last_event_and_ends(
+) {
+    let service = SyncProgressService::new();
+    let user_id = Uuid::new_v4();
+    let connection_id = Uuid::new_v4();
+    service.publish(SyncProgressEvent::new(
+        user_id,
+        connection_id,
+        SyncPhase::Started,
+    ));
+    service.publish(
+        SyncProgressEvent::new(user_id, connection_id, SyncPhase::Completed)
+            .with_transaction_count(12),
+    );
+    service.publish(SyncProgressEvent::new(
+        Uuid::new_v4(),
+        Uuid::new_v4(),
+        SyncPhase::Completed,
+    ));
+    let mut stream = Box::pin(service.events_for(user_id, Some(connection_id)));
+
+    let replayed = next_event(&mut stream).await;
+    service.publish(SyncProgressEvent::new(
+        user_id,
+        connection_id,
+        SyncPhase::Started,
+    ));
+
+    assert_eq!(replayed.phase, SyncPhase::Completed);
+    assert_eq!(replayed.transaction_count, Some(12));
+    let ended = tokio::time::timeout(Duration::from_secs(1), stream.next())
+        .await
+        .expect("stream kept waiting after the sync completed");
+    assert!(ended.is_none());
+}
+
+#[tokio::test]
+async fn given_sync_in_progress_when_streaming_connection_then_ends_after_failure() {
+    let service = SyncProgressService::new();
+    let user_id = Uuid::new_v4();
+    let connection_id = Uuid::new_v4();
+    service.publish(SyncProgressEvent::new(
+        user_id,
+        connection_id,
+        SyncPhase::FetchingAccounts,
+    ));
+    let mut stream = Box::pin(service.events_for(user_id, Some(connection_id)));
+
+    service.publish(
+        SyncProgressEvent::new(user_id, connection_id, SyncPhase::Failed)
+            .with_error("the connection needs to be re-linked"),
+    );
+
+    assert_eq!(
+        next_event(&mut stream).await.phase,
+        SyncPhase::FetchingAccounts
+    );
+    assert_eq!(next_event(&mut stream).await.phase, SyncPhase::Failed);
+    assert!(stream.next().await.is_none());
+}
+
+#[test]
+fn given_running_sync_when_beginning_again_then_refuses_until_guard_dropped() {
+    let service = Arc::new(SyncProgressService::new());
+    let connection_id = Uuid::new_v4();
+
+    let guard = service.try_begin(connection_id);
+    assert!(guard.is_some());
+    assert!(service.is_running(&connection_id));
+    assert!(service.try_begin(connection_id).is_none());
+    assert!(service.try_begin(Uuid::new_v4()).is_some());
+
+    drop(guard);
+
+    assert!(!service.is_running(&connection_id));
+    assert!(service.try_begin(connection_id).is_some());
+}
+
+#[test]
+fn given_progress_event_when_serializing_then_uses_snake_case_phase_and_hides_user() {
+    let event = SyncProgressEvent::new(Uuid::new_v4(), Uuid::new_v4(), SyncPhase::Failed)
+        .with_error("the connection needs to be re-linked");
+
+    let json = serde_json::to_value(&event).unwrap();
+
+    assert_eq!(json["phase"], "failed");
+    assert_eq!(json["error"], "the connection needs to be re-linked");
+    assert!(json.get("user_id").is_none());
+    assert_eq!(SyncPhase::FetchingAccounts.as_str(), "fetching_accounts");
+}
+
+#[tokio::test]
+async fn given_missing_credentials_when_syncing_teller_connection_then_streams_started_and_failed()
+{
+    let user_id = Uuid::new_v4();
+    let mut connection = ProviderConnection::new(user_id, "teller_enrollment_1");
+    let mut mock_db = MockDatabaseRepository::new();
+    mock_db
+        .expect_get_user_settings()
+        .returning(|_| Box::pin(async { Ok(None) }));
+    mock_db
+        .expect_get_provider_credentials_for_user()
+        .returning(|_, _| Box::pin(async { Ok(None) }));
+    let progress = Arc::new(SyncProgressService::new());
+    let service = ConnectionService::new(
+        Arc::new(mock_db),
+        Arc::new(MockCacheService::new()),
+        Arc::new(ProviderRegistry::new()),
+    )
+    .with_sync_progress(progress.clone());
+    let mut stream = Box::pin(progress.events_for(user_id, None));
+
+    let result = service
+        .sync_teller_connection(&user_id, "jwt", &mut connection)
+        .await;
+
+    assert!(matches!(result, Err(TellerSyncError::CredentialsMissing)));
+    assert_eq!(next_event(&mut stream).await.phase, SyncPhase::Started);
+    let failed = next_event(&mut stream).await;
+    assert_eq!(failed.phase, SyncPhase::Failed);
+    assert_eq!(failed.connection_id, connection.id);
+    assert_eq!(
+        failed.error.as_deref(),
+        Some("the connection needs to be re-linked")
+    );
+}
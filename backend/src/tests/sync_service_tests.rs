@@ -1,4 +1,4 @@
-use crate::models::{account::Account, transaction::Transaction};
+use crate::models::{account::Account, transaction::Transaction, user_settings::UserCalendar};
 use crate::providers::{PlaidProvider, ProviderRegistry};
 
 use crate::services::{plaid_service::RealPlaidClient, sync_service::SyncService};
@@ -36,6 +36,7 @@ fn test_calculate_account_mapping_creates_correct_mapping() {
             name: "Checking".into(),
             account_type: "depository".into(),
             balance_current: Some(dec!(100.00)),
+            iso_currency_code: "USD".to_string(),
             mask: Some("1234".to_string()),
             institution_name: None,
         },
@@ -47,6 +48,7 @@ fn test_calculate_account_mapping_creates_correct_mapping() {
             name: "Savings".into(),
             account_type: "depository".into(),
             balance_current: Some(dec!(200.00)),
+            iso_currency_code: "USD".to_string(),
             mask: Some("5678".to_string()),
             institution_name: None,
         },
@@ -72,6 +74,7 @@ fn test_calculate_account_mapping_handles_accounts_without_plaid_ids() {
         name: "Manual Account".to_string(),
         account_type: "manual".to_string(),
         balance_current: Some(dec!(500.00)),
+        iso_currency_code: "USD".to_string(),
         mask: None,
         institution_name: None,
     }];
@@ -94,6 +97,7 @@ fn test_map_transactions_to_accounts_updates_account_ids() {
         provider_account_id: None,
         provider_transaction_id: Some("plaid_txn_1".to_string()),
         amount: dec!(100.00),
+        iso_currency_code: "USD".to_string(),
         date: NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
         merchant_name: Some("Store A".to_string()),
         category_primary: "Food".to_string(),
@@ -102,6 +106,8 @@ fn test_map_transactions_to_accounts_updates_account_ids() {
         payment_channel: Some("in_store".to_string()),
         pending: false,
         created_at: Some(Utc::now()),
+        reviewed: false,
+        excluded_from_analytics: false,
     }];
 
     sync_service.map_transactions_to_accounts(&mut transactions, &account_mapping);
@@ -155,7 +161,8 @@ mod date_range_calculation_tests {
     fn given_no_previous_sync_when_calculating_range_then_returns_90_day_default() {
         let sync_service = create_test_sync_service();
 
-        let (start_date, end_date) = sync_service.calculate_sync_date_range(None);
+        let (start_date, end_date) =
+            sync_service.calculate_sync_date_range(None, &UserCalendar::default());
         let expected_start = Utc::now().date_naive() - Duration::days(90);
         let expected_end = Utc::now().date_naive();
 
@@ -168,7 +175,8 @@ mod date_range_calculation_tests {
         let sync_service = create_test_sync_service();
         let last_sync = Utc::now() - Duration::days(7); // 7 days ago
 
-        let (start_date, end_date) = sync_service.calculate_sync_date_range(Some(last_sync));
+        let (start_date, end_date) =
+            sync_service.calculate_sync_date_range(Some(last_sync), &UserCalendar::default());
         let expected_start = (last_sync - Duration::days(2)).date_naive();
         let expected_end = Utc::now().date_naive();
 
@@ -181,7 +189,8 @@ mod date_range_calculation_tests {
         let sync_service = create_test_sync_service();
         let last_sync = Utc::now() - Duration::days(365 * 6); // 6 years ago
 
-        let (start_date, end_date) = sync_service.calculate_sync_date_range(Some(last_sync));
+        let (start_date, end_date) =
+            sync_service.calculate_sync_date_range(Some(last_sync), &UserCalendar::default());
         let expected_start = Utc::now().date_naive() - Duration::days(365 * 5);
         let expected_end = Utc::now().date_naive();
 
@@ -194,7 +203,8 @@ mod date_range_calculation_tests {
         let sync_service = create_test_sync_service();
         let future_sync = Utc::now() + Duration::days(1);
 
-        let (start_date, end_date) = sync_service.calculate_sync_date_range(Some(future_sync));
+        let (start_date, end_date) =
+            sync_service.calculate_sync_date_range(Some(future_sync), &UserCalendar::default());
         let expected_end = Utc::now().date_naive();
 
         assert!(start_date <= expected_end);
@@ -224,7 +234,8 @@ mod sync_recent_transactions_integration_tests {
     async fn given_no_last_sync_when_calling_sync_recent_transactions_then_uses_90_day_window() {
         let sync_service = create_test_sync_service_for_integration();
 
-        let (start_date, end_date) = sync_service.calculate_sync_date_range(None);
+        let (start_date, end_date) =
+            sync_service.calculate_sync_date_range(None, &UserCalendar::default());
         let expected_start = Utc::now().date_naive() - Duration::days(90);
         let expected_end = Utc::now().date_naive();
 
@@ -238,7 +249,8 @@ mod sync_recent_transactions_integration_tests {
         let last_sync = Utc::now() - Duration::days(3);
         let sync_service = create_test_sync_service_for_integration();
 
-        let (start_date, end_date) = sync_service.calculate_sync_date_range(Some(last_sync));
+        let (start_date, end_date) =
+            sync_service.calculate_sync_date_range(Some(last_sync), &UserCalendar::default());
         let expected_end = Utc::now().date_naive();
         let expected_start = (last_sync - Duration::days(2)).date_naive();
 
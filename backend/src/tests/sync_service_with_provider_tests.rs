@@ -1,4 +1,7 @@
-use crate::models::{account::Account, plaid::ProviderConnection, transaction::Transaction};
+use crate::models::{
+    account::Account, plaid::ProviderConnection, transaction::Transaction,
+    user_settings::UserCalendar,
+};
 use crate::providers::{
     FinancialDataProvider, InstitutionInfo, ProviderCredentials, ProviderRegistry,
 };
@@ -73,6 +76,7 @@ async fn given_sync_service_with_provider_when_sync_then_maps_accounts_correctly
         name: "Test Account".to_string(),
         account_type: "checking".to_string(),
         balance_current: None,
+        iso_currency_code: "USD".to_string(),
         mask: None,
         institution_name: None,
     }];
@@ -84,6 +88,7 @@ async fn given_sync_service_with_provider_when_sync_then_maps_accounts_correctly
         provider_account_id: Some(provider_account_id.clone()),
         provider_transaction_id: Some("txn_123".to_string()),
         amount: rust_decimal::Decimal::new(5000, 2),
+        iso_currency_code: "USD".to_string(),
         date: NaiveDate::from_ymd_opt(2024, 1, 15).unwrap(),
         merchant_name: Some("Coffee Shop".to_string()),
         category_primary: "FOOD_AND_DRINK".to_string(),
@@ -92,6 +97,8 @@ async fn given_sync_service_with_provider_when_sync_then_maps_accounts_correctly
         payment_channel: Some("in_store".to_string()),
         pending: false,
         created_at: Some(Utc::now()),
+        reviewed: false,
+        excluded_from_analytics: false,
     };
 
     let provider: Arc<dyn FinancialDataProvider> = Arc::new(MockProvider {
@@ -131,7 +138,14 @@ async fn given_sync_service_with_provider_when_sync_then_maps_accounts_correctly
     };
 
     let (result_transactions, _cursor) = sync_service
-        .sync_bank_connection_transactions(&credentials, &connection, &accounts)
+        .sync_bank_connection_transactions(
+            &credentials,
+            &accounts,
+            SyncService::calculate_sync_date_range_static(
+                connection.last_sync_at,
+                &UserCalendar::default(),
+            ),
+        )
         .await
         .unwrap();
 
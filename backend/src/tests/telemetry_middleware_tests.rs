@@ -0,0 +1,63 @@
+use crate::middleware::telemetry_middleware::{is_traceable_path, traced_route};
+use axum::{
+    body::Body,
+    extract::Request,
+    http::StatusCode,
+    middleware::{self, Next},
+    response::Response,
+    routing::get,
+    Router,
+};
+use std::sync::{Arc, Mutex};
+use tower::ServiceExt;
+
+async fn record_route(routes: Arc<Mutex<Vec<String>>>, request: Request<Body>) -> String {
+    let recorded = routes.clone();
+    let app = Router::new()
+        .route(
+            "/api/bills/calendar/{token}/feed.ics",
+            get(|| async { StatusCode::OK }),
+        )
+        .layer(middleware::from_fn(
+            move |request: Request<Body>, next: Next| {
+                let recorded = recorded.clone();
+                async move {
+                    recorded.lock().unwrap().push(traced_route(&request));
+                    let response: Response = next.run(request).await;
+                    response
+                }
+            },
+        ));
+
+    app.oneshot(request).await.unwrap();
+    routes.lock().unwrap().pop().unwrap()
+}
+
+#[tokio::test]
+async fn given_calendar_feed_request_when_tracing_then_records_route_template_not_token() {
+    let routes = Arc::new(Mutex::new(Vec::new()));
+    let request = Request::builder()
+        .uri("/api/bills/calendar/cal_s3cr3t/feed.ics")
+        .body(Body::empty())
+        .unwrap();
+
+    let route = record_route(routes.clone(), request).await;
+
+    assert_eq!(route, "/api/bills/calendar/{token}/feed.ics");
+    assert!(!route.contains("cal_s3cr3t"));
+
+    let unmatched = Request::builder()
+        .uri("/api/bills/calendar/cal_s3cr3t/other")
+        .body(Body::empty())
+        .unwrap();
+    assert_eq!(record_route(routes, unmatched).await, "[redacted]");
+}
+
+#[test]
+fn given_paths_when_filtering_otel_spans_then_skips_paths_with_secrets() {
+    assert!(!is_traceable_path(
+        "/api/bills/calendar/cal_s3cr3t/feed.ics"
+    ));
+    assert!(is_traceable_path("/api/bills/calendar"));
+    assert!(is_traceable_path("/api/transactions"));
+}
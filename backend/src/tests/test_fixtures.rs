@@ -7,15 +7,33 @@ use crate::models::{auth::User, transaction::Transaction};
 use crate::providers::ProviderRegistry;
 
 use crate::services::{
+    access_token_service::AccessTokenService,
+    alert_service::AlertService,
     analytics_service::AnalyticsService,
+    archive_service::ArchiveService,
     auth_service::AuthService,
+    bills_service::BillsService,
     budget_service::BudgetService,
+    bulk_edit_service::BulkEditService,
     cache_service::{CacheService, MockCacheService},
     connection_service::ConnectionService,
+    currency_service::CurrencyService,
+    export_service::ExportService,
+    forecast_service::ForecastService,
+    import_service::ImportService,
+    insights_service::InsightsService,
+    investment_service::InvestmentService,
+    liability_service::LiabilityService,
+    net_worth_service::NetWorthService,
+    notification_service::NotificationService,
     plaid_service::{PlaidService, RealPlaidClient},
     repository_service::DatabaseRepository,
     repository_service::MockDatabaseRepository,
+    saved_view_service::SavedViewService,
+    sync_progress_service::SyncProgressService,
     sync_service::SyncService,
+    user_settings_service::UserSettingsService,
+    webhook_service::WebhookService,
 };
 
 use crate::config::MockEnvironment;
@@ -51,6 +69,7 @@ impl TestFixtures {
                 provider_account_id: None,
                 provider_transaction_id: Some("mock_txn_001".to_string()),
                 amount: dec!(-45.67),
+                iso_currency_code: "USD".to_string(),
                 date: NaiveDate::from_ymd_opt(2024, 1, 15).unwrap(),
                 merchant_name: Some("Starbucks Coffee".to_string()),
                 category_primary: "Food and Drink".to_string(),
@@ -59,6 +78,8 @@ impl TestFixtures {
                 payment_channel: Some("in_store".to_string()),
                 pending: false,
                 created_at: Some(Utc::now()),
+                reviewed: false,
+                excluded_from_analytics: false,
             },
             Transaction {
                 id: Uuid::parse_str("550e8400-e29b-41d4-a716-446655440011").unwrap(),
@@ -67,6 +88,7 @@ impl TestFixtures {
                 provider_account_id: None,
                 provider_transaction_id: Some("mock_txn_002".to_string()),
                 amount: dec!(-123.45),
+                iso_currency_code: "USD".to_string(),
                 date: NaiveDate::from_ymd_opt(2024, 1, 14).unwrap(),
                 merchant_name: Some("Whole Foods Market".to_string()),
                 category_primary: "Food and Drink".to_string(),
@@ -75,6 +97,8 @@ impl TestFixtures {
                 payment_channel: Some("in_store".to_string()),
                 pending: false,
                 created_at: Some(Utc::now()),
+                reviewed: false,
+                excluded_from_analytics: false,
             },
             Transaction {
                 id: Uuid::parse_str("550e8400-e29b-41d4-a716-446655440012").unwrap(),
@@ -83,6 +107,7 @@ impl TestFixtures {
                 provider_account_id: None,
                 provider_transaction_id: Some("mock_txn_003".to_string()),
                 amount: dec!(2500.00),
+                iso_currency_code: "USD".to_string(),
                 date: NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
                 merchant_name: Some("Employer Direct Deposit".to_string()),
                 category_primary: "Deposit".to_string(),
@@ -91,10 +116,39 @@ impl TestFixtures {
                 payment_channel: Some("ach".to_string()),
                 pending: false,
                 created_at: Some(Utc::now()),
+                reviewed: false,
+                excluded_from_analytics: false,
             },
         ]
     }
 
+    pub fn transaction_on(
+        date: NaiveDate,
+        amount: rust_decimal::Decimal,
+        category: &str,
+        merchant: &str,
+    ) -> Transaction {
+        Transaction {
+            id: Uuid::new_v4(),
+            account_id: Uuid::parse_str("550e8400-e29b-41d4-a716-446655440000").unwrap(),
+            user_id: Some(Uuid::parse_str("550e8400-e29b-41d4-a716-446655440001").unwrap()),
+            provider_account_id: None,
+            provider_transaction_id: Some(format!("txn_{}", Uuid::new_v4())),
+            amount,
+            iso_currency_code: "USD".to_string(),
+            date,
+            merchant_name: Some(merchant.to_string()),
+            category_primary: category.to_string(),
+            category_detailed: category.to_string(),
+            category_confidence: "HIGH".to_string(),
+            payment_channel: Some("online".to_string()),
+            pending: false,
+            created_at: Some(Utc::now()),
+            reviewed: false,
+            excluded_from_analytics: false,
+        }
+    }
+
     pub fn empty_transactions() -> Vec<Transaction> {
         vec![]
     }
@@ -110,6 +164,7 @@ impl TestFixtures {
             provider_account_id: None,
             provider_transaction_id: Some("duplicate_txn_001".to_string()),
             amount: dec!(-25.00),
+            iso_currency_code: "USD".to_string(),
             date: NaiveDate::from_ymd_opt(2024, 1, 10).unwrap(),
             merchant_name: Some("Coffee Shop".to_string()),
             category_primary: "Food and Drink".to_string(),
@@ -118,6 +173,8 @@ impl TestFixtures {
             payment_channel: Some("in_store".to_string()),
             pending: false,
             created_at: Some(Utc::now()),
+            reviewed: false,
+            excluded_from_analytics: false,
         }];
 
         let new_with_duplicate = vec![
@@ -128,6 +185,7 @@ impl TestFixtures {
                 provider_account_id: None,
                 provider_transaction_id: Some("duplicate_txn_001".to_string()),
                 amount: dec!(-25.00),
+                iso_currency_code: "USD".to_string(),
                 date: NaiveDate::from_ymd_opt(2024, 1, 10).unwrap(),
                 merchant_name: Some("Coffee Shop".to_string()),
                 category_primary: "Food and Drink".to_string(),
@@ -136,6 +194,8 @@ impl TestFixtures {
                 payment_channel: Some("in_store".to_string()),
                 pending: false,
                 created_at: Some(Utc::now()),
+                reviewed: false,
+                excluded_from_analytics: false,
             },
             Transaction {
                 id: Uuid::parse_str("550e8400-e29b-41d4-a716-446655440022").unwrap(),
@@ -144,6 +204,7 @@ impl TestFixtures {
                 provider_account_id: None,
                 provider_transaction_id: Some("new_txn_001".to_string()),
                 amount: dec!(-50.00),
+                iso_currency_code: "USD".to_string(),
                 date: NaiveDate::from_ymd_opt(2024, 1, 11).unwrap(),
                 merchant_name: Some("Gas Station".to_string()),
                 category_primary: "Transportation".to_string(),
@@ -152,6 +213,8 @@ impl TestFixtures {
                 payment_channel: Some("in_store".to_string()),
                 pending: false,
                 created_at: Some(Utc::now()),
+                reviewed: false,
+                excluded_from_analytics: false,
             },
         ];
 
@@ -229,6 +292,24 @@ impl TestFixtures {
             AuthService::new("test_jwt_secret_key_for_integration_testing".to_string()).unwrap(),
         );
         let budget_service = Arc::new(BudgetService::new());
+        let saved_view_service = Arc::new(SavedViewService::new());
+        let bulk_edit_service = Arc::new(BulkEditService::new());
+        let sync_progress_service = Arc::new(SyncProgressService::new());
+        let webhook_service = Arc::new(WebhookService::new());
+        let access_token_service = Arc::new(AccessTokenService::new());
+        let import_service = Arc::new(ImportService::new());
+        let archive_service = Arc::new(ArchiveService::new());
+        let export_service = Arc::new(ExportService::new());
+        let user_settings_service = Arc::new(UserSettingsService::new());
+        let liability_service = Arc::new(LiabilityService::new());
+        let investment_service = Arc::new(InvestmentService::new());
+        let insights_service = Arc::new(InsightsService::new());
+        let net_worth_service = Arc::new(NetWorthService::new());
+        let currency_service = Arc::new(CurrencyService::new());
+        let notification_service = Arc::new(NotificationService::new(Vec::new()));
+        let alert_service = Arc::new(AlertService::new(notification_service.clone()));
+        let bills_service = Arc::new(BillsService::new());
+        let forecast_service = Arc::new(ForecastService::new());
         let config = Self::create_test_config();
 
         let state = AppState {
@@ -243,6 +324,24 @@ impl TestFixtures {
             connection_service,
             auth_service,
             provider_registry,
+            saved_view_service,
+            bulk_edit_service,
+            sync_progress_service,
+            webhook_service,
+            access_token_service,
+            import_service,
+            archive_service,
+            export_service,
+            user_settings_service,
+            liability_service,
+            investment_service,
+            insights_service,
+            net_worth_service,
+            currency_service,
+            alert_service,
+            notification_service,
+            bills_service,
+            forecast_service,
         };
 
         Ok(create_app(state))
@@ -305,6 +404,24 @@ impl TestFixtures {
         );
 
         let budget_service = Arc::new(BudgetService::new());
+        let saved_view_service = Arc::new(SavedViewService::new());
+        let bulk_edit_service = Arc::new(BulkEditService::new());
+        let sync_progress_service = Arc::new(SyncProgressService::new());
+        let webhook_service = Arc::new(WebhookService::new());
+        let access_token_service = Arc::new(AccessTokenService::new());
+        let import_service = Arc::new(ImportService::new());
+        let archive_service = Arc::new(ArchiveService::new());
+        let export_service = Arc::new(ExportService::new());
+        let user_settings_service = Arc::new(UserSettingsService::new());
+        let liability_service = Arc::new(LiabilityService::new());
+        let investment_service = Arc::new(InvestmentService::new());
+        let insights_service = Arc::new(InsightsService::new());
+        let net_worth_service = Arc::new(NetWorthService::new());
+        let currency_service = Arc::new(CurrencyService::new());
+        let notification_service = Arc::new(NotificationService::new(Vec::new()));
+        let alert_service = Arc::new(AlertService::new(notification_service.clone()));
+        let bills_service = Arc::new(BillsService::new());
+        let forecast_service = Arc::new(ForecastService::new());
         let config = Self::create_test_config();
 
         let state = AppState {
@@ -319,6 +436,24 @@ impl TestFixtures {
             connection_service,
             auth_service,
             provider_registry,
+            saved_view_service,
+            bulk_edit_service,
+            sync_progress_service,
+            webhook_service,
+            access_token_service,
+            import_service,
+            archive_service,
+            export_service,
+            user_settings_service,
+            liability_service,
+            investment_service,
+            insights_service,
+            net_worth_service,
+            currency_service,
+            alert_service,
+            notification_service,
+            bills_service,
+            forecast_service,
         };
 
         Ok(create_app(state))
@@ -359,6 +494,24 @@ impl TestFixtures {
         );
 
         let budget_service = Arc::new(BudgetService::new());
+        let saved_view_service = Arc::new(SavedViewService::new());
+        let bulk_edit_service = Arc::new(BulkEditService::new());
+        let sync_progress_service = Arc::new(SyncProgressService::new());
+        let webhook_service = Arc::new(WebhookService::new());
+        let access_token_service = Arc::new(AccessTokenService::new());
+        let import_service = Arc::new(ImportService::new());
+        let archive_service = Arc::new(ArchiveService::new());
+        let export_service = Arc::new(ExportService::new());
+        let user_settings_service = Arc::new(UserSettingsService::new());
+        let liability_service = Arc::new(LiabilityService::new());
+        let investment_service = Arc::new(InvestmentService::new());
+        let insights_service = Arc::new(InsightsService::new());
+        let net_worth_service = Arc::new(NetWorthService::new());
+        let currency_service = Arc::new(CurrencyService::new());
+        let notification_service = Arc::new(NotificationService::new(Vec::new()));
+        let alert_service = Arc::new(AlertService::new(notification_service.clone()));
+        let bills_service = Arc::new(BillsService::new());
+        let forecast_service = Arc::new(ForecastService::new());
         let config = Self::create_test_config();
 
         let state = AppState {
@@ -373,6 +526,24 @@ impl TestFixtures {
             connection_service,
             auth_service,
             provider_registry,
+            saved_view_service,
+            bulk_edit_service,
+            sync_progress_service,
+            webhook_service,
+            access_token_service,
+            import_service,
+            archive_service,
+            export_service,
+            user_settings_service,
+            liability_service,
+            investment_service,
+            insights_service,
+            net_worth_service,
+            currency_service,
+            alert_service,
+            notification_service,
+            bills_service,
+            forecast_service,
         };
 
         Ok(create_app(state))
@@ -0,0 +1,223 @@
+use crate::models::transaction::{TransactionWithAccount, TransactionsQuery};
+use crate::models::transaction_search::{AmountComparison, TransactionSearch};
+use crate::services::repository_service::MockDatabaseRepository;
+use crate::services::transaction_search_service::TransactionSearchService;
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use uuid::Uuid;
+
+fn day(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+fn listed(merchant: &str) -> TransactionWithAccount {
+    TransactionWithAccount {
+        id: Uuid::new_v4(),
+        account_id: Uuid::new_v4(),
+        user_id: None,
+        provider_account_id: None,
+        provider_transaction_id: None,
+        amount: Decimal::new(4275, 2),
+        iso_currency_code: "USD".to_string(),
+        date: day(2025, 12, 1),
+        merchant_name: Some(merchant.to_string()),
+        category_primary: "FOOD_AND_DRINK".to_string(),
+        category_detailed: "Groceries".to_string(),
+        category_confidence: "high".to_string(),
+        payment_channel: None,
+        pending: false,
+        created_at: None,
+        reviewed: false,
+        excluded_from_analytics: false,
+        account_name: "Checking".to_string(),
+        account_type: "depository".to_string(),
+        account_mask: None,
+    }
+}
+
+fn query(search: Option<&str>, account_ids: Vec<String>) -> TransactionsQuery {
+    TransactionsQuery {
+        search: search.map(str::to_string),
+        account_ids,
+    }
+}
+
+#[test]
+fn given_qualified_query_when_parsing_then_splits_into_filters() {
+    let search = TransactionSearch::parse(
+        "merchant:amazon amount>50 before:2026-01-01 category:food Amount<=100.5 tag:\"home office\" coffe",
+    )
+    .unwrap();
+
+    assert_eq!(search.merchants, vec!["amazon"]);
+    assert_eq!(search.categories, vec!["food"]);
+    assert_eq!(search.tags, vec!["home office"]);
+    assert_eq!(search.text, vec!["coffe"]);
+    assert_eq!(
+        search.amounts,
+        vec![
+            (AmountComparison::Greater, dec!(50)),
+            (AmountComparison::LessOrEqual, dec!(100.5)),
+        ]
+    );
+    assert_eq!(search.start_date, None);
+    assert_eq!(search.end_date, Some(day(2025, 12, 31)));
+}
+
+#[test]
+fn given_bare_values_when_parsing_then_matches_amount_day_and_phrase() {
+    let search = TransactionSearch::parse("42.75 $9 2026-01-05 \"whole foods\"").unwrap();
+
+    assert_eq!(
+        search.amounts,
+        vec![
+            (AmountComparison::Equal, dec!(42.75)),
+            (AmountComparison::Equal, dec!(9)),
+        ]
+    );
+    assert_eq!(search.start_date, Some(day(2026, 1, 5)));
+    assert_eq!(search.end_date, Some(day(2026, 1, 5)));
+    assert_eq!(search.text, vec!["whole foods"]);
+}
+
+#[test]
+fn given_month_and_range_qualifiers_when_parsing_then_intersects_dates() {
+    let month = TransactionSearch::parse("on:2024-02").unwrap();
+    assert_eq!(month.start_date, Some(day(2024, 2, 1)));
+    assert_eq!(month.end_date, Some(day(2024, 2, 29)));
+
+    let december = TransactionSearch::parse("on:2025-12 after:2025-12-10").unwrap();
+    assert_eq!(december.start_date, Some(day(2025, 12, 11)));
+    assert_eq!(december.end_date, Some(day(2025, 12, 31)));
+
+    assert!(TransactionSearch::parse("   ").unwrap().is_empty());
+    assert_eq!(
+        TransactionSearch::parse("note:rent").unwrap().text,
+        vec!["note:rent"]
+    );
+}
+
+#[test]
+fn given_bad_values_when_parsing_then_rejects_with_reason() {
+    for input in [
+        "before:yesterday",
+        "on:2025-13",
+        "amount>lots",
+        "merchant:",
+        "after:2025-02-30",
+    ] {
+        let error = TransactionSearch::parse(input).unwrap_err();
+        assert!(error.starts_with("Invalid search"), "{}: {}", input, error);
+    }
+}
+
+#[test]
+fn given_search_when_building_sql_then_binds_values_and_ranks_text_matches() {
+    let search =
+        TransactionSearch::parse("amazn merchant:'; DROP TABLE x; -- category:100%_off amount>=50")
+            .unwrap();
+
+    let sql =
+        TransactionSearchService::build_sql(Uuid::new_v4(), &search, &[Uuid::new_v4()]).into_sql();
+
+    assert!(!sql.contains("DROP TABLE"));
+    assert!(!sql.contains("amazn"));
+    assert!(!sql.contains("100"));
+    assert!(sql.contains("t.search_vector @@ to_tsquery('simple', $"));
+    assert!(sql.contains(" <% t.merchant_name"));
+    assert!(!sql.contains("<% COALESCE") && !sql.contains("a.name ILIKE"));
+    assert!(sql.contains("t.account_id = ANY(ARRAY(SELECT an.id FROM accounts an"));
+    assert!(sql.contains("t.account_id = ANY($"));
+    assert!(sql.contains("ABS(t.amount) >= $"));
+    assert!(sql.contains("ORDER BY (ts_rank("));
+    assert!(sql.contains("t.date DESC, t.created_at DESC LIMIT $"));
+
+    let filters_only = TransactionSearch::parse("tag:trip on:2025-06").unwrap();
+    let sql = TransactionSearchService::build_sql(Uuid::new_v4(), &filters_only, &[]).into_sql();
+    assert!(sql.contains("LOWER(tt.tag) = LOWER($"));
+    assert!(sql.contains("t.date >= $") && sql.contains("t.date <= $"));
+    assert!(sql.contains("ORDER BY t.date DESC"));
+    assert!(!sql.contains("ts_rank") && !sql.contains("account_id = ANY"));
+}
+
+#[test]
+fn given_search_when_building_ids_sql_then_keeps_filters_without_order_or_limit() {
+    let search = TransactionSearch::parse("amazn tag:trip").unwrap();
+
+    let sql = TransactionSearchService::build_ids_sql(Uuid::new_v4(), &search, &[Uuid::new_v4()])
+        .into_sql();
+
+    assert!(sql.starts_with("SELECT t.id FROM transactions t"));
+    assert!(sql.contains("t.search_vector @@ to_tsquery('simple', $"));
+    assert!(sql.contains("LOWER(tt.tag) = LOWER($"));
+    assert!(sql.contains("t.account_id = ANY($"));
+    assert!(!sql.contains("ORDER BY") && !sql.contains("LIMIT"));
+}
+
+#[tokio::test]
+async fn given_no_search_when_finding_then_lists_without_querying_search() {
+    let kept = listed("Amazon");
+    let listing = vec![kept.clone()];
+    let account_id = kept.account_id.to_string();
+    let mut mock_db = MockDatabaseRepository::new();
+    mock_db
+        .expect_get_transactions_with_account_for_user()
+        .times(1)
+        .returning(move |_| {
+            let listing = listing.clone();
+            Box::pin(async move { Ok(listing) })
+        });
+    mock_db.expect_search_transactions().never();
+
+    let found = TransactionSearchService::find(
+        &mock_db,
+        Uuid::new_v4(),
+        &query(Some(" "), vec![account_id]),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].id, kept.id);
+}
+
+#[tokio::test]
+async fn given_search_when_finding_then_runs_it_in_the_database() {
+    let account_id = Uuid::new_v4();
+    let ranked = vec![listed("Amazon"), listed("Amazon Marketplace")];
+    let expected: Vec<Uuid> = ranked.iter().map(|t| t.id).collect();
+    let mut mock_db = MockDatabaseRepository::new();
+    mock_db
+        .expect_get_transactions_with_account_for_user()
+        .never();
+    mock_db
+        .expect_search_transactions()
+        .times(1)
+        .withf(move |_, search, account_ids| {
+            search.merchants == ["amazon"] && account_ids == [account_id]
+        })
+        .returning(move |_, _, _| {
+            let ranked = ranked.clone();
+            Box::pin(async move { Ok(ranked) })
+        });
+
+    let found = TransactionSearchService::find(
+        &mock_db,
+        Uuid::new_v4(),
+        &query(
+            Some("merchant:amazon"),
+            vec![account_id.to_string(), "not-a-uuid".to_string()],
+        ),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(found.iter().map(|t| t.id).collect::<Vec<_>>(), expected);
+
+    let error =
+        TransactionSearchService::find(&mock_db, Uuid::new_v4(), &query(Some("on:soon"), vec![]))
+            .await
+            .unwrap_err();
+    assert!(error.starts_with("Invalid search"));
+}
@@ -0,0 +1,218 @@
+use crate::models::comparison::{ComparisonBaseline, PeriodRange};
+use crate::models::user_settings::{UpdateUserSettingsRequest, UserCalendar, UserSettings};
+use crate::services::analytics_service::AnalyticsService;
+use crate::services::repository_service::MockDatabaseRepository;
+use crate::services::user_settings_service::UserSettingsService;
+use crate::test_fixtures::TestFixtures;
+use chrono::{NaiveDate, TimeZone, Utc};
+use chrono_tz::Tz;
+use rust_decimal_macros::dec;
+use uuid::Uuid;
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+fn update(
+    reporting_currency: Option<&str>,
+    timezone: Option<&str>,
+    month_start_day: Option<u32>,
+) -> UpdateUserSettingsRequest {
+    UpdateUserSettingsRequest {
+        reporting_currency: reporting_currency.map(str::to_string),
+        timezone: timezone.map(str::to_string),
+        month_start_day,
+    }
+}
+
+#[tokio::test]
+async fn given_invalid_currency_code_when_updating_settings_then_rejects_without_saving() {
+    let mut mock_db = MockDatabaseRepository::new();
+    mock_db
+        .expect_get_user_settings()
+        .returning(|_| Box::pin(async { Ok(None) }));
+    mock_db.expect_upsert_user_settings().never();
+    let request = update(Some("euro"), None, None);
+
+    let result = UserSettingsService::new()
+        .update_settings(&mock_db, Uuid::new_v4(), &request)
+        .await;
+
+    assert_eq!(result.unwrap_err(), "Invalid currency code: euro");
+}
+
+#[tokio::test]
+async fn given_timezone_and_month_start_when_updating_settings_then_keeps_other_fields() {
+    let mut mock_db = MockDatabaseRepository::new();
+    mock_db.expect_get_user_settings().returning(|_| {
+        Box::pin(async {
+            Ok(Some(UserSettings {
+                reporting_currency: "EUR".to_string(),
+                ..UserSettings::default()
+            }))
+        })
+    });
+    mock_db
+        .expect_upsert_user_settings()
+        .times(1)
+        .returning(|_, settings| {
+            let settings = settings.clone();
+            Box::pin(async move { Ok(settings) })
+        });
+    let request = update(None, Some(" America/New_York "), Some(15));
+
+    let settings = UserSettingsService::new()
+        .update_settings(&mock_db, Uuid::new_v4(), &request)
+        .await
+        .unwrap();
+
+    assert_eq!(settings.reporting_currency, "EUR");
+    assert_eq!(settings.timezone, "America/New_York");
+    assert_eq!(settings.month_start_day, 15);
+}
+
+#[test]
+fn given_unknown_timezone_or_late_start_day_when_applying_update_then_rejects() {
+    let mut settings = UserSettings::default();
+
+    assert_eq!(
+        UserSettingsService::apply_update(&mut settings, &update(None, Some("Mars/Base"), None))
+            .unwrap_err(),
+        "Invalid timezone: Mars/Base"
+    );
+    assert_eq!(
+        UserSettingsService::apply_update(&mut settings, &update(None, None, Some(29)))
+            .unwrap_err(),
+        "Invalid month_start_day: must be between 1 and 28"
+    );
+    assert_eq!(settings, UserSettings::default());
+}
+
+#[test]
+fn given_new_york_user_when_resolving_local_date_then_uses_their_day_not_utc() {
+    let settings = UserSettings {
+        timezone: "America/New_York".to_string(),
+        ..UserSettings::default()
+    };
+    let calendar = settings.calendar();
+    let late_evening = Utc.with_ymd_and_hms(2024, 3, 1, 3, 30, 0).unwrap();
+
+    assert_eq!(calendar.local_date(late_evening), date(2024, 2, 29));
+    assert_eq!(
+        UserCalendar::default().local_date(late_evening),
+        date(2024, 3, 1)
+    );
+}
+
+#[test]
+fn given_any_setting_changed_when_tagging_cached_responses_then_tag_differs() {
+    let settings = UserSettings::default();
+    let changed = [
+        UserSettings {
+            reporting_currency: "EUR".to_string(),
+            ..UserSettings::default()
+        },
+        UserSettings {
+            timezone: "Europe/London".to_string(),
+            ..UserSettings::default()
+        },
+        UserSettings {
+            month_start_day: 15,
+            ..UserSettings::default()
+        },
+    ];
+
+    assert_eq!(settings.cache_tag(), UserSettings::default().cache_tag());
+    for other in changed {
+        assert_ne!(other.cache_tag(), settings.cache_tag());
+    }
+}
+
+#[test]
+fn given_out_of_range_year_when_resolving_month_then_rejects_instead_of_panicking() {
+    let calendar = UserCalendar::new(Tz::UTC, 15);
+
+    assert_eq!(AnalyticsService::parse_month_key("300000-01"), None);
+    assert_eq!(AnalyticsService::parse_month_key("1899-12"), None);
+    assert_eq!(
+        AnalyticsService::parse_month_key("9999-12"),
+        Some((9999, 12))
+    );
+    assert_eq!(calendar.month_range(300000, 1), None);
+    assert_eq!(calendar.month_range(262143, 12), None);
+}
+
+#[test]
+fn given_month_start_on_15th_when_resolving_months_then_shifts_boundaries() {
+    let calendar = UserCalendar::new(Tz::UTC, 15);
+
+    assert_eq!(calendar.month_of(date(2024, 2, 3)), (2024, 1));
+    assert_eq!(calendar.month_of(date(2024, 2, 15)), (2024, 2));
+    assert_eq!(calendar.month_key(date(2024, 1, 14)), "2023-12");
+    assert_eq!(
+        calendar.month_range(2024, 12).unwrap(),
+        (date(2024, 12, 15), date(2025, 1, 14))
+    );
+    assert!(calendar.is_month_aligned(date(2024, 1, 15), date(2024, 3, 14)));
+    assert!(!calendar.is_month_aligned(date(2024, 1, 1), date(2024, 1, 31)));
+}
+
+#[test]
+fn given_month_start_on_15th_when_resolving_period_presets_then_uses_shifted_months() {
+    let calendar = UserCalendar::new(Tz::UTC, 15);
+    let today = date(2024, 3, 10);
+
+    assert_eq!(
+        AnalyticsService::get_period_date_range("current-month", &calendar, today),
+        Some((date(2024, 2, 15), date(2024, 3, 14)))
+    );
+    assert_eq!(
+        AnalyticsService::get_period_date_range("past-6-months", &calendar, today),
+        Some((date(2023, 9, 15), date(2024, 3, 14)))
+    );
+    assert_eq!(
+        AnalyticsService::get_period_date_range("past-year", &UserCalendar::default(), today),
+        Some((date(2023, 4, 1), date(2024, 3, 31)))
+    );
+
+    let current = PeriodRange {
+        start_date: date(2024, 2, 15),
+        end_date: date(2024, 3, 14),
+    };
+    assert_eq!(
+        AnalyticsService::baseline_range(current, ComparisonBaseline::PreviousPeriod, &calendar),
+        Some(PeriodRange {
+            start_date: date(2024, 1, 15),
+            end_date: date(2024, 2, 14),
+        })
+    );
+    assert_eq!(
+        AnalyticsService::baseline_range(current, ComparisonBaseline::YearAgo, &calendar),
+        Some(PeriodRange {
+            start_date: date(2023, 2, 15),
+            end_date: date(2023, 3, 14),
+        })
+    );
+}
+
+#[test]
+fn given_shifted_month_when_calculating_daily_spending_then_counts_days_from_start_day() {
+    let calendar = UserCalendar::new(Tz::UTC, 15);
+    let (start, end) = calendar.month_range(2024, 2).unwrap();
+    let transactions = vec![
+        TestFixtures::transaction_on(date(2024, 2, 14), dec!(99), "FOOD_AND_DRINK", "Cafe"),
+        TestFixtures::transaction_on(date(2024, 2, 15), dec!(20), "FOOD_AND_DRINK", "Cafe"),
+        TestFixtures::transaction_on(date(2024, 3, 14), dec!(30), "FOOD_AND_DRINK", "Cafe"),
+    ];
+
+    let daily = AnalyticsService::new().calculate_daily_spending(&transactions, start, end);
+    let current =
+        AnalyticsService::new().calculate_current_month_spending(&transactions, &calendar, end);
+
+    assert_eq!(daily.len(), 29);
+    assert_eq!(daily[0].date, date(2024, 2, 15));
+    assert_eq!(daily[0].spend, dec!(20));
+    assert_eq!(daily[28].day, 29);
+    assert_eq!(daily[28].cumulative, dec!(50));
+    assert_eq!(current, dec!(50));
+}
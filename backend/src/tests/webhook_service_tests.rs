@@ -0,0 +1,568 @@
+use crate::models::budget::Budget;
+use crate::models::plaid::ProviderConnection;
+use crate::models::saved_view::ViewMatches;
+use crate::models::user_settings::UserCalendar;
+use crate::models::webhook::{
+    WebhookDelivery, WebhookEndpoint, WebhookEndpointRequest, WebhookEvent, WebhookEventType,
+    WEBHOOK_STATUS_FAILED, WEBHOOK_STATUS_PENDING, WEBHOOK_STATUS_SUCCEEDED,
+};
+use crate::services::repository_service::MockDatabaseRepository;
+use crate::services::webhook_service::{
+    WebhookService, MAX_WEBHOOK_ATTEMPTS, WEBHOOK_DELIVERY_HEADER, WEBHOOK_EVENT_HEADER,
+    WEBHOOK_SIGNATURE_HEADER,
+};
+use crate::test_fixtures::TestFixtures;
+use axum::body::Bytes;
+use axum::extract::State;
+use axum::http::{HeaderMap, StatusCode};
+use axum::routing::post;
+use axum::Router;
+use chrono::{Duration, NaiveDate, TimeZone, Utc};
+use rust_decimal_macros::dec;
+use serde_json::json;
+use std::sync::{Arc, Mutex};
+use tokio::net::TcpListener;
+use uuid::Uuid;
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+fn endpoint(user_id: Uuid, url: &str, events: Vec<WebhookEventType>) -> WebhookEndpoint {
+    WebhookEndpoint {
+        id: Uuid::new_v4(),
+        user_id,
+        url: url.to_string(),
+        description: None,
+        events,
+        secret: "whsec_test".to_string(),
+        enabled: true,
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+    }
+}
+
+#[derive(Clone, Default)]
+struct ReceivedWebhooks {
+    requests: Arc<Mutex<Vec<(HeaderMap, String)>>>,
+}
+
+async fn receive(
+    State(received): State<(ReceivedWebhooks, StatusCode)>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> StatusCode {
+    let (received, status) = received;
+    received
+        .requests
+        .lock()
+        .unwrap()
+        .push((headers, String::from_utf8(body.to_vec()).unwrap()));
+    status
+}
+
+async fn spawn_receiver(status: StatusCode) -> (String, ReceivedWebhooks) {
+    let received = ReceivedWebhooks::default();
+    let app = Router::new()
+        .route("/hook", post(receive))
+        .with_state((received.clone(), status));
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    (format!("http://{}/hook", address), received)
+}
+
+/// Test receivers listen on loopback, which only an operator allowlist permits.
+fn local_service() -> WebhookService {
+    WebhookService::new().with_allowed_hosts(vec!["127.0.0.1".to_string()])
+}
+
+#[test]
+fn given_secret_and_body_when_signing_then_matches_hmac_sha256_of_timestamped_body() {
+    let body = r#"{"type":"webhook.test"}"#;
+
+    let header = WebhookService::signature_header("whsec_test", 1_700_000_000, body);
+
+    assert_eq!(
+        header,
+        "t=1700000000,v1=cf7d053522b08300fae293c3bcbf4e183d4a9538620c18dc9a46d063337936fa"
+    );
+}
+
+#[test]
+fn given_failed_attempts_when_recording_then_backs_off_exponentially_until_giving_up() {
+    let user_id = Uuid::new_v4();
+    let hook = endpoint(user_id, "https://example.com", vec![]);
+    let event = WebhookEvent::new(user_id, WebhookEventType::SyncFailed, json!({}));
+    let now = Utc.with_ymd_and_hms(2024, 3, 1, 12, 0, 0).unwrap();
+
+    let first =
+        WebhookService::record_attempt(WebhookDelivery::queued(&hook, &event), Ok(503), now);
+    assert_eq!(first.status, WEBHOOK_STATUS_PENDING);
+    assert_eq!(first.response_status, Some(503));
+    assert_eq!(first.next_attempt_at, Some(now + Duration::seconds(60)));
+
+    let second = WebhookService::record_attempt(first, Err("connection refused".to_string()), now);
+    assert_eq!(second.attempts, 2);
+    assert_eq!(second.response_status, None);
+    assert_eq!(second.error.as_deref(), Some("connection refused"));
+    assert_eq!(second.next_attempt_at, Some(now + Duration::seconds(120)));
+
+    let last = WebhookService::record_attempt(
+        WebhookDelivery {
+            attempts: MAX_WEBHOOK_ATTEMPTS - 1,
+            ..second
+        },
+        Ok(500),
+        now,
+    );
+    assert_eq!(last.status, WEBHOOK_STATUS_FAILED);
+    assert_eq!(last.next_attempt_at, None);
+}
+
+#[test]
+fn given_invalid_requests_when_building_endpoint_then_rejects_them() {
+    let existing = endpoint(Uuid::new_v4(), "https://example.com", vec![]);
+    let request = |url: &str, events: Vec<WebhookEventType>| WebhookEndpointRequest {
+        url: url.to_string(),
+        description: None,
+        events,
+        enabled: None,
+    };
+
+    for invalid in [
+        request("ftp://example.com/hook", vec![WebhookEventType::SyncFailed]),
+        request("not a url", vec![WebhookEventType::SyncFailed]),
+        request("https://example.com/hook", vec![]),
+        request("https://example.com/hook", vec![WebhookEventType::Test]),
+    ] {
+        let error = WebhookService::build_endpoint(existing.clone(), &invalid).unwrap_err();
+        assert!(error.starts_with("Invalid"), "{}", error);
+    }
+
+    let built = WebhookService::build_endpoint(
+        existing.clone(),
+        &request(
+            " http://192.168.1.20:8123/api/webhook/sumurai ",
+            vec![
+                WebhookEventType::SyncFailed,
+                WebhookEventType::BudgetExceeded,
+                WebhookEventType::SyncFailed,
+            ],
+        ),
+    )
+    .unwrap();
+    assert_eq!(built.url, "http://192.168.1.20:8123/api/webhook/sumurai");
+    assert_eq!(
+        built.events,
+        vec![
+            WebhookEventType::BudgetExceeded,
+            WebhookEventType::SyncFailed
+        ]
+    );
+    assert_eq!(built.secret, existing.secret);
+}
+
+#[test]
+fn given_spending_over_budget_when_building_events_then_reports_once_per_budget_and_month() {
+    let user_id = Uuid::new_v4();
+    let groceries = Budget::new(user_id, "GROCERIES".to_string(), dec!(200));
+    let dining = Budget::new(user_id, "FOOD_AND_DRINK".to_string(), dec!(100));
+    let transactions = vec![
+        TestFixtures::transaction_on(date(2024, 3, 2), dec!(120), "GROCERIES", "Market"),
+        TestFixtures::transaction_on(date(2024, 3, 9), dec!(90), "GROCERIES", "Market"),
+        TestFixtures::transaction_on(date(2024, 2, 20), dec!(500), "FOOD_AND_DRINK", "Cafe"),
+    ];
+
+    let events = WebhookService::budget_exceeded_events(
+        &[groceries.clone(), dining],
+        &ViewMatches::default(),
+        &transactions,
+        &UserCalendar::default(),
+        date(2024, 3, 10),
+    );
+
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].event_type, WebhookEventType::BudgetExceeded);
+    assert_eq!(
+        events[0].dedup_key,
+        Some(format!("budget.exceeded:{}:2024-03", groceries.id))
+    );
+    assert_eq!(events[0].data["spent"], json!("210"));
+}
+
+#[test]
+fn given_missing_credentials_when_building_failure_events_then_adds_reauth_event() {
+    let user_id = Uuid::new_v4();
+    let connection = ProviderConnection::new(user_id, "item-9");
+
+    let events = WebhookService::sync_failed_events(
+        user_id,
+        &connection,
+        Some("the connection needs to be re-linked"),
+        true,
+    );
+
+    let types: Vec<_> = events.iter().map(|e| e.event_type).collect();
+    assert_eq!(
+        types,
+        vec![
+            WebhookEventType::SyncFailed,
+            WebhookEventType::ConnectionReauthRequired
+        ]
+    );
+    assert_eq!(events[1].data["connection_id"], json!(connection.id));
+}
+
+#[tokio::test]
+async fn given_endpoints_when_publishing_then_queues_only_enabled_subscribers() {
+    let user_id = Uuid::new_v4();
+    let subscribed = endpoint(
+        user_id,
+        "https://a.example.com",
+        vec![WebhookEventType::SyncCompleted],
+    );
+    let other_event = endpoint(
+        user_id,
+        "https://b.example.com",
+        vec![WebhookEventType::SyncFailed],
+    );
+    let mut disabled = endpoint(
+        user_id,
+        "https://c.example.com",
+        vec![WebhookEventType::SyncCompleted],
+    );
+    disabled.enabled = false;
+    let subscribed_id = subscribed.id;
+
+    let mut mock_db = MockDatabaseRepository::new();
+    mock_db
+        .expect_get_webhook_endpoints_for_user()
+        .returning(move |_| {
+            let endpoints = vec![subscribed.clone(), other_event.clone(), disabled.clone()];
+            Box::pin(async move { Ok(endpoints) })
+        });
+    mock_db
+        .expect_insert_webhook_delivery()
+        .withf(move |delivery| {
+            delivery.endpoint_id == subscribed_id
+                && delivery.event_type == "sync.completed"
+                && delivery.payload["type"] == "sync.completed"
+        })
+        .times(1)
+        .returning(|_| Box::pin(async { Ok(true) }));
+
+    let event = WebhookEvent::new(user_id, WebhookEventType::SyncCompleted, json!({}));
+    let queued = WebhookService::new()
+        .publish(&mock_db, &event)
+        .await
+        .unwrap();
+
+    assert_eq!(queued, 1);
+}
+
+#[tokio::test]
+async fn given_local_receiver_when_sending_test_then_delivers_signed_payload_and_logs_success() {
+    let (url, received) = spawn_receiver(StatusCode::NO_CONTENT).await;
+    let user_id = Uuid::new_v4();
+    let hook = endpoint(user_id, &url, vec![WebhookEventType::SyncFailed]);
+    let endpoint_id = hook.id;
+    let logged = Arc::new(Mutex::new(Vec::new()));
+    let log = logged.clone();
+
+    let mut mock_db = MockDatabaseRepository::new();
+    mock_db
+        .expect_get_webhook_endpoint_by_id()
+        .returning(move |_| {
+            let hook = hook.clone();
+            Box::pin(async move { Ok(Some(hook)) })
+        });
+    mock_db
+        .expect_insert_webhook_delivery()
+        .times(1)
+        .returning(|_| Box::pin(async { Ok(true) }));
+    mock_db
+        .expect_update_webhook_delivery()
+        .times(1)
+        .returning(move |delivery| {
+            log.lock().unwrap().push(delivery.clone());
+            Box::pin(async { Ok(()) })
+        });
+
+    let delivery = local_service()
+        .send_test(&mock_db, user_id, endpoint_id)
+        .await
+        .unwrap();
+
+    assert_eq!(delivery.status, WEBHOOK_STATUS_SUCCEEDED);
+    assert_eq!(delivery.response_status, Some(204));
+    assert_eq!(
+        logged.lock().unwrap().as_slice(),
+        std::slice::from_ref(&delivery)
+    );
+
+    let requests = received.requests.lock().unwrap();
+    assert_eq!(requests.len(), 1);
+    let (headers, body) = &requests[0];
+    assert_eq!(headers[WEBHOOK_EVENT_HEADER], "webhook.test");
+    assert_eq!(
+        headers[WEBHOOK_DELIVERY_HEADER],
+        delivery.id.to_string().as_str()
+    );
+    let signature = headers[WEBHOOK_SIGNATURE_HEADER].to_str().unwrap();
+    let timestamp: i64 = signature
+        .strip_prefix("t=")
+        .and_then(|rest| rest.split(',').next())
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert_eq!(
+        signature,
+        WebhookService::signature_header("whsec_test", timestamp, body)
+    );
+    let payload: serde_json::Value = serde_json::from_str(body).unwrap();
+    assert_eq!(payload["type"], "webhook.test");
+    assert_eq!(payload["data"]["endpoint_id"], json!(endpoint_id));
+}
+
+#[tokio::test]
+async fn given_failing_receiver_when_processing_due_deliveries_then_schedules_retry() {
+    let (url, received) = spawn_receiver(StatusCode::SERVICE_UNAVAILABLE).await;
+    let user_id = Uuid::new_v4();
+    let hook = endpoint(user_id, &url, vec![WebhookEventType::SyncFailed]);
+    let event = WebhookEvent::new(user_id, WebhookEventType::SyncFailed, json!({}));
+    let queued = WebhookDelivery::queued(&hook, &event);
+    let updated = Arc::new(Mutex::new(None));
+    let captured = updated.clone();
+
+    let mut mock_db = MockDatabaseRepository::new();
+    mock_db
+        .expect_claim_due_webhook_deliveries()
+        .returning(move |_, _, _| {
+            let deliveries = vec![queued.clone()];
+            Box::pin(async move { Ok(deliveries) })
+        });
+    mock_db
+        .expect_get_webhook_endpoint_by_id()
+        .returning(move |_| {
+            let hook = hook.clone();
+            Box::pin(async move { Ok(Some(hook)) })
+        });
+    mock_db
+        .expect_update_webhook_delivery()
+        .returning(move |delivery| {
+            *captured.lock().unwrap() = Some(delivery.clone());
+            Box::pin(async { Ok(()) })
+        });
+
+    let now = Utc::now();
+    let attempted = local_service().process_due(&mock_db, now).await.unwrap();
+
+    assert_eq!(attempted, 1);
+    assert_eq!(received.requests.lock().unwrap().len(), 1);
+    let delivery = updated.lock().unwrap().clone().unwrap();
+    assert_eq!(delivery.status, WEBHOOK_STATUS_PENDING);
+    assert_eq!(delivery.attempts, 1);
+    assert_eq!(delivery.response_status, Some(503));
+    assert!(delivery.next_attempt_at.unwrap() > now + Duration::seconds(59));
+}
+
+#[tokio::test]
+async fn given_endpoint_lookup_failing_for_one_delivery_when_processing_due_then_sends_the_rest() {
+    let (url, received) = spawn_receiver(StatusCode::NO_CONTENT).await;
+    let user_id = Uuid::new_v4();
+    let hook = endpoint(user_id, &url, vec![WebhookEventType::SyncFailed]);
+    let unreadable = endpoint(user_id, &url, vec![WebhookEventType::SyncFailed]);
+    let unreadable_id = unreadable.id;
+    let event = WebhookEvent::new(user_id, WebhookEventType::SyncFailed, json!({}));
+    let queued = vec![
+        WebhookDelivery::queued(&unreadable, &event),
+        WebhookDelivery::queued(&hook, &event),
+    ];
+    let updated = Arc::new(Mutex::new(Vec::new()));
+    let captured = updated.clone();
+
+    let mut mock_db = MockDatabaseRepository::new();
+    mock_db
+        .expect_claim_due_webhook_deliveries()
+        .returning(move |_, _, _| {
+            let deliveries = queued.clone();
+            Box::pin(async move { Ok(deliveries) })
+        });
+    mock_db
+        .expect_get_webhook_endpoint_by_id()
+        .returning(move |endpoint_id| {
+            let found = if *endpoint_id == unreadable_id {
+                Err(anyhow::anyhow!("connection reset"))
+            } else {
+                Ok(Some(hook.clone()))
+            };
+            Box::pin(async move { found })
+        });
+    mock_db
+        .expect_update_webhook_delivery()
+        .times(1)
+        .returning(move |delivery| {
+            captured.lock().unwrap().push(delivery.clone());
+            Box::pin(async { Ok(()) })
+        });
+
+    let attempted = local_service()
+        .process_due(&mock_db, Utc::now())
+        .await
+        .unwrap();
+
+    assert_eq!(attempted, 2);
+    assert_eq!(received.requests.lock().unwrap().len(), 1);
+    let updated = updated.lock().unwrap();
+    assert_eq!(updated[0].status, WEBHOOK_STATUS_SUCCEEDED);
+    assert_ne!(updated[0].endpoint_id, unreadable_id);
+}
+
+#[test]
+fn given_internal_addresses_when_checking_then_only_public_ones_pass() {
+    for blocked in [
+        "127.0.0.1",
+        "10.1.2.3",
+        "172.16.0.1",
+        "192.168.1.10",
+        "169.254.169.254",
+        "100.64.0.1",
+        "0.0.0.0",
+        "255.255.255.255",
+        "::1",
+        "::",
+        "fd00:ec2::254",
+        "fe80::1",
+        "::ffff:192.168.1.10",
+        "::127.0.0.1",
+        "::169.254.169.254",
+        "64:ff9b::10.0.0.1",
+        "64:ff9b::a9fe:a9fe",
+    ] {
+        assert!(
+            !WebhookService::is_public_address(blocked.parse().unwrap()),
+            "{}",
+            blocked
+        );
+    }
+    for public in [
+        "93.184.216.34",
+        "8.8.8.8",
+        "2606:4700:4700::1111",
+        "::ffff:8.8.8.8",
+        "64:ff9b::808:808",
+    ] {
+        assert!(
+            WebhookService::is_public_address(public.parse().unwrap()),
+            "{}",
+            public
+        );
+    }
+}
+
+#[tokio::test]
+async fn given_internal_urls_when_sending_test_then_refuses_without_connecting() {
+    let (local_url, received) = spawn_receiver(StatusCode::NO_CONTENT).await;
+    let port = local_url
+        .split(':')
+        .nth(2)
+        .unwrap()
+        .split('/')
+        .next()
+        .unwrap();
+    let user_id = Uuid::new_v4();
+
+    for url in [
+        local_url.clone(),
+        format!("http://localhost:{}/hook", port),
+        "http://169.254.169.254/latest/meta-data/".to_string(),
+        "http://[::1]/hook".to_string(),
+        "http://10.0.0.5:8080/hook".to_string(),
+    ] {
+        let hook = endpoint(user_id, &url, vec![WebhookEventType::SyncFailed]);
+        let endpoint_id = hook.id;
+        let mut mock_db = MockDatabaseRepository::new();
+        mock_db
+            .expect_get_webhook_endpoint_by_id()
+            .returning(move |_| {
+                let hook = hook.clone();
+                Box::pin(async move { Ok(Some(hook)) })
+            });
+        mock_db
+            .expect_insert_webhook_delivery()
+            .returning(|_| Box::pin(async { Ok(true) }));
+        mock_db
+            .expect_update_webhook_delivery()
+            .returning(|_| Box::pin(async { Ok(()) }));
+
+        let delivery = WebhookService::new()
+            .send_test(&mock_db, user_id, endpoint_id)
+            .await
+            .unwrap();
+
+        assert_eq!(delivery.status, WEBHOOK_STATUS_FAILED, "{}", url);
+        assert_eq!(delivery.response_status, None, "{}", url);
+        assert!(
+            delivery
+                .error
+                .as_deref()
+                .unwrap()
+                .contains("loopback, private or link-local"),
+            "{}: {:?}",
+            url,
+            delivery.error
+        );
+    }
+    assert!(received.requests.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn given_redirecting_receiver_when_sending_test_then_does_not_follow_redirect() {
+    let followed = Arc::new(Mutex::new(false));
+    let flag = followed.clone();
+    let app = Router::new()
+        .route(
+            "/hook",
+            post(|| async { (StatusCode::TEMPORARY_REDIRECT, [("location", "/internal")]) }),
+        )
+        .route(
+            "/internal",
+            post(move || async move {
+                *flag.lock().unwrap() = true;
+                StatusCode::OK
+            }),
+        );
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/hook", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    let user_id = Uuid::new_v4();
+    let hook = endpoint(user_id, &url, vec![WebhookEventType::SyncFailed]);
+    let endpoint_id = hook.id;
+    let mut mock_db = MockDatabaseRepository::new();
+    mock_db
+        .expect_get_webhook_endpoint_by_id()
+        .returning(move |_| {
+            let hook = hook.clone();
+            Box::pin(async move { Ok(Some(hook)) })
+        });
+    mock_db
+        .expect_insert_webhook_delivery()
+        .returning(|_| Box::pin(async { Ok(true) }));
+    mock_db
+        .expect_update_webhook_delivery()
+        .returning(|_| Box::pin(async { Ok(()) }));
+
+    let delivery = local_service()
+        .send_test(&mock_db, user_id, endpoint_id)
+        .await
+        .unwrap();
+
+    assert_eq!(delivery.status, WEBHOOK_STATUS_FAILED);
+    assert_eq!(delivery.response_status, Some(307));
+    assert!(!*followed.lock().unwrap());
+}